@@ -134,7 +134,7 @@ fn main() {
 
     let publication = publication.unwrap();
 
-    let channel_status = publication.lock().unwrap().channel_status();
+    let channel_status = publication.channel_status();
 
     println!(
         "Publication channel status {}: {} ",
@@ -158,7 +158,7 @@ fn main() {
         println!("offering {}/{}", i + 1, settings.number_of_messages);
         let _unused = stdout().flush();
 
-        let result = publication.lock().unwrap().offer_part(src_buffer, 0, c_str_msg.len() as i32);
+        let result = publication.offer_part(src_buffer, 0, c_str_msg.len() as i32);
 
         if let Ok(code) = result {
             println!("Sent with code {}!", code);
@@ -166,7 +166,7 @@ fn main() {
             println!("Offer with error: {:?}", result.err());
         }
 
-        if !publication.lock().unwrap().is_connected() {
+        if !publication.is_connected() {
             println!("No active subscribers detected");
         }
 
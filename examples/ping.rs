@@ -34,6 +34,7 @@ use aeron_rs::{
     example_config::{DEFAULT_FRAGMENT_COUNT_LIMIT, DEFAULT_MESSAGE_LENGTH, DEFAULT_PING_CHANNEL, DEFAULT_PING_STREAM_ID},
     fragment_assembler::FragmentAssembler,
     image::Image,
+    offer_retry_policy::OfferRetryPolicy,
     publication::Publication,
     subscription::Subscription,
     utils::{errors::AeronError, types::Index},
@@ -85,32 +86,32 @@ fn parse_cmd_line() -> CmdOpts {
 
 fn send_ping_and_receive_pong(
     mut fragment_handler: impl FnMut(&AtomicBuffer, Index, Index, &Header),
-    publication: Arc<Mutex<Publication>>,
+    publication: Arc<Publication>,
     subscription: Arc<Mutex<Subscription>>,
     settings: &CmdOpts,
 ) {
     let buffer = AlignedBuffer::with_capacity(settings.message_length);
     let src_buffer = AtomicBuffer::from_aligned(&buffer);
     let idle_strategy: BusySpinIdleStrategy = Default::default();
+    let mut offer_idle_strategy: BusySpinIdleStrategy = Default::default();
+    let retry_policy = OfferRetryPolicy::new();
 
     for _i in 0..settings.number_of_messages {
-        let position = loop {
-            // timestamps in the message are relative to this app, so just send the timepoint directly.
-            let mut start = Instant::now();
-            unsafe {
-                let slice = ::std::slice::from_raw_parts(&mut start as *mut Instant as *mut u8, std::mem::size_of_val(&start));
-                src_buffer.put_bytes(0, slice);
-            }
-            let position = publication
-                .lock()
-                .unwrap()
-                .offer_part(src_buffer, 0, settings.message_length)
-                .unwrap();
-
-            if position > 0 {
-                break position;
-            }
-        };
+        // timestamps in the message are relative to this app, so just send the timepoint directly.
+        let mut start = Instant::now();
+        unsafe {
+            let slice = ::std::slice::from_raw_parts(&mut start as *mut Instant as *mut u8, std::mem::size_of_val(&start));
+            src_buffer.put_bytes(0, slice);
+        }
+        let position = publication
+            .offer_blocking(
+                src_buffer,
+                0,
+                settings.message_length,
+                &mut offer_idle_strategy,
+                &retry_policy,
+            )
+            .expect("Ping offer failed");
 
         // Wait for image
         while subscription.lock().unwrap().image_by_index(0).is_none() {
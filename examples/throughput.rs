@@ -30,7 +30,7 @@ use aeron_rs::{
     concurrent::{
         atomic_buffer::AtomicBuffer,
         logbuffer::{buffer_claim::BufferClaim, header::Header},
-        strategies::{BusySpinIdleStrategy, Strategy},
+        strategies::{BusySpinIdleStrategy, IdleAction, IdlePolicy, Strategy},
     },
     context::Context,
     example_config::{
@@ -39,6 +39,7 @@ use aeron_rs::{
     },
     fragment_assembler::FragmentAssembler,
     image::Image,
+    offer_retry_policy::OfferRetryPolicy,
     utils::{errors::AeronError, rate_reporter::RateReporter, types::Index},
 };
 use lazy_static::lazy_static;
@@ -136,6 +137,23 @@ fn error_handler(error: AeronError) {
     println!("Error: {:?}", error);
 }
 
+// Each idle between claim attempts is a retry after back pressure or an admin action.
+struct CountingIdleStrategy {
+    inner: BusySpinIdleStrategy,
+    idle_count: u64,
+}
+
+impl IdlePolicy for CountingIdleStrategy {
+    fn idle_action(&mut self) -> IdleAction {
+        self.idle_count += 1;
+        self.inner.idle_action()
+    }
+
+    fn reset_idle(&mut self) {
+        self.inner.reset_idle();
+    }
+}
+
 fn str_to_c(val: &str) -> CString {
     CString::new(val).expect("Error converting str to CString")
 }
@@ -212,10 +230,10 @@ fn main() {
         publication = aeron.find_exclusive_publication(publication_id);
     }
 
-    let publication = publication.unwrap();
+    let mut publication = publication.unwrap();
     let subscription = subscription.unwrap();
 
-    let offer_idle_strategy = BusySpinIdleStrategy::default();
+    let retry_policy = OfferRetryPolicy::new();
     let poll_idle_strategy = BusySpinIdleStrategy::default();
 
     let rate_reporter = Arc::new(Mutex::new(RateReporter::new(1_000_000, print_rate)));
@@ -257,7 +275,10 @@ fn main() {
         let src_buffer = AtomicBuffer::from_aligned(&buffer);
 
         let mut buffer_claim = BufferClaim::default();
-        let mut back_pressure_count = 0;
+        let mut offer_idle_strategy = CountingIdleStrategy {
+            inner: BusySpinIdleStrategy::default(),
+            idle_count: 0,
+        };
 
         PRINTING_ACTIVE.store(true, Ordering::SeqCst);
 
@@ -271,20 +292,20 @@ fn main() {
                 break;
             }
 
-            offer_idle_strategy.reset();
-
             // let offer_start = SystemTime::now()
             //     .duration_since(UNIX_EPOCH)
             //     .unwrap()
             //     .as_nanos() as u64;
 
-            while let Err(AeronError::BackPressured) = publication
-                .lock()
-                .unwrap()
-                .try_claim(settings.message_length, &mut buffer_claim)
-            {
-                back_pressure_count += 1;
-                offer_idle_strategy.idle();
+            if let Err(error) = publication.try_claim_blocking(
+                settings.message_length,
+                &mut buffer_claim,
+                &mut offer_idle_strategy,
+                &retry_policy,
+            ) {
+                println!("Claim failed: {:?}", error);
+                RUNNING.store(false, Ordering::SeqCst);
+                break;
             }
 
             buffer_claim.buffer().put::<i64>(buffer_claim.offset(), i);
@@ -299,8 +320,6 @@ fn main() {
             // let b: [u8; 8] = i.to_le_bytes();
             // src_buffer.put_bytes(0, &b);
             // while let Err(AeronError::BackPressured) = publication
-            //     .lock()
-            //     .unwrap()
             //     .offer_part(src_buffer, 0, settings.message_length)
             // {
             //     back_pressure_count += 1;
@@ -317,7 +336,7 @@ fn main() {
 
         println!(
             "Done streaming. Back pressure ratio {}",
-            back_pressure_count as f64 / settings.number_of_messages as f64
+            offer_idle_strategy.idle_count as f64 / settings.number_of_messages as f64
         );
 
         if RUNNING.load(Ordering::SeqCst) && settings.linger_timeout_ms > 0 {
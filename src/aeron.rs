@@ -34,6 +34,7 @@ use crate::{
         ring_buffer::ManyToOneRingBuffer,
         strategies::SleepingIdleStrategy,
     },
+    conductor_proxy::ConductorProxy,
     context::{Context, OnAvailableCounter, OnAvailableImage, OnCloseClient, OnUnavailableCounter, OnUnavailableImage},
     counter::Counter,
     driver_proxy::DriverProxy,
@@ -75,6 +76,7 @@ pub struct Aeron {
     to_clients_copy_receiver: CopyBroadcastReceiver,
 
     conductor: Arc<Mutex<ClientConductor>>, // need mutable access to conductor
+    conductor_proxy: ConductorProxy,
     idle_strategy: Arc<SleepingIdleStrategy>,
     conductor_stopper: Option<AgentStopper>,
     conductor_invoker: AgentInvoker<ClientConductor>,
@@ -128,6 +130,7 @@ impl Aeron {
             context.pre_touch_mapped_memory(),
         );
 
+        let local_conductor_proxy = local_conductor.lock().expect("Mutex poisoned").conductor_proxy();
         let use_agent_invoker = context.use_conductor_agent_invoker();
 
         let mut aeronchik = Self {
@@ -143,6 +146,7 @@ impl Aeron {
             to_clients_broadcast_receiver: local_to_clients_broadcast_receiver.clone(),
             to_clients_copy_receiver: CopyBroadcastReceiver::new(local_to_clients_broadcast_receiver),
             conductor: local_conductor.clone(),
+            conductor_proxy: local_conductor_proxy,
             idle_strategy: local_idle_strategy.clone(),
             conductor_stopper: None,
             conductor_invoker: AgentInvoker::new(local_conductor.clone(), context.error_handler()),
@@ -230,7 +234,7 @@ impl Aeron {
      * @param registration_id of the Publication returned by Aeron::add_publication
      * @return Publication associated with the registration_id
      */
-    pub fn find_publication(&mut self, registration_id: i64) -> Result<Arc<Mutex<Publication>>, AeronError> {
+    pub fn find_publication(&mut self, registration_id: i64) -> Result<Arc<Publication>, AeronError> {
         self.conductor
             .lock()
             .expect("Mutex poisoned")
@@ -268,7 +272,7 @@ impl Aeron {
      * @param registration_id of the ExclusivePublication returned by Aeron::add_exclusive_publication
      * @return ExclusivePublication associated with the registration_id
      */
-    pub fn find_exclusive_publication(&mut self, registration_id: i64) -> Result<Arc<Mutex<ExclusivePublication>>, AeronError> {
+    pub fn find_exclusive_publication(&mut self, registration_id: i64) -> Result<ExclusivePublication, AeronError> {
         self.conductor
             .lock()
             .expect("Mutex poisoned")
@@ -498,7 +502,7 @@ impl Aeron {
      * @return CountersReader for the Aeron media driver in use.
      */
     pub fn counters_reader(&self) -> Result<Arc<CountersReader>, AeronError> {
-        self.conductor.lock().expect("Mutex poisoned").counters_reader()
+        self.conductor_proxy.counters_reader()
     }
 
     /**
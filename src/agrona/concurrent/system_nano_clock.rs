@@ -1,3 +1,13 @@
+use std::time::Instant;
+
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref START_INSTANT: Instant = Instant::now();
+}
+
+/// Monotonic clock measuring nanoseconds elapsed since the first time any clock in the process was read.
+#[derive(Clone, Copy, Default)]
 pub struct NanoClock;
 
 impl NanoClock {
@@ -5,7 +15,25 @@ impl NanoClock {
         NanoClock
     }
 
-    pub fn nano_time(&self) -> u64 {
-        std::time::Instant::now().elapsed().as_nanos() as u64
+    pub fn nano_time(&self) -> i64 {
+        START_INSTANT.elapsed().as_nanos() as i64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::NanoClock;
+
+    #[test]
+    fn should_advance_with_elapsed_time() {
+        let clock = NanoClock::instance();
+
+        let start = clock.nano_time();
+        std::thread::sleep(Duration::from_millis(10));
+        let elapsed = clock.nano_time() - start;
+
+        assert!(elapsed >= 10_000_000, "elapsed {}ns", elapsed);
     }
-}
\ No newline at end of file
+}
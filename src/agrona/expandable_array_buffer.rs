@@ -30,7 +30,7 @@ impl ExpandableArrayBuffer {
         true
     }
 
-    fn ensure_capacity(&mut self, index: i32, length: i32) {
+    pub fn ensure_capacity(&mut self, index: i32, length: i32) {
         if index < 0 || length < 0 {
             panic!("negative value: index={} length={}", index, length);
         }
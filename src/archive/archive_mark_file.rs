@@ -0,0 +1,360 @@
+use std::path::Path;
+
+use io_aeron_archive_codecs_mark::*;
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::utils::errors::{AeronError, IllegalStateError};
+use crate::utils::memory_mapped_file::MemoryMappedFile;
+use crate::utils::misc::unix_time_ms;
+use crate::utils::types::Index;
+
+/**
+ * Name of the mark file within the archive directory.
+ */
+pub const ARCHIVE_MARK_FILE_NAME: &str = "archive-mark.dat";
+
+/**
+ * Length of the header when the mark file does not record it. The error buffer follows the header.
+ */
+pub const HEADER_LENGTH: Index = 8 * 1024;
+
+// IC: offsets of the fields the archive updates while running within the MarkFileHeader block
+const VERSION_OFFSET: Index = 0;
+const ACTIVITY_TIMESTAMP_OFFSET: Index = 8;
+
+const VAR_DATA_LENGTH_SIZE: usize = 4;
+
+/**
+ * Read only view of the mark file an archive keeps in its directory while running. It identifies the archive
+ * process, the channels it can be reached on and carries its error log.
+ * <p>
+ * Older archives write the MarkFileHeader at the start of the file, newer ones put a message header in front.
+ * Both layouts are understood.
+ */
+pub struct ArchiveMarkFile {
+    file: MemoryMappedFile,
+    header_offset: Index,
+    start_timestamp: i64,
+    pid: i64,
+    control_stream_id: i32,
+    local_control_stream_id: i32,
+    events_stream_id: i32,
+    header_length: Index,
+    error_buffer_length: Index,
+    control_channel: String,
+    local_control_channel: String,
+    events_channel: String,
+    aeron_directory: String,
+}
+
+impl ArchiveMarkFile {
+    /**
+     * Map the mark file of an archive.
+     *
+     * @param archive_dir directory of the archive which contains the mark file.
+     * @return the ArchiveMarkFile if the file could be mapped and its header lies within the file.
+     */
+    pub fn open(archive_dir: impl AsRef<Path>) -> Result<Self, AeronError> {
+        let file = MemoryMappedFile::map_existing(archive_dir.as_ref().join(ARCHIVE_MARK_FILE_NAME), false)?;
+        let buffer = file.memory_ptr();
+
+        if file.memory_size()
+            < (message_header_codec::ENCODED_LENGTH + mark_file_header_codec::SBE_BLOCK_LENGTH as usize) as Index
+        {
+            return Err(IllegalStateError::MarkFileTooShort(file.memory_size()).into());
+        }
+
+        let header_decoder = MessageHeaderDecoder::default().wrap(ReadBuf::new(buffer), 0);
+        let has_message_header = header_decoder.schema_id() == mark_file_header_codec::SBE_SCHEMA_ID
+            && header_decoder.template_id() == mark_file_header_codec::SBE_TEMPLATE_ID;
+
+        let mut decoder = if has_message_header {
+            MarkFileHeaderDecoder::default().header(header_decoder)
+        } else {
+            MarkFileHeaderDecoder::default().wrap(
+                ReadBuf::new(buffer),
+                0,
+                mark_file_header_codec::SBE_BLOCK_LENGTH,
+                mark_file_header_codec::SBE_SCHEMA_VERSION,
+            )
+        };
+
+        let header_offset = if has_message_header {
+            message_header_codec::ENCODED_LENGTH as Index
+        } else {
+            0
+        };
+        let start_timestamp = decoder.start_timestamp();
+        let pid = decoder.pid();
+        let control_stream_id = decoder.control_stream_id();
+        let local_control_stream_id = decoder.local_control_stream_id();
+        let events_stream_id = decoder.events_stream_id();
+        let header_length = decoder.header_length().unwrap_or(HEADER_LENGTH);
+        let error_buffer_length = decoder.error_buffer_length().unwrap_or(0);
+
+        // IC: var data has to be read in schema order
+        let control_channel = read_var_data(&mut decoder, buffer, |decoder| decoder.control_channel_decoder())?;
+        let local_control_channel = read_var_data(&mut decoder, buffer, |decoder| decoder.local_control_channel_decoder())?;
+        let events_channel = read_var_data(&mut decoder, buffer, |decoder| decoder.events_channel_decoder())?;
+        let aeron_directory = read_var_data(&mut decoder, buffer, |decoder| decoder.aeron_directory_decoder())?;
+
+        Ok(Self {
+            file,
+            header_offset,
+            start_timestamp,
+            pid,
+            control_stream_id,
+            local_control_stream_id,
+            events_stream_id,
+            header_length,
+            error_buffer_length,
+            control_channel,
+            local_control_channel,
+            events_channel,
+            aeron_directory,
+        })
+    }
+
+    /**
+     * Semantic version of the mark file, zero while the archive is starting up.
+     *
+     * @return semantic version of the mark file.
+     */
+    pub fn version(&self) -> i32 {
+        self.file
+            .atomic_buffer(0, self.file.memory_size())
+            .get_volatile::<i32>(self.header_offset + VERSION_OFFSET)
+    }
+
+    /**
+     * Time in ms since epoch at which the archive was started.
+     *
+     * @return time in ms since epoch at which the archive was started.
+     */
+    pub fn start_timestamp(&self) -> i64 {
+        self.start_timestamp
+    }
+
+    /**
+     * Time in ms since epoch of the last activity the archive recorded, updated while it is running.
+     *
+     * @return time in ms since epoch of the last activity of the archive.
+     */
+    pub fn activity_timestamp(&self) -> i64 {
+        self.file
+            .atomic_buffer(0, self.file.memory_size())
+            .get_volatile::<i64>(self.header_offset + ACTIVITY_TIMESTAMP_OFFSET)
+    }
+
+    /**
+     * Is the archive active, i.e. has it updated its activity timestamp within the timeout?
+     *
+     * @param timeout_ms after which an archive which did not update the activity timestamp is considered gone.
+     * @return true if the archive is active otherwise false.
+     */
+    pub fn is_active(&self, timeout_ms: i64) -> bool {
+        self.version() > 0 && unix_time_ms() as i64 - self.activity_timestamp() <= timeout_ms
+    }
+
+    pub fn pid(&self) -> i64 {
+        self.pid
+    }
+
+    pub fn control_channel(&self) -> &str {
+        &self.control_channel
+    }
+
+    pub fn control_stream_id(&self) -> i32 {
+        self.control_stream_id
+    }
+
+    pub fn local_control_channel(&self) -> &str {
+        &self.local_control_channel
+    }
+
+    pub fn local_control_stream_id(&self) -> i32 {
+        self.local_control_stream_id
+    }
+
+    pub fn events_channel(&self) -> &str {
+        &self.events_channel
+    }
+
+    pub fn events_stream_id(&self) -> i32 {
+        self.events_stream_id
+    }
+
+    pub fn aeron_directory(&self) -> &str {
+        &self.aeron_directory
+    }
+
+    /**
+     * Buffer holding the distinct error log of the archive, to be read with error_log_reader.
+     *
+     * @return buffer holding the distinct error log of the archive.
+     */
+    pub fn error_buffer(&self) -> AtomicBuffer {
+        let length = self
+            .error_buffer_length
+            .min(self.file.memory_size() - self.header_length)
+            .max(0);
+
+        self.file
+            .atomic_buffer(self.header_length.min(self.file.memory_size()), length)
+    }
+}
+
+/**
+ * Read a variable length field of the mark file header, checking that its length and data lie within the file.
+ */
+fn read_var_data<'a>(
+    decoder: &mut MarkFileHeaderDecoder<'a>,
+    buffer: &'a [u8],
+    decode_coordinates: impl FnOnce(&mut MarkFileHeaderDecoder<'a>) -> (usize, usize),
+) -> Result<String, AeronError> {
+    let offset = decoder.get_limit();
+    if offset + VAR_DATA_LENGTH_SIZE > buffer.len() {
+        return Err(IllegalStateError::MarkFileFieldCorrupted { offset, length: 0 }.into());
+    }
+
+    let (data_offset, length) = decode_coordinates(decoder);
+    if length > buffer.len() - data_offset {
+        return Err(IllegalStateError::MarkFileFieldCorrupted { offset, length }.into());
+    }
+
+    Ok(String::from_utf8_lossy(&buffer[data_offset..data_offset + length]).into_owned())
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs::{self, OpenOptions};
+    use std::os::unix::fs::FileExt;
+
+    use io_aeron_archive_codecs_mark::*;
+
+    use crate::archive::archive_mark_file::{ArchiveMarkFile, ARCHIVE_MARK_FILE_NAME};
+    use crate::archive::client::context::Context;
+    use crate::utils::errors::{AeronError, IllegalStateError};
+    use crate::utils::misc::{semantic_version_compose, unix_time_ms};
+
+    // IC: the control channel is the first var data field, straight after the block
+    const CONTROL_CHANNEL_LENGTH_OFFSET: u64 = mark_file_header_codec::SBE_BLOCK_LENGTH as u64;
+
+    fn write_mark_file(dir: &std::path::Path, with_message_header: bool, activity_timestamp: i64) {
+        let mut data = vec![0u8; 16 * 1024];
+
+        let mut encoder = if with_message_header {
+            MarkFileHeaderEncoder::default()
+                .wrap(WriteBuf::new(&mut data), message_header_codec::ENCODED_LENGTH)
+                .header(0)
+                .parent()
+                .unwrap()
+        } else {
+            MarkFileHeaderEncoder::default().wrap(WriteBuf::new(&mut data), 0)
+        };
+        encoder.version(semantic_version_compose(1, 0, 0));
+        encoder.activity_timestamp(activity_timestamp);
+        encoder.start_timestamp(1_000);
+        encoder.pid(4242);
+        encoder.control_stream_id(10);
+        encoder.local_control_stream_id(11);
+        encoder.events_stream_id(30);
+        encoder.header_length(8 * 1024);
+        encoder.error_buffer_length(4 * 1024);
+        encoder.control_channel(b"aeron:udp?endpoint=archive-host:8010");
+        encoder.local_control_channel(b"aeron:ipc");
+        encoder.events_channel(b"aeron:udp?control-mode=dynamic|control=archive-host:8030");
+        encoder.aeron_directory(b"/dev/shm/aeron");
+
+        fs::write(dir.join(ARCHIVE_MARK_FILE_NAME), &data).unwrap();
+    }
+
+    #[test]
+    fn should_read_mark_file_with_message_header() {
+        let dir = tempfile::tempdir().unwrap();
+        write_mark_file(dir.path(), true, unix_time_ms() as i64);
+
+        let mark_file = ArchiveMarkFile::open(dir.path()).unwrap();
+
+        assert_eq!(mark_file.pid(), 4242);
+        assert_eq!(mark_file.start_timestamp(), 1_000);
+        assert_eq!(mark_file.control_channel(), "aeron:udp?endpoint=archive-host:8010");
+        assert_eq!(mark_file.control_stream_id(), 10);
+        assert_eq!(mark_file.local_control_channel(), "aeron:ipc");
+        assert_eq!(mark_file.aeron_directory(), "/dev/shm/aeron");
+        assert_eq!(mark_file.error_buffer().capacity(), 4 * 1024);
+        assert!(mark_file.is_active(10_000));
+
+        let mut context = Context::new();
+        context.set_control_request_from_mark_file(&mark_file);
+        assert_eq!(context.control_request_channel(), "aeron:udp?endpoint=archive-host:8010");
+        assert_eq!(context.control_request_stream_id(), 10);
+    }
+
+    #[test]
+    fn should_read_mark_file_without_message_header() {
+        let dir = tempfile::tempdir().unwrap();
+        write_mark_file(dir.path(), false, unix_time_ms() as i64 - 60_000);
+
+        let mark_file = ArchiveMarkFile::open(dir.path()).unwrap();
+
+        assert_eq!(mark_file.events_stream_id(), 30);
+        assert_eq!(
+            mark_file.events_channel(),
+            "aeron:udp?control-mode=dynamic|control=archive-host:8030"
+        );
+        assert!(!mark_file.is_active(10_000));
+    }
+
+    fn patch_mark_file(dir: &std::path::Path, offset: u64, bytes: &[u8]) {
+        let file = OpenOptions::new().write(true).open(dir.join(ARCHIVE_MARK_FILE_NAME)).unwrap();
+        file.write_all_at(bytes, offset).unwrap();
+    }
+
+    #[test]
+    fn should_become_active_once_version_is_set() {
+        let dir = tempfile::tempdir().unwrap();
+        write_mark_file(dir.path(), false, unix_time_ms() as i64);
+        patch_mark_file(dir.path(), 0, &0_i32.to_le_bytes());
+
+        let mark_file = ArchiveMarkFile::open(dir.path()).unwrap();
+        assert_eq!(mark_file.version(), 0);
+        assert!(!mark_file.is_active(10_000));
+
+        patch_mark_file(dir.path(), 0, &semantic_version_compose(1, 0, 0).to_le_bytes());
+        assert_eq!(mark_file.version(), semantic_version_compose(1, 0, 0));
+        assert!(mark_file.is_active(10_000));
+    }
+
+    #[test]
+    fn should_reject_channel_length_past_end_of_file() {
+        let dir = tempfile::tempdir().unwrap();
+        write_mark_file(dir.path(), false, unix_time_ms() as i64);
+        patch_mark_file(dir.path(), CONTROL_CHANNEL_LENGTH_OFFSET, &u32::MAX.to_le_bytes());
+
+        match ArchiveMarkFile::open(dir.path()) {
+            Err(AeronError::IllegalState(IllegalStateError::MarkFileFieldCorrupted { offset, length })) => {
+                assert_eq!(offset, CONTROL_CHANNEL_LENGTH_OFFSET as usize);
+                assert_eq!(length, u32::MAX as usize);
+            }
+            other => panic!("expected a corrupted field error, got {:?}", other.err()),
+        }
+    }
+
+    #[test]
+    fn should_reject_mark_file_truncated_within_its_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        write_mark_file(dir.path(), true, unix_time_ms() as i64);
+        let file = OpenOptions::new()
+            .write(true)
+            .open(dir.path().join(ARCHIVE_MARK_FILE_NAME))
+            .unwrap();
+        file.set_len(message_header_codec::ENCODED_LENGTH as u64 + CONTROL_CHANNEL_LENGTH_OFFSET + 10)
+            .unwrap();
+
+        assert!(matches!(
+            ArchiveMarkFile::open(dir.path()),
+            Err(AeronError::IllegalState(IllegalStateError::MarkFileFieldCorrupted { .. }))
+        ));
+    }
+}
@@ -0,0 +1,389 @@
+use std::path::Path;
+
+use io_aeron_archive_codecs::*;
+
+use crate::archive::client::recording_descriptor_consumer::RecordingDescriptor;
+use crate::archive::client::recording_descriptor_poller::decode_descriptor;
+use crate::utils::bit_utils::align;
+use crate::utils::errors::{AeronError, IllegalStateError};
+use crate::utils::memory_mapped_file::MemoryMappedFile;
+use crate::utils::misc::{semantic_version_major, semantic_version_to_string};
+use crate::utils::types::Index;
+
+/**
+ * Name of the catalog file within the archive directory.
+ */
+pub const CATALOG_FILE_NAME: &str = "archive.catalog";
+
+/**
+ * Major version of the catalog layout which can be read.
+ */
+pub const CATALOG_MAJOR_VERSION: u8 = 3;
+
+/**
+ * Length of the header in front of each recording descriptor in the catalog.
+ */
+pub const DESCRIPTOR_HEADER_LENGTH: Index = recording_descriptor_header_codec::SBE_BLOCK_LENGTH as Index;
+
+// IC: stripped channel, original channel and source identity, each prefixed with a u32 length
+const DESCRIPTOR_VAR_DATA_FIELD_COUNT: usize = 3;
+const VAR_DATA_LENGTH_SIZE: usize = 4;
+
+/**
+ * Entry for a recording in the catalog.
+ */
+#[derive(Clone, Debug)]
+pub struct CatalogEntry {
+    pub state: RecordingState,
+    pub checksum: i32,
+    pub descriptor: RecordingDescriptor,
+}
+
+/**
+ * Read only view of the catalog of an archive which works without the archive running, e.g. for inspecting
+ * an archive on a host where the archive process is down.
+ * <p>
+ * The catalog is a header followed by aligned frames of a RecordingDescriptorHeader and a RecordingDescriptor,
+ * both encoded without a message header.
+ */
+pub struct Catalog {
+    file: MemoryMappedFile,
+    version: i32,
+    header_length: Index,
+    next_recording_id: i64,
+    alignment: Index,
+}
+
+impl Catalog {
+    /**
+     * Map the catalog of an archive.
+     *
+     * @param archive_dir directory of the archive which contains the catalog file.
+     * @return the Catalog if the file could be mapped and its version is supported.
+     */
+    pub fn open(archive_dir: impl AsRef<Path>) -> Result<Self, AeronError> {
+        let file = MemoryMappedFile::map_existing(archive_dir.as_ref().join(CATALOG_FILE_NAME), false)?;
+
+        if file.memory_size() < catalog_header_codec::SBE_BLOCK_LENGTH as Index {
+            return Err(IllegalStateError::CatalogFileTooShort(file.memory_size()).into());
+        }
+
+        let decoder = CatalogHeaderDecoder::default().wrap(
+            ReadBuf::new(file.memory_ptr()),
+            0,
+            catalog_header_codec::SBE_BLOCK_LENGTH,
+            catalog_header_codec::SBE_SCHEMA_VERSION,
+        );
+        let version = decoder.version();
+        let header_length = decoder.length();
+        let next_recording_id = decoder.next_recording_id();
+        let alignment = decoder.alignment();
+
+        if semantic_version_major(version) != CATALOG_MAJOR_VERSION {
+            return Err(IllegalStateError::CatalogVersionNotSupported {
+                version: semantic_version_to_string(version),
+                expected_major: CATALOG_MAJOR_VERSION,
+            }
+            .into());
+        }
+
+        if header_length <= 0 || alignment <= 0 || header_length > file.memory_size() {
+            return Err(IllegalStateError::CatalogFileTooShort(file.memory_size()).into());
+        }
+
+        Ok(Self {
+            file,
+            version,
+            header_length,
+            next_recording_id,
+            alignment,
+        })
+    }
+
+    /**
+     * Semantic version of the catalog layout.
+     *
+     * @return semantic version of the catalog layout.
+     */
+    pub fn version(&self) -> i32 {
+        self.version
+    }
+
+    /**
+     * Recording id which the archive will assign to its next recording.
+     *
+     * @return recording id which the archive will assign to its next recording.
+     */
+    pub fn next_recording_id(&self) -> i64 {
+        self.next_recording_id
+    }
+
+    /**
+     * Alignment of the recording descriptor frames in the catalog.
+     *
+     * @return alignment of the recording descriptor frames in the catalog.
+     */
+    pub fn alignment(&self) -> Index {
+        self.alignment
+    }
+
+    /**
+     * Iterate over the entries in the catalog, in order of recording id, including invalidated entries.
+     * Iteration stops with an error at the first descriptor whose length or variable length fields run past its
+     * frame or the end of the file.
+     *
+     * @param consumer called for each entry in the catalog.
+     * @return the number of entries visited.
+     */
+    pub fn for_each(&self, mut consumer: impl FnMut(CatalogEntry)) -> Result<i32, AeronError> {
+        let buffer = self.file.memory_ptr();
+        let capacity = self.file.memory_size();
+        let mut offset = self.header_length;
+        let mut count = 0;
+
+        while offset + DESCRIPTOR_HEADER_LENGTH <= capacity {
+            let header_decoder = RecordingDescriptorHeaderDecoder::default().wrap(
+                ReadBuf::new(buffer),
+                offset as usize,
+                recording_descriptor_header_codec::SBE_BLOCK_LENGTH,
+                recording_descriptor_header_codec::SBE_SCHEMA_VERSION,
+            );
+            let length = header_decoder.length();
+            // IC: the unused remainder of the file is zeroed, so the first empty frame marks the end
+            if length <= 0 {
+                break;
+            }
+
+            let descriptor_offset = offset + DESCRIPTOR_HEADER_LENGTH;
+            if length > capacity - descriptor_offset
+                || !var_data_fits(buffer, descriptor_offset as usize, (descriptor_offset + length) as usize)
+            {
+                return Err(IllegalStateError::CatalogDescriptorCorrupted { offset, length }.into());
+            }
+
+            // IC: the decoder only sees the frame, so it can't read the descriptor of the next recording
+            let mut decoder = RecordingDescriptorDecoder::default().wrap(
+                ReadBuf::new(&buffer[..(descriptor_offset + length) as usize]),
+                descriptor_offset as usize,
+                recording_descriptor_codec::SBE_BLOCK_LENGTH,
+                recording_descriptor_codec::SBE_SCHEMA_VERSION,
+            );
+
+            consumer(CatalogEntry {
+                state: header_decoder.state(),
+                checksum: header_decoder.checksum(),
+                descriptor: decode_descriptor(&mut decoder),
+            });
+
+            count += 1;
+            offset += align(DESCRIPTOR_HEADER_LENGTH + length, self.alignment);
+        }
+
+        Ok(count)
+    }
+
+    /**
+     * Find the entry for a recording.
+     *
+     * @param recording_id to look up.
+     * @return the entry for the recording if in the catalog.
+     */
+    pub fn find_recording(&self, recording_id: i64) -> Result<Option<CatalogEntry>, AeronError> {
+        let mut found = None;
+        self.for_each(|entry| {
+            if found.is_none() && entry.descriptor.recording_id == recording_id {
+                found = Some(entry);
+            }
+        })?;
+
+        Ok(found)
+    }
+}
+
+/**
+ * Check that the variable length fields of a descriptor, whose lengths are read from the file, end within its frame.
+ */
+fn var_data_fits(buffer: &[u8], descriptor_offset: usize, frame_end: usize) -> bool {
+    let mut offset = descriptor_offset + recording_descriptor_codec::SBE_BLOCK_LENGTH as usize;
+
+    for _ in 0..DESCRIPTOR_VAR_DATA_FIELD_COUNT {
+        if offset + VAR_DATA_LENGTH_SIZE > frame_end {
+            return false;
+        }
+
+        let mut length_bytes = [0u8; VAR_DATA_LENGTH_SIZE];
+        length_bytes.copy_from_slice(&buffer[offset..offset + VAR_DATA_LENGTH_SIZE]);
+        let length = u32::from_le_bytes(length_bytes) as usize;
+        offset += VAR_DATA_LENGTH_SIZE;
+
+        if length > frame_end - offset {
+            return false;
+        }
+        offset += length;
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use io_aeron_archive_codecs::*;
+
+    use crate::archive::catalog::{Catalog, CATALOG_FILE_NAME, DESCRIPTOR_HEADER_LENGTH};
+    use crate::utils::bit_utils::align;
+    use crate::utils::errors::{AeronError, IllegalStateError};
+    use crate::utils::misc::semantic_version_compose;
+    use crate::utils::types::Index;
+
+    const ALIGNMENT: Index = 32;
+
+    fn encode_recording(data: &mut [u8], offset: Index, recording_id: i64, state: RecordingState) -> Index {
+        let mut encoder =
+            RecordingDescriptorEncoder::default().wrap(WriteBuf::new(data), (offset + DESCRIPTOR_HEADER_LENGTH) as usize);
+        encoder.recording_id(recording_id);
+        encoder.start_position(0);
+        encoder.stop_position(1024 * recording_id);
+        encoder.segment_file_length(128 * 1024 * 1024);
+        encoder.term_buffer_length(64 * 1024);
+        encoder.mtu_length(1408);
+        encoder.stream_id(1000 + recording_id as i32);
+        encoder.stripped_channel(b"aeron:ipc");
+        encoder.original_channel(b"aeron:ipc?alias=test");
+        encoder.source_identity(b"aeron:ipc");
+        let length = encoder.encoded_length() as Index;
+
+        let mut header_encoder = RecordingDescriptorHeaderEncoder::default().wrap(WriteBuf::new(data), offset as usize);
+        header_encoder.length(length);
+        header_encoder.state(state);
+
+        offset + align(DESCRIPTOR_HEADER_LENGTH + length, ALIGNMENT)
+    }
+
+    #[test]
+    fn should_read_recordings_from_catalog() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut data = vec![0u8; 4096];
+
+        let mut encoder = CatalogHeaderEncoder::default().wrap(WriteBuf::new(&mut data), 0);
+        encoder.version(semantic_version_compose(3, 0, 0));
+        encoder.length(catalog_header_codec::SBE_BLOCK_LENGTH as i32);
+        encoder.next_recording_id(2);
+        encoder.alignment(ALIGNMENT);
+
+        let offset = encode_recording(
+            &mut data,
+            catalog_header_codec::SBE_BLOCK_LENGTH as Index,
+            0,
+            RecordingState::VALID,
+        );
+        encode_recording(&mut data, offset, 1, RecordingState::INVALID);
+        fs::write(dir.path().join(CATALOG_FILE_NAME), &data).unwrap();
+
+        let catalog = Catalog::open(dir.path()).unwrap();
+        assert_eq!(catalog.next_recording_id(), 2);
+
+        let mut entries = Vec::new();
+        assert_eq!(catalog.for_each(|entry| entries.push(entry)).unwrap(), 2);
+        assert_eq!(entries[0].state, RecordingState::VALID);
+        assert_eq!(entries[0].descriptor.stream_id, 1000);
+        assert_eq!(entries[0].descriptor.original_channel, "aeron:ipc?alias=test");
+        assert_eq!(entries[1].state, RecordingState::INVALID);
+        assert_eq!(entries[1].descriptor.stop_position, 1024);
+
+        let entry = catalog.find_recording(1).unwrap().unwrap();
+        assert_eq!(entry.descriptor.segment_file_length, 128 * 1024 * 1024);
+        assert!(catalog.find_recording(2).unwrap().is_none());
+    }
+
+    fn encode_catalog(data: &mut [u8]) -> (Index, Index) {
+        let mut encoder = CatalogHeaderEncoder::default().wrap(WriteBuf::new(data), 0);
+        encoder.version(semantic_version_compose(3, 0, 0));
+        encoder.length(catalog_header_codec::SBE_BLOCK_LENGTH as i32);
+        encoder.next_recording_id(2);
+        encoder.alignment(ALIGNMENT);
+
+        let first_offset = catalog_header_codec::SBE_BLOCK_LENGTH as Index;
+        let second_offset = encode_recording(data, first_offset, 0, RecordingState::VALID);
+        encode_recording(data, second_offset, 1, RecordingState::VALID);
+
+        (first_offset, second_offset)
+    }
+
+    fn set_descriptor_length(data: &mut [u8], offset: Index, length: Index) {
+        RecordingDescriptorHeaderEncoder::default()
+            .wrap(WriteBuf::new(data), offset as usize)
+            .length(length);
+    }
+
+    fn assert_corrupted(data: &[u8], expected_offset: Index, expected_length: Index) {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(CATALOG_FILE_NAME), data).unwrap();
+        let catalog = Catalog::open(dir.path()).unwrap();
+
+        let mut recording_ids = Vec::new();
+        match catalog.for_each(|entry| recording_ids.push(entry.descriptor.recording_id)) {
+            Err(AeronError::IllegalState(IllegalStateError::CatalogDescriptorCorrupted { offset, length })) => {
+                assert_eq!(offset, expected_offset);
+                assert_eq!(length, expected_length);
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert_eq!(recording_ids, vec![0]);
+        assert!(catalog.find_recording(1).is_err());
+    }
+
+    #[test]
+    fn should_reject_descriptor_with_length_past_end_of_file() {
+        let mut data = vec![0u8; 1024];
+        let (_, second_offset) = encode_catalog(&mut data);
+        set_descriptor_length(&mut data, second_offset, 1024);
+
+        assert_corrupted(&data, second_offset, 1024);
+    }
+
+    #[test]
+    fn should_reject_descriptor_with_length_shorter_than_its_fields() {
+        let mut data = vec![0u8; 1024];
+        let (_, second_offset) = encode_catalog(&mut data);
+        // IC: the source identity at the end of the descriptor now runs past the frame into the zeroed remainder
+        set_descriptor_length(&mut data, second_offset, 100);
+
+        assert_corrupted(&data, second_offset, 100);
+    }
+
+    #[test]
+    fn should_reject_descriptor_with_channel_length_past_its_frame() {
+        let mut data = vec![0u8; 1024];
+        let (_, second_offset) = encode_catalog(&mut data);
+        let length = RecordingDescriptorHeaderDecoder::default()
+            .wrap(
+                ReadBuf::new(&data),
+                second_offset as usize,
+                recording_descriptor_header_codec::SBE_BLOCK_LENGTH,
+                recording_descriptor_header_codec::SBE_SCHEMA_VERSION,
+            )
+            .length();
+
+        let stripped_channel_length_offset =
+            (second_offset + DESCRIPTOR_HEADER_LENGTH) as usize + recording_descriptor_codec::SBE_BLOCK_LENGTH as usize;
+        data[stripped_channel_length_offset..stripped_channel_length_offset + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert_corrupted(&data, second_offset, length);
+    }
+
+    #[test]
+    fn should_reject_unsupported_catalog_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut data = vec![0u8; 1024];
+
+        let mut encoder = CatalogHeaderEncoder::default().wrap(WriteBuf::new(&mut data), 0);
+        encoder.version(semantic_version_compose(2, 0, 0));
+        encoder.length(catalog_header_codec::SBE_BLOCK_LENGTH as i32);
+        encoder.alignment(ALIGNMENT);
+        fs::write(dir.path().join(CATALOG_FILE_NAME), &data).unwrap();
+
+        assert!(Catalog::open(dir.path()).is_err());
+    }
+}
@@ -1,140 +1,1512 @@
-// use std::sync::{Arc, Mutex};
-// use crate::context::NULL_VALUE;
-// use crate::archive::client::context::Context;
-// use crate::aeron::Aeron;
-// // use crate::agrona::concurrent::system_nano_clock::NanoClock;
-// // use crate::archive::client::archive_proxy::ArchiveProxy;
-// // use crate::archive::client::control_response_poller::ControlResponsePoller;
-// // use crate::archive::client::recording_descriptor_poller::RecordingDescriptorPoller;
-// use crate::client_conductor::ClientConductor;
-// use crate::concurrent::agent_invoker::AgentInvoker;
-// use crate::concurrent::strategies::YieldingIdleStrategy;
-
-pub(crate) const MESSAGE_TIMEOUT_DEFAULT_NS: i64 = 10_000_000_000;
-// const NULL_TIMESTAMP: i64 = NULL_VALUE;
-// const NULL_POSITION: i64 = NULL_VALUE;
-// const NULL_LENGTH: i64 = NULL_VALUE;
-// const NOT_CONNECTED_MSG: &str = "not connected";
-// const FRAGMENT_LIMIT: i32 = 10;
-//
-// pub struct AeronArchive {
-//     is_closed: bool,
-//     is_in_callback: bool,
-//     last_correlation_id: i64,
-//     control_session_id: i64,
-//     message_timeout_ns: i64,
-//     context: Context,
-//     aeron: Arc<Mutex<Aeron>>,
-//     archive_proxy: ArchiveProxy,
-//     // IC: Set to Yielding only for now
-//     idle_strategy: YieldingIdleStrategy,
-//     // IC: for polling control response channel
-//     control_response_poller: ControlResponsePoller,
-//     lock: Mutex<()>,
-//     nano_clock: NanoClock,
-//     aeron_client_invoker: AgentInvoker<ClientConductor>,
-//     agent_invoker: AgentInvoker<ClientConductor>,
-//     recording_descriptor_poller: RecordingDescriptorPoller,
-//     recording_subscription_descriptor_poller: RecordingSubscriptionDescriptorPoller,
-// }
-//
-// impl AeronArchive {
-//     pub fn new(
-//         control_session_id: i64,
-//         message_timeout_ns: i64,
-//         context: Context,
-//         aeron: Arc<Mutex<Aeron>>,
-//         archive_proxy: ArchiveProxy,
-//         idle_strategy: Box<dyn IdleStrategy>,
-//         control_response_poller: ControlResponsePoller,
-//         nano_clock: NanoClock,
-//         aeron_client_invoker: AgentInvoker<ClientConductor>,
-//         agent_invoker: AgentInvoker<>,
-//         recording_descriptor_poller: RecordingDescriptorPoller,
-//         recording_subscription_descriptor_poller: RecordingSubscriptionDescriptorPoller,
-//     ) -> Self {
-//         Self {
-//             is_closed: false,
-//             is_in_callback: false,
-//             last_correlation_id: NULL_VALUE,
-//             control_session_id,
-//             message_timeout_ns,
-//             context,
-//             aeron,
-//             archive_proxy,
-//             idle_strategy,
-//             control_response_poller,
-//             lock: Mutex::new(()),
-//             nano_clock,
-//             aeron_client_invoker,
-//             agent_invoker,
-//             recording_descriptor_poller,
-//             recording_subscription_descriptor_poller,
-//         }
-//     }
-//
-//
-//     fn segment_file_base_position(
-//         start_position: i64,
-//         position: i64,
-//         term_buffer_length: i32,
-//         segment_file_length: i32,
-//     ) -> i64 {
-//         let start_term_base_position = start_position - (start_position & (term_buffer_length - 1) as i64);
-//         let length_from_base_position = position - start_term_base_position;
-//         let segments = length_from_base_position - (length_from_base_position & (segment_file_length - 1) as i64);
-//
-//         start_term_base_position + segments
-//     }
-//
-//     /**
-//      * Notify the archive that this control session is closed, so it can promptly release resources then close the
-//      * local resources associated with the client.
-//      */
-//     pub fn close(&mut self) {
-//         let _lock = self.lock.lock().unwrap();
-//
-//         if !self.is_closed {
-//             self.is_closed = true;
-//             let error_handler = self.context.error_handler();
-//             let mut result_ex: Option<Box<dyn std::error::Error>> = None;
-//
-//             if self.archive_proxy.publication().is_connected() {
-//                 result_ex = quiet_close(
-//                     result_ex,
-//                     || self.archive_proxy.close_session(self.control_session_id),
-//                 );
-//             }
-//
-//             if !self.context.owns_aeron_client() {
-//                 result_ex = quiet_close(result_ex, self.archive_proxy.publication());
-//                 result_ex = quiet_close(result_ex, self.control_response_poller.subscription());
-//             }
-//
-//             let mut rethrow = false;
-//             match self.context.close() {
-//                 Ok(_) => {}
-//                 Err(ex) => {
-//                     rethrow = true;
-//                     if let Some(ref mut err) = result_ex {
-//                         err.add_source(ex);
-//                     } else {
-//                         result_ex = Some(Box::new(ex));
-//                     }
-//                 }
-//             }
-//
-//             if let Some(ex) = result_ex {
-//                 if let Some(handler) = error_handler {
-//                     handler.on_error(&ex);
-//                 }
-//
-//                 if rethrow {
-//                     // bookmark
-//                     println!("Bookmark: rethrow")
-//                     // LangUtil::rethrow_unchecked(&ex);
-//                 }
-//             }
-//         }
-//     }
-// }
\ No newline at end of file
+use std::ffi::CString;
+use std::sync::{Arc, Mutex};
+
+use io_aeron_archive_codecs::{ControlResponseCode, SourceLocation};
+
+use crate::aeron::Aeron;
+use crate::agrona::concurrent::system_nano_clock::NanoClock;
+use crate::archive::client::archive_proxy::{ArchiveProxy, DEFAULT_RETRY_ATTEMPTS};
+use crate::archive::client::context::Context;
+use crate::archive::client::control_response_poller::{ControlResponsePoller, FRAGMENT_LIMIT};
+use crate::archive::client::recording_descriptor_consumer::{RecordingDescriptor, RecordingDescriptorConsumer};
+use crate::archive::client::recording_descriptor_poller::RecordingDescriptorPoller;
+use crate::archive::client::recording_signal_consumer::RecordingSignalConsumer;
+use crate::archive::client::replication_params::ReplicationParams;
+use crate::channel_uri::ChannelUri;
+use crate::concurrent::strategies::{Strategy, YieldingIdleStrategy};
+use crate::context::NULL_VALUE;
+use crate::log;
+use crate::subscription::Subscription;
+use crate::utils::errors::{AeronError, ArchiveError, ArchiveErrorCode, GenericError};
+
+/**
+ * Represents a timestamp that has not been set. Can be used when the time is not known.
+ */
+pub const NULL_TIMESTAMP: i64 = NULL_VALUE;
+
+/**
+ * Represents a position that has not been set. Can be used when the position is not known.
+ */
+pub const NULL_POSITION: i64 = NULL_VALUE;
+
+/**
+ * Represents a length that has not been set. If null length is provided then replay the whole recorded stream.
+ */
+pub const NULL_LENGTH: i64 = NULL_VALUE;
+
+/**
+ * Client for interacting with a local or remote Aeron Archive which records and replays message streams.
+ * <p>
+ * This client provides a simple interaction model which is mostly synchronous and may not be optimal.
+ * The underlying components such as the ArchiveProxy and the ControlResponsePoller may be used directly if
+ * a more asynchronous interaction is required.
+ * <p>
+ * Note: The control session is closed and, if owned, the Aeron client is closed when the AeronArchive is dropped.
+ */
+pub struct AeronArchive {
+    // IC: proxy and poller are declared first so the publication and subscription are released
+    // before the last reference to the Aeron client goes away.
+    archive_proxy: ArchiveProxy,
+    control_response_poller: ControlResponsePoller,
+    recording_descriptor_poller: RecordingDescriptorPoller,
+    is_closed: bool,
+    control_session_id: i64,
+    message_timeout_ns: i64,
+    keep_alive_interval_ns: i64,
+    time_of_last_request_ns: i64,
+    idle_strategy: YieldingIdleStrategy,
+    nano_clock: NanoClock,
+    recording_signal_consumer: Box<dyn RecordingSignalConsumer + Send>,
+    context: Context,
+    aeron: Arc<Mutex<Aeron>>,
+}
+
+impl AeronArchive {
+    /**
+     * Connect to an Aeron archive by providing a Context. This will create a control session.
+     * <p>
+     * Before connecting Context::conclude() will be called.
+     * If an error occurs then Context::close() will be called.
+     *
+     * @param context for connection configuration.
+     * @return the newly created AeronArchive client.
+     */
+    pub fn connect(context: Context) -> Result<Self, AeronError> {
+        let idle_strategy = context.idle_strategy();
+        let mut async_connect = Self::async_connect(context)?;
+
+        loop {
+            if let Some(aeron_archive) = async_connect.poll()? {
+                return Ok(aeron_archive);
+            }
+
+            idle_and_invoke(&async_connect.aeron, &idle_strategy);
+        }
+    }
+
+    /**
+     * Begin an attempt at creating a connection which can be completed by calling AsyncConnect::poll() until
+     * it returns the client, before complete it will return None.
+     * <p>
+     * Before connecting Context::conclude() will be called.
+     * If an error occurs then Context::close() will be called, either here or when the AsyncConnect is dropped.
+     *
+     * @param context for connection configuration.
+     * @return the AsyncConnect that can be polled for completion.
+     */
+    pub fn async_connect(context: Context) -> Result<AsyncConnect, AeronError> {
+        AsyncConnect::new(context)
+    }
+
+    fn new(
+        context: Context,
+        aeron: Arc<Mutex<Aeron>>,
+        archive_proxy: ArchiveProxy,
+        control_response_poller: ControlResponsePoller,
+        control_session_id: i64,
+    ) -> Self {
+        let nano_clock = NanoClock::instance();
+
+        let recording_descriptor_poller = RecordingDescriptorPoller::new(
+            control_response_poller.subscription(),
+            context.error_handler(),
+            context.recording_signal_consumer(),
+            control_session_id,
+            FRAGMENT_LIMIT,
+        );
+
+        Self {
+            archive_proxy,
+            control_response_poller,
+            recording_descriptor_poller,
+            is_closed: false,
+            control_session_id,
+            message_timeout_ns: context.message_timeout_ns(),
+            keep_alive_interval_ns: context.keep_alive_interval_ns(),
+            time_of_last_request_ns: nano_clock.nano_time(),
+            idle_strategy: context.idle_strategy(),
+            nano_clock,
+            recording_signal_consumer: context.recording_signal_consumer(),
+            context,
+            aeron,
+        }
+    }
+
+    /**
+     * Get the Context used to connect this archive client.
+     *
+     * @return the Context used to connect this archive client.
+     */
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    /**
+     * The control session id allocated for this connection to the archive.
+     *
+     * @return control session id allocated for this connection to the archive.
+     */
+    pub fn control_session_id(&self) -> i64 {
+        self.control_session_id
+    }
+
+    /**
+     * The ArchiveProxy for send asynchronous messages to the connected archive.
+     *
+     * @return the ArchiveProxy for send asynchronous messages to the connected archive.
+     */
+    pub fn archive_proxy(&mut self) -> &mut ArchiveProxy {
+        &mut self.archive_proxy
+    }
+
+    /**
+     * Get the ControlResponsePoller for polling additional events on the control channel.
+     *
+     * @return the ControlResponsePoller for polling additional events on the control channel.
+     */
+    pub fn control_response_poller(&mut self) -> &mut ControlResponsePoller {
+        &mut self.control_response_poller
+    }
+
+    /**
+     * Get the Aeron client used by this archive client.
+     *
+     * @return the Aeron client used by this archive client.
+     */
+    pub fn aeron(&self) -> Arc<Mutex<Aeron>> {
+        self.aeron.clone()
+    }
+
+    /**
+     * Has this archive client been closed?
+     *
+     * @return true if the archive client has been closed.
+     */
+    pub fn is_closed(&self) -> bool {
+        self.is_closed
+    }
+
+    /**
+     * Send a keep-alive to the archive so it does not time out the control session.
+     */
+    pub fn keep_alive(&mut self) -> Result<(), AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self.archive_proxy.keep_alive(self.control_session_id, correlation_id)?;
+        self.ensure_sent(is_sent, "keep alive")
+    }
+
+    /**
+     * Send a keep-alive if nothing has been sent to the archive for the keep-alive interval.
+     * <p>
+     * This is done while waiting for responses and from poll_for_recording_signals(), so it only has to be called
+     * when the client is otherwise left idle, e.g. from the duty cycle of the agent owning this client.
+     *
+     * @return true if a keep-alive was sent.
+     */
+    pub fn keep_alive_if_required(&mut self) -> Result<bool, AeronError> {
+        if self.nano_clock.nano_time() - self.time_of_last_request_ns < self.keep_alive_interval_ns {
+            return Ok(false);
+        }
+
+        self.keep_alive()?;
+        Ok(true)
+    }
+
+    /**
+     * Start recording a channel and stream pairing.
+     * <p>
+     * Channels that include session id parameters are considered different than channels without session ids. If a
+     * publication matches both a session id specific channel recording and a non session id specific recording, it will
+     * be recorded twice.
+     *
+     * @param channel        to be recorded.
+     * @param stream_id       to be recorded.
+     * @param source_location of the publication to be recorded.
+     * @return the subscription id of the recording.
+     */
+    pub fn start_recording(&mut self, channel: &str, stream_id: i32, source_location: SourceLocation) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent =
+            self.archive_proxy
+                .start_recording(channel, stream_id, source_location, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "start recording")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Extend an existing, non-active recording of a channel and stream pairing.
+     * <p>
+     * The channel must be configured for the initial position from which it will be extended. This can be done
+     * with ChannelUriStringBuilder::initial_position(). The details required to initialise can be found by
+     * calling list_recording().
+     *
+     * @param recording_id    of the existing recording.
+     * @param channel        to be recorded.
+     * @param stream_id       to be recorded.
+     * @param source_location of the publication to be recorded.
+     * @return the subscription id of the recording.
+     */
+    pub fn extend_recording(
+        &mut self,
+        recording_id: i64,
+        channel: &str,
+        stream_id: i32,
+        source_location: SourceLocation,
+    ) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self.archive_proxy.extend_recording(
+            channel,
+            stream_id,
+            source_location,
+            recording_id,
+            correlation_id,
+            self.control_session_id,
+        )?;
+        self.ensure_sent(is_sent, "extend recording")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Stop recording for a channel and stream pairing.
+     * <p>
+     * Channels that include session id parameters are considered different than channels without session ids. Stopping
+     * a recording on a channel without a session id parameter will not stop the recording of any session id specific
+     * recordings that use the same channel and stream id.
+     *
+     * @param channel  to stop recording for.
+     * @param stream_id to stop recording for.
+     */
+    pub fn stop_recording(&mut self, channel: &str, stream_id: i32) -> Result<(), AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .stop_recording(channel, stream_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "stop recording")?;
+
+        self.poll_for_response(correlation_id).map(|_| ())
+    }
+
+    /**
+     * Stop recording for a subscription id that has been returned from start_recording() or extend_recording().
+     *
+     * @param subscription_id is the Subscription::registration_id() for the recording in the archive.
+     */
+    pub fn stop_recording_by_subscription_id(&mut self, subscription_id: i64) -> Result<(), AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .stop_recording_subscription(subscription_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "stop recording")?;
+
+        self.poll_for_response(correlation_id).map(|_| ())
+    }
+
+    /**
+     * Stop recording for a recording id, i.e. the identity of the recording, regardless of which subscriptions
+     * are feeding it.
+     *
+     * @param recording_id of the recording to be stopped.
+     * @return true if the recording was stopped or false if it was not active.
+     */
+    pub fn stop_recording_by_identity(&mut self, recording_id: i64) -> Result<bool, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .stop_recording_by_identity(recording_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "stop recording")?;
+
+        Ok(self.poll_for_response(correlation_id)? != 0)
+    }
+
+    /**
+     * Truncate a stopped recording to a given position that is less than the stopped position. The provided position
+     * must be on a fragment boundary. Truncating a recording to the start position effectively deletes the recording.
+     *
+     * @param recording_id of the stopped recording to be truncated.
+     * @param position    to which the recording will be truncated.
+     * @return count of deleted segment files.
+     */
+    pub fn truncate_recording(&mut self, recording_id: i64, position: i64) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .truncate_recording(recording_id, position, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "truncate recording")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Purge a stopped recording, i.e. mark recording as invalid and delete the corresponding segment files.
+     * The space in the catalog will be reclaimed upon compaction.
+     *
+     * @param recording_id of the stopped recording to be purged.
+     * @return count of deleted segment files.
+     */
+    pub fn purge_recording(&mut self, recording_id: i64) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .purge_recording(recording_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "purge recording")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Detach segments from the beginning of a recording up to the provided new start position.
+     * <p>
+     * The new start position must be the first byte position of a segment after the existing start position.
+     * <p>
+     * It is not possible to detach segments which are active for recording or being replayed.
+     *
+     * @param recording_id      to which the operation applies.
+     * @param new_start_position for the recording after the segments are detached.
+     * @see segment_file_base_position
+     */
+    pub fn detach_segments(&mut self, recording_id: i64, new_start_position: i64) -> Result<(), AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent =
+            self.archive_proxy
+                .detach_segments(recording_id, new_start_position, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "detach segments")?;
+
+        self.poll_for_response(correlation_id).map(|_| ())
+    }
+
+    /**
+     * Delete segments which have been previously detached from a recording.
+     *
+     * @param recording_id to which the operation applies.
+     * @return count of deleted segment files.
+     * @see detach_segments
+     */
+    pub fn delete_detached_segments(&mut self, recording_id: i64) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .delete_detached_segments(recording_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "delete detached segments")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Purge (detach and delete) segments from the beginning of a recording up to the provided new start position.
+     * <p>
+     * The new start position must be the first byte position of a segment after the existing start position.
+     * <p>
+     * It is not possible to detach segments which are active for recording or being replayed.
+     *
+     * @param recording_id      to which the operation applies.
+     * @param new_start_position for the recording after the segments are detached.
+     * @return count of deleted segment files.
+     * @see segment_file_base_position
+     */
+    pub fn purge_segments(&mut self, recording_id: i64, new_start_position: i64) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent =
+            self.archive_proxy
+                .purge_segments(recording_id, new_start_position, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "purge segments")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Attach segments to the beginning of a recording to restore history that was previously detached.
+     * <p>
+     * Segment files must match the existing recording and join exactly to the start position of the recording
+     * they are being attached to.
+     *
+     * @param recording_id to which the operation applies.
+     * @return count of attached segment files.
+     * @see detach_segments
+     */
+    pub fn attach_segments(&mut self, recording_id: i64) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .attach_segments(recording_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "attach segments")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Migrate segments from a source recording and attach them to the beginning of a destination recording.
+     * <p>
+     * The source recording must match the destination recording for segment length, term length, mtu length,
+     * stream id, plus the stop position and term id of the source must join with the start position of the
+     * destination and be on a segment boundary.
+     * <p>
+     * The source recording will be effectively truncated back to its start position after the migration.
+     *
+     * @param src_recording_id source recording from which the segments will be migrated.
+     * @param dst_recording_id destination recording to which the segments will be attached.
+     * @return count of attached segment files.
+     */
+    pub fn migrate_segments(&mut self, src_recording_id: i64, dst_recording_id: i64) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent =
+            self.archive_proxy
+                .migrate_segments(src_recording_id, dst_recording_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "migrate segments")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Start a replay for a length in bytes of a recording from a position. If the position is NULL_POSITION then
+     * the stream will be replayed from the start.
+     * <p>
+     * The lower 32-bits of the returned value contains the Image::session_id() of the received replay. All
+     * 64-bits are required to uniquely identify the replay when calling stop_replay(). The lower 32-bits can be
+     * obtained by casting the i64 value to an i32.
+     *
+     * @param recording_id      to be replayed.
+     * @param position         from which the replay should begin or NULL_POSITION if from the start.
+     * @param length           of the stream to be replayed. Use i64::MAX to follow a live recording or NULL_LENGTH
+     *                         to replay the whole stream of unknown length.
+     * @param replay_channel    to which the replay should be sent.
+     * @param replay_stream_id   to which the replay should be sent.
+     * @return the id of the replay session which will be the same as the Image::session_id() of the received
+     * replay for correlation with the matching channel and stream id in the lower 32 bits.
+     */
+    pub fn start_replay(
+        &mut self,
+        recording_id: i64,
+        position: i64,
+        length: i64,
+        replay_channel: &str,
+        replay_stream_id: i32,
+    ) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self.archive_proxy.replay(
+            recording_id,
+            position,
+            length,
+            replay_channel,
+            replay_stream_id,
+            correlation_id,
+            self.control_session_id,
+        )?;
+        self.ensure_sent(is_sent, "replay")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Start a replay for a length in bytes of a recording from a position bounded by a position counter.
+     * If the position is NULL_POSITION then the stream will be replayed from the start.
+     * <p>
+     * The lower 32-bits of the returned value contains the Image::session_id() of the received replay. All
+     * 64-bits are required to uniquely identify the replay when calling stop_replay().
+     *
+     * @param recording_id      to be replayed.
+     * @param position         from which the replay should begin or NULL_POSITION if from the start.
+     * @param length           of the stream to be replayed or NULL_LENGTH to replay the whole stream.
+     * @param limit_counter_id   to use to bound replay.
+     * @param replay_channel    to which the replay should be sent.
+     * @param replay_stream_id   to which the replay should be sent.
+     * @return the id of the replay session which will be the same as the Image::session_id() of the received
+     * replay for correlation with the matching channel and stream id in the lower 32 bits.
+     */
+    pub fn start_bounded_replay(
+        &mut self,
+        recording_id: i64,
+        position: i64,
+        length: i64,
+        limit_counter_id: i32,
+        replay_channel: &str,
+        replay_stream_id: i32,
+    ) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self.archive_proxy.bounded_replay(
+            recording_id,
+            position,
+            length,
+            limit_counter_id,
+            replay_channel,
+            replay_stream_id,
+            correlation_id,
+            self.control_session_id,
+        )?;
+        self.ensure_sent(is_sent, "bounded replay")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Stop a replay session.
+     *
+     * @param replay_session_id to stop replay for.
+     */
+    pub fn stop_replay(&mut self, replay_session_id: i64) -> Result<(), AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .stop_replay(replay_session_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "stop replay")?;
+
+        self.poll_for_response(correlation_id).map(|_| ())
+    }
+
+    /**
+     * Stop all replay sessions for a given recording id or all replays in general.
+     *
+     * @param recording_id to stop replay for or NULL_VALUE for all replays.
+     */
+    pub fn stop_all_replays(&mut self, recording_id: i64) -> Result<(), AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .stop_all_replays(recording_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "stop all replays")?;
+
+        self.poll_for_response(correlation_id).map(|_| ())
+    }
+
+    /**
+     * Replay a length in bytes of a recording from a position and for convenience create a Subscription
+     * to receive the replay. If the position is NULL_POSITION then the stream will be replayed from the start.
+     * <p>
+     * The replay channel gets the session-id param of the replay added, so the returned Subscription only
+     * sees the replayed image. It is registered with the driver by the time it is returned.
+     *
+     * @param recording_id    to be replayed.
+     * @param position       from which the replay should begin or NULL_POSITION if from the start.
+     * @param length         of the stream to be replayed or NULL_LENGTH to replay the whole stream.
+     * @param replay_channel  to which the replay should be sent.
+     * @param replay_stream_id to which the replay should be sent.
+     * @return the Subscription for consuming the replay.
+     */
+    pub fn replay(
+        &mut self,
+        recording_id: i64,
+        position: i64,
+        length: i64,
+        replay_channel: &str,
+        replay_stream_id: i32,
+    ) -> Result<Arc<Mutex<Subscription>>, AeronError> {
+        let replay_session_id = self.start_replay(recording_id, position, length, replay_channel, replay_stream_id)?;
+        let replay_channel = ChannelUri::add_session_id(replay_channel, replay_session_id as i32)?;
+
+        let registration_id = self
+            .aeron
+            .lock()
+            .expect("Mutex poisoned")
+            .add_subscription(str_to_c(&replay_channel)?, replay_stream_id)?;
+
+        self.await_subscription(registration_id)
+    }
+
+    /**
+     * List all recording descriptors from a recording id with a limit of record count.
+     * <p>
+     * If the from recording id is greater than the largest known id then nothing is returned.
+     *
+     * @param from_recording_id at which to begin the listing.
+     * @param record_count     to limit for each query.
+     * @param consumer        to which the descriptors are dispatched.
+     * @return the number of descriptors found and consumed.
+     */
+    pub fn list_recordings_with(
+        &mut self,
+        from_recording_id: i64,
+        record_count: i32,
+        consumer: &mut impl RecordingDescriptorConsumer,
+    ) -> Result<i32, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent =
+            self.archive_proxy
+                .list_recordings(from_recording_id, record_count, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "list recordings")?;
+
+        self.poll_for_descriptors(correlation_id, record_count, consumer)
+    }
+
+    /**
+     * List all recording descriptors from a recording id with a limit of record count.
+     *
+     * @param from_recording_id at which to begin the listing.
+     * @param record_count     to limit for each query.
+     * @return the descriptors found.
+     */
+    pub fn list_recordings(&mut self, from_recording_id: i64, record_count: i32) -> Result<Vec<RecordingDescriptor>, AeronError> {
+        let mut descriptors = Vec::new();
+        self.list_recordings_with(from_recording_id, record_count, &mut |descriptor| {
+            descriptors.push(descriptor)
+        })?;
+
+        Ok(descriptors)
+    }
+
+    /**
+     * List recording descriptors from a recording id with a limit of record count for a given channel and stream id.
+     * <p>
+     * If the from recording id is greater than the largest known id then nothing is returned.
+     *
+     * @param from_recording_id at which to begin the listing.
+     * @param record_count     to limit for each query.
+     * @param channel_fragment for a contains match on the original channel stored with the archive descriptor.
+     * @param stream_id        to match.
+     * @param consumer        to which the descriptors are dispatched.
+     * @return the number of descriptors found and consumed.
+     */
+    pub fn list_recordings_for_uri_with(
+        &mut self,
+        from_recording_id: i64,
+        record_count: i32,
+        channel_fragment: &str,
+        stream_id: i32,
+        consumer: &mut impl RecordingDescriptorConsumer,
+    ) -> Result<i32, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self.archive_proxy.list_recordings_for_uri(
+            from_recording_id,
+            record_count,
+            channel_fragment,
+            stream_id,
+            correlation_id,
+            self.control_session_id,
+        )?;
+        self.ensure_sent(is_sent, "list recordings")?;
+
+        self.poll_for_descriptors(correlation_id, record_count, consumer)
+    }
+
+    /**
+     * List recording descriptors from a recording id with a limit of record count for a given channel and stream id.
+     *
+     * @param from_recording_id at which to begin the listing.
+     * @param record_count     to limit for each query.
+     * @param channel_fragment for a contains match on the original channel stored with the archive descriptor.
+     * @param stream_id        to match.
+     * @return the descriptors found.
+     */
+    pub fn list_recordings_for_uri(
+        &mut self,
+        from_recording_id: i64,
+        record_count: i32,
+        channel_fragment: &str,
+        stream_id: i32,
+    ) -> Result<Vec<RecordingDescriptor>, AeronError> {
+        let mut descriptors = Vec::new();
+        self.list_recordings_for_uri_with(
+            from_recording_id,
+            record_count,
+            channel_fragment,
+            stream_id,
+            &mut |descriptor| descriptors.push(descriptor),
+        )?;
+
+        Ok(descriptors)
+    }
+
+    /**
+     * List a recording descriptor for a single recording id.
+     *
+     * @param recording_id at which to begin the listing.
+     * @param consumer    to which the descriptors are dispatched.
+     * @return the number of descriptors found and consumed.
+     */
+    pub fn list_recording_with(
+        &mut self,
+        recording_id: i64,
+        consumer: &mut impl RecordingDescriptorConsumer,
+    ) -> Result<i32, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .list_recording(recording_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "list recording")?;
+
+        self.poll_for_descriptors(correlation_id, 1, consumer)
+    }
+
+    /**
+     * Get the descriptor for a single recording id.
+     *
+     * @param recording_id to be listed.
+     * @return the descriptor or None if the recording is not known to the archive.
+     */
+    pub fn list_recording(&mut self, recording_id: i64) -> Result<Option<RecordingDescriptor>, AeronError> {
+        let mut descriptor = None;
+        self.list_recording_with(recording_id, &mut |found| descriptor = Some(found))?;
+
+        Ok(descriptor)
+    }
+
+    /**
+     * Find the last recording that matches the given criteria.
+     *
+     * @param min_recording_id  to search back to.
+     * @param channel_fragment for a contains match on the original channel stored with the archive descriptor.
+     * @param stream_id        of the recording to match.
+     * @param session_id       of the recording to match.
+     * @return the recording id if found otherwise NULL_VALUE if not found.
+     */
+    pub fn find_last_matching_recording(
+        &mut self,
+        min_recording_id: i64,
+        channel_fragment: &str,
+        stream_id: i32,
+        session_id: i32,
+    ) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self.archive_proxy.find_last_matching_recording(
+            min_recording_id,
+            channel_fragment,
+            stream_id,
+            session_id,
+            correlation_id,
+            self.control_session_id,
+        )?;
+        self.ensure_sent(is_sent, "find last matching recording")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Replicate a recording from a source archive to this archive, which can be considered a backup for a primary
+     * archive. The source recording will be replayed via the provided replay channel and use the original stream id.
+     * If the destination recording id is NULL_VALUE then a new destination recording is created, otherwise
+     * the provided destination recording id will be extended. The details of the source recording descriptor
+     * will be replicated.
+     * <p>
+     * Errors will be reported asynchronously and can be checked for with poll_for_recording_signals while
+     * progress of the replication is reported via the RecordingSignalConsumer of the Context.
+     *
+     * @param src_recording_id     recording id which must exist in the source archive.
+     * @param src_control_stream_id remote control stream id for the source archive to instruct the replay on.
+     * @param src_control_channel  remote control channel for the source archive to instruct the replay on.
+     * @param params             optional parameters to configure the behaviour of the replication.
+     * @return the replication id which can be used to stop the replication.
+     */
+    pub fn replicate(
+        &mut self,
+        src_recording_id: i64,
+        src_control_stream_id: i32,
+        src_control_channel: &str,
+        params: &ReplicationParams,
+    ) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self.archive_proxy.replicate(
+            src_recording_id,
+            src_control_stream_id,
+            src_control_channel,
+            params,
+            correlation_id,
+            self.control_session_id,
+        )?;
+        self.ensure_sent(is_sent, "replicate")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Replicate a recording from a source archive to this archive with the replication subscription being
+     * tagged with the given channel and subscription tags.
+     *
+     * @param src_recording_id     recording id which must exist in the source archive.
+     * @param dst_recording_id     recording to extend in the destination, otherwise NULL_VALUE for a new recording.
+     * @param channel_tag_id       used to tag the replication subscription.
+     * @param subscription_tag_id  used to tag the replication subscription.
+     * @param src_control_stream_id remote control stream id for the source archive to instruct the replay on.
+     * @param src_control_channel  remote control channel for the source archive to instruct the replay on.
+     * @param live_destination    destination for the live stream if merge is required. Empty for no merge.
+     * @return the replication id which can be used to stop the replication.
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn tagged_replicate(
+        &mut self,
+        src_recording_id: i64,
+        dst_recording_id: i64,
+        channel_tag_id: i64,
+        subscription_tag_id: i64,
+        src_control_stream_id: i32,
+        src_control_channel: &str,
+        live_destination: &str,
+    ) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self.archive_proxy.tagged_replicate(
+            src_recording_id,
+            dst_recording_id,
+            channel_tag_id,
+            subscription_tag_id,
+            src_control_stream_id,
+            src_control_channel,
+            live_destination,
+            correlation_id,
+            self.control_session_id,
+        )?;
+        self.ensure_sent(is_sent, "tagged replicate")?;
+
+        self.poll_for_response(correlation_id)
+    }
+
+    /**
+     * Stop a replication session by id returned from replicate or tagged_replicate.
+     *
+     * @param replication_id to stop replication for.
+     */
+    pub fn stop_replication(&mut self, replication_id: i64) -> Result<(), AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.next_correlation_id()?;
+        let is_sent = self
+            .archive_proxy
+            .stop_replication(replication_id, correlation_id, self.control_session_id)?;
+        self.ensure_sent(is_sent, "stop replication")?;
+
+        self.poll_for_response(correlation_id).map(|_| ())
+    }
+
+    /**
+     * Poll for recording signals, dispatching them to the RecordingSignalConsumer of the Context.
+     * <p>
+     * Used to await the outcome of operations which complete asynchronously on the archive, e.g. replication.
+     * Errors for this control session are handed to the error handler of the Context.
+     *
+     * @return positive value if signals dispatched otherwise 0.
+     */
+    pub fn poll_for_recording_signals(&mut self) -> Result<i32, AeronError> {
+        self.ensure_open()?;
+        self.keep_alive_if_required()?;
+
+        let poller = &mut self.control_response_poller;
+        if poller.poll() != 0 && poller.is_poll_complete() && poller.control_session_id() == self.control_session_id {
+            if poller.is_code_error() {
+                let error = ArchiveError::Response {
+                    correlation_id: poller.correlation_id(),
+                    error_code: ArchiveErrorCode::from(poller.relevant_id()),
+                    message: poller.error_message().to_string(),
+                };
+                self.context.error_handler().call(error.into());
+            } else if poller.is_recording_signal() {
+                self.dispatch_recording_signal();
+            }
+
+            return Ok(1);
+        }
+
+        Ok(0)
+    }
+
+    /**
+     * Notify the archive that this control session is closed, so it can promptly release resources then close the
+     * local resources associated with the client.
+     */
+    pub fn close(mut self) {
+        self.close_session();
+    }
+
+    fn close_session(&mut self) {
+        if self.is_closed {
+            return;
+        }
+        self.is_closed = true;
+
+        let is_connected = self.archive_proxy.publication().is_connected();
+        if is_connected {
+            if let Err(err) = self.archive_proxy.close_session(self.control_session_id) {
+                log!(
+                    warn,
+                    "failed to close archive control session {}: {}",
+                    self.control_session_id,
+                    err
+                );
+            }
+        }
+
+        self.context.close();
+    }
+
+    fn ensure_open(&self) -> Result<(), AeronError> {
+        if self.is_closed {
+            return Err(ArchiveError::ClientClosed.into());
+        }
+
+        Ok(())
+    }
+
+    fn next_correlation_id(&self) -> Result<i64, AeronError> {
+        self.aeron.lock().expect("Mutex poisoned").next_correlation_id()
+    }
+
+    fn ensure_sent(&mut self, is_sent: bool, request: &'static str) -> Result<(), AeronError> {
+        if !is_sent {
+            return Err(ArchiveError::RequestNotSent(request).into());
+        }
+
+        self.time_of_last_request_ns = self.nano_clock.nano_time();
+        Ok(())
+    }
+
+    // IC: waits for the response to correlation_id and hands back its relevant_id. Errors for other requests
+    // of this session, e.g. ones sent through the archive proxy directly, go to the error handler.
+    fn poll_for_response(&mut self, correlation_id: i64) -> Result<i64, AeronError> {
+        let deadline_ns = self.nano_clock.nano_time() + self.message_timeout_ns;
+
+        loop {
+            self.poll_next_response(correlation_id, deadline_ns)?;
+
+            let poller = &self.control_response_poller;
+            if poller.control_session_id() != self.control_session_id {
+                self.invoke_aeron_client();
+                continue;
+            }
+
+            let code = poller.code();
+            if code == ControlResponseCode::ERROR {
+                let error = ArchiveError::Response {
+                    correlation_id: poller.correlation_id(),
+                    error_code: ArchiveErrorCode::from(poller.relevant_id()),
+                    message: poller.error_message().to_string(),
+                };
+
+                if poller.correlation_id() == correlation_id {
+                    return Err(error.into());
+                }
+
+                self.context.error_handler().call(error.into());
+            } else if poller.correlation_id() == correlation_id {
+                if code != ControlResponseCode::OK {
+                    return Err(ArchiveError::UnexpectedResponse { correlation_id, code }.into());
+                }
+
+                return Ok(poller.relevant_id());
+            }
+        }
+    }
+
+    fn poll_next_response(&mut self, correlation_id: i64, deadline_ns: i64) -> Result<(), AeronError> {
+        self.idle_strategy.reset();
+
+        loop {
+            let fragments = self.control_response_poller.poll();
+
+            if self.control_response_poller.is_poll_complete() {
+                if self.control_response_poller.is_recording_signal()
+                    && self.control_response_poller.control_session_id() == self.control_session_id
+                {
+                    self.dispatch_recording_signal();
+                    continue;
+                }
+
+                return Ok(());
+            }
+
+            if fragments > 0 {
+                continue;
+            }
+
+            let is_connected = self
+                .control_response_poller
+                .subscription()
+                .lock()
+                .expect("Mutex poisoned")
+                .is_connected();
+            if !is_connected {
+                return Err(ArchiveError::ResponseChannelNotConnected.into());
+            }
+
+            check_deadline(&self.nano_clock, deadline_ns, "awaiting response", correlation_id)?;
+            self.keep_alive_if_required()?;
+
+            self.idle_strategy.idle();
+            self.invoke_aeron_client();
+        }
+    }
+
+    fn poll_for_descriptors(
+        &mut self,
+        correlation_id: i64,
+        record_count: i32,
+        consumer: &mut impl RecordingDescriptorConsumer,
+    ) -> Result<i32, AeronError> {
+        let mut existing_remain_count = record_count;
+        let mut deadline_ns = self.nano_clock.nano_time() + self.message_timeout_ns;
+
+        self.recording_descriptor_poller.reset(correlation_id, record_count);
+        self.idle_strategy.reset();
+
+        loop {
+            let fragments = self.recording_descriptor_poller.poll(consumer)?;
+            let remaining_record_count = self.recording_descriptor_poller.remaining_record_count();
+
+            if self.recording_descriptor_poller.is_dispatch_complete() {
+                return Ok(record_count - remaining_record_count);
+            }
+
+            // IC: each descriptor received extends the deadline, long listings are fine as long as they progress
+            if remaining_record_count != existing_remain_count {
+                existing_remain_count = remaining_record_count;
+                deadline_ns = self.nano_clock.nano_time() + self.message_timeout_ns;
+            }
+
+            self.invoke_aeron_client();
+
+            if fragments > 0 {
+                continue;
+            }
+
+            let is_connected = self
+                .recording_descriptor_poller
+                .subscription()
+                .lock()
+                .expect("Mutex poisoned")
+                .is_connected();
+            if !is_connected {
+                return Err(ArchiveError::ResponseChannelNotConnected.into());
+            }
+
+            check_deadline(
+                &self.nano_clock,
+                deadline_ns,
+                "awaiting recording descriptors",
+                correlation_id,
+            )?;
+            self.keep_alive_if_required()?;
+            self.idle_strategy.idle();
+        }
+    }
+
+    fn await_subscription(&mut self, registration_id: i64) -> Result<Arc<Mutex<Subscription>>, AeronError> {
+        let deadline_ns = self.nano_clock.nano_time() + self.message_timeout_ns;
+        self.idle_strategy.reset();
+
+        loop {
+            let result = self.aeron.lock().expect("Mutex poisoned").find_subscription(registration_id);
+            match result {
+                Ok(subscription) => return Ok(subscription),
+                Err(AeronError::SubscriptionNotReady(_)) => {}
+                Err(err) => return Err(err),
+            }
+
+            check_deadline(&self.nano_clock, deadline_ns, "awaiting subscription", registration_id)?;
+            self.idle_strategy.idle();
+            self.invoke_aeron_client();
+        }
+    }
+
+    fn dispatch_recording_signal(&self) {
+        let poller = &self.control_response_poller;
+        self.recording_signal_consumer.on_signal(
+            poller.control_session_id(),
+            poller.correlation_id(),
+            poller.recording_id(),
+            poller.subscription_id(),
+            poller.position(),
+            poller.recording_signal(),
+        );
+    }
+
+    fn invoke_aeron_client(&self) {
+        let aeron = self.aeron.lock().expect("Mutex poisoned");
+        if aeron.uses_agent_invoker() {
+            aeron.conductor_agent_invoker().invoke();
+        }
+    }
+}
+
+impl Drop for AeronArchive {
+    fn drop(&mut self) {
+        self.close_session();
+    }
+}
+
+/**
+ * Find the base position of the segment file which contains a given position of a recording. The result can be used
+ * as the new start position when detaching or purging segments.
+ *
+ * @param start_position      of the recording.
+ * @param position           within the recording for which the segment base is required.
+ * @param term_buffer_length  of the stream, a power of 2.
+ * @param segment_file_length of the recording, a power of 2 multiple of the term buffer length.
+ * @return the position at the beginning of the segment file containing the position.
+ */
+pub fn segment_file_base_position(start_position: i64, position: i64, term_buffer_length: i32, segment_file_length: i32) -> i64 {
+    let start_term_base_position = start_position - (start_position & (term_buffer_length - 1) as i64);
+    let length_from_base_position = position - start_term_base_position;
+    let segments = length_from_base_position - (length_from_base_position & (segment_file_length - 1) as i64);
+
+    start_term_base_position + segments
+}
+
+fn str_to_c(value: &str) -> Result<CString, AeronError> {
+    CString::new(value).map_err(|_| GenericError::StringToCStringConversionFailed.into())
+}
+
+fn check_deadline(nano_clock: &NanoClock, deadline_ns: i64, action: &'static str, correlation_id: i64) -> Result<(), AeronError> {
+    if deadline_ns - nano_clock.nano_time() < 0 {
+        return Err(ArchiveError::Timeout { action, correlation_id }.into());
+    }
+
+    Ok(())
+}
+
+fn idle_and_invoke(aeron: &Arc<Mutex<Aeron>>, idle_strategy: &YieldingIdleStrategy) {
+    idle_strategy.idle();
+
+    let aeron = aeron.lock().expect("Mutex poisoned");
+    if aeron.uses_agent_invoker() {
+        aeron.conductor_agent_invoker().invoke();
+    }
+}
+
+/**
+ * The steps an AsyncConnect goes through while connecting to an archive.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AsyncConnectState {
+    CreatePublication,
+    CreateSubscription,
+    SendConnect,
+    AwaitResponse,
+    SendChallengeResponse,
+    AwaitChallengeResponse,
+    Done,
+}
+
+impl AsyncConnectState {
+    fn description(self) -> &'static str {
+        match self {
+            AsyncConnectState::CreatePublication => "creating control request publication",
+            AsyncConnectState::CreateSubscription => "creating control response subscription",
+            AsyncConnectState::SendConnect => "sending connect request",
+            AsyncConnectState::AwaitResponse => "awaiting connect response",
+            AsyncConnectState::SendChallengeResponse => "sending challenge response",
+            AsyncConnectState::AwaitChallengeResponse => "awaiting challenge response",
+            AsyncConnectState::Done => "connected",
+        }
+    }
+}
+
+/**
+ * Allows for the async establishment of an archive session. Nothing in here blocks, so it can be driven from
+ * the duty cycle of an agent.
+ * <p>
+ * Dropping an AsyncConnect before it is done releases the publication and subscription and closes the Context.
+ */
+pub struct AsyncConnect {
+    state: AsyncConnectState,
+    publication_registration_id: i64,
+    subscription_registration_id: i64,
+    correlation_id: i64,
+    control_session_id: i64,
+    encoded_credentials_from_challenge: Vec<u8>,
+    deadline_ns: i64,
+    nano_clock: NanoClock,
+    archive_proxy: Option<ArchiveProxy>,
+    control_response_poller: Option<ControlResponsePoller>,
+    context: Option<Context>,
+    aeron: Arc<Mutex<Aeron>>,
+}
+
+impl AsyncConnect {
+    fn new(mut context: Context) -> Result<Self, AeronError> {
+        if let Err(err) = context.conclude() {
+            context.close();
+            return Err(err);
+        }
+
+        let aeron = context.aeron().expect("Aeron client is set on conclude");
+
+        match Self::add_resources(&context, &aeron) {
+            Ok((subscription_registration_id, publication_registration_id, correlation_id)) => {
+                let nano_clock = NanoClock::instance();
+
+                Ok(Self {
+                    state: AsyncConnectState::CreatePublication,
+                    publication_registration_id,
+                    subscription_registration_id,
+                    correlation_id,
+                    control_session_id: NULL_VALUE,
+                    encoded_credentials_from_challenge: Vec::new(),
+                    deadline_ns: nano_clock.nano_time() + context.message_timeout_ns(),
+                    nano_clock,
+                    archive_proxy: None,
+                    control_response_poller: None,
+                    context: Some(context),
+                    aeron,
+                })
+            }
+            Err(err) => {
+                context.close();
+                Err(err)
+            }
+        }
+    }
+
+    fn add_resources(context: &Context, aeron: &Arc<Mutex<Aeron>>) -> Result<(i64, i64, i64), AeronError> {
+        let mut aeron = aeron.lock().expect("Mutex poisoned");
+
+        let subscription_registration_id = aeron.add_subscription(
+            str_to_c(&context.control_response_channel())?,
+            context.control_response_stream_id(),
+        )?;
+        let publication_registration_id = aeron.add_publication(
+            str_to_c(&context.control_request_channel())?,
+            context.control_request_stream_id(),
+        )?;
+
+        Ok((
+            subscription_registration_id,
+            publication_registration_id,
+            aeron.next_correlation_id()?,
+        ))
+    }
+
+    /**
+     * Get the current state the connect attempt is in.
+     *
+     * @return the current state the connect attempt is in.
+     */
+    pub fn state(&self) -> AsyncConnectState {
+        self.state
+    }
+
+    /**
+     * Poll for a complete connection.
+     *
+     * @return a new AeronArchive if successfully connected otherwise None.
+     */
+    pub fn poll(&mut self) -> Result<Option<AeronArchive>, AeronError> {
+        if self.state == AsyncConnectState::Done {
+            return Ok(None);
+        }
+
+        check_deadline(
+            &self.nano_clock,
+            self.deadline_ns,
+            self.state.description(),
+            self.correlation_id,
+        )?;
+
+        if self.state == AsyncConnectState::CreatePublication {
+            let result = self
+                .aeron
+                .lock()
+                .expect("Mutex poisoned")
+                .find_publication(self.publication_registration_id);
+            match result {
+                Ok(publication) => {
+                    let context = self.context.as_ref().expect("context is kept until done");
+                    self.archive_proxy = Some(ArchiveProxy::new_with_retries(
+                        publication,
+                        context.message_timeout_ns(),
+                        DEFAULT_RETRY_ATTEMPTS,
+                    ));
+                    self.state = AsyncConnectState::CreateSubscription;
+                }
+                Err(AeronError::PublicationNotReady(_)) => return Ok(None),
+                Err(err) => return Err(err),
+            }
+        }
+
+        if self.state == AsyncConnectState::CreateSubscription {
+            let result = self
+                .aeron
+                .lock()
+                .expect("Mutex poisoned")
+                .find_subscription(self.subscription_registration_id);
+            match result {
+                Ok(subscription) => {
+                    self.control_response_poller = Some(ControlResponsePoller::new(subscription));
+                    self.state = AsyncConnectState::SendConnect;
+                }
+                Err(AeronError::SubscriptionNotReady(_)) => return Ok(None),
+                Err(err) => return Err(err),
+            }
+        }
+
+        let archive_proxy = self.archive_proxy.as_mut().expect("publication is created");
+        let control_response_poller = self.control_response_poller.as_mut().expect("subscription is created");
+
+        if self.state == AsyncConnectState::SendConnect {
+            // IC: an ephemeral port (":0") in the response channel has to be resolved before telling the archive about it
+            let response_channel = control_response_poller
+                .subscription()
+                .lock()
+                .expect("Mutex poisoned")
+                .try_resolve_channel_endpoint_port();
+            let response_channel = match response_channel {
+                Some(response_channel) => response_channel,
+                None => return Ok(None),
+            };
+
+            let context = self.context.as_ref().expect("context is kept until done");
+            let encoded_credentials = context.credentials_supplier().encoded_credentials();
+            if !archive_proxy.try_connect(
+                &response_channel,
+                context.control_response_stream_id(),
+                &encoded_credentials,
+                self.correlation_id,
+            )? {
+                return Ok(None);
+            }
+
+            self.state = AsyncConnectState::AwaitResponse;
+        }
+
+        if self.state == AsyncConnectState::SendChallengeResponse {
+            if !archive_proxy.try_challenge_response(
+                &self.encoded_credentials_from_challenge,
+                self.correlation_id,
+                self.control_session_id,
+            )? {
+                return Ok(None);
+            }
+
+            self.state = AsyncConnectState::AwaitChallengeResponse;
+        }
+
+        control_response_poller.poll();
+
+        if control_response_poller.is_poll_complete() && control_response_poller.correlation_id() == self.correlation_id {
+            self.control_session_id = control_response_poller.control_session_id();
+
+            if control_response_poller.was_challenged() {
+                let context = self.context.as_ref().expect("context is kept until done");
+                self.encoded_credentials_from_challenge = context
+                    .credentials_supplier()
+                    .on_challenge(control_response_poller.encoded_challenge());
+                self.correlation_id = self.aeron.lock().expect("Mutex poisoned").next_correlation_id()?;
+                self.state = AsyncConnectState::SendChallengeResponse;
+
+                return Ok(None);
+            }
+
+            if !control_response_poller.is_control_response() {
+                return Ok(None);
+            }
+
+            let control_session_id = self.control_session_id;
+
+            if !control_response_poller.is_code_ok() {
+                let _ = archive_proxy.close_session(control_session_id);
+                return Err(ArchiveError::Response {
+                    correlation_id: self.correlation_id,
+                    error_code: ArchiveErrorCode::from(control_response_poller.relevant_id()),
+                    message: control_response_poller.error_message().to_string(),
+                }
+                .into());
+            }
+
+            self.state = AsyncConnectState::Done;
+
+            return Ok(Some(AeronArchive::new(
+                self.context.take().expect("context is kept until done"),
+                self.aeron.clone(),
+                self.archive_proxy.take().expect("publication is created"),
+                self.control_response_poller.take().expect("subscription is created"),
+                control_session_id,
+            )));
+        }
+
+        Ok(None)
+    }
+}
+
+impl Drop for AsyncConnect {
+    fn drop(&mut self) {
+        // IC: proxy and poller go before the context may release an owned Aeron client
+        self.archive_proxy = None;
+        self.control_response_poller = None;
+
+        if let Some(mut context) = self.context.take() {
+            context.close();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::archive::client::aeron_archive::segment_file_base_position;
+
+    const TERM_LENGTH: i32 = 64 * 1024;
+    const SEGMENT_LENGTH: i32 = 4 * TERM_LENGTH;
+
+    #[test]
+    fn should_align_position_to_segment_of_recording_starting_at_zero() {
+        assert_eq!(segment_file_base_position(0, 0, TERM_LENGTH, SEGMENT_LENGTH), 0);
+        assert_eq!(
+            segment_file_base_position(0, SEGMENT_LENGTH as i64 - 1, TERM_LENGTH, SEGMENT_LENGTH),
+            0
+        );
+        assert_eq!(
+            segment_file_base_position(0, 2 * SEGMENT_LENGTH as i64 + 100, TERM_LENGTH, SEGMENT_LENGTH),
+            2 * SEGMENT_LENGTH as i64
+        );
+    }
+
+    #[test]
+    fn should_align_segments_from_start_term_base_position() {
+        let start_position = TERM_LENGTH as i64 + 1024;
+        let start_term_base_position = TERM_LENGTH as i64;
+
+        assert_eq!(
+            segment_file_base_position(start_position, start_position, TERM_LENGTH, SEGMENT_LENGTH),
+            start_term_base_position
+        );
+        assert_eq!(
+            segment_file_base_position(
+                start_position,
+                start_term_base_position + SEGMENT_LENGTH as i64,
+                TERM_LENGTH,
+                SEGMENT_LENGTH
+            ),
+            start_term_base_position + SEGMENT_LENGTH as i64
+        );
+    }
+}
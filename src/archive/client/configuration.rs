@@ -1,15 +1,20 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use crate::utils::errors::AeronError;
+
 pub const PROTOCOL_MAJOR_VERSION: i32 = 1;
 pub const PROTOCOL_MINOR_VERSION: i32 = 10;
 pub const PROTOCOL_PATCH_VERSION: i32 = 0;
-// IC: Java does some bit shifting here...
-pub const PROTOCOL_SEMANTIC_VERSION: i32 = 0;
-// SemanticVersion::compose(
-// Self::PROTOCOL_MAJOR_VERSION,
-// Self::PROTOCOL_MINOR_VERSION,
-// Self::PROTOCOL_PATCH_VERSION,
-// );
+// IC: Java uses SemanticVersion::compose(major, minor, patch)
+pub const PROTOCOL_SEMANTIC_VERSION: i32 =
+    (PROTOCOL_MAJOR_VERSION << 16) | (PROTOCOL_MINOR_VERSION << 8) | PROTOCOL_PATCH_VERSION;
 pub const MESSAGE_TIMEOUT_PROP_NAME: &str = "aeron.archive.message.timeout";
 pub const MESSAGE_TIMEOUT_DEFAULT_NS: i64 = 10_000_000_000;
+pub const KEEP_ALIVE_INTERVAL_PROP_NAME: &str = "aeron.archive.keep.alive.interval";
+pub const KEEP_ALIVE_INTERVAL_DEFAULT_NS: i64 = 1_000_000_000;
 pub const CONTROL_CHANNEL_PROP_NAME: &str = "aeron.archive.control.channel";
 pub const CONTROL_CHANNEL_DEFAULT: &str = "aeron:udp?endpoint=localhost:8010";
 pub const CONTROL_STREAM_ID_PROP_NAME: &str = "aeron.archive.control.stream.id";
@@ -34,6 +39,299 @@ pub const CONTROL_TERM_BUFFER_LENGTH_PROP_NAME: &str = "aeron.archive.control.te
 pub const CONTROL_TERM_BUFFER_LENGTH_DEFAULT: i32 = 64 * 1024;
 pub const CONTROL_MTU_LENGTH_PROP_NAME: &str = "aeron.archive.control.mtu.length";
 pub const CONTROL_MTU_LENGTH_DEFAULT: i32 = 1408; // IC: Java uses aeron.driver.Configuration.MTU_LENGTH_DEFAULT
+pub const AERON_DIR_PROP_NAME: &str = "aeron.dir"; // IC: Java reads it through CommonContext
+
+/**
+ * Configuration properties for the Aeron Archive client, keyed by the *_PROP_NAME constants.
+ * <p>
+ * Properties can be read from a Java style .properties file, so the same deployment files as for Java clients
+ * can be used, and from environment variables named after the property in upper case with '.' replaced by '_',
+ * e.g. AERON_ARCHIVE_CONTROL_CHANNEL for aeron.archive.control.channel.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct Configuration {
+    properties: HashMap<String, String>,
+}
+
+impl Configuration {
+    /**
+     * Read the properties which are set as environment variables.
+     *
+     * @return the properties set as environment variables.
+     */
+    pub fn from_env() -> Self {
+        let properties = PROP_NAMES
+            .iter()
+            .filter_map(|name| env::var(env_var_name(name)).ok().map(|value| (name.to_string(), value)))
+            .collect();
+
+        Self { properties }
+    }
+
+    /**
+     * Read the properties from a Java style .properties file.
+     *
+     * @param path of the properties file.
+     * @return the properties in the file.
+     */
+    pub fn from_properties_file(path: impl AsRef<Path>) -> Result<Self, AeronError> {
+        let content = fs::read_to_string(path).map_err(AeronError::PropertiesFileError)?;
+
+        Ok(Self::from_properties(&content))
+    }
+
+    /**
+     * Parse properties in the format of a Java .properties file.
+     *
+     * @param content of a properties file.
+     * @return the parsed properties.
+     */
+    pub fn from_properties(content: &str) -> Self {
+        Self {
+            properties: parse_properties(content),
+        }
+    }
+
+    /**
+     * Add the properties of another Configuration, replacing values for names which are already present.
+     *
+     * @param other properties which take precedence.
+     * @return reference to this Configuration instance
+     */
+    pub fn merge(&mut self, other: Configuration) -> &Self {
+        self.properties.extend(other.properties);
+        self
+    }
+
+    /**
+     * Get the raw value of a property.
+     *
+     * @param name of the property.
+     * @return the value of the property if set.
+     */
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.properties.get(name).map(String::as_str)
+    }
+}
+
+// IC: properties which are read from the environment
+const PROP_NAMES: [&str; 12] = [
+    MESSAGE_TIMEOUT_PROP_NAME,
+    KEEP_ALIVE_INTERVAL_PROP_NAME,
+    CONTROL_CHANNEL_PROP_NAME,
+    CONTROL_STREAM_ID_PROP_NAME,
+    CONTROL_RESPONSE_CHANNEL_PROP_NAME,
+    CONTROL_RESPONSE_STREAM_ID_PROP_NAME,
+    RECORDING_EVENTS_CHANNEL_PROP_NAME,
+    RECORDING_EVENTS_STREAM_ID_PROP_NAME,
+    CONTROL_TERM_BUFFER_SPARSE_PROP_NAME,
+    CONTROL_TERM_BUFFER_LENGTH_PROP_NAME,
+    CONTROL_MTU_LENGTH_PROP_NAME,
+    AERON_DIR_PROP_NAME,
+];
+
+/**
+ * Name of the environment variable for a property, e.g. AERON_ARCHIVE_CONTROL_CHANNEL for aeron.archive.control.channel.
+ *
+ * @param prop_name of the property.
+ * @return name of the environment variable.
+ */
+pub fn env_var_name(prop_name: &str) -> String {
+    prop_name.replace('.', "_").to_uppercase()
+}
+
+/**
+ * Parse a duration in nanoseconds with an optional unit suffix of s, ms, us or ns. Without suffix the value is
+ * in nanoseconds, as for Java's SystemUtil.parseDuration.
+ *
+ * @param value to parse.
+ * @return the duration in nanoseconds or None if the value is not a valid duration.
+ */
+pub fn parse_duration_ns(value: &str) -> Option<i64> {
+    let value = value.trim().to_lowercase();
+    let (number, multiplier) = if let Some(number) = value.strip_suffix("ms") {
+        (number, 1_000_000)
+    } else if let Some(number) = value.strip_suffix("us") {
+        (number, 1_000)
+    } else if let Some(number) = value.strip_suffix("ns") {
+        (number, 1)
+    } else if let Some(number) = value.strip_suffix('s') {
+        (number, 1_000_000_000)
+    } else {
+        (value.as_str(), 1)
+    };
+
+    number.trim().parse::<i64>().ok()?.checked_mul(multiplier)
+}
+
+/**
+ * Parse a size in bytes with an optional unit suffix of k, m or g, as for Java's SystemUtil.parseSize.
+ *
+ * @param value to parse.
+ * @return the size in bytes or None if the value is not a valid size.
+ */
+pub fn parse_size(value: &str) -> Option<i64> {
+    let value = value.trim().to_lowercase();
+    let (number, multiplier) = match value.chars().last() {
+        Some('k') => (&value[..value.len() - 1], 1024),
+        Some('m') => (&value[..value.len() - 1], 1024 * 1024),
+        Some('g') => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value.as_str(), 1),
+    };
+
+    number.trim().parse::<i64>().ok()?.checked_mul(multiplier)
+}
+
+/**
+ * Parse a boolean of true or false ignoring case.
+ *
+ * @param value to parse.
+ * @return the boolean or None if the value is neither true nor false.
+ */
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/**
+ * Parse properties in the format of a Java .properties file: comment lines start with '#' or '!', keys are
+ * separated from values by '=', ':' or whitespace, a trailing '\' continues a line and the usual escapes
+ * including \uXXXX are supported.
+ *
+ * @param content of a properties file.
+ * @return the properties by name.
+ */
+pub fn parse_properties(content: &str) -> HashMap<String, String> {
+    let mut properties = HashMap::new();
+    let mut lines = content.lines();
+
+    while let Some(line) = lines.next() {
+        let mut logical_line = line.trim_start().to_string();
+        if logical_line.is_empty() || logical_line.starts_with('#') || logical_line.starts_with('!') {
+            continue;
+        }
+
+        while ends_with_continuation(&logical_line) {
+            logical_line.pop();
+            match lines.next() {
+                Some(next) => logical_line.push_str(next.trim_start()),
+                None => break,
+            }
+        }
+
+        let (key, value) = split_key_value(&logical_line);
+        properties.insert(unescape(key), unescape(value));
+    }
+
+    properties
+}
+
+// IC: an odd number of trailing backslashes means the last one escapes the line end
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|c| *c == '\\').count() % 2 == 1
+}
+
+fn split_key_value(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    let mut key_end = line.len();
+
+    for (index, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '=' || c == ':' || c.is_whitespace() {
+            key_end = index;
+            break;
+        }
+    }
+
+    let key = &line[..key_end];
+    let mut rest = line[key_end..].trim_start();
+    if rest.starts_with('=') || rest.starts_with(':') {
+        rest = rest[1..].trim_start();
+    }
+
+    (key, rest)
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('t') => result.push('\t'),
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some('f') => result.push('\u{c}'),
+            Some('u') => {
+                let code: String = chars.by_ref().take(4).collect();
+                if let Some(c) = u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                    result.push(c);
+                }
+            }
+            Some(other) => result.push(other),
+            None => {}
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use crate::archive::client::configuration::*;
+
+    #[test]
+    fn should_parse_java_properties() {
+        let properties = parse_properties(
+            "# deployment config\n\
+             ! also a comment\n\
+             aeron.archive.control.channel = aeron:udp?endpoint=archive:8010\n\
+             aeron.archive.control.stream.id: 100\n\
+             aeron.archive.message.timeout 5s\n\
+             aeron.archive.control.response.channel=aeron:udp?\\\n    endpoint=localhost:0\n\
+             aeron.dir=C\\:\\\\aeron\\u0021\n",
+        );
+
+        assert_eq!(properties.len(), 5);
+        assert_eq!(properties[CONTROL_CHANNEL_PROP_NAME], "aeron:udp?endpoint=archive:8010");
+        assert_eq!(properties[CONTROL_STREAM_ID_PROP_NAME], "100");
+        assert_eq!(properties[MESSAGE_TIMEOUT_PROP_NAME], "5s");
+        assert_eq!(
+            properties[CONTROL_RESPONSE_CHANNEL_PROP_NAME],
+            "aeron:udp?endpoint=localhost:0"
+        );
+        assert_eq!(properties[AERON_DIR_PROP_NAME], "C:\\aeron!");
+    }
+
+    #[test]
+    fn should_parse_durations_and_sizes() {
+        assert_eq!(parse_duration_ns("100"), Some(100));
+        assert_eq!(parse_duration_ns("10s"), Some(10_000_000_000));
+        assert_eq!(parse_duration_ns("250ms"), Some(250_000_000));
+        assert_eq!(parse_duration_ns("7us"), Some(7_000));
+        assert_eq!(parse_duration_ns("7NS"), Some(7));
+        assert_eq!(parse_duration_ns("ten seconds"), None);
+
+        assert_eq!(parse_size("1408"), Some(1408));
+        assert_eq!(parse_size("64k"), Some(64 * 1024));
+        assert_eq!(parse_size("16M"), Some(16 * 1024 * 1024));
+        assert_eq!(parse_size("1g"), Some(1024 * 1024 * 1024));
+        assert_eq!(parse_size("lots"), None);
+    }
 
-// default configuration for Aeron Archive
-pub struct Configuration {}
\ No newline at end of file
+    #[test]
+    fn should_name_env_var_after_property() {
+        assert_eq!(env_var_name(CONTROL_CHANNEL_PROP_NAME), "AERON_ARCHIVE_CONTROL_CHANNEL");
+    }
+}
@@ -1,13 +1,19 @@
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::path::Path;
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::Ordering;
-use std::time::Duration;
+
 use crate::aeron::Aeron;
-use crate::concurrent::logbuffer::term_reader::ErrorHandler;
-use crate::concurrent::strategies::YieldingIdleStrategy;
+use crate::archive::archive_mark_file::ArchiveMarkFile;
 use crate::archive::client::configuration::*;
-use crate::channel_uri::ChannelUri;
-use crate::utils::errors::AeronError;
+use crate::archive::client::credentials_supplier::{CredentialsSupplier, NullCredentialsSupplier};
+use crate::archive::client::recording_signal_consumer::{no_op_recording_signal_consumer, RecordingSignalConsumer};
 use crate::channel_uri::*;
+use crate::concurrent::logbuffer::frame_descriptor::FRAME_ALIGNMENT;
+use crate::concurrent::logbuffer::log_buffer_descriptor::check_term_length;
+use crate::concurrent::logbuffer::term_reader::ErrorHandler;
+use crate::concurrent::strategies::YieldingIdleStrategy;
+use crate::utils::errors::{AeronError, IllegalArgumentError};
 
 // IC: Context is not Cloneable because it may contain an Aeron instance.
 // I don't know why Java allows it, Aeron doesn't have clone in Java. Unless it's just a shallow clone. <- bookmark.
@@ -16,6 +22,7 @@ pub struct Context {
     // IC: No guard - see below
     is_concluded: bool,
     message_timeout_ns: i64,
+    keep_alive_interval_ns: i64,
     recording_events_channel: String,
     recording_events_stream_id: i32,
     control_request_channel: String,
@@ -27,21 +34,73 @@ pub struct Context {
     control_mtu_length: i32,
     idle_strategy: YieldingIdleStrategy, // IC: Java uses idle strategy interface, contains idle(int workCount) method
     aeron_directory_name: String,
-    // IC: does not make sense to make Aeron cloneable. A new Aeron instance should only be created by connecting to the Media Driver.
-    aeron: Option<Aeron>,
-    error_handler: Box<dyn ErrorHandler>,
+    // IC: Aeron is shared behind Arc<Mutex<>> so the archive client can use an existing Aeron client instance.
+    aeron: Option<Arc<Mutex<Aeron>>>,
+    error_handler: Box<dyn ErrorHandler + Send>,
+    recording_signal_consumer: Box<dyn RecordingSignalConsumer + Send>,
+    credentials_supplier: Box<dyn CredentialsSupplier + Send>,
+    // IC: values from the configuration which could not be parsed, reported on conclude unless a setter replaced them
+    invalid_properties: BTreeMap<&'static str, String>,
     // IC: commenting out below for now
-    // credentials_supplier: Box<dyn CredentialsSupplier>,
-    // recording_signal_consumer: Box<dyn RecordingSignalConsumer>,
     // agent_invoker: AgentInvoker, // only 1 writer, the setter, and latter has no usages
     owns_aeron_client: bool,
 }
 
+impl Default for Context {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Context {
+    /**
+     * Create a context with the default values, ignoring any properties set as environment variables.
+     */
     pub fn new() -> Self {
+        Self::with_defaults()
+    }
+
+    /**
+     * Create a context with the defaults overridden by any properties set as environment variables.
+     *
+     * @return the new Context.
+     */
+    pub fn from_env() -> Self {
+        Self::with_configuration(&Configuration::from_env())
+    }
+
+    /**
+     * Create a context from a Java style .properties file. Properties set as environment variables take
+     * precedence over the file and values given to setters take precedence over both.
+     *
+     * @param path of the properties file.
+     * @return the new Context.
+     */
+    pub fn from_properties_file(path: impl AsRef<Path>) -> Result<Self, AeronError> {
+        let mut configuration = Configuration::from_properties_file(path)?;
+        configuration.merge(Configuration::from_env());
+
+        Ok(Self::with_configuration(&configuration))
+    }
+
+    /**
+     * Create a context with the defaults overridden by the given configuration properties. Values which can't be
+     * parsed are reported by conclude unless replaced by a setter.
+     *
+     * @param configuration properties to apply.
+     * @return the new Context.
+     */
+    pub fn with_configuration(configuration: &Configuration) -> Self {
+        let mut context = Self::with_defaults();
+        context.apply_configuration(configuration);
+        context
+    }
+
+    fn with_defaults() -> Self {
         Self {
             is_concluded: false,
             message_timeout_ns: MESSAGE_TIMEOUT_DEFAULT_NS,
+            keep_alive_interval_ns: KEEP_ALIVE_INTERVAL_DEFAULT_NS,
             recording_events_channel: RECORDING_EVENTS_CHANNEL_DEFAULT.to_string(),
             recording_events_stream_id: RECORDING_EVENTS_STREAM_ID_DEFAULT,
             control_request_channel: CONTROL_CHANNEL_DEFAULT.to_string(),
@@ -51,64 +110,537 @@ impl Context {
             control_term_buffer_sparse: CONTROL_TERM_BUFFER_SPARSE_DEFAULT,
             control_term_buffer_length: CONTROL_TERM_BUFFER_LENGTH_DEFAULT,
             control_mtu_length: CONTROL_MTU_LENGTH_DEFAULT,
-            idle_strategy: YieldingIdleStrategy {},
+            idle_strategy: YieldingIdleStrategy::default(),
             aeron_directory_name: crate::context::Context::default_aeron_path(),
             aeron: None,
             error_handler: Box::new(crate::context::default_error_handler),
-            owns_aeron_client: false
+            recording_signal_consumer: Box::new(no_op_recording_signal_consumer),
+            credentials_supplier: Box::new(NullCredentialsSupplier::default()),
+            invalid_properties: BTreeMap::new(),
+            owns_aeron_client: false,
+        }
+    }
+
+    fn apply_configuration(&mut self, configuration: &Configuration) {
+        if let Some(value) = self.read(configuration, MESSAGE_TIMEOUT_PROP_NAME, parse_duration_ns) {
+            self.message_timeout_ns = value;
         }
+        if let Some(value) = self.read(configuration, KEEP_ALIVE_INTERVAL_PROP_NAME, parse_duration_ns) {
+            self.keep_alive_interval_ns = value;
+        }
+        if let Some(value) = configuration.get(RECORDING_EVENTS_CHANNEL_PROP_NAME) {
+            self.recording_events_channel = value.to_string();
+        }
+        if let Some(value) = self.read(configuration, RECORDING_EVENTS_STREAM_ID_PROP_NAME, parse_i32) {
+            self.recording_events_stream_id = value;
+        }
+        if let Some(value) = configuration.get(CONTROL_CHANNEL_PROP_NAME) {
+            self.control_request_channel = value.to_string();
+        }
+        if let Some(value) = self.read(configuration, CONTROL_STREAM_ID_PROP_NAME, parse_i32) {
+            self.control_request_stream_id = value;
+        }
+        if let Some(value) = configuration.get(CONTROL_RESPONSE_CHANNEL_PROP_NAME) {
+            self.control_response_channel = value.to_string();
+        }
+        if let Some(value) = self.read(configuration, CONTROL_RESPONSE_STREAM_ID_PROP_NAME, parse_i32) {
+            self.control_response_stream_id = value;
+        }
+        if let Some(value) = self.read(configuration, CONTROL_TERM_BUFFER_SPARSE_PROP_NAME, parse_bool) {
+            self.control_term_buffer_sparse = value;
+        }
+        if let Some(value) = self.read(configuration, CONTROL_TERM_BUFFER_LENGTH_PROP_NAME, parse_size_i32) {
+            self.control_term_buffer_length = value;
+        }
+        if let Some(value) = self.read(configuration, CONTROL_MTU_LENGTH_PROP_NAME, parse_size_i32) {
+            self.control_mtu_length = value;
+        }
+        if let Some(value) = configuration.get(AERON_DIR_PROP_NAME) {
+            self.aeron_directory_name = value.to_string();
+        }
+    }
+
+    fn read<T>(&mut self, configuration: &Configuration, name: &'static str, parse: fn(&str) -> Option<T>) -> Option<T> {
+        let value = configuration.get(name)?;
+        let parsed = parse(value);
+        if parsed.is_none() {
+            self.invalid_properties.insert(name, value.to_string());
+        }
+
+        parsed
+    }
+
+    fn validate(&self) -> Result<(), AeronError> {
+        if let Some((name, value)) = self.invalid_properties.iter().next() {
+            return Err(IllegalArgumentError::InvalidPropertyValue {
+                name,
+                value: value.clone(),
+            }
+            .into());
+        }
+
+        if self.message_timeout_ns <= 0 {
+            return Err(out_of_range(
+                MESSAGE_TIMEOUT_PROP_NAME,
+                self.message_timeout_ns,
+                "a positive duration",
+            ));
+        }
+
+        if self.keep_alive_interval_ns <= 0 {
+            return Err(out_of_range(
+                KEEP_ALIVE_INTERVAL_PROP_NAME,
+                self.keep_alive_interval_ns,
+                "a positive duration",
+            ));
+        }
+
+        if check_term_length(self.control_term_buffer_length).is_err() {
+            return Err(out_of_range(
+                CONTROL_TERM_BUFFER_LENGTH_PROP_NAME,
+                self.control_term_buffer_length,
+                "a power of 2 between 64k and 1g",
+            ));
+        }
+
+        if !(32..=65504).contains(&self.control_mtu_length) || self.control_mtu_length % FRAME_ALIGNMENT != 0 {
+            return Err(out_of_range(
+                CONTROL_MTU_LENGTH_PROP_NAME,
+                self.control_mtu_length,
+                "a multiple of 32 between 32 and 65504",
+            ));
+        }
+
+        Ok(())
     }
 
     // concludes the configuration
-    pub fn conclude(&mut self) -> Result<(), AeronError>{
+    #[allow(clippy::arc_with_non_send_sync)]
+    pub fn conclude(&mut self) -> Result<(), AeronError> {
         // IC: Java explicits makes this atomic in case multiple threads call it on the same context instance
-        // But we would literally never do it in rust. Gonna comment out the whole thing.
-        // if 0 != self.is_concluded.fetch_add(1, Ordering::SeqCst) {
-        //     panic!("ConcurrentConcludeException");
-        // }
+        // But we would literally never do it in rust. A second conclude is a no-op.
+        if self.is_concluded {
+            return Ok(());
+        }
 
+        self.validate()?;
 
         // IC: archive client can 'own' an aeron client, or use an existing aeron client instance
         if self.aeron.is_none() {
-            let aeron = Aeron::new(crate::context::Context::new())?;
-            self.aeron = Some(aeron);
+            let mut aeron_context = crate::context::Context::new();
+            aeron_context.set_aeron_dir(self.aeron_directory_name.clone());
+            let error_handler = self.error_handler.clone();
+            aeron_context.set_error_handler(move |error| error_handler.call(error));
+
+            self.aeron = Some(Arc::new(Mutex::new(Aeron::new(aeron_context)?)));
             // when closing the archive client, also close aeron client if former owns latter
             self.owns_aeron_client = true;
         }
 
-        // IC: No credentials support
-        // if self.credentials_supplier.is_none() {
-        //     self.credentials_supplier = Some(NullCredentialsSupplier::new());
-        // }
-
-        self.control_request_channel = self.apply_default_params(&mut self.control_request_channel.clone())?;
-        self.control_response_channel = self.apply_default_params(&mut self.control_response_channel.clone())?;
+        self.control_request_channel = self.apply_default_params(&self.control_request_channel)?;
+        self.control_response_channel = self.apply_default_params(&self.control_response_channel)?;
+        self.is_concluded = true;
 
         Ok(())
     }
 
-    pub fn apply_default_params(&mut self, channel: &mut String) -> Result<String, AeronError> {
+    pub fn apply_default_params(&self, channel: &str) -> Result<String, AeronError> {
         // IC: ChannelUri unnecessarily wraps Arc and Mutex, so you have to remove them
         let a = ChannelUri::parse(channel)?;
         let lock = Arc::try_unwrap(a).expect("Lock still has multiple owners");
         let mut channel_uri = lock.into_inner().expect("Mutex cannot be locked");
         if !channel_uri.contains_key(TERM_LENGTH_PARAM_NAME) {
             // IC: Java has 'insert' instead of 'put'
-            channel_uri.put(TERM_LENGTH_PARAM_NAME.to_string().as_str(), self.control_term_buffer_length.to_string());
+            channel_uri.put(TERM_LENGTH_PARAM_NAME, self.control_term_buffer_length.to_string());
         }
 
         if !channel_uri.contains_key(MTU_LENGTH_PARAM_NAME) {
-            channel_uri.put(MTU_LENGTH_PARAM_NAME.to_string().as_str(), self.control_mtu_length.to_string());
+            channel_uri.put(MTU_LENGTH_PARAM_NAME, self.control_mtu_length.to_string());
         }
 
         if !channel_uri.contains_key(SPARSE_PARAM_NAME) {
-            channel_uri.put(SPARSE_PARAM_NAME.to_string().as_str(), self.control_term_buffer_sparse.to_string());
+            channel_uri.put(SPARSE_PARAM_NAME, self.control_term_buffer_sparse.to_string());
         }
         Ok(channel_uri.to_string())
     }
+
+    /**
+     * Close the context and free applicable resources.
+     * <p>
+     * If the aeron client was created by this context then it is closed as well.
+     */
+    pub fn close(&mut self) {
+        if self.owns_aeron_client {
+            // IC: dropping the last reference to Aeron stops the client conductor
+            self.aeron = None;
+        }
+    }
+
+    pub fn message_timeout_ns(&self) -> i64 {
+        self.message_timeout_ns
+    }
+
+    /**
+     * Set the message timeout in nanoseconds to wait for sending or receiving a message.
+     *
+     * @param message_timeout_ns to wait for sending or receiving a message.
+     * @return reference to this Context instance
+     */
+    pub fn set_message_timeout_ns(&mut self, message_timeout_ns: i64) -> &Self {
+        self.message_timeout_ns = message_timeout_ns;
+        self.invalid_properties.remove(MESSAGE_TIMEOUT_PROP_NAME);
+        self
+    }
+
+    pub fn keep_alive_interval_ns(&self) -> i64 {
+        self.keep_alive_interval_ns
+    }
+
+    /**
+     * Set the interval in nanoseconds after which the client sends a keep-alive to the archive
+     * if no other request has been sent in between.
+     *
+     * @param keep_alive_interval_ns between keep-alive messages.
+     * @return reference to this Context instance
+     */
+    pub fn set_keep_alive_interval_ns(&mut self, keep_alive_interval_ns: i64) -> &Self {
+        self.keep_alive_interval_ns = keep_alive_interval_ns;
+        self.invalid_properties.remove(KEEP_ALIVE_INTERVAL_PROP_NAME);
+        self
+    }
+
+    pub fn recording_events_channel(&self) -> String {
+        self.recording_events_channel.clone()
+    }
+
+    pub fn set_recording_events_channel(&mut self, recording_events_channel: String) -> &Self {
+        self.recording_events_channel = recording_events_channel;
+        self
+    }
+
+    pub fn recording_events_stream_id(&self) -> i32 {
+        self.recording_events_stream_id
+    }
+
+    pub fn set_recording_events_stream_id(&mut self, recording_events_stream_id: i32) -> &Self {
+        self.recording_events_stream_id = recording_events_stream_id;
+        self.invalid_properties.remove(RECORDING_EVENTS_STREAM_ID_PROP_NAME);
+        self
+    }
+
+    pub fn control_request_channel(&self) -> String {
+        self.control_request_channel.clone()
+    }
+
+    /**
+     * Set the channel URI on which the control requests are sent to the archive.
+     *
+     * @param control_request_channel channel URI for sending control requests.
+     * @return reference to this Context instance
+     */
+    pub fn set_control_request_channel(&mut self, control_request_channel: String) -> &Self {
+        self.control_request_channel = control_request_channel;
+        self
+    }
+
+    /**
+     * Send control requests to the channel and stream id a running archive published in its mark file,
+     * instead of configuring them explicitly.
+     *
+     * @param mark_file of the archive to connect to.
+     * @return reference to this Context instance
+     */
+    pub fn set_control_request_from_mark_file(&mut self, mark_file: &ArchiveMarkFile) -> &Self {
+        self.set_control_request_channel(mark_file.control_channel().to_string());
+        self.set_control_request_stream_id(mark_file.control_stream_id());
+        self
+    }
+
+    pub fn control_request_stream_id(&self) -> i32 {
+        self.control_request_stream_id
+    }
+
+    pub fn set_control_request_stream_id(&mut self, control_request_stream_id: i32) -> &Self {
+        self.control_request_stream_id = control_request_stream_id;
+        self.invalid_properties.remove(CONTROL_STREAM_ID_PROP_NAME);
+        self
+    }
+
+    pub fn control_response_channel(&self) -> String {
+        self.control_response_channel.clone()
+    }
+
+    /**
+     * Set the channel URI on which the control responses are received from the archive.
+     *
+     * @param control_response_channel channel URI for receiving control responses.
+     * @return reference to this Context instance
+     */
+    pub fn set_control_response_channel(&mut self, control_response_channel: String) -> &Self {
+        self.control_response_channel = control_response_channel;
+        self
+    }
+
+    pub fn control_response_stream_id(&self) -> i32 {
+        self.control_response_stream_id
+    }
+
+    pub fn set_control_response_stream_id(&mut self, control_response_stream_id: i32) -> &Self {
+        self.control_response_stream_id = control_response_stream_id;
+        self.invalid_properties.remove(CONTROL_RESPONSE_STREAM_ID_PROP_NAME);
+        self
+    }
+
+    pub fn control_term_buffer_sparse(&self) -> bool {
+        self.control_term_buffer_sparse
+    }
+
+    pub fn set_control_term_buffer_sparse(&mut self, control_term_buffer_sparse: bool) -> &Self {
+        self.control_term_buffer_sparse = control_term_buffer_sparse;
+        self.invalid_properties.remove(CONTROL_TERM_BUFFER_SPARSE_PROP_NAME);
+        self
+    }
+
+    pub fn control_term_buffer_length(&self) -> i32 {
+        self.control_term_buffer_length
+    }
+
+    pub fn set_control_term_buffer_length(&mut self, control_term_buffer_length: i32) -> &Self {
+        self.control_term_buffer_length = control_term_buffer_length;
+        self.invalid_properties.remove(CONTROL_TERM_BUFFER_LENGTH_PROP_NAME);
+        self
+    }
+
+    pub fn control_mtu_length(&self) -> i32 {
+        self.control_mtu_length
+    }
+
+    pub fn set_control_mtu_length(&mut self, control_mtu_length: i32) -> &Self {
+        self.control_mtu_length = control_mtu_length;
+        self.invalid_properties.remove(CONTROL_MTU_LENGTH_PROP_NAME);
+        self
+    }
+
+    pub fn idle_strategy(&self) -> YieldingIdleStrategy {
+        self.idle_strategy
+    }
+
+    pub fn aeron_directory_name(&self) -> String {
+        self.aeron_directory_name.clone()
+    }
+
+    /**
+     * Set the top level Aeron directory used for communication between the Aeron client created by
+     * this context and the Media Driver. Has no effect if an Aeron client is supplied.
+     *
+     * @param aeron_directory_name the top level Aeron directory.
+     * @return reference to this Context instance
+     */
+    pub fn set_aeron_directory_name(&mut self, aeron_directory_name: String) -> &Self {
+        self.aeron_directory_name = aeron_directory_name;
+        self
+    }
+
+    pub fn aeron(&self) -> Option<Arc<Mutex<Aeron>>> {
+        self.aeron.clone()
+    }
+
+    /**
+     * Use an existing Aeron client instead of creating one on conclude. The archive client will then not
+     * close the Aeron client unless ownership is given with set_owns_aeron_client.
+     *
+     * @param aeron client to use.
+     * @return reference to this Context instance
+     */
+    pub fn set_aeron(&mut self, aeron: Arc<Mutex<Aeron>>) -> &Self {
+        self.aeron = Some(aeron);
+        self
+    }
+
+    pub fn owns_aeron_client(&self) -> bool {
+        self.owns_aeron_client
+    }
+
+    /**
+     * Does this context own the Aeron client and thus takes responsibility for closing it?
+     *
+     * @param owns_aeron_client does this context own the Aeron client.
+     * @return reference to this Context instance
+     */
+    pub fn set_owns_aeron_client(&mut self, owns_aeron_client: bool) -> &Self {
+        self.owns_aeron_client = owns_aeron_client;
+        self
+    }
+
+    pub fn error_handler(&self) -> Box<dyn ErrorHandler + Send> {
+        self.error_handler.clone()
+    }
+
+    /**
+     * Set the handler for errors which happen outside of a request/response, e.g. on close.
+     *
+     * @param handler called when errors arise.
+     * @return reference to this Context instance
+     */
+    pub fn set_error_handler(&mut self, handler: impl ErrorHandler + Send + 'static) -> &Self {
+        self.error_handler = Box::new(handler);
+        self
+    }
+
+    pub fn recording_signal_consumer(&self) -> Box<dyn RecordingSignalConsumer + Send> {
+        self.recording_signal_consumer.clone()
+    }
+
+    /**
+     * Set the consumer of recording signals which arrive on the control response channel, e.g. to await
+     * replication or segment operations.
+     *
+     * @param consumer called with each RecordingSignal for this control session.
+     * @return reference to this Context instance
+     */
+    pub fn set_recording_signal_consumer(&mut self, consumer: impl RecordingSignalConsumer + Send + 'static) -> &Self {
+        self.recording_signal_consumer = Box::new(consumer);
+        self
+    }
+
+    pub fn credentials_supplier(&self) -> &(dyn CredentialsSupplier + Send) {
+        self.credentials_supplier.as_ref()
+    }
+
+    /**
+     * Set the CredentialsSupplier to be used for authentication with the archive.
+     *
+     * @param credentials_supplier to be used for authentication with the archive.
+     * @return reference to this Context instance
+     */
+    pub fn set_credentials_supplier(&mut self, credentials_supplier: impl CredentialsSupplier + Send + 'static) -> &Self {
+        self.credentials_supplier = Box::new(credentials_supplier);
+        self
+    }
+}
+
+fn parse_i32(value: &str) -> Option<i32> {
+    value.trim().parse().ok()
+}
+
+fn parse_size_i32(value: &str) -> Option<i32> {
+    parse_size(value).and_then(|size| i32::try_from(size).ok())
+}
+
+fn out_of_range(name: &'static str, value: impl ToString, expected: &'static str) -> AeronError {
+    IllegalArgumentError::PropertyOutOfRange {
+        name,
+        value: value.to_string(),
+        expected,
+    }
+    .into()
 }
 
 #[cfg(test)]
 mod test {
+    use crate::archive::client::configuration::*;
+    use crate::archive::client::context::Context;
+    use crate::utils::errors::{AeronError, IllegalArgumentError};
 
-}
\ No newline at end of file
+    #[test]
+    fn should_apply_default_control_params() {
+        let context = Context::new();
+        let channel = context.apply_default_params("aeron:udp?endpoint=localhost:8010").unwrap();
+
+        assert!(channel.contains("term-length=65536"));
+        assert!(channel.contains("mtu=1408"));
+        assert!(channel.contains("sparse=true"));
+    }
+
+    #[test]
+    fn should_keep_explicit_control_params() {
+        let context = Context::new();
+        let channel = context
+            .apply_default_params("aeron:udp?endpoint=localhost:8010|term-length=131072|mtu=8192")
+            .unwrap();
+
+        assert!(channel.contains("term-length=131072"));
+        assert!(channel.contains("mtu=8192"));
+        assert!(channel.contains("sparse=true"));
+    }
+
+    #[test]
+    fn should_apply_configuration() {
+        let configuration = Configuration::from_properties(
+            "aeron.archive.control.channel=aeron:udp?endpoint=archive:8010\n\
+             aeron.archive.control.stream.id=100\n\
+             aeron.archive.message.timeout=5s\n\
+             aeron.archive.control.term.buffer.length=128k\n\
+             aeron.archive.control.term.buffer.sparse=false\n\
+             aeron.archive.recording.events.stream.id=31\n",
+        );
+        let context = Context::with_configuration(&configuration);
+
+        assert_eq!(context.control_request_channel(), "aeron:udp?endpoint=archive:8010");
+        assert_eq!(context.control_request_stream_id(), 100);
+        assert_eq!(context.message_timeout_ns(), 5_000_000_000);
+        assert_eq!(context.control_term_buffer_length(), 128 * 1024);
+        assert!(!context.control_term_buffer_sparse());
+        assert_eq!(context.control_response_stream_id(), CONTROL_RESPONSE_STREAM_ID_DEFAULT);
+        assert_eq!(context.recording_events_stream_id(), 31);
+    }
+
+    #[test]
+    fn should_only_read_environment_when_created_from_env() {
+        // IC: no other test reads this property, so setting it does not race with tests running in parallel
+        std::env::set_var("AERON_ARCHIVE_RECORDING_EVENTS_STREAM_ID", "55");
+
+        assert_eq!(
+            Context::new().recording_events_stream_id(),
+            RECORDING_EVENTS_STREAM_ID_DEFAULT
+        );
+        assert_eq!(
+            Context::default().recording_events_stream_id(),
+            RECORDING_EVENTS_STREAM_ID_DEFAULT
+        );
+        assert_eq!(Context::from_env().recording_events_stream_id(), 55);
+    }
+
+    #[test]
+    fn should_report_invalid_property_on_conclude() {
+        let configuration = Configuration::from_properties("aeron.archive.control.stream.id=ten\n");
+        let mut context = Context::with_configuration(&configuration);
+
+        match context.conclude() {
+            Err(AeronError::IllegalArgument(IllegalArgumentError::InvalidPropertyValue { name, value })) => {
+                assert_eq!(name, CONTROL_STREAM_ID_PROP_NAME);
+                assert_eq!(value, "ten");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_let_setter_replace_invalid_property() {
+        let configuration = Configuration::from_properties("aeron.archive.control.mtu.length=huge\n");
+        let mut context = Context::with_configuration(&configuration);
+        context.set_control_mtu_length(8192);
+        context.set_message_timeout_ns(0);
+
+        match context.conclude() {
+            Err(AeronError::IllegalArgument(IllegalArgumentError::PropertyOutOfRange { name, .. })) => {
+                assert_eq!(name, MESSAGE_TIMEOUT_PROP_NAME)
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_reject_term_length_which_is_not_power_of_two() {
+        let mut context = Context::with_configuration(&Configuration::default());
+        context.set_control_term_buffer_length(100_000);
+
+        match context.conclude() {
+            Err(AeronError::IllegalArgument(IllegalArgumentError::PropertyOutOfRange { name, value, .. })) => {
+                assert_eq!(name, CONTROL_TERM_BUFFER_LENGTH_PROP_NAME);
+                assert_eq!(value, "100000");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
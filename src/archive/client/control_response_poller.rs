@@ -1,41 +1,156 @@
-use crate::aeron::Aeron;
-use crate::context::Context;
-use crate::subscription::Subscription;
+use std::sync::{Arc, Mutex};
+
+use io_aeron_archive_codecs::*;
+
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::header::Header;
 use crate::context::NULL_VALUE;
+use crate::controlled_fragment_assembler::ControlledFragmentAssembler;
+use crate::image::ControlledPollAction;
+use crate::log;
+use crate::subscription::Subscription;
+use crate::utils::errors::AeronError;
+use crate::utils::types::Index;
 
+/**
+ * Limit to apply when polling response messages.
+ */
+pub const FRAGMENT_LIMIT: i32 = 10;
 
+/**
+ * Encapsulate the polling and decoding of archive control protocol response messages.
+ */
 pub struct ControlResponsePoller {
-    pub fragment_limit: i32,
-    subscription: Subscription,
+    fragment_limit: i32,
+    subscription: Arc<Mutex<Subscription>>,
     fragment_assembler: ControlledFragmentAssembler,
-    message_header_decoder: MessageHeaderDecoder,
-    control_response_decoder: ControlResponseDecoder,
-    challenge_decoder: ChallengeDecoder,
-    recording_signal_event_decoder: RecordingSignalEventDecoder,
+    // IC: decoded state lives in its own struct so it can be borrowed by the assembler delegate while
+    // the assembler itself is borrowed mutably.
+    response: ControlResponse,
+}
+
+#[derive(Debug, Clone)]
+struct ControlResponse {
     control_session_id: i64,
     correlation_id: i64,
     relevant_id: i64,
-    template_id: i32,
-    version: i32,
-    code: ControlResponseCode,
-    error_message: Option<String>,
     recording_id: i64,
     subscription_id: i64,
     position: i64,
-    recording_signal: Option<RecordingSignal>,
-    encoded_challenge: Option<Vec<u8>>,
+    template_id: i32,
+    version: i32,
+    code: ControlResponseCode,
+    recording_signal: RecordingSignal,
+    error_message: String,
+    encoded_challenge: Vec<u8>,
     is_poll_complete: bool,
+    was_challenged: bool,
+}
+
+impl Default for ControlResponse {
+    fn default() -> Self {
+        Self {
+            control_session_id: NULL_VALUE,
+            correlation_id: NULL_VALUE,
+            relevant_id: NULL_VALUE,
+            recording_id: NULL_VALUE,
+            subscription_id: NULL_VALUE,
+            position: NULL_VALUE,
+            template_id: NULL_VALUE as i32,
+            version: 0,
+            code: ControlResponseCode::NullVal,
+            recording_signal: RecordingSignal::NullVal,
+            error_message: String::new(),
+            encoded_challenge: Vec::new(),
+            is_poll_complete: false,
+            was_challenged: false,
+        }
+    }
+}
+
+impl ControlResponse {
+    fn on_fragment(
+        &mut self,
+        buffer: &AtomicBuffer,
+        offset: Index,
+        length: Index,
+        _header: &Header,
+    ) -> Result<ControlledPollAction, AeronError> {
+        if self.is_poll_complete {
+            return Ok(ControlledPollAction::ABORT);
+        }
+
+        let read_buf = ReadBuf::new(buffer.as_sub_slice(offset, length));
+        let header_decoder = MessageHeaderDecoder::default().wrap(read_buf, 0);
+
+        if header_decoder.schema_id() != control_response_codec::SBE_SCHEMA_ID {
+            log!(
+                warn,
+                "expected schema_id={}, actual={}",
+                control_response_codec::SBE_SCHEMA_ID,
+                header_decoder.schema_id()
+            );
+            return Ok(ControlledPollAction::CONTINUE);
+        }
+
+        self.template_id = header_decoder.template_id() as i32;
+
+        if header_decoder.template_id() == control_response_codec::SBE_TEMPLATE_ID {
+            let mut decoder = ControlResponseDecoder::default().header(header_decoder);
+            self.control_session_id = decoder.control_session_id();
+            self.correlation_id = decoder.correlation_id();
+            self.relevant_id = decoder.relevant_id();
+            self.code = decoder.code();
+            self.version = decoder.version().unwrap_or(0);
+            let coordinates = decoder.error_message_decoder();
+            self.error_message = String::from_utf8_lossy(decoder.error_message_slice(coordinates)).into_owned();
+            self.is_poll_complete = true;
+
+            return Ok(ControlledPollAction::BREAK);
+        }
+
+        if header_decoder.template_id() == challenge_codec::SBE_TEMPLATE_ID {
+            let mut decoder = ChallengeDecoder::default().header(header_decoder);
+            self.control_session_id = decoder.control_session_id();
+            self.correlation_id = decoder.correlation_id();
+            self.relevant_id = NULL_VALUE;
+            self.code = ControlResponseCode::NullVal;
+            self.version = decoder.version().unwrap_or(0);
+            self.error_message.clear();
+            let coordinates = decoder.encoded_challenge_decoder();
+            self.encoded_challenge = decoder.encoded_challenge_slice(coordinates).to_vec();
+            self.is_poll_complete = true;
+            self.was_challenged = true;
+
+            return Ok(ControlledPollAction::BREAK);
+        }
+
+        if header_decoder.template_id() == recording_signal_event_codec::SBE_TEMPLATE_ID {
+            let decoder = RecordingSignalEventDecoder::default().header(header_decoder);
+            self.control_session_id = decoder.control_session_id();
+            self.correlation_id = decoder.correlation_id();
+            self.recording_id = decoder.recording_id();
+            self.subscription_id = decoder.subscription_id();
+            self.position = decoder.position();
+            self.recording_signal = decoder.signal();
+            self.is_poll_complete = true;
+
+            return Ok(ControlledPollAction::BREAK);
+        }
+
+        Ok(ControlledPollAction::CONTINUE)
+    }
 }
 
 impl ControlResponsePoller {
     /**
      * Create a poller for a given subscription to an archive for control response messages with a default
-     * fragment limit for polling as {@link #FRAGMENT_LIMIT}.
+     * fragment limit for polling as FRAGMENT_LIMIT.
      *
      * @param subscription to poll for new events.
      */
-    pub fn new(subscription: Subscription) -> Self {
-        Self::with_fragment_limit(subscription, 10)
+    pub fn new(subscription: Arc<Mutex<Subscription>>) -> Self {
+        Self::with_fragment_limit(subscription, FRAGMENT_LIMIT)
     }
 
     /**
@@ -44,57 +159,247 @@ impl ControlResponsePoller {
      * @param subscription  to poll for new events.
      * @param fragment_limit to apply when polling.
      */
-    pub fn with_fragment_limit(subscription: Subscription, fragment_limit: i32) -> Self {
+    pub fn with_fragment_limit(subscription: Arc<Mutex<Subscription>>, fragment_limit: i32) -> Self {
         Self {
             fragment_limit,
             subscription,
-            fragment_assembler: ControlledFragmentAssembler::new(Self::on_fragment),
-            message_header_decoder: MessageHeaderDecoder::new(),
-            control_response_decoder: ControlResponseDecoder::new(),
-            challenge_decoder: ChallengeDecoder::new(),
-            recording_signal_event_decoder: RecordingSignalEventDecoder::new(),
-            control_session_id: NULL_VALUE,
-            correlation_id: NULL_VALUE,
-            relevant_id: NULL_VALUE,
-            template_id: NULL_VALUE as i32,
-            version: 0,
-            code: NULL_VALUE,
-            error_message: None,
-            recording_id: NULL_VALUE,
-            subscription_id: NULL_VALUE,
-            position: NULL_VALUE,
-            recording_signal: None,
-            encoded_challenge: None,
-            is_poll_complete: false,
+            fragment_assembler: ControlledFragmentAssembler::default(),
+            response: ControlResponse::default(),
         }
     }
 
     /**
-     * Get the {@link Subscription} used for polling responses.
+     * Get the Subscription used for polling responses.
      *
-     * @return the {@link Subscription} used for polling responses.
+     * @return the Subscription used for polling responses.
      */
-    pub fn subscription(&self) -> &Subscription {
-        &self.subscription
+    pub fn subscription(&self) -> Arc<Mutex<Subscription>> {
+        self.subscription.clone()
     }
 
+    /**
+     * Poll for control response events.
+     *
+     * @return the number of fragments read during the operation. Zero if no events are available.
+     */
     pub fn poll(&mut self) -> i32 {
-        if self.is_poll_complete {
-            self.is_poll_complete = false;
-            self.template_id = NULL_VALUE as i32;
-            self.control_session_id = NULL_VALUE;
-            self.correlation_id = NULL_VALUE;
-            self.relevant_id = NULL_VALUE;
-            self.recording_id = NULL_VALUE;
-            self.subscription_id = NULL_VALUE;
-            self.position = NULL_VALUE;
-            self.recording_signal = None;
-            self.version = 0;
-            self.error_message = None;
-            self.encoded_challenge = None;
+        if self.response.is_poll_complete {
+            self.response = ControlResponse::default();
         }
 
-        self.subscription.controlled_poll(&mut self.fragment_assembler, self.fragment_limit)
+        let response = &mut self.response;
+        let fragment_assembler = &mut self.fragment_assembler;
+        let mut handler = |buffer: &AtomicBuffer, offset: Index, length: Index, header: &Header| {
+            fragment_assembler.on_fragment(
+                &mut |buffer: &AtomicBuffer, offset: Index, length: Index, header: &Header| {
+                    response.on_fragment(buffer, offset, length, header)
+                },
+                buffer,
+                offset,
+                length,
+                header,
+            )
+        };
+
+        self.subscription
+            .lock()
+            .expect("Mutex poisoned")
+            .controlled_poll(&mut handler, self.fragment_limit)
+    }
+
+    /**
+     * Control session id of the last polled message or NULL_VALUE if poll returned nothing.
+     *
+     * @return control session id of the last polled message or NULL_VALUE if poll returned nothing.
+     */
+    pub fn control_session_id(&self) -> i64 {
+        self.response.control_session_id
+    }
+
+    /**
+     * Correlation id of the last polled message or NULL_VALUE if poll returned nothing.
+     *
+     * @return correlation id of the last polled message or NULL_VALUE if poll returned nothing.
+     */
+    pub fn correlation_id(&self) -> i64 {
+        self.response.correlation_id
+    }
+
+    /**
+     * Get the relevant id returned with the response, e.g. replay session id.
+     *
+     * @return the relevant id returned with the response.
+     */
+    pub fn relevant_id(&self) -> i64 {
+        self.response.relevant_id
+    }
+
+    /**
+     * Get the recording id of the last received RecordingSignalEvent.
+     *
+     * @return the recording id of the last received RecordingSignalEvent.
+     */
+    pub fn recording_id(&self) -> i64 {
+        self.response.recording_id
+    }
+
+    /**
+     * Get the subscription id of the last received RecordingSignalEvent.
+     *
+     * @return the subscription id of the last received RecordingSignalEvent.
+     */
+    pub fn subscription_id(&self) -> i64 {
+        self.response.subscription_id
+    }
+
+    /**
+     * Get the recording position of the last received RecordingSignalEvent.
+     *
+     * @return the recording position of the last received RecordingSignalEvent.
+     */
+    pub fn position(&self) -> i64 {
+        self.response.position
+    }
+
+    /**
+     * Get the recording signal of the last received RecordingSignalEvent.
+     *
+     * @return the recording signal of the last received RecordingSignalEvent.
+     */
+    pub fn recording_signal(&self) -> RecordingSignal {
+        self.response.recording_signal
+    }
+
+    /**
+     * Get the template id of the last received message.
+     *
+     * @return the template id of the last received message.
+     */
+    pub fn template_id(&self) -> i32 {
+        self.response.template_id
+    }
+
+    /**
+     * Get the version of the archive protocol sent with the response or zero if not sent.
+     *
+     * @return the version of the archive protocol sent with the response.
+     */
+    pub fn version(&self) -> i32 {
+        self.response.version
+    }
+
+    /**
+     * Has the last polling action received a complete message?
+     *
+     * @return true if the last polling action received a complete message?
+     */
+    pub fn is_poll_complete(&self) -> bool {
+        self.response.is_poll_complete
+    }
+
+    /**
+     * Did the last polling action receive a challenge message?
+     *
+     * @return true if the last polling action received a challenge message.
+     */
+    pub fn was_challenged(&self) -> bool {
+        self.response.was_challenged
+    }
+
+    /**
+     * Get the encoded challenge of the last challenge.
+     *
+     * @return the encoded challenge of the last challenge.
+     */
+    pub fn encoded_challenge(&self) -> &[u8] {
+        &self.response.encoded_challenge
+    }
+
+    /**
+     * Get the response code of the last response.
+     *
+     * @return the response code of the last response.
+     */
+    pub fn code(&self) -> ControlResponseCode {
+        self.response.code
+    }
+
+    /**
+     * Get the error message of the last response.
+     *
+     * @return the error message of the last response.
+     */
+    pub fn error_message(&self) -> &str {
+        &self.response.error_message
+    }
+
+    /**
+     * Was the last polled message a ControlResponse?
+     *
+     * @return true if the last polled message was a ControlResponse.
+     */
+    pub fn is_control_response(&self) -> bool {
+        self.response.template_id == control_response_codec::SBE_TEMPLATE_ID as i32
+    }
+
+    /**
+     * Was the last polled message a RecordingSignalEvent?
+     *
+     * @return true if the last polled message was a RecordingSignalEvent.
+     */
+    pub fn is_recording_signal(&self) -> bool {
+        self.response.template_id == recording_signal_event_codec::SBE_TEMPLATE_ID as i32
+    }
+
+    /**
+     * Is the last polled message a ControlResponse with a code of OK?
+     *
+     * @return true if the last polled message was a ControlResponse with a code of OK.
+     */
+    pub fn is_code_ok(&self) -> bool {
+        self.is_control_response() && self.response.code == ControlResponseCode::OK
+    }
+
+    /**
+     * Is the last polled message a ControlResponse with a code of ERROR?
+     *
+     * @return true if the last polled message was a ControlResponse with a code of ERROR.
+     */
+    pub fn is_code_error(&self) -> bool {
+        self.is_control_response() && self.response.code == ControlResponseCode::ERROR
     }
 }
 
+#[cfg(test)]
+mod test {
+    use io_aeron_archive_codecs::*;
+
+    use crate::archive::client::control_response_poller::ControlResponse;
+    use crate::concurrent::atomic_buffer::AtomicBuffer;
+    use crate::concurrent::logbuffer::header::Header;
+    use crate::image::ControlledPollAction;
+    use crate::utils::types::Index;
+
+    #[test]
+    fn should_decode_challenge() {
+        let mut data = vec![0u8; 256];
+        let mut encoder = ChallengeEncoder::default().wrap(WriteBuf::new(&mut data), message_header_codec::ENCODED_LENGTH);
+        encoder = encoder.header(0).parent().unwrap();
+        encoder.control_session_id(7);
+        encoder.correlation_id(42);
+        encoder.version(1);
+        encoder.encoded_challenge(b"nonce");
+        let length = (message_header_codec::ENCODED_LENGTH + encoder.encoded_length()) as Index;
+        let buffer = AtomicBuffer::wrap_slice(&mut data);
+
+        let mut response = ControlResponse::default();
+        let action = response.on_fragment(&buffer, 0, length, &Header::new(0, 0)).unwrap();
+
+        assert!(action == ControlledPollAction::BREAK);
+        assert!(response.is_poll_complete);
+        assert!(response.was_challenged);
+        assert_eq!(response.control_session_id, 7);
+        assert_eq!(response.correlation_id, 42);
+        assert_eq!(response.encoded_challenge, b"nonce");
+    }
+}
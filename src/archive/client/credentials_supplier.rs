@@ -0,0 +1,67 @@
+/**
+ * Supplier of credentials for authentication with an archive.
+ * <p>
+ * Implement this to provide credentials when connecting and to respond to any challenge the archive sends
+ * during the connect handshake.
+ */
+pub trait CredentialsSupplier {
+    /**
+     * Provide encoded credentials to be included in the connect request to the archive.
+     *
+     * @return encoded credentials to include in the connect request to the archive.
+     */
+    fn encoded_credentials(&self) -> Vec<u8>;
+
+    /**
+     * Given some encoded challenge data, provide the credentials to be sent in a challenge response.
+     *
+     * @param encoded_challenge from the archive.
+     * @return encoded credentials to be sent in the challenge response.
+     */
+    fn on_challenge(&self, encoded_challenge: &[u8]) -> Vec<u8>;
+}
+
+/**
+ * Null provider of credentials when no authentication is required.
+ */
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NullCredentialsSupplier {}
+
+impl CredentialsSupplier for NullCredentialsSupplier {
+    fn encoded_credentials(&self) -> Vec<u8> {
+        Vec::new()
+    }
+
+    fn on_challenge(&self, _encoded_challenge: &[u8]) -> Vec<u8> {
+        Vec::new()
+    }
+}
+
+/**
+ * Provider of a fixed token, e.g. a shared secret, which is sent on connect and as the response to any challenge.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct StaticCredentialsSupplier {
+    token: Vec<u8>,
+}
+
+impl StaticCredentialsSupplier {
+    /**
+     * Create a supplier for a fixed token.
+     *
+     * @param token sent as the encoded credentials.
+     */
+    pub fn new(token: impl Into<Vec<u8>>) -> Self {
+        Self { token: token.into() }
+    }
+}
+
+impl CredentialsSupplier for StaticCredentialsSupplier {
+    fn encoded_credentials(&self) -> Vec<u8> {
+        self.token.clone()
+    }
+
+    fn on_challenge(&self, _encoded_challenge: &[u8]) -> Vec<u8> {
+        self.token.clone()
+    }
+}
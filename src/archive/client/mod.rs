@@ -1,6 +1,14 @@
 pub mod aeron_archive;
-pub mod context;
-pub mod configuration;
 pub mod archive_proxy;
-// pub mod control_response_poller;
-// pub mod recording_descriptor_poller;
\ No newline at end of file
+pub mod configuration;
+pub mod context;
+pub mod control_response_poller;
+pub mod credentials_supplier;
+pub mod recording_descriptor_consumer;
+pub mod recording_descriptor_poller;
+pub mod recording_events_adapter;
+pub mod recording_events_listener;
+pub mod recording_pos;
+pub mod recording_signal_consumer;
+pub mod replay_merge;
+pub mod replication_params;
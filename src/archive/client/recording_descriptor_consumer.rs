@@ -0,0 +1,78 @@
+/**
+ * A recording descriptor returned as a result of requesting a listing of recordings.
+ */
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RecordingDescriptor {
+    /**
+     * of the originating session requesting to list recordings.
+     */
+    pub control_session_id: i64,
+    /**
+     * of the associated request to list recordings.
+     */
+    pub correlation_id: i64,
+    /**
+     * of this recording descriptor.
+     */
+    pub recording_id: i64,
+    /**
+     * of this recording.
+     */
+    pub start_timestamp: i64,
+    /**
+     * of this recording.
+     */
+    pub stop_timestamp: i64,
+    /**
+     * position of the first recorded message in the stream.
+     */
+    pub start_position: i64,
+    /**
+     * reached for the recording or NULL_POSITION if still active.
+     */
+    pub stop_position: i64,
+    /**
+     * of the recorded stream.
+     */
+    pub initial_term_id: i32,
+    /**
+     * of the recording which is a multiple of term_buffer_length.
+     */
+    pub segment_file_length: i32,
+    /**
+     * of the recorded stream.
+     */
+    pub term_buffer_length: i32,
+    /**
+     * of the recorded stream.
+     */
+    pub mtu_length: i32,
+    /**
+     * of the recorded stream.
+     */
+    pub session_id: i32,
+    /**
+     * of the recorded stream.
+     */
+    pub stream_id: i32,
+    /**
+     * of the recorded stream which is used for the recording subscription in the archive.
+     */
+    pub stripped_channel: String,
+    /**
+     * of the recorded stream provided to the start recording request.
+     */
+    pub original_channel: String,
+    /**
+     * of the recorded stream, e.g. address of the publisher.
+     */
+    pub source_identity: String,
+}
+
+/**
+ * Consumer for descriptors of recordings as they are delivered by a RecordingDescriptorPoller, e.g. a closure
+ * pushing them into a Vec.
+ */
+pub trait RecordingDescriptorConsumer: FnMut(RecordingDescriptor) {}
+
+impl<T: FnMut(RecordingDescriptor)> RecordingDescriptorConsumer for T {}
@@ -1,98 +1,436 @@
+use std::sync::{Arc, Mutex};
+
+use io_aeron_archive_codecs::*;
+
+use crate::archive::client::recording_descriptor_consumer::{RecordingDescriptor, RecordingDescriptorConsumer};
+use crate::archive::client::recording_signal_consumer::RecordingSignalConsumer;
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::header::Header;
+use crate::concurrent::logbuffer::term_reader::ErrorHandler;
+use crate::controlled_fragment_assembler::ControlledFragmentAssembler;
+use crate::image::ControlledPollAction;
+use crate::log;
 use crate::subscription::Subscription;
+use crate::utils::errors::{AeronError, ArchiveError, ArchiveErrorCode};
+use crate::utils::types::Index;
 
+/**
+ * Encapsulate the polling, decoding, dispatching of recording descriptors from an archive.
+ */
 pub struct RecordingDescriptorPoller {
-    message_header_decoder: MessageHeaderDecoder,
-    control_response_decoder: ControlResponseDecoder,
-    recording_descriptor_decoder: RecordingDescriptorDecoder,
-    recording_signal_event_decoder: RecordingSignalEventDecoder,
-
-    control_session_id: i64,
     fragment_limit: i32,
-    subscription: Subscription,
+    subscription: Arc<Mutex<Subscription>>,
     fragment_assembler: ControlledFragmentAssembler,
-    error_handler: Arc<dyn ErrorHandler>,
-    recording_signal_consumer: Arc<dyn RecordingSignalConsumer>,
+    // IC: same split as in ControlResponsePoller, the assembler delegate borrows the dispatch state
+    dispatch: DescriptorDispatch,
+}
 
+struct DescriptorDispatch {
+    control_session_id: i64,
     correlation_id: i64,
     remaining_record_count: i32,
     is_dispatch_complete: bool,
-    recording_descriptor_consumer: Option<Box<dyn RecordingDescriptorConsumer>>,
+    error_handler: Box<dyn ErrorHandler + Send>,
+    recording_signal_consumer: Box<dyn RecordingSignalConsumer + Send>,
+    // IC: error for the awaited request, handed back from poll() as fragment handlers can't return errors
+    error: Option<AeronError>,
 }
 
+impl DescriptorDispatch {
+    fn on_fragment(
+        &mut self,
+        consumer: &mut impl RecordingDescriptorConsumer,
+        buffer: &AtomicBuffer,
+        offset: Index,
+        length: Index,
+    ) -> ControlledPollAction {
+        if self.is_dispatch_complete {
+            return ControlledPollAction::ABORT;
+        }
+
+        let header_decoder = MessageHeaderDecoder::default().wrap(ReadBuf::new(buffer.as_sub_slice(offset, length)), 0);
+
+        if header_decoder.schema_id() != recording_descriptor_codec::SBE_SCHEMA_ID {
+            log!(
+                warn,
+                "expected schema_id={}, actual={}",
+                recording_descriptor_codec::SBE_SCHEMA_ID,
+                header_decoder.schema_id()
+            );
+            return ControlledPollAction::CONTINUE;
+        }
+
+        match header_decoder.template_id() {
+            control_response_codec::SBE_TEMPLATE_ID => {
+                let mut decoder = ControlResponseDecoder::default().header(header_decoder);
+                if decoder.control_session_id() != self.control_session_id {
+                    return ControlledPollAction::CONTINUE;
+                }
+
+                let code = decoder.code();
+                let correlation_id = decoder.correlation_id();
+
+                if code == ControlResponseCode::RECORDING_UNKNOWN && correlation_id == self.correlation_id {
+                    self.is_dispatch_complete = true;
+                    return ControlledPollAction::BREAK;
+                }
+
+                if code == ControlResponseCode::ERROR {
+                    let error_code = ArchiveErrorCode::from(decoder.relevant_id());
+                    let coordinates = decoder.error_message_decoder();
+                    let error = ArchiveError::Response {
+                        correlation_id,
+                        error_code,
+                        message: String::from_utf8_lossy(decoder.error_message_slice(coordinates)).into_owned(),
+                    };
+
+                    if correlation_id == self.correlation_id {
+                        self.error = Some(error.into());
+                        self.is_dispatch_complete = true;
+                        return ControlledPollAction::BREAK;
+                    }
+
+                    self.error_handler.call(error.into());
+                }
+            }
+
+            recording_descriptor_codec::SBE_TEMPLATE_ID => {
+                let mut decoder = RecordingDescriptorDecoder::default().header(header_decoder);
+                if decoder.control_session_id() != self.control_session_id || decoder.correlation_id() != self.correlation_id {
+                    return ControlledPollAction::CONTINUE;
+                }
+
+                consumer(decode_descriptor(&mut decoder));
+
+                self.remaining_record_count -= 1;
+                if self.remaining_record_count == 0 {
+                    self.is_dispatch_complete = true;
+                    return ControlledPollAction::BREAK;
+                }
+            }
+
+            recording_signal_event_codec::SBE_TEMPLATE_ID => {
+                let decoder = RecordingSignalEventDecoder::default().header(header_decoder);
+                if decoder.control_session_id() == self.control_session_id {
+                    self.recording_signal_consumer.on_signal(
+                        decoder.control_session_id(),
+                        decoder.correlation_id(),
+                        decoder.recording_id(),
+                        decoder.subscription_id(),
+                        decoder.position(),
+                        decoder.signal(),
+                    );
+                }
+            }
+
+            _ => {}
+        }
+
+        ControlledPollAction::CONTINUE
+    }
+}
+
+pub(crate) fn decode_descriptor(decoder: &mut RecordingDescriptorDecoder) -> RecordingDescriptor {
+    let mut descriptor = RecordingDescriptor {
+        control_session_id: decoder.control_session_id(),
+        correlation_id: decoder.correlation_id(),
+        recording_id: decoder.recording_id(),
+        start_timestamp: decoder.start_timestamp(),
+        stop_timestamp: decoder.stop_timestamp(),
+        start_position: decoder.start_position(),
+        stop_position: decoder.stop_position(),
+        initial_term_id: decoder.initial_term_id(),
+        segment_file_length: decoder.segment_file_length(),
+        term_buffer_length: decoder.term_buffer_length(),
+        mtu_length: decoder.mtu_length(),
+        session_id: decoder.session_id(),
+        stream_id: decoder.stream_id(),
+        ..RecordingDescriptor::default()
+    };
+
+    // IC: var data has to be read in schema order
+    let coordinates = decoder.stripped_channel_decoder();
+    descriptor.stripped_channel = String::from_utf8_lossy(decoder.stripped_channel_slice(coordinates)).into_owned();
+    let coordinates = decoder.original_channel_decoder();
+    descriptor.original_channel = String::from_utf8_lossy(decoder.original_channel_slice(coordinates)).into_owned();
+    let coordinates = decoder.source_identity_decoder();
+    descriptor.source_identity = String::from_utf8_lossy(decoder.source_identity_slice(coordinates)).into_owned();
+
+    descriptor
+}
 
 impl RecordingDescriptorPoller {
+    /**
+     * Create a poller for a given subscription to an archive for control response messages.
+     *
+     * @param subscription     to poll for new events.
+     * @param error_handler     to call for asynchronous errors.
+     * @param recording_signal_consumer for consuming interleaved recording signals on the control session.
+     * @param control_session_id to filter the responses.
+     * @param fragment_limit    to apply for each polling operation.
+     */
     pub fn new(
-        subscription: Subscription,
-        error_handler: Arc<dyn ErrorHandler>,
+        subscription: Arc<Mutex<Subscription>>,
+        error_handler: Box<dyn ErrorHandler + Send>,
+        recording_signal_consumer: Box<dyn RecordingSignalConsumer + Send>,
         control_session_id: i64,
         fragment_limit: i32,
-        recording_signal_consumer: Option<Arc<dyn RecordingSignalConsumer>>,
     ) -> Self {
-        let recording_signal_consumer = recording_signal_consumer.unwrap_or_else(|| {
-            Arc::new(NoOpRecordingSignalConsumer::default()) as Arc<dyn RecordingSignalConsumer>
-        });
-
         Self {
-            message_header_decoder: MessageHeaderDecoder::default(),
-            control_response_decoder: ControlResponseDecoder::default(),
-            recording_descriptor_decoder: RecordingDescriptorDecoder::default(),
-            recording_signal_event_decoder: RecordingSignalEventDecoder::default(),
-
-            control_session_id,
             fragment_limit,
             subscription,
             fragment_assembler: ControlledFragmentAssembler::default(),
-            error_handler,
-            recording_signal_consumer,
-
-            correlation_id: 0,
-            remaining_record_count: 0,
-            is_dispatch_complete: false,
-            recording_descriptor_consumer: None,
+            dispatch: DescriptorDispatch {
+                control_session_id,
+                correlation_id: 0,
+                remaining_record_count: 0,
+                is_dispatch_complete: false,
+                error_handler,
+                recording_signal_consumer,
+                error: None,
+            },
         }
     }
 
-    pub fn subscription(&self) -> &Subscription {
-        &self.subscription
+    /**
+     * Get the Subscription used for polling responses.
+     *
+     * @return the Subscription used for polling responses.
+     */
+    pub fn subscription(&self) -> Arc<Mutex<Subscription>> {
+        self.subscription.clone()
     }
 
-    pub fn poll(&mut self) -> Result<i32, AeronError> {
-        if self.is_dispatch_complete {
-            return Ok(0);
+    /**
+     * Poll for recording events and dispatch them to the consumer for this instance.
+     *
+     * @param consumer for the recording descriptors of the current request.
+     * @return the number of fragments read during the operation. Zero if no events are available.
+     */
+    pub fn poll(&mut self, consumer: &mut impl RecordingDescriptorConsumer) -> Result<i32, AeronError> {
+        if self.dispatch.is_dispatch_complete {
+            self.dispatch.is_dispatch_complete = false;
         }
 
+        let dispatch = &mut self.dispatch;
+        let fragment_assembler = &mut self.fragment_assembler;
+        let mut handler = |buffer: &AtomicBuffer, offset: Index, length: Index, header: &Header| {
+            fragment_assembler.on_fragment(
+                &mut |buffer: &AtomicBuffer, offset: Index, length: Index, _header: &Header| {
+                    Ok(dispatch.on_fragment(consumer, buffer, offset, length))
+                },
+                buffer,
+                offset,
+                length,
+                header,
+            )
+        };
+
         let fragments_read = self
             .subscription
-            .controlled_poll(&mut self.fragment_assembler, self.fragment_limit)?;
+            .lock()
+            .expect("Mutex poisoned")
+            .controlled_poll(&mut handler, self.fragment_limit);
 
-        if self.is_dispatch_complete {
-            return Ok(0);
+        match self.dispatch.error.take() {
+            Some(error) => Err(error),
+            None => Ok(fragments_read),
         }
-
-        Ok(fragments_read)
     }
 
+    /**
+     * Control session id for filtering responses.
+     *
+     * @return control session id for filtering responses.
+     */
     pub fn control_session_id(&self) -> i64 {
-        self.control_session_id
+        self.dispatch.control_session_id
     }
 
+    /**
+     * Is the dispatch of descriptors complete?
+     *
+     * @return true if the dispatch of descriptors complete?
+     */
     pub fn is_dispatch_complete(&self) -> bool {
-        self.is_dispatch_complete
+        self.dispatch.is_dispatch_complete
     }
 
+    /**
+     * Get the number of remaining records are expected.
+     *
+     * @return the number of remaining records are expected.
+     */
     pub fn remaining_record_count(&self) -> i32 {
-        self.remaining_record_count
+        self.dispatch.remaining_record_count
     }
 
-    pub fn reset(
-        &mut self,
-        correlation_id: i64,
-        record_count: i32,
-        consumer: Box<dyn RecordingDescriptorConsumer>,
-    ) {
-        self.correlation_id = correlation_id;
-        self.recording_descriptor_consumer = Some(consumer);
-        self.remaining_record_count = record_count;
-        self.is_dispatch_complete = false;
+    /**
+     * Reset the poller to dispatch the descriptors returned from a query.
+     *
+     * @param correlation_id for the response.
+     * @param record_count   of descriptors to expect.
+     */
+    pub fn reset(&mut self, correlation_id: i64, record_count: i32) {
+        self.dispatch.correlation_id = correlation_id;
+        self.dispatch.remaining_record_count = record_count;
+        self.dispatch.is_dispatch_complete = false;
+        self.dispatch.error = None;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::Arc;
+
+    use io_aeron_archive_codecs::*;
+
+    use crate::archive::client::recording_descriptor_consumer::RecordingDescriptor;
+    use crate::archive::client::recording_descriptor_poller::DescriptorDispatch;
+    use crate::archive::client::recording_signal_consumer::no_op_recording_signal_consumer;
+    use crate::concurrent::atomic_buffer::AtomicBuffer;
+    use crate::image::ControlledPollAction;
+    use crate::utils::errors::{AeronError, ArchiveError, ArchiveErrorCode};
+    use crate::utils::types::Index;
+
+    const CONTROL_SESSION_ID: i64 = 7;
+    const CORRELATION_ID: i64 = 42;
+
+    fn dispatch(remaining_record_count: i32) -> DescriptorDispatch {
+        DescriptorDispatch {
+            control_session_id: CONTROL_SESSION_ID,
+            correlation_id: CORRELATION_ID,
+            remaining_record_count,
+            is_dispatch_complete: false,
+            error_handler: Box::new(|_| {}),
+            recording_signal_consumer: Box::new(no_op_recording_signal_consumer),
+            error: None,
+        }
+    }
+
+    fn encode_descriptor(data: &mut [u8], recording_id: i64) -> Index {
+        let mut encoder = RecordingDescriptorEncoder::default().wrap(WriteBuf::new(data), message_header_codec::ENCODED_LENGTH);
+        encoder = encoder.header(0).parent().unwrap();
+        encoder.control_session_id(CONTROL_SESSION_ID);
+        encoder.correlation_id(CORRELATION_ID);
+        encoder.recording_id(recording_id);
+        encoder.start_timestamp(1);
+        encoder.stop_timestamp(2);
+        encoder.start_position(0);
+        encoder.stop_position(4096);
+        encoder.initial_term_id(3);
+        encoder.segment_file_length(128 * 1024 * 1024);
+        encoder.term_buffer_length(64 * 1024);
+        encoder.mtu_length(1408);
+        encoder.session_id(11);
+        encoder.stream_id(1001);
+        encoder.stripped_channel(b"aeron:udp?endpoint=localhost:40123");
+        encoder.original_channel(b"aeron:udp?endpoint=localhost:40123|alias=test");
+        encoder.source_identity(b"127.0.0.1:54321");
+
+        (message_header_codec::ENCODED_LENGTH + encoder.encoded_length()) as Index
+    }
+
+    fn encode_error(data: &mut [u8], correlation_id: i64) -> Index {
+        let mut encoder = ControlResponseEncoder::default().wrap(WriteBuf::new(data), message_header_codec::ENCODED_LENGTH);
+        encoder = encoder.header(0).parent().unwrap();
+        encoder.control_session_id(CONTROL_SESSION_ID);
+        encoder.correlation_id(correlation_id);
+        encoder.relevant_id(ArchiveErrorCode::UnknownRecording.code());
+        encoder.code(ControlResponseCode::ERROR);
+        encoder.error_message(b"unknown recording");
+
+        (message_header_codec::ENCODED_LENGTH + encoder.encoded_length()) as Index
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn should_decode_descriptors_until_record_count_reached() {
+        let mut data = vec![0u8; 512];
+        let length = encode_descriptor(&mut data, 5);
+        let buffer = AtomicBuffer::wrap_slice(&mut data);
+
+        let mut descriptors: Vec<RecordingDescriptor> = Vec::new();
+        let mut dispatch = dispatch(2);
+
+        let action = dispatch.on_fragment(&mut |descriptor| descriptors.push(descriptor), &buffer, 0, length);
+        assert!(action == ControlledPollAction::CONTINUE);
+        assert!(!dispatch.is_dispatch_complete);
+
+        let action = dispatch.on_fragment(&mut |descriptor| descriptors.push(descriptor), &buffer, 0, length);
+        assert!(action == ControlledPollAction::BREAK);
+        assert!(dispatch.is_dispatch_complete);
+
+        assert_eq!(descriptors.len(), 2);
+        let descriptor = &descriptors[0];
+        assert_eq!(descriptor.recording_id, 5);
+        assert_eq!(descriptor.stop_position, 4096);
+        assert_eq!(descriptor.stream_id, 1001);
+        assert_eq!(descriptor.stripped_channel, "aeron:udp?endpoint=localhost:40123");
+        assert_eq!(descriptor.original_channel, "aeron:udp?endpoint=localhost:40123|alias=test");
+        assert_eq!(descriptor.source_identity, "127.0.0.1:54321");
+    }
+
+    #[test]
+    fn should_keep_error_for_awaited_correlation_id() {
+        let mut data = vec![0u8; 512];
+        let length = encode_error(&mut data, CORRELATION_ID);
+        let buffer = AtomicBuffer::wrap_slice(&mut data);
+
+        let mut dispatch = dispatch(10);
+        let action = dispatch.on_fragment(&mut |_| panic!("no descriptor expected"), &buffer, 0, length);
+
+        assert!(action == ControlledPollAction::BREAK);
+        match dispatch.error {
+            Some(AeronError::Archive(ArchiveError::Response { error_code, .. })) => {
+                assert_eq!(error_code, ArchiveErrorCode::UnknownRecording)
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_skip_descriptors_of_other_requests() {
+        let mut data = vec![0u8; 512];
+        let length = encode_descriptor(&mut data, 5);
+        let buffer = AtomicBuffer::wrap_slice(&mut data);
+
+        let mut dispatch = dispatch(1);
+        dispatch.correlation_id = CORRELATION_ID + 1;
+        let action = dispatch.on_fragment(&mut |_| panic!("no descriptor expected"), &buffer, 0, length);
+
+        assert!(action == ControlledPollAction::CONTINUE);
+        assert_eq!(dispatch.remaining_record_count, 1);
+    }
+
+    #[test]
+    fn should_dispatch_recording_signal_while_awaiting_descriptors() {
+        let mut data = vec![0u8; 512];
+        let mut encoder =
+            RecordingSignalEventEncoder::default().wrap(WriteBuf::new(&mut data), message_header_codec::ENCODED_LENGTH);
+        encoder = encoder.header(0).parent().unwrap();
+        encoder.control_session_id(CONTROL_SESSION_ID);
+        encoder.correlation_id(CORRELATION_ID + 1);
+        encoder.recording_id(5);
+        encoder.subscription_id(9);
+        encoder.position(1024);
+        encoder.signal(RecordingSignal::STOP);
+        let length = (message_header_codec::ENCODED_LENGTH + encoder.encoded_length()) as Index;
+        let buffer = AtomicBuffer::wrap_slice(&mut data);
+
+        let signalled_position = Arc::new(AtomicI64::new(-1));
+        let position = signalled_position.clone();
+        let mut dispatch = dispatch(1);
+        dispatch.recording_signal_consumer = Box::new(move |_, _, recording_id, _, signal_position, signal| {
+            assert_eq!(recording_id, 5);
+            assert!(signal == RecordingSignal::STOP);
+            position.store(signal_position, Ordering::SeqCst);
+        });
+
+        let action = dispatch.on_fragment(&mut |_| panic!("no descriptor expected"), &buffer, 0, length);
+
+        assert!(action == ControlledPollAction::CONTINUE);
+        assert_eq!(signalled_position.load(Ordering::SeqCst), 1024);
+        assert_eq!(dispatch.remaining_record_count, 1);
+    }
+}
@@ -0,0 +1,232 @@
+use std::sync::{Arc, Mutex};
+
+use io_aeron_archive_codecs::*;
+
+use crate::archive::client::recording_events_listener::RecordingEventsListener;
+use crate::concurrent::agent_runner::Agent;
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::header::Header;
+use crate::log;
+use crate::subscription::Subscription;
+use crate::utils::errors::AeronError;
+use crate::utils::types::Index;
+
+/**
+ * Limit to apply when polling recording events.
+ */
+pub const FRAGMENT_LIMIT: i32 = 10;
+
+/**
+ * Adapter for decoding recording events from the archive's recording events stream and dispatching them
+ * to a RecordingEventsListener.
+ * <p>
+ * It can be polled from an existing duty cycle or run as an Agent under an AgentRunner.
+ */
+pub struct RecordingEventsAdapter<L: RecordingEventsListener> {
+    fragment_limit: i32,
+    subscription: Arc<Mutex<Subscription>>,
+    listener: L,
+}
+
+impl<L: RecordingEventsListener> RecordingEventsAdapter<L> {
+    /**
+     * Create a poller for a given subscription to an archive for recording events.
+     *
+     * @param listener      to which events are dispatched.
+     * @param subscription  to poll for new events.
+     * @param fragment_limit to apply for each polling operation.
+     */
+    pub fn new(listener: L, subscription: Arc<Mutex<Subscription>>, fragment_limit: i32) -> Self {
+        Self {
+            fragment_limit,
+            subscription,
+            listener,
+        }
+    }
+
+    /**
+     * Get the Subscription the recording events are polled from.
+     *
+     * @return the Subscription the recording events are polled from.
+     */
+    pub fn subscription(&self) -> Arc<Mutex<Subscription>> {
+        self.subscription.clone()
+    }
+
+    /**
+     * Get the listener events are dispatched to.
+     *
+     * @return the listener events are dispatched to.
+     */
+    pub fn listener(&mut self) -> &mut L {
+        &mut self.listener
+    }
+
+    /**
+     * Poll for recording events and dispatch them to the RecordingEventsListener for this instance.
+     *
+     * @return the number of fragments read during the operation. Zero if no events are available.
+     */
+    pub fn poll(&mut self) -> i32 {
+        let listener = &mut self.listener;
+
+        self.subscription.lock().expect("Mutex poisoned").poll(
+            &mut |buffer: &AtomicBuffer, offset: Index, length: Index, _header: &Header| {
+                on_fragment(listener, buffer, offset, length)
+            },
+            self.fragment_limit,
+        )
+    }
+}
+
+impl<L: RecordingEventsListener> Agent for RecordingEventsAdapter<L> {
+    fn on_start(&mut self) -> Result<(), AeronError> {
+        Ok(())
+    }
+
+    fn do_work(&mut self) -> Result<i32, AeronError> {
+        Ok(self.poll())
+    }
+
+    fn on_close(&mut self) -> Result<(), AeronError> {
+        Ok(())
+    }
+}
+
+fn on_fragment(listener: &mut impl RecordingEventsListener, buffer: &AtomicBuffer, offset: Index, length: Index) {
+    let header_decoder = MessageHeaderDecoder::default().wrap(ReadBuf::new(buffer.as_sub_slice(offset, length)), 0);
+
+    if header_decoder.schema_id() != recording_started_codec::SBE_SCHEMA_ID {
+        log!(
+            warn,
+            "expected schema_id={}, actual={}",
+            recording_started_codec::SBE_SCHEMA_ID,
+            header_decoder.schema_id()
+        );
+        return;
+    }
+
+    match header_decoder.template_id() {
+        recording_started_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = RecordingStartedDecoder::default().header(header_decoder);
+            let recording_id = decoder.recording_id();
+            let start_position = decoder.start_position();
+            let session_id = decoder.session_id();
+            let stream_id = decoder.stream_id();
+            let coordinates = decoder.channel_decoder();
+            let channel = String::from_utf8_lossy(decoder.channel_slice(coordinates)).into_owned();
+            let coordinates = decoder.source_identity_decoder();
+            let source_identity = String::from_utf8_lossy(decoder.source_identity_slice(coordinates)).into_owned();
+
+            listener.on_start(
+                recording_id,
+                start_position,
+                session_id,
+                stream_id,
+                &channel,
+                &source_identity,
+            );
+        }
+
+        recording_progress_codec::SBE_TEMPLATE_ID => {
+            let decoder = RecordingProgressDecoder::default().header(header_decoder);
+            listener.on_progress(decoder.recording_id(), decoder.start_position(), decoder.position());
+        }
+
+        recording_stopped_codec::SBE_TEMPLATE_ID => {
+            let decoder = RecordingStoppedDecoder::default().header(header_decoder);
+            listener.on_stop(decoder.recording_id(), decoder.start_position(), decoder.stop_position());
+        }
+
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use io_aeron_archive_codecs::*;
+
+    use crate::archive::client::recording_events_adapter::{on_fragment, RecordingEventsAdapter};
+    use crate::archive::client::recording_events_listener::RecordingEventsListener;
+    use crate::concurrent::agent_runner::Agent;
+    use crate::concurrent::atomic_buffer::AtomicBuffer;
+    use crate::utils::types::Index;
+
+    #[derive(Default)]
+    struct CapturingListener {
+        events: Vec<String>,
+    }
+
+    impl RecordingEventsListener for CapturingListener {
+        fn on_start(
+            &mut self,
+            recording_id: i64,
+            start_position: i64,
+            session_id: i32,
+            stream_id: i32,
+            channel: &str,
+            source_identity: &str,
+        ) {
+            self.events.push(format!(
+                "start {} {} {} {} {} {}",
+                recording_id, start_position, session_id, stream_id, channel, source_identity
+            ));
+        }
+
+        fn on_progress(&mut self, recording_id: i64, start_position: i64, position: i64) {
+            self.events
+                .push(format!("progress {} {} {}", recording_id, start_position, position));
+        }
+
+        fn on_stop(&mut self, recording_id: i64, start_position: i64, stop_position: i64) {
+            self.events
+                .push(format!("stop {} {} {}", recording_id, start_position, stop_position));
+        }
+    }
+
+    #[test]
+    fn should_dispatch_recording_events() {
+        let mut listener = CapturingListener::default();
+        let mut data = vec![0u8; 256];
+
+        let mut encoder = RecordingStartedEncoder::default().wrap(WriteBuf::new(&mut data), message_header_codec::ENCODED_LENGTH);
+        encoder = encoder.header(0).parent().unwrap();
+        encoder.recording_id(3);
+        encoder.start_position(0);
+        encoder.session_id(5);
+        encoder.stream_id(1001);
+        encoder.channel(b"aeron:ipc");
+        encoder.source_identity(b"aeron:ipc");
+        let length = (message_header_codec::ENCODED_LENGTH + encoder.encoded_length()) as Index;
+        on_fragment(&mut listener, &AtomicBuffer::wrap_slice(&mut data), 0, length);
+
+        let mut encoder =
+            RecordingProgressEncoder::default().wrap(WriteBuf::new(&mut data), message_header_codec::ENCODED_LENGTH);
+        encoder = encoder.header(0).parent().unwrap();
+        encoder.recording_id(3);
+        encoder.start_position(0);
+        encoder.position(1024);
+        let length = (message_header_codec::ENCODED_LENGTH + encoder.encoded_length()) as Index;
+        on_fragment(&mut listener, &AtomicBuffer::wrap_slice(&mut data), 0, length);
+
+        let mut encoder = RecordingStoppedEncoder::default().wrap(WriteBuf::new(&mut data), message_header_codec::ENCODED_LENGTH);
+        encoder = encoder.header(0).parent().unwrap();
+        encoder.recording_id(3);
+        encoder.start_position(0);
+        encoder.stop_position(2048);
+        let length = (message_header_codec::ENCODED_LENGTH + encoder.encoded_length()) as Index;
+        on_fragment(&mut listener, &AtomicBuffer::wrap_slice(&mut data), 0, length);
+
+        assert_eq!(
+            listener.events,
+            vec!["start 3 0 5 1001 aeron:ipc aeron:ipc", "progress 3 0 1024", "stop 3 0 2048"]
+        );
+    }
+
+    #[test]
+    fn should_be_runnable_by_agent_runner() {
+        fn assert_runnable<A: Agent + Send + Sync>() {}
+
+        assert_runnable::<RecordingEventsAdapter<CapturingListener>>();
+    }
+}
@@ -0,0 +1,42 @@
+/**
+ * Event listener for observing the status of recordings for an Archive.
+ */
+pub trait RecordingEventsListener {
+    /**
+     * Fired when a recording is started.
+     *
+     * @param recording_id    assigned to the new recording.
+     * @param start_position  in the stream at which the recording started.
+     * @param session_id      of the publication being recorded.
+     * @param stream_id       of the publication being recorded.
+     * @param channel        of the publication being recorded.
+     * @param source_identity of the publication being recorded.
+     */
+    fn on_start(
+        &mut self,
+        recording_id: i64,
+        start_position: i64,
+        session_id: i32,
+        stream_id: i32,
+        channel: &str,
+        source_identity: &str,
+    );
+
+    /**
+     * Progress indication of an active recording.
+     *
+     * @param recording_id   for which progress is being reported.
+     * @param start_position in the stream at which the recording started.
+     * @param position      reached in recording the publication.
+     */
+    fn on_progress(&mut self, recording_id: i64, start_position: i64, position: i64);
+
+    /**
+     * Fired when a recording is stopped.
+     *
+     * @param recording_id   of the publication that has stopped recording.
+     * @param start_position in the stream at which the recording started.
+     * @param stop_position  at which the recording stopped.
+     */
+    fn on_stop(&mut self, recording_id: i64, start_position: i64, stop_position: i64);
+}
@@ -0,0 +1,213 @@
+use crate::archive::client::aeron_archive::NULL_POSITION;
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::counters::{self, CountersReader, NULL_COUNTER_ID, RECORD_ALLOCATED};
+use crate::context::NULL_VALUE;
+use crate::utils::types::{Index, I32_SIZE, I64_SIZE};
+
+/**
+ * Type id of a recording position counter.
+ */
+pub const RECORDING_POSITION_TYPE_ID: i32 = 100;
+
+/**
+ * Represents a null recording id when not found.
+ */
+pub const NULL_RECORDING_ID: i64 = NULL_VALUE;
+
+/**
+ * Offset in the key at which the recording id is stored.
+ */
+pub const RECORDING_ID_OFFSET: Index = 0;
+
+/**
+ * Offset in the key at which the session id is stored.
+ */
+pub const SESSION_ID_OFFSET: Index = RECORDING_ID_OFFSET + I64_SIZE;
+
+/**
+ * Offset in the key at which the source identity length is stored.
+ */
+pub const SOURCE_IDENTITY_LENGTH_OFFSET: Index = SESSION_ID_OFFSET + I32_SIZE;
+
+/**
+ * Offset in the key at which the source identity is stored.
+ */
+pub const SOURCE_IDENTITY_OFFSET: Index = SOURCE_IDENTITY_LENGTH_OFFSET + I32_SIZE;
+
+/**
+ * Find the active counter id for a stream based on the recording id.
+ *
+ * @param counters_reader to search within.
+ * @param recording_id    for the active recording.
+ * @return the counter id if found otherwise NULL_COUNTER_ID.
+ */
+pub fn find_counter_id_by_recording(counters_reader: &CountersReader, recording_id: i64) -> i32 {
+    find_counter_id(counters_reader, |key| key.get::<i64>(RECORDING_ID_OFFSET) == recording_id)
+}
+
+/**
+ * Find the active counter id for a stream based on the session id.
+ *
+ * @param counters_reader to search within.
+ * @param session_id      for the active recording.
+ * @return the counter id if found otherwise NULL_COUNTER_ID.
+ */
+pub fn find_counter_id_by_session(counters_reader: &CountersReader, session_id: i32) -> i32 {
+    find_counter_id(counters_reader, |key| key.get::<i32>(SESSION_ID_OFFSET) == session_id)
+}
+
+/**
+ * Get the recording id for a given counter id.
+ *
+ * @param counters_reader to search within.
+ * @param counter_id      for the active recording.
+ * @return the recording id if found otherwise NULL_RECORDING_ID.
+ */
+pub fn get_recording_id(counters_reader: &CountersReader, counter_id: i32) -> i64 {
+    match key_offset(counters_reader, counter_id) {
+        Some(key_offset) => counters_reader
+            .meta_data_buffer()
+            .get::<i64>(key_offset + RECORDING_ID_OFFSET),
+        None => NULL_RECORDING_ID,
+    }
+}
+
+/**
+ * Get the Image::source_identity for the recording.
+ *
+ * @param counters_reader to search within.
+ * @param counter_id      for the active recording.
+ * @return Image::source_identity for the recording or None if not found.
+ */
+pub fn get_source_identity(counters_reader: &CountersReader, counter_id: i32) -> Option<String> {
+    let key_offset = key_offset(counters_reader, counter_id)?;
+    let buffer = counters_reader.meta_data_buffer();
+    let length = buffer.get::<i32>(key_offset + SOURCE_IDENTITY_LENGTH_OFFSET);
+
+    // IC: the source identity is truncated by the archive to what fits in the key
+    let length = length.clamp(0, counters::MAX_KEY_LENGTH - SOURCE_IDENTITY_OFFSET);
+    let bytes = buffer.as_sub_slice(key_offset + SOURCE_IDENTITY_OFFSET, length);
+
+    Some(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/**
+ * Is the recording counter still active.
+ *
+ * @param counters_reader to search within.
+ * @param counter_id      to search for.
+ * @param recording_id    to confirm it is still the same value.
+ * @return true if the counter is still active otherwise false.
+ */
+pub fn is_active(counters_reader: &CountersReader, counter_id: i32, recording_id: i64) -> bool {
+    get_recording_id(counters_reader, counter_id) == recording_id && recording_id != NULL_RECORDING_ID
+}
+
+/**
+ * Get the current position of an active recording.
+ *
+ * @param counters_reader to search within.
+ * @param counter_id      for the active recording.
+ * @return the recorded position or NULL_POSITION if the counter is not a recording position.
+ */
+pub fn get_recording_position(counters_reader: &CountersReader, counter_id: i32) -> i64 {
+    if key_offset(counters_reader, counter_id).is_none() {
+        return NULL_POSITION;
+    }
+
+    counters_reader
+        .counter_value(counter_id)
+        .map_or(NULL_POSITION, |position| position as i64)
+}
+
+fn find_counter_id(counters_reader: &CountersReader, mut predicate: impl FnMut(&AtomicBuffer) -> bool) -> i32 {
+    let mut found = NULL_COUNTER_ID;
+
+    counters_reader.for_each(|counter_id, type_id, key, _label| {
+        if found == NULL_COUNTER_ID && type_id == RECORDING_POSITION_TYPE_ID && predicate(key) {
+            found = counter_id;
+        }
+    });
+
+    found
+}
+
+fn key_offset(counters_reader: &CountersReader, counter_id: i32) -> Option<Index> {
+    if counter_id < 0 || counter_id >= counters_reader.max_counter_id() {
+        return None;
+    }
+
+    let buffer = counters_reader.meta_data_buffer();
+    let record_offset = CountersReader::metadata_offset(counter_id);
+
+    if buffer.get_volatile::<i32>(record_offset) == RECORD_ALLOCATED
+        && buffer.get::<i32>(record_offset + *counters::TYPE_ID_OFFSET) == RECORDING_POSITION_TYPE_ID
+    {
+        Some(record_offset + *counters::KEY_OFFSET)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::archive::client::recording_pos::{
+        find_counter_id_by_recording, find_counter_id_by_session, get_recording_id, get_recording_position, get_source_identity,
+        is_active, NULL_RECORDING_ID, RECORDING_ID_OFFSET, RECORDING_POSITION_TYPE_ID, SESSION_ID_OFFSET,
+        SOURCE_IDENTITY_LENGTH_OFFSET, SOURCE_IDENTITY_OFFSET,
+    };
+    use crate::concurrent::atomic_buffer::{AlignedBuffer, AtomicBuffer};
+    use crate::concurrent::counters::{self, CountersManager, CountersReader, NULL_COUNTER_ID};
+    use crate::utils::types::Index;
+
+    const NUM_COUNTERS: Index = 4;
+
+    fn allocate_recording_pos(manager: &mut CountersManager, recording_id: i64, session_id: i32, source: &str) -> i32 {
+        let mut key = vec![0u8; SOURCE_IDENTITY_OFFSET as usize + source.len()];
+        let buffer = AtomicBuffer::wrap_slice(&mut key);
+        buffer.put::<i64>(RECORDING_ID_OFFSET, recording_id);
+        buffer.put::<i32>(SESSION_ID_OFFSET, session_id);
+        buffer.put::<i32>(SOURCE_IDENTITY_LENGTH_OFFSET, source.len() as i32);
+        buffer.put_bytes(SOURCE_IDENTITY_OFFSET, source.as_bytes());
+
+        manager
+            .allocate_opt(
+                RECORDING_POSITION_TYPE_ID,
+                Some(&key),
+                Option::<fn(&mut AtomicBuffer)>::None,
+                "rec-pos",
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn should_find_recording_counters() {
+        let m_buff = AlignedBuffer::with_capacity(NUM_COUNTERS * counters::METADATA_LENGTH);
+        let v_buff = AlignedBuffer::with_capacity(NUM_COUNTERS * counters::COUNTER_LENGTH);
+        let metadata_buffer = AtomicBuffer::from_aligned(&m_buff);
+        let values_buffer = AtomicBuffer::from_aligned(&v_buff);
+        let mut manager = CountersManager::new(metadata_buffer, values_buffer);
+        let reader = &CountersReader::new(metadata_buffer, values_buffer);
+
+        let other_id = manager.allocate("other").unwrap();
+        let first_id = allocate_recording_pos(&mut manager, 7, 100, "127.0.0.1:40123");
+        let second_id = allocate_recording_pos(&mut manager, 8, 200, "aeron:ipc");
+        manager.set_counter_value(second_id, 4096);
+
+        assert_eq!(find_counter_id_by_recording(reader, 8), second_id);
+        assert_eq!(find_counter_id_by_session(reader, 100), first_id);
+        assert_eq!(find_counter_id_by_session(reader, 300), NULL_COUNTER_ID);
+
+        assert_eq!(get_recording_id(reader, first_id), 7);
+        assert_eq!(get_recording_id(reader, other_id), NULL_RECORDING_ID);
+        assert_eq!(get_source_identity(reader, first_id).unwrap(), "127.0.0.1:40123");
+        assert_eq!(get_recording_position(reader, second_id), 4096);
+
+        assert!(is_active(reader, second_id, 8));
+        assert!(!is_active(reader, second_id, 7));
+
+        manager.free(second_id);
+        assert!(!is_active(reader, second_id, 8));
+        assert_eq!(find_counter_id_by_recording(reader, 8), NULL_COUNTER_ID);
+    }
+}
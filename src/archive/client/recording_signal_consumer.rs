@@ -0,0 +1,76 @@
+use io_aeron_archive_codecs::RecordingSignal;
+
+/**
+ * Interface for consuming RecordingSignal notifications which are delivered on the control response channel
+ * of an archive session, e.g. when a recording is started, extended, replicated or merged.
+ */
+pub trait RecordingSignalConsumer {
+    /**
+     * Signal of operation taken on a recording.
+     *
+     * @param control_session_id that initiated the operation.
+     * @param correlation_id    that initiated the operation, could be the replication id.
+     * @param recording_id      which has signalled.
+     * @param subscription_id   of the Subscription associated with the recording.
+     * @param position         of the recorded stream at the point of signal.
+     * @param signal           type of the operation applied to the recording.
+     */
+    fn on_signal(
+        &self,
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+        subscription_id: i64,
+        position: i64,
+        signal: RecordingSignal,
+    );
+
+    fn clone_box(&self) -> Box<dyn RecordingSignalConsumer + Send>;
+}
+
+impl Clone for Box<dyn RecordingSignalConsumer + Send> {
+    fn clone(&self) -> Box<dyn RecordingSignalConsumer + Send> {
+        self.clone_box()
+    }
+}
+
+impl<F> RecordingSignalConsumer for F
+where
+    F: Fn(i64, i64, i64, i64, i64, RecordingSignal) + Clone + Send + 'static,
+{
+    fn on_signal(
+        &self,
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+        subscription_id: i64,
+        position: i64,
+        signal: RecordingSignal,
+    ) {
+        self(
+            control_session_id,
+            correlation_id,
+            recording_id,
+            subscription_id,
+            position,
+            signal,
+        )
+    }
+
+    fn clone_box(&self) -> Box<dyn RecordingSignalConsumer + Send> {
+        Box::new(self.clone())
+    }
+}
+
+/**
+ * Consumer which ignores all signals, the default for an archive Context.
+ */
+pub fn no_op_recording_signal_consumer(
+    _control_session_id: i64,
+    _correlation_id: i64,
+    _recording_id: i64,
+    _subscription_id: i64,
+    _position: i64,
+    _signal: RecordingSignal,
+) {
+}
@@ -0,0 +1,632 @@
+use std::sync::{Arc, Mutex};
+
+use io_aeron_archive_codecs::ControlResponseCode;
+
+use crate::agrona::concurrent::system_nano_clock::NanoClock;
+use crate::archive::client::aeron_archive::{AeronArchive, NULL_POSITION};
+use crate::channel_uri::{
+    ChannelUri, ENDPOINT_PARAM_NAME, EOS_PARAM_NAME, IPC_CHANNEL, LINGER_PARAM_NAME, MDC_CONTROL_MODE_MANUAL,
+    MDC_CONTROL_MODE_PARAM_NAME,
+};
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::header::Header;
+use crate::context::NULL_VALUE;
+use crate::subscription::Subscription;
+use crate::utils::errors::{AeronError, ArchiveError, ArchiveErrorCode, IllegalArgumentError, IllegalStateError};
+use crate::utils::types::Index;
+
+/**
+ * The maximum window at which a live destination should be added when trying to merge.
+ */
+pub const LIVE_ADD_MAX_WINDOW: i64 = 32 * 1024 * 1024;
+
+/**
+ * The default timeout for a merge to make progress in milliseconds.
+ */
+pub const MERGE_PROGRESS_TIMEOUT_DEFAULT_MS: i64 = 5_000;
+
+const REPLAY_REMOVE_THRESHOLD: i64 = 0;
+const INITIAL_GET_RECORDING_POSITION_BACKOFF_MS: i64 = 8;
+const GET_RECORDING_POSITION_BACKOFF_MAX_MS: i64 = 500;
+
+/**
+ * The steps a ReplayMerge goes through on the way to being merged with the live stream.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMergeState {
+    ResolveReplayPort,
+    GetRecordingPosition,
+    Replay,
+    Catchup,
+    AttemptLiveJoin,
+    Merged,
+    Failed,
+    Closed,
+}
+
+impl ReplayMergeState {
+    fn description(self) -> &'static str {
+        match self {
+            ReplayMergeState::ResolveReplayPort => "RESOLVE_REPLAY_PORT",
+            ReplayMergeState::GetRecordingPosition => "GET_RECORDING_POSITION",
+            ReplayMergeState::Replay => "REPLAY",
+            ReplayMergeState::Catchup => "CATCHUP",
+            ReplayMergeState::AttemptLiveJoin => "ATTEMPT_LIVE_JOIN",
+            ReplayMergeState::Merged => "MERGED",
+            ReplayMergeState::Failed => "FAILED",
+            ReplayMergeState::Closed => "CLOSED",
+        }
+    }
+}
+
+/**
+ * Replay a recorded stream from a starting position and merge with live stream for a full history of a stream.
+ * <p>
+ * Once constructed either of poll() or do_work(), interleaved with consumption of the image(), should be
+ * called in a duty cycle loop until is_merged() is true. After which the ReplayMerge can be dropped and
+ * continued usage can be made of the Subscription or its Image. If an error occurs or progress stops, the
+ * merge will fail and has_failed() will be true.
+ * <p>
+ * If the endpoint on the replay destination uses a port of 0, then the OS will assign a port from the ephemeral
+ * range and this will be added to the replay channel for instructing the archive.
+ * <p>
+ * NOTE: Merging is only supported with UDP streams.
+ * <p>
+ * NOTE: ReplayMerge is not threadsafe and should not be used with a shared AeronArchive client.
+ */
+pub struct ReplayMerge<'a> {
+    recording_id: i64,
+    start_position: i64,
+    merge_progress_timeout_ms: i64,
+    replay_session_id: i64,
+    active_correlation_id: i64,
+    next_target_position: i64,
+    position_of_last_progress: i64,
+    time_of_last_progress_ms: i64,
+    time_of_next_get_recording_position_ms: i64,
+    get_recording_position_backoff_ms: i64,
+    is_live_added: bool,
+    is_replay_active: bool,
+    state: ReplayMergeState,
+    // IC: images are owned by the subscription, so only the session id of the replay image is kept
+    image_session_id: Option<i32>,
+    nano_clock: NanoClock,
+    replay_destination: String,
+    live_destination: String,
+    replay_endpoint: String,
+    replay_channel_uri: Arc<Mutex<ChannelUri>>,
+    subscription: Arc<Mutex<Subscription>>,
+    archive: &'a mut AeronArchive,
+}
+
+impl<'a> ReplayMerge<'a> {
+    /**
+     * Create a ReplayMerge to manage the merging of a replayed stream and switching over to live stream as
+     * appropriate.
+     *
+     * @param subscription           to use for the replay and live stream. Must be a multi-destination subscription.
+     * @param archive                to use for the replay.
+     * @param replay_channel          to use as a template for what the archive will use.
+     * @param replay_destination      to send the replay to and the destination added by the Subscription.
+     * @param live_destination        for the live stream and the destination added by the Subscription.
+     * @param recording_id            for the replay.
+     * @param start_position          for the replay.
+     * @param merge_progress_timeout_ms timeout for making progress towards a merge.
+     */
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        subscription: Arc<Mutex<Subscription>>,
+        archive: &'a mut AeronArchive,
+        replay_channel: &str,
+        replay_destination: &str,
+        live_destination: &str,
+        recording_id: i64,
+        start_position: i64,
+        merge_progress_timeout_ms: i64,
+    ) -> Result<Self, AeronError> {
+        let subscription_channel = subscription
+            .lock()
+            .expect("Mutex poisoned")
+            .channel()
+            .to_string_lossy()
+            .into_owned();
+
+        if subscription_channel.starts_with(IPC_CHANNEL)
+            || replay_channel.starts_with(IPC_CHANNEL)
+            || replay_destination.starts_with(IPC_CHANNEL)
+            || live_destination.starts_with(IPC_CHANNEL)
+        {
+            return Err(IllegalArgumentError::IpcMergingNotSupported.into());
+        }
+
+        let manual_control_mode = format!("{}={}", MDC_CONTROL_MODE_PARAM_NAME, MDC_CONTROL_MODE_MANUAL);
+        if !subscription_channel.contains(&manual_control_mode) {
+            return Err(IllegalArgumentError::SubscriptionMustUseManualControlMode {
+                uri: subscription_channel,
+            }
+            .into());
+        }
+
+        let replay_channel_uri = ChannelUri::parse(replay_channel)?;
+        replay_channel_uri
+            .lock()
+            .expect("Mutex poisoned")
+            .put(LINGER_PARAM_NAME, String::from("0"));
+        replay_channel_uri
+            .lock()
+            .expect("Mutex poisoned")
+            .put(EOS_PARAM_NAME, String::from("false"));
+
+        let replay_endpoint = ChannelUri::parse(replay_destination)?
+            .lock()
+            .expect("Mutex poisoned")
+            .get(ENDPOINT_PARAM_NAME)
+            .to_string();
+
+        let state = if replay_endpoint.ends_with(":0") {
+            ReplayMergeState::ResolveReplayPort
+        } else {
+            replay_channel_uri
+                .lock()
+                .expect("Mutex poisoned")
+                .put(ENDPOINT_PARAM_NAME, replay_endpoint.clone());
+            ReplayMergeState::GetRecordingPosition
+        };
+
+        subscription
+            .lock()
+            .expect("Mutex poisoned")
+            .add_destination(String::from(replay_destination))?;
+
+        let nano_clock = NanoClock::instance();
+        let now_ms = nano_clock.nano_time() / 1_000_000;
+
+        Ok(Self {
+            recording_id,
+            start_position,
+            merge_progress_timeout_ms,
+            replay_session_id: NULL_VALUE,
+            active_correlation_id: NULL_VALUE,
+            next_target_position: NULL_VALUE,
+            position_of_last_progress: NULL_VALUE,
+            time_of_last_progress_ms: now_ms,
+            time_of_next_get_recording_position_ms: now_ms,
+            get_recording_position_backoff_ms: INITIAL_GET_RECORDING_POSITION_BACKOFF_MS,
+            is_live_added: false,
+            is_replay_active: false,
+            state,
+            image_session_id: None,
+            nano_clock,
+            replay_destination: String::from(replay_destination),
+            live_destination: String::from(live_destination),
+            replay_endpoint,
+            replay_channel_uri,
+            subscription,
+            archive,
+        })
+    }
+
+    /**
+     * Get the Subscription used to consume the replayed and merged stream.
+     *
+     * @return the Subscription used to consume the replayed and merged stream.
+     */
+    pub fn subscription(&self) -> Arc<Mutex<Subscription>> {
+        self.subscription.clone()
+    }
+
+    /**
+     * Get the current state of the merge.
+     *
+     * @return the current state of the merge.
+     */
+    pub fn state(&self) -> ReplayMergeState {
+        self.state
+    }
+
+    /**
+     * Process the operation of the merge. Do not call the processing of fragments on the subscription.
+     *
+     * @return indication of work done processing the merge.
+     */
+    pub fn do_work(&mut self) -> Result<i32, AeronError> {
+        let now_ms = self.nano_clock.nano_time() / 1_000_000;
+
+        let result = match self.state {
+            ReplayMergeState::ResolveReplayPort => self.resolve_replay_port(now_ms),
+            ReplayMergeState::GetRecordingPosition => self.get_recording_position(now_ms),
+            ReplayMergeState::Replay => self.replay(now_ms),
+            ReplayMergeState::Catchup => self.catchup(now_ms),
+            ReplayMergeState::AttemptLiveJoin => self.attempt_live_join(now_ms),
+            _ => return Ok(0),
+        };
+
+        match result.and_then(|work_count| self.check_progress(now_ms).map(|_| work_count)) {
+            Ok(work_count) => Ok(work_count),
+            Err(err) => {
+                self.state = ReplayMergeState::Failed;
+                Err(err)
+            }
+        }
+    }
+
+    /**
+     * Poll the Image used for the merging replay and live stream. The do_work() method will be called before the
+     * poll so that processing of the merge can be done.
+     *
+     * @param fragment_handler to call for fragments.
+     * @param fragment_limit   for poll call.
+     * @return number of fragments processed.
+     */
+    pub fn poll(
+        &mut self,
+        fragment_handler: &mut impl FnMut(&AtomicBuffer, Index, Index, &Header),
+        fragment_limit: i32,
+    ) -> Result<i32, AeronError> {
+        self.do_work()?;
+
+        let session_id = match self.image_session_id {
+            Some(session_id) => session_id,
+            None => return Ok(0),
+        };
+
+        let mut subscription = self.subscription.lock().expect("Mutex poisoned");
+        let index = subscription
+            .images()
+            .iter()
+            .position(|image| image.session_id() == session_id);
+
+        Ok(index
+            .and_then(|index| subscription.image_by_index(index))
+            .map_or(0, |image| image.poll(fragment_handler, fragment_limit)))
+    }
+
+    /**
+     * Is the live stream merged and the replay stopped?
+     *
+     * @return true if live stream is merged and the replay stopped or false if not.
+     */
+    pub fn is_merged(&self) -> bool {
+        self.state == ReplayMergeState::Merged
+    }
+
+    /**
+     * Has the replay merge failed due to an error?
+     *
+     * @return true if replay merge has failed due to an error.
+     */
+    pub fn has_failed(&self) -> bool {
+        self.state == ReplayMergeState::Failed
+    }
+
+    /**
+     * Is the live destination added to the subscription?
+     *
+     * @return true if live destination added to the subscription.
+     */
+    pub fn is_live_added(&self) -> bool {
+        self.is_live_added
+    }
+
+    /**
+     * The session id of the Image used for the replay and merged live stream.
+     *
+     * @return the session id of the Image for the replay and live stream, None if not yet available.
+     */
+    pub fn image_session_id(&self) -> Option<i32> {
+        self.image_session_id
+    }
+
+    /**
+     * Close and stop any active replay. Will remove the replay destination from the subscription.
+     * This operation will not remove the live destination if it has been added, so it can be used for
+     * further consumption.
+     */
+    pub fn close(mut self) {
+        self.close_merge();
+    }
+
+    fn close_merge(&mut self) {
+        if self.state == ReplayMergeState::Closed {
+            return;
+        }
+
+        if !self.archive.is_closed() {
+            if self.state != ReplayMergeState::Merged {
+                let _ = self
+                    .subscription
+                    .lock()
+                    .expect("Mutex poisoned")
+                    .remove_destination(self.replay_destination.clone());
+            }
+
+            let is_connected = self.archive.archive_proxy().publication().is_connected();
+            if self.is_replay_active && is_connected {
+                let _ = self.stop_replay();
+            }
+        }
+
+        self.state = ReplayMergeState::Closed;
+    }
+
+    fn resolve_replay_port(&mut self, now_ms: i64) -> Result<i32, AeronError> {
+        let resolved_endpoint = self
+            .subscription
+            .lock()
+            .expect("Mutex poisoned")
+            .local_socket_addresses()
+            .into_iter()
+            .next();
+
+        if let Some(resolved_endpoint) = resolved_endpoint {
+            if let Some(i) = resolved_endpoint.rfind(':') {
+                let endpoint = format!(
+                    "{}{}",
+                    &self.replay_endpoint[..self.replay_endpoint.len() - 2],
+                    &resolved_endpoint[i..]
+                );
+                self.replay_channel_uri
+                    .lock()
+                    .expect("Mutex poisoned")
+                    .put(ENDPOINT_PARAM_NAME, endpoint);
+
+                self.time_of_last_progress_ms = now_ms;
+                self.state = ReplayMergeState::GetRecordingPosition;
+                return Ok(1);
+            }
+        }
+
+        Ok(0)
+    }
+
+    fn get_recording_position(&mut self, now_ms: i64) -> Result<i32, AeronError> {
+        let mut work_count = 0;
+
+        if self.active_correlation_id == NULL_VALUE {
+            if self.call_get_recording_position(now_ms)? {
+                self.time_of_last_progress_ms = now_ms;
+                work_count += 1;
+            }
+        } else if self.poll_for_response()? {
+            self.next_target_position = self.archive.control_response_poller().relevant_id();
+            self.active_correlation_id = NULL_VALUE;
+
+            if self.next_target_position == NULL_POSITION {
+                // IC: recording is not active, so the stop position is where the replay has to get to
+                let correlation_id = self.next_correlation_id()?;
+                let control_session_id = self.archive.control_session_id();
+                if self
+                    .archive
+                    .archive_proxy()
+                    .get_stop_position(self.recording_id, correlation_id, control_session_id)?
+                {
+                    self.active_correlation_id = correlation_id;
+                    self.time_of_last_progress_ms = now_ms;
+                }
+            } else {
+                self.time_of_last_progress_ms = now_ms;
+                self.state = ReplayMergeState::Replay;
+            }
+
+            work_count += 1;
+        }
+
+        Ok(work_count)
+    }
+
+    fn replay(&mut self, now_ms: i64) -> Result<i32, AeronError> {
+        let mut work_count = 0;
+
+        if self.active_correlation_id == NULL_VALUE {
+            let correlation_id = self.next_correlation_id()?;
+            let replay_channel = self.replay_channel_uri.lock().expect("Mutex poisoned").to_string();
+            let stream_id = self.subscription.lock().expect("Mutex poisoned").stream_id();
+            let control_session_id = self.archive.control_session_id();
+
+            if self.archive.archive_proxy().replay(
+                self.recording_id,
+                self.start_position,
+                i64::MAX,
+                &replay_channel,
+                stream_id,
+                correlation_id,
+                control_session_id,
+            )? {
+                self.active_correlation_id = correlation_id;
+                self.time_of_last_progress_ms = now_ms;
+                work_count += 1;
+            }
+        } else if self.poll_for_response()? {
+            self.is_replay_active = true;
+            self.replay_session_id = self.archive.control_response_poller().relevant_id();
+            self.time_of_last_progress_ms = now_ms;
+            self.active_correlation_id = NULL_VALUE;
+
+            // IC: reset the backoff when moving to CATCHUP
+            self.get_recording_position_backoff_ms = INITIAL_GET_RECORDING_POSITION_BACKOFF_MS;
+            self.time_of_next_get_recording_position_ms = now_ms;
+
+            self.state = ReplayMergeState::Catchup;
+            work_count += 1;
+        }
+
+        Ok(work_count)
+    }
+
+    fn catchup(&mut self, now_ms: i64) -> Result<i32, AeronError> {
+        let mut work_count = 0;
+        let subscription = self.subscription.lock().expect("Mutex poisoned");
+
+        if self.image_session_id.is_none() && subscription.is_connected() {
+            self.time_of_last_progress_ms = now_ms;
+
+            let image = subscription.image_by_session_id(self.replay_session_id as i32);
+            self.image_session_id = image.map(|image| image.session_id());
+            self.position_of_last_progress = image.map_or(NULL_VALUE, |image| image.position());
+        }
+
+        if let Some(image) = self
+            .image_session_id
+            .and_then(|session_id| subscription.image_by_session_id(session_id))
+        {
+            let position = image.position();
+
+            if position >= self.next_target_position {
+                self.time_of_last_progress_ms = now_ms;
+                self.position_of_last_progress = position;
+                self.active_correlation_id = NULL_VALUE;
+                self.state = ReplayMergeState::AttemptLiveJoin;
+                work_count += 1;
+            } else if position > self.position_of_last_progress {
+                self.time_of_last_progress_ms = now_ms;
+                self.position_of_last_progress = position;
+            } else if image.is_closed() {
+                return Err(IllegalStateError::MergeImageClosed.into());
+            }
+        }
+
+        Ok(work_count)
+    }
+
+    fn attempt_live_join(&mut self, now_ms: i64) -> Result<i32, AeronError> {
+        let mut work_count = 0;
+
+        if self.active_correlation_id == NULL_VALUE {
+            if self.call_get_recording_position(now_ms)? {
+                self.time_of_last_progress_ms = now_ms;
+                work_count += 1;
+            }
+        } else if self.poll_for_response()? {
+            self.next_target_position = self.archive.control_response_poller().relevant_id();
+            self.active_correlation_id = NULL_VALUE;
+
+            if self.next_target_position != NULL_POSITION {
+                let mut next_state = ReplayMergeState::Catchup;
+
+                let image_state = {
+                    let subscription = self.subscription.lock().expect("Mutex poisoned");
+                    self.image_session_id
+                        .and_then(|session_id| subscription.image_by_session_id(session_id))
+                        .map(|image| (image.position(), image.term_buffer_length(), image.active_transport_count()))
+                };
+
+                if let Some((position, term_buffer_length, active_transport_count)) = image_state {
+                    if self.should_add_live_destination(position, term_buffer_length) {
+                        self.subscription
+                            .lock()
+                            .expect("Mutex poisoned")
+                            .add_destination(self.live_destination.clone())?;
+                        self.time_of_last_progress_ms = now_ms;
+                        self.position_of_last_progress = position;
+                        self.is_live_added = true;
+                    } else if self.should_stop_and_remove_replay(position, active_transport_count) {
+                        self.subscription
+                            .lock()
+                            .expect("Mutex poisoned")
+                            .remove_destination(self.replay_destination.clone())?;
+                        self.stop_replay()?;
+                        self.time_of_last_progress_ms = now_ms;
+                        self.position_of_last_progress = position;
+                        next_state = ReplayMergeState::Merged;
+                    } else if position > self.position_of_last_progress {
+                        self.time_of_last_progress_ms = now_ms;
+                        self.position_of_last_progress = position;
+                    }
+                }
+
+                self.state = next_state;
+            }
+
+            work_count += 1;
+        }
+
+        Ok(work_count)
+    }
+
+    fn call_get_recording_position(&mut self, now_ms: i64) -> Result<bool, AeronError> {
+        if now_ms < self.time_of_next_get_recording_position_ms {
+            return Ok(false);
+        }
+
+        let correlation_id = self.next_correlation_id()?;
+        let control_session_id = self.archive.control_session_id();
+        let result =
+            self.archive
+                .archive_proxy()
+                .get_recording_position(self.recording_id, correlation_id, control_session_id)?;
+
+        if result {
+            self.active_correlation_id = correlation_id;
+        }
+
+        // IC: back off regardless of the result, the archive should not be hammered while the replay catches up
+        self.get_recording_position_backoff_ms =
+            (self.get_recording_position_backoff_ms * 2).min(GET_RECORDING_POSITION_BACKOFF_MAX_MS);
+        self.time_of_next_get_recording_position_ms = now_ms + self.get_recording_position_backoff_ms;
+
+        Ok(result)
+    }
+
+    fn stop_replay(&mut self) -> Result<(), AeronError> {
+        let correlation_id = self.next_correlation_id()?;
+        let control_session_id = self.archive.control_session_id();
+
+        if self
+            .archive
+            .archive_proxy()
+            .stop_replay(self.replay_session_id, correlation_id, control_session_id)?
+        {
+            self.is_replay_active = false;
+        }
+
+        Ok(())
+    }
+
+    fn check_progress(&self, now_ms: i64) -> Result<(), AeronError> {
+        if now_ms > self.time_of_last_progress_ms + self.merge_progress_timeout_ms {
+            return Err(ArchiveError::MergeNoProgress(self.state.description()).into());
+        }
+
+        Ok(())
+    }
+
+    fn should_add_live_destination(&self, position: i64, term_buffer_length: i32) -> bool {
+        !self.is_live_added
+            && (self.next_target_position - position) <= ((term_buffer_length >> 2) as i64).min(LIVE_ADD_MAX_WINDOW)
+    }
+
+    fn should_stop_and_remove_replay(&self, position: i64, active_transport_count: i32) -> bool {
+        self.is_live_added && (self.next_target_position - position) <= REPLAY_REMOVE_THRESHOLD && active_transport_count >= 2
+    }
+
+    fn next_correlation_id(&self) -> Result<i64, AeronError> {
+        self.archive.aeron().lock().expect("Mutex poisoned").next_correlation_id()
+    }
+
+    fn poll_for_response(&mut self) -> Result<bool, AeronError> {
+        let correlation_id = self.active_correlation_id;
+        let control_session_id = self.archive.control_session_id();
+        let poller = self.archive.control_response_poller();
+
+        if poller.poll() > 0 && poller.is_poll_complete() && poller.control_session_id() == control_session_id {
+            if poller.code() == ControlResponseCode::ERROR {
+                return Err(ArchiveError::Response {
+                    correlation_id: poller.correlation_id(),
+                    error_code: ArchiveErrorCode::from(poller.relevant_id()),
+                    message: poller.error_message().to_string(),
+                }
+                .into());
+            }
+
+            return Ok(poller.correlation_id() == correlation_id);
+        }
+
+        Ok(false)
+    }
+}
+
+impl<'a> Drop for ReplayMerge<'a> {
+    fn drop(&mut self) {
+        self.close_merge();
+    }
+}
@@ -0,0 +1,138 @@
+use crate::archive::client::aeron_archive::NULL_POSITION;
+use crate::context::NULL_VALUE;
+
+/**
+ * Optional parameters for a replication request to an archive. Defaults replicate a new recording
+ * from the source archive and, when it catches up, keep following it until the source recording stops.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplicationParams {
+    stop_position: i64,
+    dst_recording_id: i64,
+    live_destination: String,
+    replication_channel: String,
+    channel_tag_id: i64,
+    subscription_tag_id: i64,
+    file_io_max_length: i32,
+}
+
+impl Default for ReplicationParams {
+    fn default() -> Self {
+        Self {
+            stop_position: NULL_POSITION,
+            dst_recording_id: NULL_VALUE,
+            live_destination: String::new(),
+            replication_channel: String::new(),
+            channel_tag_id: NULL_VALUE,
+            subscription_tag_id: NULL_VALUE,
+            file_io_max_length: NULL_VALUE as i32,
+        }
+    }
+}
+
+impl ReplicationParams {
+    pub fn stop_position(&self) -> i64 {
+        self.stop_position
+    }
+
+    /**
+     * Set the position at which the replication should stop. NULL_POSITION to keep replicating.
+     *
+     * @param stop_position at which the replication stops.
+     * @return reference to this ReplicationParams instance
+     */
+    pub fn set_stop_position(&mut self, stop_position: i64) -> &Self {
+        self.stop_position = stop_position;
+        self
+    }
+
+    pub fn dst_recording_id(&self) -> i64 {
+        self.dst_recording_id
+    }
+
+    /**
+     * Set the recording in the destination archive to extend. NULL_VALUE creates a new recording.
+     *
+     * @param dst_recording_id to be extended by the replication.
+     * @return reference to this ReplicationParams instance
+     */
+    pub fn set_dst_recording_id(&mut self, dst_recording_id: i64) -> &Self {
+        self.dst_recording_id = dst_recording_id;
+        self
+    }
+
+    pub fn live_destination(&self) -> &str {
+        &self.live_destination
+    }
+
+    /**
+     * Set the destination for the live stream if a merge is required. Empty when no merge is required.
+     *
+     * @param live_destination for the live stream.
+     * @return reference to this ReplicationParams instance
+     */
+    pub fn set_live_destination(&mut self, live_destination: String) -> &Self {
+        self.live_destination = live_destination;
+        self
+    }
+
+    pub fn replication_channel(&self) -> &str {
+        &self.replication_channel
+    }
+
+    /**
+     * Set the channel over which the replication takes place. Empty to use the archive's default.
+     *
+     * @param replication_channel to replicate over.
+     * @return reference to this ReplicationParams instance
+     */
+    pub fn set_replication_channel(&mut self, replication_channel: String) -> &Self {
+        self.replication_channel = replication_channel;
+        self
+    }
+
+    pub fn channel_tag_id(&self) -> i64 {
+        self.channel_tag_id
+    }
+
+    /**
+     * Set the tag used for the replication subscription channel. NULL_VALUE to use the replication id.
+     *
+     * @param channel_tag_id for the replication subscription.
+     * @return reference to this ReplicationParams instance
+     */
+    pub fn set_channel_tag_id(&mut self, channel_tag_id: i64) -> &Self {
+        self.channel_tag_id = channel_tag_id;
+        self
+    }
+
+    pub fn subscription_tag_id(&self) -> i64 {
+        self.subscription_tag_id
+    }
+
+    /**
+     * Set the tag used for the replication subscription. NULL_VALUE to use the replication id.
+     *
+     * @param subscription_tag_id for the replication subscription.
+     * @return reference to this ReplicationParams instance
+     */
+    pub fn set_subscription_tag_id(&mut self, subscription_tag_id: i64) -> &Self {
+        self.subscription_tag_id = subscription_tag_id;
+        self
+    }
+
+    pub fn file_io_max_length(&self) -> i32 {
+        self.file_io_max_length
+    }
+
+    /**
+     * Set the maximum length of a file IO operation while replicating. NULL_VALUE to use the archive's default.
+     *
+     * @param file_io_max_length for reading and writing recording files.
+     * @return reference to this ReplicationParams instance
+     */
+    pub fn set_file_io_max_length(&mut self, file_io_max_length: i32) -> &Self {
+        self.file_io_max_length = file_io_max_length;
+        self
+    }
+}
@@ -1 +1,4 @@
+pub mod archive_mark_file;
+pub mod catalog;
 pub mod client;
+pub mod segment_reader;
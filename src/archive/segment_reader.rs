@@ -0,0 +1,649 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::archive::client::aeron_archive::{segment_file_base_position, NULL_POSITION};
+use crate::archive::client::recording_descriptor_consumer::RecordingDescriptor;
+use crate::concurrent::atomic_buffer::AtomicBuffer;
+use crate::concurrent::logbuffer::{data_frame_header, frame_descriptor, header::Header, log_buffer_descriptor, term_reader};
+use crate::utils::bit_utils::align;
+use crate::utils::errors::{AeronError, IllegalArgumentError, IllegalStateError};
+use crate::utils::memory_mapped_file::MemoryMappedFile;
+use crate::utils::types::Index;
+
+/**
+ * Suffix of the files in the archive directory which contain the segments of a recording.
+ */
+pub const SEGMENT_FILE_SUFFIX: &str = ".rec";
+
+/**
+ * Name of the file for the segment of a recording which begins at a given position.
+ *
+ * @param recording_id          of the recording.
+ * @param segment_base_position at which the segment begins.
+ * @return the name of the segment file in the archive directory.
+ */
+pub fn segment_file_name(recording_id: i64, segment_base_position: i64) -> String {
+    format!("{}-{}{}", recording_id, segment_base_position, SEGMENT_FILE_SUFFIX)
+}
+
+/**
+ * Parse the name of a segment file in the format <recording_id>-<segment_base_position>.rec.
+ *
+ * @param file_name of the segment file without any directory.
+ * @return the recording id and the segment base position if the name is that of a segment file.
+ */
+pub fn parse_segment_file_name(file_name: &str) -> Option<(i64, i64)> {
+    let stem = file_name.strip_suffix(SEGMENT_FILE_SUFFIX)?;
+    let (recording_id, position) = stem.split_at(stem.find('-')?);
+
+    Some((recording_id.parse().ok()?, position[1..].parse().ok()?))
+}
+
+/**
+ * A fragment, or a reassembled message, read from the segment files of a recording.
+ * <p>
+ * For a reassembled message the frame fields are those of the last fragment, the same as the Header given to
+ * the delegate of a FragmentAssembler, and start_position is that of the first fragment.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecordedFragment {
+    /**
+     * position in the recording at which the frame begins.
+     */
+    pub start_position: i64,
+    /**
+     * position in the recording after the frame, as given by Header::position.
+     */
+    pub position: i64,
+    pub session_id: i32,
+    pub stream_id: i32,
+    pub term_id: i32,
+    pub term_offset: i32,
+    pub flags: u8,
+    pub reserved_value: i64,
+    pub payload: Vec<u8>,
+}
+
+enum Scan {
+    Fragment(RecordedFragment),
+    EndOfSegment,
+    EndOfData,
+    Corrupted { position: i64, frame_length: i32 },
+}
+
+impl Scan {
+    fn into_result(self) -> Result<Option<RecordedFragment>, AeronError> {
+        match self {
+            Scan::Fragment(fragment) => Ok(Some(fragment)),
+            Scan::EndOfSegment | Scan::EndOfData => Ok(None),
+            Scan::Corrupted { position, frame_length } => {
+                Err(IllegalStateError::SegmentFrameCorrupted { position, frame_length }.into())
+            }
+        }
+    }
+}
+
+/**
+ * Read only view of a single segment file of a recording. The file holds a run of whole terms, starting at a
+ * term aligned position, so frames are walked term by term in the same way as a log buffer.
+ */
+pub struct SegmentFile {
+    file: MemoryMappedFile,
+    recording_id: i64,
+    segment_base_position: i64,
+    term_buffer_length: Index,
+}
+
+impl SegmentFile {
+    /**
+     * Map a segment file. The recording id and the segment base position are taken from the name of the file.
+     *
+     * @param path               of the segment file.
+     * @param term_buffer_length of the recorded stream.
+     * @return the SegmentFile if the name is valid and the file could be mapped.
+     */
+    pub fn open(path: impl AsRef<Path>, term_buffer_length: Index) -> Result<Self, AeronError> {
+        let path = path.as_ref();
+        let file_name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        let (recording_id, segment_base_position) = parse_segment_file_name(file_name)
+            .ok_or_else(|| IllegalArgumentError::InvalidSegmentFileName(path.display().to_string()))?;
+
+        log_buffer_descriptor::check_term_length(term_buffer_length)?;
+
+        Ok(Self {
+            file: MemoryMappedFile::map_existing(path.to_path_buf(), false)?,
+            recording_id,
+            segment_base_position,
+            term_buffer_length,
+        })
+    }
+
+    pub fn recording_id(&self) -> i64 {
+        self.recording_id
+    }
+
+    pub fn segment_base_position(&self) -> i64 {
+        self.segment_base_position
+    }
+
+    /**
+     * Position in the recording at which this segment file ends.
+     *
+     * @return the position after the last complete term in the file.
+     */
+    pub fn end_position(&self) -> i64 {
+        let terms = self.file.memory_size() / self.term_buffer_length;
+        self.segment_base_position + terms as i64 * self.term_buffer_length as i64
+    }
+
+    /**
+     * Read the fragment which begins at, or is the first after padding from, a position in this segment.
+     *
+     * @param position of a frame in the segment.
+     * @return the fragment or None if there is no more data in the segment, or an error if a frame on the way
+     * has a length which does not fit in its term or an unknown type.
+     */
+    pub fn fragment_at(&self, position: i64) -> Result<Option<RecordedFragment>, AeronError> {
+        self.scan(position).into_result()
+    }
+
+    /**
+     * Iterate over the fragments in this segment starting from a position.
+     *
+     * @param from_position of a frame in the segment.
+     * @return iterator over the fragments up to the end of the data in the segment, which ends after an error for
+     * a corrupted frame.
+     */
+    pub fn fragments(&self, from_position: i64) -> SegmentFragments<'_> {
+        SegmentFragments {
+            segment: self,
+            position: Some(from_position),
+        }
+    }
+
+    fn scan(&self, mut position: i64) -> Scan {
+        let term_length = self.term_buffer_length;
+        let mut header = Header::new(0, term_length);
+
+        while position >= self.segment_base_position && position < self.end_position() {
+            let term_base = (position - self.segment_base_position) as Index & !(term_length - 1);
+            let term_offset = position as Index & (term_length - 1);
+            let term_buffer = self.file.atomic_buffer(term_base, term_length);
+
+            // IC: an empty frame marks the end of the recorded data, padding up to the end of a term means the data
+            // carries on in the next term. The file may be damaged so any other frame is checked before it is read.
+            let frame_length = frame_descriptor::frame_length_volatile(&term_buffer, term_offset);
+            if frame_length == 0 {
+                return Scan::EndOfData;
+            }
+
+            let frame_type = term_buffer.get::<u16>(term_offset + *data_frame_header::TYPE_FIELD_OFFSET);
+            if frame_length < data_frame_header::LENGTH
+                || frame_length > term_length - term_offset
+                || (frame_type != data_frame_header::HDR_TYPE_DATA && frame_type != data_frame_header::HDR_TYPE_PAD)
+            {
+                return Scan::Corrupted { position, frame_length };
+            }
+
+            if frame_type == data_frame_header::HDR_TYPE_PAD {
+                position += align(frame_length, frame_descriptor::FRAME_ALIGNMENT) as i64;
+                continue;
+            }
+
+            let mut fragment = None;
+            let outcome = term_reader::read(
+                term_buffer,
+                term_offset,
+                &mut |buffer: &AtomicBuffer, offset: Index, length: Index, header: &Header| {
+                    fragment = Some(RecordedFragment {
+                        start_position: position - term_offset as i64 + header.offset() as i64,
+                        position: 0,
+                        session_id: header.session_id(),
+                        stream_id: header.stream_id(),
+                        term_id: header.term_id(),
+                        term_offset: header.offset(),
+                        flags: header.flags(),
+                        reserved_value: header.reserved_value(),
+                        payload: buffer.as_sub_slice(offset, length).to_vec(),
+                    });
+                },
+                1,
+                &mut header,
+            );
+
+            let mut fragment = fragment.expect("term reader reads a checked data frame");
+            fragment.position = position - term_offset as i64 + outcome.offset as i64;
+            return Scan::Fragment(fragment);
+        }
+
+        Scan::EndOfSegment
+    }
+}
+
+/**
+ * Iterator over the fragments in a single segment file.
+ */
+pub struct SegmentFragments<'a> {
+    segment: &'a SegmentFile,
+    position: Option<i64>,
+}
+
+impl<'a> SegmentFragments<'a> {
+    /**
+     * Reassemble fragmented messages, in the same way as FragmentAssembler, so only whole messages are returned.
+     *
+     * @return iterator over the whole messages.
+     */
+    pub fn messages(self) -> RecordedMessages<Self> {
+        RecordedMessages::new(self)
+    }
+}
+
+impl<'a> Iterator for SegmentFragments<'a> {
+    type Item = Result<RecordedFragment, AeronError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.segment.fragment_at(self.position?).transpose()?;
+        self.position = result.as_ref().ok().map(|fragment| fragment.position);
+
+        Some(result)
+    }
+}
+
+/**
+ * Iterator over the fragments of a recording in a range of positions, moving from one segment file to the next.
+ * The iteration ends at the end position of the range, where the recorded data ends or after an error for a
+ * corrupted frame.
+ */
+pub struct RecordingReader {
+    archive_dir: PathBuf,
+    recording_id: i64,
+    term_buffer_length: Index,
+    segment_file_length: Index,
+    segment: Option<SegmentFile>,
+    position: i64,
+    stop_position: i64,
+}
+
+impl RecordingReader {
+    /**
+     * Open the segment file of a recording which contains the position to start reading from.
+     *
+     * @param archive_dir   which contains the segment files of the recording.
+     * @param descriptor    of the recording, e.g. from the Catalog.
+     * @param from_position to start reading from or NULL_POSITION for the start of the recording.
+     * @param to_position   to read up to or NULL_POSITION for the stop position of the recording.
+     * @return the RecordingReader if the first segment file could be opened.
+     */
+    pub fn new(
+        archive_dir: impl AsRef<Path>,
+        descriptor: &RecordingDescriptor,
+        from_position: i64,
+        to_position: i64,
+    ) -> Result<Self, AeronError> {
+        let position = if from_position == NULL_POSITION {
+            descriptor.start_position
+        } else {
+            from_position
+        };
+        let stop_position = if to_position == NULL_POSITION {
+            descriptor.stop_position
+        } else {
+            to_position
+        };
+
+        let segment_base = segment_file_base_position(
+            descriptor.start_position,
+            position,
+            descriptor.term_buffer_length,
+            descriptor.segment_file_length,
+        );
+        let archive_dir = archive_dir.as_ref().to_path_buf();
+        let segment = SegmentFile::open(
+            archive_dir.join(segment_file_name(descriptor.recording_id, segment_base)),
+            descriptor.term_buffer_length,
+        )?;
+
+        Ok(Self {
+            archive_dir,
+            recording_id: descriptor.recording_id,
+            term_buffer_length: descriptor.term_buffer_length,
+            segment_file_length: descriptor.segment_file_length,
+            segment: Some(segment),
+            position,
+            stop_position,
+        })
+    }
+
+    /**
+     * Position the reader has reached in the recording.
+     *
+     * @return the position after the last fragment returned.
+     */
+    pub fn position(&self) -> i64 {
+        self.position
+    }
+
+    /**
+     * Reassemble fragmented messages, in the same way as FragmentAssembler, so only whole messages are returned.
+     *
+     * @return iterator over the whole messages.
+     */
+    pub fn messages(self) -> RecordedMessages<Self> {
+        RecordedMessages::new(self)
+    }
+
+    fn next_segment(&self, segment: &SegmentFile) -> Option<SegmentFile> {
+        let segment_base = segment.segment_base_position() + self.segment_file_length as i64;
+        let path = self.archive_dir.join(segment_file_name(self.recording_id, segment_base));
+
+        // IC: a missing segment file means the recording has not rolled over to it yet
+        SegmentFile::open(path, self.term_buffer_length).ok()
+    }
+}
+
+impl Iterator for RecordingReader {
+    type Item = Result<RecordedFragment, AeronError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.stop_position == NULL_POSITION || self.position < self.stop_position {
+            let segment = self.segment.take()?;
+
+            match segment.scan(self.position) {
+                Scan::Fragment(fragment) => {
+                    self.position = fragment.position;
+                    self.segment = Some(segment);
+                    return Some(Ok(fragment));
+                }
+                Scan::EndOfSegment => {
+                    self.position = segment.end_position();
+                    self.segment = self.next_segment(&segment);
+                }
+                Scan::EndOfData => return None,
+                corrupted @ Scan::Corrupted { .. } => return corrupted.into_result().transpose(),
+            }
+        }
+
+        None
+    }
+}
+
+/**
+ * Iterator which reassembles fragmented messages per session from an iterator over recorded fragments.
+ * <p>
+ * Unfragmented messages and errors are passed through. A fragment which ends a message without its beginning
+ * having been seen, e.g. when reading from the middle of a message, is dropped.
+ */
+pub struct RecordedMessages<I> {
+    fragments: I,
+    builder_by_session_id_map: HashMap<i32, RecordedFragment>,
+}
+
+impl<I: Iterator<Item = Result<RecordedFragment, AeronError>>> RecordedMessages<I> {
+    pub fn new(fragments: I) -> Self {
+        Self {
+            fragments,
+            builder_by_session_id_map: HashMap::new(),
+        }
+    }
+}
+
+impl<I: Iterator<Item = Result<RecordedFragment, AeronError>>> Iterator for RecordedMessages<I> {
+    type Item = Result<RecordedFragment, AeronError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for result in &mut self.fragments {
+            let fragment = match result {
+                Ok(fragment) => fragment,
+                Err(err) => return Some(Err(err)),
+            };
+            let flags = fragment.flags;
+
+            if (flags & frame_descriptor::UNFRAGMENTED) == frame_descriptor::UNFRAGMENTED {
+                return Some(Ok(fragment));
+            } else if (flags & frame_descriptor::BEGIN_FRAG) == frame_descriptor::BEGIN_FRAG {
+                self.builder_by_session_id_map.insert(fragment.session_id, fragment);
+            } else if let Some(mut builder) = self.builder_by_session_id_map.remove(&fragment.session_id) {
+                builder.payload.extend_from_slice(&fragment.payload);
+
+                let message = RecordedFragment {
+                    start_position: builder.start_position,
+                    payload: builder.payload,
+                    ..fragment
+                };
+
+                if flags & frame_descriptor::END_FRAG == frame_descriptor::END_FRAG {
+                    return Some(Ok(message));
+                }
+                self.builder_by_session_id_map.insert(message.session_id, message);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use crate::archive::client::recording_descriptor_consumer::RecordingDescriptor;
+    use crate::archive::segment_reader::{parse_segment_file_name, segment_file_name, RecordingReader, SegmentFile};
+    use crate::concurrent::atomic_buffer::AtomicBuffer;
+    use crate::concurrent::logbuffer::{
+        data_frame_header::{self, DataFrameHeaderDefn},
+        frame_descriptor, log_buffer_descriptor,
+    };
+    use crate::utils::bit_utils::align;
+    use crate::utils::errors::{AeronError, IllegalStateError};
+    use crate::utils::types::Index;
+
+    const TERM_LENGTH: Index = log_buffer_descriptor::TERM_MIN_LENGTH;
+    const SEGMENT_LENGTH: Index = TERM_LENGTH * 2;
+    const SESSION_ID: i32 = 7;
+    const STREAM_ID: i32 = 1001;
+
+    fn put_frame(
+        buffer: &AtomicBuffer,
+        term_base: Index,
+        term_offset: Index,
+        flags: u8,
+        frame_type: u16,
+        length: Index,
+    ) -> Index {
+        let frame = buffer.overlay_struct::<DataFrameHeaderDefn>(term_base + term_offset);
+        unsafe {
+            (*frame).frame_length = data_frame_header::LENGTH + length;
+            (*frame).version = data_frame_header::CURRENT_VERSION;
+            (*frame).flags = flags;
+            (*frame).frame_type = frame_type;
+            (*frame).term_offset = term_offset;
+            (*frame).session_id = SESSION_ID;
+            (*frame).stream_id = STREAM_ID;
+            (*frame).term_id = term_base / TERM_LENGTH;
+            (*frame).reserved_value = term_offset as i64;
+        }
+        for i in 0..length {
+            buffer.put(term_base + term_offset + data_frame_header::LENGTH + i, flags);
+        }
+
+        term_offset + align(data_frame_header::LENGTH + length, frame_descriptor::FRAME_ALIGNMENT)
+    }
+
+    // IC: term 0 holds an unfragmented message then padding, term 1 a message in two fragments then padding
+    // when the recording carries on in the next segment
+    fn segment_data(is_full: bool) -> Vec<u8> {
+        let mut data = vec![0u8; SEGMENT_LENGTH as usize];
+        let buffer = AtomicBuffer::wrap_slice(&mut data);
+
+        let offset = put_frame(
+            &buffer,
+            0,
+            0,
+            frame_descriptor::UNFRAGMENTED,
+            data_frame_header::HDR_TYPE_DATA,
+            96,
+        );
+        put_frame(
+            &buffer,
+            0,
+            offset,
+            frame_descriptor::UNFRAGMENTED,
+            data_frame_header::HDR_TYPE_PAD,
+            TERM_LENGTH - offset - data_frame_header::LENGTH,
+        );
+        let offset = put_frame(
+            &buffer,
+            TERM_LENGTH,
+            0,
+            frame_descriptor::BEGIN_FRAG,
+            data_frame_header::HDR_TYPE_DATA,
+            64,
+        );
+        let offset = put_frame(
+            &buffer,
+            TERM_LENGTH,
+            offset,
+            frame_descriptor::END_FRAG,
+            data_frame_header::HDR_TYPE_DATA,
+            32,
+        );
+        if is_full {
+            put_frame(
+                &buffer,
+                TERM_LENGTH,
+                offset,
+                frame_descriptor::UNFRAGMENTED,
+                data_frame_header::HDR_TYPE_PAD,
+                TERM_LENGTH - offset - data_frame_header::LENGTH,
+            );
+        }
+
+        data
+    }
+
+    #[test]
+    fn should_parse_segment_file_name() {
+        assert_eq!(segment_file_name(5, 131072), "5-131072.rec");
+        assert_eq!(parse_segment_file_name("5-131072.rec"), Some((5, 131072)));
+        assert_eq!(parse_segment_file_name("archive.catalog"), None);
+        assert_eq!(parse_segment_file_name("5-abc.rec"), None);
+    }
+
+    #[test]
+    fn should_read_fragments_across_terms() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(segment_file_name(3, 0));
+        fs::write(&path, segment_data(false)).unwrap();
+
+        let segment = SegmentFile::open(&path, TERM_LENGTH).unwrap();
+        assert_eq!(segment.recording_id(), 3);
+        assert_eq!(segment.end_position(), SEGMENT_LENGTH as i64);
+
+        let fragments: Vec<_> = segment.fragments(0).collect::<Result<_, _>>().unwrap();
+        assert_eq!(fragments.len(), 3);
+        assert_eq!(fragments[0].start_position, 0);
+        assert_eq!(fragments[0].position, 128);
+        assert_eq!(fragments[0].payload.len(), 96);
+        assert_eq!(fragments[0].session_id, SESSION_ID);
+        assert_eq!(fragments[1].start_position, TERM_LENGTH as i64);
+        assert_eq!(fragments[1].flags, frame_descriptor::BEGIN_FRAG);
+        assert_eq!(fragments[2].reserved_value, 96);
+
+        let messages: Vec<_> = segment.fragments(0).messages().collect::<Result<_, _>>().unwrap();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].start_position, TERM_LENGTH as i64);
+        assert_eq!(messages[1].position, TERM_LENGTH as i64 + 96 + 64);
+        assert_eq!(messages[1].payload.len(), 96);
+        assert!(messages[1].payload[..64].iter().all(|b| *b == frame_descriptor::BEGIN_FRAG));
+        assert!(messages[1].payload[64..].iter().all(|b| *b == frame_descriptor::END_FRAG));
+    }
+
+    #[test]
+    fn should_read_recording_range_across_segments() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(segment_file_name(3, 0)), segment_data(true)).unwrap();
+        fs::write(
+            dir.path().join(segment_file_name(3, SEGMENT_LENGTH as i64)),
+            segment_data(false),
+        )
+        .unwrap();
+
+        let descriptor = RecordingDescriptor {
+            recording_id: 3,
+            start_position: 0,
+            stop_position: SEGMENT_LENGTH as i64 + TERM_LENGTH as i64 + 96 + 64,
+            term_buffer_length: TERM_LENGTH,
+            segment_file_length: SEGMENT_LENGTH,
+            ..Default::default()
+        };
+
+        let reader = RecordingReader::new(dir.path(), &descriptor, 128, -1).unwrap();
+        let messages: Vec<_> = reader.messages().collect::<Result<_, _>>().unwrap();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].start_position, TERM_LENGTH as i64);
+        assert_eq!(messages[1].start_position, SEGMENT_LENGTH as i64);
+        assert_eq!(messages[2].position, descriptor.stop_position);
+
+        let mut reader = RecordingReader::new(dir.path(), &descriptor, -1, SEGMENT_LENGTH as i64 + 1).unwrap();
+        assert_eq!(reader.by_ref().count(), 4);
+        assert_eq!(reader.position(), SEGMENT_LENGTH as i64 + 128);
+    }
+
+    fn set_frame_field<T: Copy>(data: &mut [u8], frame_offset: Index, field_offset: Index, value: T) {
+        AtomicBuffer::wrap_slice(data).put::<T>(frame_offset + field_offset, value);
+    }
+
+    fn assert_corrupted(data: Vec<u8>, position: i64, frame_length: i32) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(segment_file_name(3, 0));
+        fs::write(&path, data).unwrap();
+        let segment = SegmentFile::open(&path, TERM_LENGTH).unwrap();
+
+        let results: Vec<_> = segment.fragments(0).messages().collect();
+        assert!(results.len() <= 2, "iteration must end after the corrupted frame");
+        match results.last() {
+            Some(Err(AeronError::IllegalState(IllegalStateError::SegmentFrameCorrupted {
+                position: corrupted_position,
+                frame_length: corrupted_length,
+            }))) => {
+                assert_eq!(*corrupted_position, position);
+                assert_eq!(*corrupted_length, frame_length);
+            }
+            other => panic!("expected a corrupted frame error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn should_reject_frame_with_length_past_end_of_term() {
+        let mut data = segment_data(false);
+        let length = TERM_LENGTH - 128 + 1;
+        set_frame_field(&mut data, 128, 0, length);
+
+        assert_corrupted(data, 128, length);
+    }
+
+    #[test]
+    fn should_reject_frame_with_length_shorter_than_its_header() {
+        let mut data = segment_data(false);
+        set_frame_field(&mut data, 0, 0, 16_i32);
+
+        assert_corrupted(data, 0, 16);
+    }
+
+    #[test]
+    fn should_reject_frame_with_negative_length() {
+        let mut data = segment_data(false);
+        set_frame_field(&mut data, TERM_LENGTH, 0, -64_i32);
+
+        assert_corrupted(data, TERM_LENGTH as i64, -64);
+    }
+
+    #[test]
+    fn should_reject_frame_with_unknown_type() {
+        let mut data = segment_data(false);
+        set_frame_field(&mut data, 0, *data_frame_header::TYPE_FIELD_OFFSET, 0x7f_u16);
+
+        assert_corrupted(data, 0, 128);
+    }
+}
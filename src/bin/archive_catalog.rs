@@ -0,0 +1,128 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{env, process};
+
+use aeron_rs::{
+    archive::catalog::{Catalog, CatalogEntry, CATALOG_FILE_NAME},
+    utils::misc::semantic_version_to_string,
+};
+
+struct CmdOpts {
+    archive_dir: String,
+    stream_id: Option<i32>,
+    channel: Option<String>,
+}
+
+impl Default for CmdOpts {
+    fn default() -> Self {
+        Self {
+            archive_dir: String::from("archive"),
+            stream_id: None,
+            channel: None,
+        }
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("Usage: archive_catalog [<archive-dir>] [--stream-id <id>] [--channel <channel fragment>]");
+    process::exit(1)
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut settings = CmdOpts::default();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--stream-id" => {
+                settings.stream_id = Some(args.next().and_then(|id| id.parse().ok()).unwrap_or_else(|| usage()));
+            }
+            "--channel" => settings.channel = Some(args.next().unwrap_or_else(|| usage())),
+            "-h" | "--help" => usage(),
+            _ => settings.archive_dir = arg,
+        }
+    }
+
+    settings
+}
+
+fn matches(settings: &CmdOpts, entry: &CatalogEntry) -> bool {
+    let descriptor = &entry.descriptor;
+
+    settings.stream_id.map_or(true, |stream_id| stream_id == descriptor.stream_id)
+        && settings.channel.as_ref().map_or(true, |channel| {
+            descriptor.stripped_channel.contains(channel.as_str()) || descriptor.original_channel.contains(channel.as_str())
+        })
+}
+
+fn main() {
+    pretty_env_logger::init();
+
+    let settings = parse_cmd_line();
+
+    println!("Using file: {}/{}", settings.archive_dir, CATALOG_FILE_NAME);
+
+    let catalog = Catalog::open(&settings.archive_dir).unwrap_or_else(|err| {
+        eprintln!("Can't open catalog: {}", err);
+        process::exit(1)
+    });
+
+    println!(
+        "Catalog v{}, next_recording_id: {}, alignment: {}",
+        semantic_version_to_string(catalog.version()),
+        catalog.next_recording_id(),
+        catalog.alignment()
+    );
+    println!("===========================");
+
+    let mut matched = 0;
+    let result = catalog.for_each(|entry| {
+        if !matches(&settings, &entry) {
+            return;
+        }
+
+        matched += 1;
+        let descriptor = &entry.descriptor;
+        println!(
+            "recording_id={} state={:?} start_position={} stop_position={} start_timestamp={} stop_timestamp={} \
+             initial_term_id={} segment_file_length={} term_buffer_length={} mtu_length={} session_id={} stream_id={} \
+             stripped_channel={} original_channel={} source_identity={}",
+            descriptor.recording_id,
+            entry.state,
+            descriptor.start_position,
+            descriptor.stop_position,
+            descriptor.start_timestamp,
+            descriptor.stop_timestamp,
+            descriptor.initial_term_id,
+            descriptor.segment_file_length,
+            descriptor.term_buffer_length,
+            descriptor.mtu_length,
+            descriptor.session_id,
+            descriptor.stream_id,
+            descriptor.stripped_channel,
+            descriptor.original_channel,
+            descriptor.source_identity
+        );
+    });
+    let total = result.unwrap_or_else(|err| {
+        eprintln!("Can't read catalog: {}", err);
+        process::exit(1)
+    });
+
+    println!("===========================");
+    println!("{} of {} recordings", matched, total);
+}
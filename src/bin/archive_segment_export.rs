@@ -0,0 +1,206 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    env,
+    fs::File,
+    io::{self, BufWriter, Write},
+    process,
+};
+
+use aeron_rs::{
+    archive::{
+        catalog::Catalog,
+        client::aeron_archive::NULL_POSITION,
+        segment_reader::{RecordedFragment, RecordingReader},
+    },
+    utils::errors::AeronError,
+};
+
+#[derive(PartialEq)]
+enum Format {
+    Binary,
+    Hex,
+}
+
+struct CmdOpts {
+    archive_dir: String,
+    recording_id: Option<i64>,
+    from_position: i64,
+    to_position: i64,
+    format: Format,
+    output: Option<String>,
+    fragments: bool,
+}
+
+impl Default for CmdOpts {
+    fn default() -> Self {
+        Self {
+            archive_dir: String::from("archive"),
+            recording_id: None,
+            from_position: NULL_POSITION,
+            to_position: NULL_POSITION,
+            format: Format::Binary,
+            output: None,
+            fragments: false,
+        }
+    }
+}
+
+fn usage() -> ! {
+    eprintln!(
+        "Usage: archive_segment_export [<archive-dir>] --recording-id <id> [--from <position>] [--to <position>] \
+         [--format binary|hex] [--output <file>] [--fragments]"
+    );
+    eprintln!("  binary writes each message as a little endian i32 length followed by the payload");
+    process::exit(1)
+}
+
+fn parse_number<T: std::str::FromStr>(arg: Option<String>) -> T {
+    arg.and_then(|value| value.parse().ok()).unwrap_or_else(|| usage())
+}
+
+fn parse_cmd_line() -> CmdOpts {
+    let mut settings = CmdOpts::default();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--recording-id" => settings.recording_id = Some(parse_number(args.next())),
+            "--from" => settings.from_position = parse_number(args.next()),
+            "--to" => settings.to_position = parse_number(args.next()),
+            "--format" => {
+                settings.format = match args.next().as_deref() {
+                    Some("binary") => Format::Binary,
+                    Some("hex") => Format::Hex,
+                    _ => usage(),
+                }
+            }
+            "--output" => settings.output = Some(args.next().unwrap_or_else(|| usage())),
+            "--fragments" => settings.fragments = true,
+            "-h" | "--help" => usage(),
+            _ => settings.archive_dir = arg,
+        }
+    }
+
+    if settings.recording_id.is_none() {
+        usage();
+    }
+
+    settings
+}
+
+fn write_hex(out: &mut dyn Write, message: &RecordedFragment) -> io::Result<()> {
+    writeln!(
+        out,
+        "position={} session_id={} stream_id={} flags={:#04x} reserved_value={} length={}",
+        message.start_position,
+        message.session_id,
+        message.stream_id,
+        message.flags,
+        message.reserved_value,
+        message.payload.len()
+    )?;
+
+    for (i, line) in message.payload.chunks(16).enumerate() {
+        let hex: Vec<String> = line.iter().map(|b| format!("{:02x}", b)).collect();
+        let ascii: String = line
+            .iter()
+            .map(|b| if b.is_ascii_graphic() || *b == b' ' { *b as char } else { '.' })
+            .collect();
+        writeln!(out, "{:08x}  {:<47}  |{}|", i * 16, hex.join(" "), ascii)?;
+    }
+
+    Ok(())
+}
+
+fn export(settings: &CmdOpts, reader: RecordingReader, out: &mut dyn Write) -> io::Result<i64> {
+    let messages: Box<dyn Iterator<Item = Result<RecordedFragment, AeronError>>> = if settings.fragments {
+        Box::new(reader)
+    } else {
+        Box::new(reader.messages())
+    };
+
+    let mut count = 0;
+    for message in messages {
+        let message = message.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        match settings.format {
+            Format::Binary => {
+                out.write_all(&(message.payload.len() as i32).to_le_bytes())?;
+                out.write_all(&message.payload)?;
+            }
+            Format::Hex => write_hex(out, &message)?,
+        }
+        count += 1;
+    }
+    out.flush()?;
+
+    Ok(count)
+}
+
+fn main() {
+    pretty_env_logger::init();
+
+    let settings = parse_cmd_line();
+    let recording_id = settings.recording_id.unwrap_or_else(|| usage());
+
+    let catalog = Catalog::open(&settings.archive_dir).unwrap_or_else(|err| {
+        eprintln!("Can't open catalog: {}", err);
+        process::exit(1)
+    });
+
+    let entry = catalog
+        .find_recording(recording_id)
+        .unwrap_or_else(|err| {
+            eprintln!("Can't read catalog: {}", err);
+            process::exit(1)
+        })
+        .unwrap_or_else(|| {
+            eprintln!("Recording {} not found in {}", recording_id, settings.archive_dir);
+            process::exit(1)
+        });
+
+    let reader = RecordingReader::new(
+        &settings.archive_dir,
+        &entry.descriptor,
+        settings.from_position,
+        settings.to_position,
+    )
+    .unwrap_or_else(|err| {
+        eprintln!("Can't open segment file: {}", err);
+        process::exit(1)
+    });
+
+    let mut out: Box<dyn Write> = match &settings.output {
+        Some(path) => Box::new(BufWriter::new(File::create(path).unwrap_or_else(|err| {
+            eprintln!("Can't create {}: {}", path, err);
+            process::exit(1)
+        }))),
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+
+    match export(&settings, reader, &mut out) {
+        Ok(count) => eprintln!(
+            "Exported {} {}",
+            count,
+            if settings.fragments { "fragments" } else { "messages" }
+        ),
+        Err(err) => {
+            eprintln!("Export failed: {}", err);
+            process::exit(1)
+        }
+    }
+}
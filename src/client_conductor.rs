@@ -19,12 +19,14 @@ use std::{
     ffi::{CStr, CString},
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Mutex, Weak,
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex, MutexGuard, Weak,
     },
 };
 
 use crate::utils::errors::{DriverInteractionError, GenericError, IllegalArgumentError};
 use crate::{
+    command::error_response_flyweight::ERROR_CODE_GENERIC_ERROR,
     concurrent::{
         agent_runner::Agent,
         atomic_buffer::AtomicBuffer,
@@ -33,8 +35,8 @@ use crate::{
         counters::{self, CountersReader},
         logbuffer::term_reader::ErrorHandler,
         position::UnsafeBufferPosition,
-        status::status_indicator_reader,
     },
+    conductor_proxy::{ConductorCommand, ConductorProxy},
     context::{
         OnAvailableCounter, OnAvailableImage, OnCloseClient, OnNewPublication, OnNewSubscription, OnUnavailableCounter,
         OnUnavailableImage,
@@ -71,8 +73,8 @@ pub enum RegistrationStatus {
 
 struct PublicationStateDefn {
     error_message: CString,
-    buffers: Option<Arc<LogBuffers>>, // PublicationStateDefn could be created without it
-    publication: Option<Weak<Mutex<Publication>>>, // and then these fields will be set later.
+    buffers: Option<Arc<LogBuffers>>,       // PublicationStateDefn could be created without it
+    publication: Option<Weak<Publication>>, // and then these fields will be set later.
     channel: CString,
     registration_id: i64,
     original_registration_id: i64,
@@ -108,7 +110,7 @@ impl PublicationStateDefn {
 struct ExclusivePublicationStateDefn {
     error_message: CString,
     buffers: Option<Arc<LogBuffers>>,
-    publication: Option<Weak<Mutex<ExclusivePublication>>>,
+    publication_closed: Option<Arc<AtomicBool>>, // set once the only handle is handed out
     channel: CString,
     registration_id: i64,
     // original_registration_id: i64,
@@ -126,7 +128,7 @@ impl ExclusivePublicationStateDefn {
         Self {
             error_message: CString::new("").unwrap(),
             buffers: None,
-            publication: None,
+            publication_closed: None,
             channel,
             registration_id,
             time_of_registration_ms: now_ms,
@@ -236,14 +238,21 @@ impl LogBuffersDefn {
     }
 }
 
+/**
+ * Destination states by correlation id, shared with the ConductorProxy so resource handles can check the driver
+ * response without taking the conductor lock.
+ */
+pub(crate) type DestinationStates = Arc<Mutex<HashMap<i64, DestinationStateDefn>>>;
+
 #[allow(dead_code)]
-struct DestinationStateDefn {
+pub(crate) struct DestinationStateDefn {
     error_message: CString,
     correlation_id: i64,
     registration_id: i64,
     time_of_registration_ms: Moment,
     error_code: i32,
     status: RegistrationStatus,
+    timed_out: bool,
 }
 
 impl DestinationStateDefn {
@@ -255,6 +264,45 @@ impl DestinationStateDefn {
             time_of_registration_ms: now_ms,
             error_code: -1,
             status: RegistrationStatus::Awaiting,
+            timed_out: false,
+        }
+    }
+
+    /**
+     * State of a command still in the conductor queue, the conductor sets the registration time once it sends
+     * the command to the driver.
+     */
+    pub(crate) fn queued(correlation_id: i64, registration_id: i64) -> Self {
+        Self::new(correlation_id, registration_id, MAX_MOMENT)
+    }
+
+    fn check_timeout(&mut self, now_ms: Moment, driver_timeout_ms: Moment) {
+        // IC: saturating as commands still in the queue have no registration time yet
+        let response_deadline_ms = self.time_of_registration_ms.saturating_add(driver_timeout_ms);
+        if self.status == RegistrationStatus::Awaiting && now_ms > response_deadline_ms {
+            self.timed_out = true;
+        }
+    }
+
+    /**
+     * Driver response to the destination command.
+     *
+     * @return true once the driver succeeded, false while still awaiting the response.
+     */
+    pub(crate) fn response(&self, driver_timeout_ms: Moment) -> Result<bool, AeronError> {
+        match self.status {
+            RegistrationStatus::Awaiting => {
+                if self.timed_out {
+                    Err(DriverInteractionError::NoResponse(driver_timeout_ms).into())
+                } else {
+                    Ok(false)
+                }
+            }
+            RegistrationStatus::Registered => Ok(true),
+            RegistrationStatus::Errored => Err(ClientConductor::return_registration_error(
+                self.error_code,
+                &self.error_message,
+            )),
         }
     }
 }
@@ -265,7 +313,7 @@ pub struct ClientConductor {
     exclusive_publication_by_registration_id: HashMap<i64, ExclusivePublicationStateDefn>,
     subscription_by_registration_id: HashMap<i64, SubscriptionStateDefn>,
     counter_by_registration_id: HashMap<i64, CounterStateDefn>,
-    destination_state_by_correlation_id: HashMap<i64, DestinationStateDefn>,
+    destination_state_by_correlation_id: DestinationStates,
 
     log_buffers_by_registration_id: HashMap<i64, LogBuffersDefn>,
     lingering_image_lists: Vec<ImageListLingerDefn>,
@@ -292,7 +340,9 @@ pub struct ClientConductor {
     pre_touch_mapped_memory: bool,
     is_in_callback: bool,
     driver_active: AtomicBool,
-    is_closed: AtomicBool,
+    is_closed: Arc<AtomicBool>,
+    command_sender: Sender<ConductorCommand>,
+    command_receiver: Receiver<ConductorCommand>,
     //admin_lock: Mutex<()>,
     heartbeat_timestamp: Option<Box<AtomicCounter>>,
 
@@ -300,8 +350,6 @@ pub struct ClientConductor {
     time_of_last_keepalive_ms: Moment,
     time_of_last_check_managed_resources_ms: Moment,
 
-    arced_self: Option<Arc<Mutex<ClientConductor>>>,
-
     padding: [u8; crate::utils::misc::CACHE_LINE_LENGTH as usize],
 }
 
@@ -325,6 +373,8 @@ impl ClientConductor {
         inter_service_timeout_ns: Moment,
         pre_touch_mapped_memory: bool,
     ) -> Arc<Mutex<Self>> {
+        let (command_sender, command_receiver) = mpsc::channel();
+
         let mut selfy = Self {
             publication_by_registration_id: Default::default(),
             exclusive_publication_by_registration_id: Default::default(),
@@ -351,13 +401,14 @@ impl ClientConductor {
             pre_touch_mapped_memory,
             is_in_callback: false,
             driver_active: AtomicBool::from(true),
-            is_closed: AtomicBool::from(false),
+            is_closed: Arc::new(AtomicBool::from(false)),
+            command_sender,
+            command_receiver,
             //admin_lock: Mutex::new(()),
             heartbeat_timestamp: None,
             time_of_last_do_work_ms: epoch_clock(),
             time_of_last_keepalive_ms: epoch_clock(),
             time_of_last_check_managed_resources_ms: epoch_clock(),
-            arced_self: None,
             padding: [0; crate::utils::misc::CACHE_LINE_LENGTH as usize],
         };
 
@@ -369,7 +420,6 @@ impl ClientConductor {
         let another_selfy = arc_selfy.clone();
         let mut another_selfy_mut = another_selfy.lock().expect("Mutex poisoned");
         another_selfy_mut.driver_listener_adapter = Some(DriverListenerAdapter::new(broadcast_receiver, arc_selfy.clone()));
-        another_selfy_mut.arced_self = Some(arc_selfy.clone());
 
         arc_selfy
     }
@@ -403,15 +453,22 @@ impl ClientConductor {
         Ok(self.counters_reader.clone())
     }
 
-    pub fn channel_status(&self, counter_id: i32) -> i64 {
-        match counter_id {
-            0 => status_indicator_reader::CHANNEL_ENDPOINT_INITIALIZING,
-            status_indicator_reader::NO_ID_ALLOCATED => status_indicator_reader::CHANNEL_ENDPOINT_ACTIVE,
-            _ => self
-                .counters_reader
-                .counter_value(counter_id)
-                .expect("Error getting counter value") as i64,
-        }
+    /**
+     * Handle for resources to query the conductor and send it commands without taking its lock.
+     */
+    pub fn conductor_proxy(&self) -> ConductorProxy {
+        ConductorProxy::new(
+            self.driver_proxy.clone(),
+            self.counters_reader.clone(),
+            self.destination_state_by_correlation_id.clone(),
+            self.driver_timeout_ms,
+            self.is_closed.clone(),
+            self.command_sender.clone(),
+        )
+    }
+
+    fn destination_states(&self) -> MutexGuard<'_, HashMap<i64, DestinationStateDefn>> {
+        self.destination_state_by_correlation_id.lock().expect("Mutex poisoned")
     }
 
     pub fn is_closed(&self) -> bool {
@@ -605,7 +662,7 @@ impl ClientConductor {
         Ok(registration_id)
     }
 
-    pub fn find_publication(&mut self, registration_id: i64) -> Result<Arc<Mutex<Publication>>, AeronError> {
+    pub fn find_publication(&mut self, registration_id: i64) -> Result<Arc<Publication>, AeronError> {
         /*
         let _guard = self
             .admin_lock
@@ -619,6 +676,7 @@ impl ClientConductor {
 
         let mut publication_to_remove: Option<i64> = None;
 
+        let conductor_proxy = self.conductor_proxy();
         let result = if let Some(state) = self.publication_by_registration_id.get_mut(&registration_id) {
             // try to upgrade weak ptr to strong one.
             if let Some(maybe_publication) = &state.publication {
@@ -648,9 +706,8 @@ impl ClientConductor {
                             UnsafeBufferPosition::new(self.counter_values_buffer, state.publication_limit_counter_id);
 
                         if let Some(buffers) = &state.buffers {
-                            let self_for_pub = self.arced_self.as_ref().unwrap();
                             let publication = Publication::new(
-                                self_for_pub.clone(),
+                                conductor_proxy,
                                 state.channel.clone(),
                                 state.registration_id,
                                 state.original_registration_id,
@@ -661,7 +718,7 @@ impl ClientConductor {
                                 buffers.clone(),
                             );
 
-                            let new_pub = Arc::new(Mutex::new(publication));
+                            let new_pub = Arc::new(publication);
                             state.publication = Some(Arc::downgrade(&new_pub));
                             log!(
                                 trace,
@@ -755,30 +812,23 @@ impl ClientConductor {
         Ok(registration_id)
     }
 
-    pub(crate) fn find_exclusive_publication(
-        &mut self,
-        registration_id: i64,
-    ) -> Result<Arc<Mutex<ExclusivePublication>>, AeronError> {
+    pub(crate) fn find_exclusive_publication(&mut self, registration_id: i64) -> Result<ExclusivePublication, AeronError> {
         log!(trace, "find_exclusive_publication: with registration_id {}", registration_id);
 
         self.ensure_not_reentrant();
         self.ensure_open()?;
         let mut publication_to_remove: Option<i64> = None;
 
+        let conductor_proxy = self.conductor_proxy();
         let result = if let Some(state) = self.exclusive_publication_by_registration_id.get_mut(&registration_id) {
-            // try to upgrade weak ptr to strong one.
-            if let Some(maybe_publication) = &state.publication {
-                // If there is publication - just return it
-                if let Some(publication) = maybe_publication.upgrade() {
-                    log!(
-                        trace,
-                        "find_exclusive_publication: existing exclusive publication with registration_id {} FOUND",
-                        registration_id
-                    );
-                    Ok(publication)
-                } else {
-                    Err(GenericError::ExclusivePublicationAlreadyDropped.into())
-                }
+            // The only handle was already handed out, there is none to return
+            if state.publication_closed.is_some() {
+                log!(
+                    trace,
+                    "find_exclusive_publication: exclusive publication with registration_id {} already TAKEN",
+                    registration_id
+                );
+                Err(GenericError::ExclusivePublicationAlreadyTaken.into())
             } else {
                 // Otherwise fill in the state.publication
                 match state.status {
@@ -795,7 +845,7 @@ impl ClientConductor {
 
                         if let Some(buffers) = &state.buffers {
                             let publication = ExclusivePublication::new(
-                                self.arced_self.as_ref().unwrap().clone(),
+                                conductor_proxy,
                                 state.channel.clone(),
                                 state.registration_id,
                                 state.stream_id,
@@ -805,8 +855,7 @@ impl ClientConductor {
                                 buffers.clone(),
                             );
 
-                            let new_pub = Arc::new(Mutex::new(publication));
-                            state.publication = Some(Arc::downgrade(&new_pub));
+                            state.publication_closed = Some(publication.closed_flag());
 
                             log!(
                                 trace,
@@ -814,7 +863,7 @@ impl ClientConductor {
                                 registration_id
                             );
 
-                            Ok(new_pub)
+                            Ok(publication)
                         } else {
                             Err(GenericError::BufferNotSetForExclusivePublication {
                                 registration_id: state.registration_id,
@@ -1123,7 +1172,12 @@ impl ClientConductor {
         Ok(())
     }
 
-    pub fn add_destination(&mut self, publication_registration_id: i64, endpoint_channel: CString) -> Result<i64, AeronError> {
+    fn add_destination(
+        &mut self,
+        publication_registration_id: i64,
+        correlation_id: i64,
+        endpoint_channel: CString,
+    ) -> Result<(), AeronError> {
         log!(
             trace,
             "add_destination: with publication_registration_id:{} channel: {}",
@@ -1132,14 +1186,12 @@ impl ClientConductor {
         );
 
         self.verify_driver_is_active()?;
-        self.ensure_not_reentrant();
         self.ensure_open()?;
 
-        let correlation_id = self
-            .driver_proxy
-            .add_destination(publication_registration_id, endpoint_channel)?;
+        self.driver_proxy
+            .add_destination(publication_registration_id, correlation_id, endpoint_channel)?;
 
-        self.destination_state_by_correlation_id.insert(
+        self.destination_states().insert(
             correlation_id,
             DestinationStateDefn::new(correlation_id, publication_registration_id, (self.epoch_clock)()),
         );
@@ -1150,10 +1202,15 @@ impl ClientConductor {
             correlation_id
         );
 
-        Ok(correlation_id)
+        Ok(())
     }
 
-    pub fn remove_destination(&mut self, publication_registration_id: i64, endpoint_channel: CString) -> Result<i64, AeronError> {
+    fn remove_destination(
+        &mut self,
+        publication_registration_id: i64,
+        correlation_id: i64,
+        endpoint_channel: CString,
+    ) -> Result<(), AeronError> {
         log!(
             trace,
             "remove_destination: with publication_registration_id:{} channel: {}",
@@ -1162,16 +1219,14 @@ impl ClientConductor {
         );
 
         self.verify_driver_is_active()?;
-        self.ensure_not_reentrant();
         self.ensure_open()?;
 
-        let correlation_id = self
-            .driver_proxy
-            .remove_destination(publication_registration_id, endpoint_channel)?;
+        self.driver_proxy
+            .remove_destination(publication_registration_id, correlation_id, endpoint_channel)?;
 
         // FIXME: the code is ported from C++ as is. But it seems there is a bug. We need to remove destination from
         // destination_state_by_correlation_id instead of inserting.
-        self.destination_state_by_correlation_id.insert(
+        self.destination_states().insert(
             correlation_id,
             DestinationStateDefn::new(correlation_id, publication_registration_id, (self.epoch_clock)()),
         );
@@ -1182,14 +1237,15 @@ impl ClientConductor {
             correlation_id
         );
 
-        Ok(correlation_id)
+        Ok(())
     }
 
-    pub fn add_rcv_destination(
+    fn add_rcv_destination(
         &mut self,
         subscription_registration_id: i64,
+        correlation_id: i64,
         endpoint_channel: CString,
-    ) -> Result<i64, AeronError> {
+    ) -> Result<(), AeronError> {
         log!(
             trace,
             "add_rcv_destination: with subscription_registration_id:{} channel: {}",
@@ -1198,14 +1254,12 @@ impl ClientConductor {
         );
 
         self.verify_driver_is_active()?;
-        self.ensure_not_reentrant();
         self.ensure_open()?;
 
-        let correlation_id = self
-            .driver_proxy
-            .add_rcv_destination(subscription_registration_id, endpoint_channel)?;
+        self.driver_proxy
+            .add_rcv_destination(subscription_registration_id, correlation_id, endpoint_channel)?;
 
-        self.destination_state_by_correlation_id.insert(
+        self.destination_states().insert(
             correlation_id,
             DestinationStateDefn::new(correlation_id, subscription_registration_id, (self.epoch_clock)()),
         );
@@ -1216,14 +1270,15 @@ impl ClientConductor {
             correlation_id
         );
 
-        Ok(correlation_id)
+        Ok(())
     }
 
-    pub fn remove_rcv_destination(
+    fn remove_rcv_destination(
         &mut self,
         subscription_registration_id: i64,
+        correlation_id: i64,
         endpoint_channel: CString,
-    ) -> Result<i64, AeronError> {
+    ) -> Result<(), AeronError> {
         log!(
             trace,
             "remove_rcv_destination: with subscription_registration_id:{} channel: {}",
@@ -1232,14 +1287,12 @@ impl ClientConductor {
         );
 
         self.verify_driver_is_active()?;
-        self.ensure_not_reentrant();
         self.ensure_open()?;
 
-        let correlation_id = self
-            .driver_proxy
-            .remove_rcv_destination(subscription_registration_id, endpoint_channel)?;
+        self.driver_proxy
+            .remove_rcv_destination(subscription_registration_id, correlation_id, endpoint_channel)?;
 
-        self.destination_state_by_correlation_id.insert(
+        self.destination_states().insert(
             correlation_id,
             DestinationStateDefn::new(correlation_id, subscription_registration_id, (self.epoch_clock)()),
         );
@@ -1250,40 +1303,114 @@ impl ClientConductor {
             correlation_id
         );
 
-        Ok(correlation_id)
+        Ok(())
+    }
+
+    /**
+     * Carry out commands queued by resource handles through their ConductorProxy.
+     *
+     * @return number of commands processed.
+     */
+    pub(crate) fn process_conductor_commands(&mut self) -> usize {
+        let mut work_count = 0;
+
+        while let Ok(command) = self.command_receiver.try_recv() {
+            self.on_conductor_command(command);
+            work_count += 1;
+        }
+
+        work_count
+    }
+
+    fn on_conductor_command(&mut self, command: ConductorCommand) {
+        let (registration_id, correlation_id, result) = match command {
+            ConductorCommand::AddDestination {
+                registration_id,
+                correlation_id,
+                channel,
+            } => (
+                registration_id,
+                correlation_id,
+                self.add_destination(registration_id, correlation_id, channel),
+            ),
+            ConductorCommand::RemoveDestination {
+                registration_id,
+                correlation_id,
+                channel,
+            } => (
+                registration_id,
+                correlation_id,
+                self.remove_destination(registration_id, correlation_id, channel),
+            ),
+            ConductorCommand::AddRcvDestination {
+                registration_id,
+                correlation_id,
+                channel,
+            } => (
+                registration_id,
+                correlation_id,
+                self.add_rcv_destination(registration_id, correlation_id, channel),
+            ),
+            ConductorCommand::RemoveRcvDestination {
+                registration_id,
+                correlation_id,
+                channel,
+            } => (
+                registration_id,
+                correlation_id,
+                self.remove_rcv_destination(registration_id, correlation_id, channel),
+            ),
+            ConductorCommand::ReleasePublication(registration_id) => {
+                let _ignored = self.release_publication(registration_id);
+                return;
+            }
+            ConductorCommand::ReleaseExclusivePublication(registration_id) => {
+                let _ignored = self.release_exclusive_publication(registration_id);
+                return;
+            }
+            ConductorCommand::ReleaseSubscription { registration_id, images } => {
+                let _ignored = self.release_subscription(registration_id, images);
+                return;
+            }
+            ConductorCommand::ReleaseCounter(registration_id) => {
+                let _ignored = self.release_counter(registration_id);
+                return;
+            }
+        };
+
+        // IC: the handle already returned the correlation id, so the failure is reported by find_destination_response
+        if let Err(error) = result {
+            let now_ms = (self.epoch_clock)();
+            let mut destination_states = self.destination_states();
+            let state = destination_states
+                .entry(correlation_id)
+                .or_insert_with(|| DestinationStateDefn::new(correlation_id, registration_id, now_ms));
+            state.status = RegistrationStatus::Errored;
+            state.error_code = ERROR_CODE_GENERIC_ERROR;
+            state.error_message = CString::new(error.to_string()).unwrap_or_default();
+        }
     }
 
     pub fn find_destination_response(&mut self, correlation_id: i64) -> Result<bool, AeronError> {
         self.ensure_not_reentrant();
         self.ensure_open()?;
 
-        let destination_to_remove: Option<i64> = None;
+        self.process_conductor_commands();
 
-        let result = if let Some(state) = self.destination_state_by_correlation_id.get_mut(&correlation_id) {
-            match state.status {
-                RegistrationStatus::Awaiting => {
-                    if (self.epoch_clock)() > state.time_of_registration_ms + self.driver_timeout_ms {
-                        Err(DriverInteractionError::NoResponse(self.driver_timeout_ms).into())
-                    } else {
-                        Ok(false)
-                    }
-                }
-                RegistrationStatus::Registered => Ok(true),
-                RegistrationStatus::Errored => Err(ClientConductor::return_registration_error(
-                    state.error_code,
-                    &state.error_message,
-                )),
-            }
+        let now_ms = (self.epoch_clock)();
+        if let Some(state) = self.destination_states().get_mut(&correlation_id) {
+            state.check_timeout(now_ms, self.driver_timeout_ms);
+            state.response(self.driver_timeout_ms)
         } else {
             Err(GenericError::UnknownCorrelationId(correlation_id).into())
-        };
-
-        if let Some(id) = destination_to_remove {
-            // Regardless of status remove this destination from the map
-            self.destination_state_by_correlation_id.remove(&id);
         }
+    }
 
-        result
+    fn on_check_destination_timeouts(&mut self, now_ms: Moment) {
+        let driver_timeout_ms = self.driver_timeout_ms;
+        for state in self.destination_states().values_mut() {
+            state.check_timeout(now_ms, driver_timeout_ms);
+        }
     }
 
     pub fn add_available_counter_handler(&mut self, handler: Box<dyn OnAvailableCounter>) -> Result<(), AeronError> {
@@ -1341,17 +1468,15 @@ impl ClientConductor {
         for pub_defn in self.publication_by_registration_id.values() {
             if let Some(maybe_publication) = &pub_defn.publication {
                 if let Some(publication) = maybe_publication.upgrade() {
-                    publication.lock().expect("Mutex on pub poisoned").close();
+                    publication.close();
                 }
             }
         }
         self.publication_by_registration_id.clear();
 
         for pub_defn in self.exclusive_publication_by_registration_id.values() {
-            if let Some(maybe_publication) = &pub_defn.publication {
-                if let Some(publication) = maybe_publication.upgrade() {
-                    publication.lock().expect("Mutex on ExPub poisoned").close();
-                }
+            if let Some(publication_closed) = &pub_defn.publication_closed {
+                publication_closed.store(true, Ordering::Release);
             }
         }
         self.exclusive_publication_by_registration_id.clear();
@@ -1458,12 +1583,13 @@ impl Agent for ClientConductor {
     }
 
     fn do_work(&mut self) -> Result<i32, AeronError> {
-        let mut work_count = 0;
+        let mut work_count = self.process_conductor_commands();
 
         let dla = self.driver_listener_adapter.take().unwrap();
         work_count += dla.receive_messages(self)?;
         self.driver_listener_adapter.replace(dla);
         work_count += self.on_heartbeat_check_timeouts() as usize;
+        self.on_check_destination_timeouts((self.epoch_clock)());
         Ok(work_count as i32)
     }
 
@@ -1588,6 +1714,7 @@ impl DriverListener for ClientConductor {
     }
 
     fn on_subscription_ready(&mut self, registration_id: i64, channel_status_id: i32) {
+        let conductor_proxy = self.conductor_proxy();
         if let Some(state) = self.subscription_by_registration_id.get_mut(&registration_id) {
             log!(
                 trace,
@@ -1599,7 +1726,7 @@ impl DriverListener for ClientConductor {
             state.status = RegistrationStatus::Registered;
 
             let subscr = Arc::new(Mutex::new(Subscription::new(
-                self.arced_self.as_ref().unwrap().clone(),
+                conductor_proxy,
                 state.registration_id,
                 state.channel.clone(),
                 state.stream_id,
@@ -1615,7 +1742,7 @@ impl DriverListener for ClientConductor {
     }
 
     fn on_operation_success(&mut self, correlation_id: i64) {
-        if let Some(state) = self.destination_state_by_correlation_id.get_mut(&correlation_id) {
+        if let Some(state) = self.destination_states().get_mut(&correlation_id) {
             log!(trace, "on_operation_success: correlation_id {}", correlation_id);
 
             if state.status == RegistrationStatus::Awaiting {
@@ -1668,16 +1795,14 @@ impl DriverListener for ClientConductor {
         for (reg_id, publication_defn) in &self.publication_by_registration_id {
             if let Some(maybe_publication) = &publication_defn.publication {
                 if let Some(publication) = maybe_publication.upgrade() {
-                    if publication.lock().expect("Mutex on pub poisoned").channel_status_id()
-                        == offending_command_correlation_id as i32
-                    {
+                    if publication.channel_status_id() == offending_command_correlation_id as i32 {
                         log!(trace, "on_channel_endpoint_error_response: for publication, offending_command_correlation_id {}, error_message {}", offending_command_correlation_id, error_message.to_str().unwrap());
 
                         self.error_handler.call(ChannelEndpointException(
                             offending_command_correlation_id,
                             String::from(error_message.to_str().expect("CString conversion error")),
                         ));
-                        publication.lock().expect("Mutex on pub poisoned").close();
+                        publication.close();
                         publication_to_remove.push(*reg_id);
                     }
                 }
@@ -1690,18 +1815,14 @@ impl DriverListener for ClientConductor {
 
         let mut epublication_to_remove: Vec<i64> = Vec::new();
         for (reg_id, publication_defn) in &self.exclusive_publication_by_registration_id {
-            if let Some(maybe_publication) = &publication_defn.publication {
-                if let Some(publication) = maybe_publication.upgrade() {
-                    if publication.lock().expect("Mutex on pub poisoned").channel_status_id()
-                        == offending_command_correlation_id as i32
-                    {
-                        self.error_handler.call(ChannelEndpointException(
-                            offending_command_correlation_id,
-                            String::from(error_message.to_str().expect("CString conversion error")),
-                        ));
-                        publication.lock().expect("Mutex on pub poisoned").close();
-                        epublication_to_remove.push(*reg_id);
-                    }
+            if let Some(publication_closed) = &publication_defn.publication_closed {
+                if publication_defn.channel_status_id == offending_command_correlation_id as i32 {
+                    self.error_handler.call(ChannelEndpointException(
+                        offending_command_correlation_id,
+                        String::from(error_message.to_str().expect("CString conversion error")),
+                    ));
+                    publication_closed.store(true, Ordering::Release);
+                    epublication_to_remove.push(*reg_id);
                 }
             }
         }
@@ -1768,10 +1889,7 @@ impl DriverListener for ClientConductor {
             return;
         }
 
-        if let Some(destination) = self
-            .destination_state_by_correlation_id
-            .get_mut(&offending_command_correlation_id)
-        {
+        if let Some(destination) = self.destination_states().get_mut(&offending_command_correlation_id) {
             log!(
                 trace,
                 "on_error_response: for destination, offending_command_correlation_id {}, error_code {}, error_message {}",
@@ -1894,6 +2012,7 @@ impl DriverListener for ClientConductor {
     }
 
     fn on_available_counter(&mut self, registration_id: i64, counter_id: i32) {
+        let conductor_proxy = self.conductor_proxy();
         if let Some(state) = self.counter_by_registration_id.get_mut(&registration_id) {
             log!(
                 trace,
@@ -1907,7 +2026,7 @@ impl DriverListener for ClientConductor {
                 state.counter_id = counter_id;
 
                 let cnt = Arc::new(Counter::new(
-                    self.arced_self.as_ref().unwrap().clone(),
+                    conductor_proxy,
                     self.counter_values_buffer,
                     state.registration_id,
                     counter_id,
@@ -1969,6 +2088,7 @@ mod tests {
     use nix::unistd;
 
     use crate::command::control_protocol_events::AeronCommand;
+    use crate::command::correlated_message_flyweight::CorrelatedMessageFlyweight;
     use crate::command::counter_message_flyweight::CounterMessageFlyweight;
     use crate::command::error_response_flyweight::{ERROR_CODE_GENERIC_ERROR, ERROR_CODE_INVALID_CHANNEL};
     use crate::command::publication_message_flyweight::PublicationMessageFlyweight;
@@ -1980,6 +2100,7 @@ mod tests {
     use crate::concurrent::logbuffer::log_buffer_descriptor;
     use crate::concurrent::ring_buffer;
     use crate::concurrent::ring_buffer::ManyToOneRingBuffer;
+    use crate::concurrent::status::status_indicator_reader;
     use crate::utils::memory_mapped_file::MemoryMappedFile;
     use crate::utils::misc::unix_time_ms;
 
@@ -1992,6 +2113,7 @@ mod tests {
     const PUBLICATION_LIMIT_COUNTER_ID_2: i32 = 1;
     const CHANNEL_STATUS_INDICATOR_ID: i32 = 2;
     const COUNTER_ID: i32 = 3;
+    const PUBLICATION_REGISTRATION_ID: i64 = 42;
     const TERM_LENGTH: i32 = log_buffer_descriptor::TERM_MIN_LENGTH;
     const PAGE_SIZE: i32 = log_buffer_descriptor::AERON_PAGE_MIN_SIZE;
     const COUNTER_TYPE_ID: i32 = 102;
@@ -2263,7 +2385,6 @@ mod tests {
         );
 
         let publication = test.conductor.lock().unwrap().find_publication(id).unwrap();
-        let publication = publication.lock().unwrap();
 
         assert_eq!(publication.registration_id(), id);
         assert_eq!(publication.channel(), str_to_c(CHANNEL));
@@ -2303,6 +2424,9 @@ mod tests {
             assert!(publication.is_ok());
         }
 
+        // IC: dropping the handle only queues the release for the conductor duty cycle
+        test.conductor.lock().unwrap().process_conductor_commands();
+
         let count = test.many_to_one_ring_buffer.read(
             |msg_type_id, buffer| {
                 let message = RemoveMessageFlyweight::new(buffer, 0);
@@ -2499,7 +2623,6 @@ mod tests {
         );
 
         let publication = test.conductor.lock().unwrap().find_exclusive_publication(id).unwrap();
-        let publication = publication.lock().unwrap();
 
         assert_eq!(publication.registration_id(), id);
         assert_eq!(publication.channel(), str_to_c(CHANNEL));
@@ -2536,6 +2659,8 @@ mod tests {
             assert!(publication.is_ok());
         }
 
+        test.conductor.lock().unwrap().process_conductor_commands();
+
         let count = test.many_to_one_ring_buffer.read(
             |msg_type_id, buffer| {
                 let message = RemoveMessageFlyweight::new(buffer, 0);
@@ -2572,7 +2697,7 @@ mod tests {
     }
 
     #[test]
-    fn should_return_same_exclusive_publication_after_log_buffers_created() {
+    fn should_hand_out_exclusive_publication_only_once_after_log_buffers_created() {
         let test = ClientConductorTest::new();
 
         let id = test
@@ -2596,8 +2721,10 @@ mod tests {
         let publication2 = test.conductor.lock().unwrap().find_exclusive_publication(id);
 
         assert!(publication1.is_ok());
-        assert!(publication2.is_ok());
-        assert!(Arc::ptr_eq(&publication1.unwrap(), &publication2.unwrap()));
+        assert!(matches!(
+            publication2,
+            Err(AeronError::Generic(GenericError::ExclusivePublicationAlreadyTaken))
+        ));
     }
 
     #[test]
@@ -2799,6 +2926,8 @@ mod tests {
             assert!(subscription.is_ok());
         }
 
+        test.conductor.lock().unwrap().process_conductor_commands();
+
         let count = test.many_to_one_ring_buffer.read(
             |msg_type_id, buffer| {
                 let message = RemoveMessageFlyweight::new(buffer, 0);
@@ -3672,6 +3801,8 @@ mod tests {
             assert!(subscription.unwrap().lock().unwrap().has_image(correlation_id));
         }
 
+        test.conductor.lock().unwrap().process_conductor_commands();
+
         let sub_called: bool = ON_NEW_SUB_CALLED8.load(Ordering::SeqCst);
         assert!(sub_called);
         let img_called: bool = ON_NEW_IMG_CALLED8.load(Ordering::SeqCst);
@@ -3709,7 +3840,7 @@ mod tests {
             .lock()
             .unwrap()
             .close_all_resources(*test.current_time.lock().unwrap());
-        assert!(publication.unwrap().lock().unwrap().is_closed());
+        assert!(publication.unwrap().is_closed());
     }
 
     #[test]
@@ -3741,7 +3872,7 @@ mod tests {
             .lock()
             .unwrap()
             .close_all_resources(*test.current_time.lock().unwrap());
-        assert!(publication.unwrap().lock().unwrap().is_closed());
+        assert!(publication.unwrap().is_closed());
     }
 
     #[test]
@@ -3844,9 +3975,9 @@ mod tests {
             .lock()
             .unwrap()
             .close_all_resources(*test.current_time.lock().unwrap());
-        assert!(publication.unwrap().lock().unwrap().is_closed());
+        assert!(publication.unwrap().is_closed());
         assert!(subscription.unwrap().lock().unwrap().is_closed());
-        assert!(ex_pub.unwrap().lock().unwrap().is_closed());
+        assert!(ex_pub.unwrap().is_closed());
     }
 
     #[test]
@@ -4010,6 +4141,8 @@ mod tests {
             assert!(counter.is_ok());
         }
 
+        test.conductor.lock().unwrap().process_conductor_commands();
+
         let count = test.many_to_one_ring_buffer.read(
             |msg_type_id, buffer| {
                 let message = RemoveMessageFlyweight::new(buffer, 0);
@@ -4026,6 +4159,118 @@ mod tests {
         assert!(counter_post.is_err());
     }
 
+    #[test]
+    fn should_read_counter_without_conductor_lock() {
+        let test = ClientConductorTest::new();
+
+        let no_key_buffer = Vec::with_capacity(1);
+        let id = test
+            .conductor
+            .lock()
+            .unwrap()
+            .add_counter(COUNTER_TYPE_ID, &no_key_buffer, COUNTER_LABEL)
+            .unwrap();
+        test.conductor.lock().unwrap().on_available_counter(id, COUNTER_ID);
+        let counter = test.conductor.lock().unwrap().find_counter(id).unwrap();
+
+        // IC: do_work holds this lock, counter queries must not wait for it
+        let _conductor_guard = test.conductor.lock().unwrap();
+        assert!(counter.state().is_ok());
+        assert!(counter.label().is_ok());
+    }
+
+    #[test]
+    fn should_send_queued_add_destination_on_duty_cycle() {
+        let test = ClientConductorTest::new();
+        let proxy = test.conductor.lock().unwrap().conductor_proxy();
+
+        let correlation_id = proxy
+            .add_destination(PUBLICATION_REGISTRATION_ID, str_to_c(CHANNEL))
+            .expect("failed to queue add destination");
+
+        let count = test.many_to_one_ring_buffer.read(|_msg_type_id, _buffer| {}, 1000);
+        assert_eq!(count, 0);
+
+        test.conductor.lock().unwrap().process_conductor_commands();
+
+        let count = test.many_to_one_ring_buffer.read(
+            |msg_type_id, buffer| {
+                let message = CorrelatedMessageFlyweight::new(buffer, 0);
+
+                assert_eq!(msg_type_id, AeronCommand::AddDestination);
+                assert_eq!(message.correlation_id(), correlation_id);
+            },
+            1000,
+        );
+        assert_eq!(count, 1);
+
+        assert!(!proxy.find_destination_response(correlation_id).unwrap());
+        test.conductor.lock().unwrap().on_operation_success(correlation_id);
+        assert!(proxy.find_destination_response(correlation_id).unwrap());
+    }
+
+    #[test]
+    fn should_find_destination_response_for_command_still_queued() {
+        let test = ClientConductorTest::new();
+        let proxy = test.conductor.lock().unwrap().conductor_proxy();
+
+        let correlation_id = proxy
+            .add_destination(PUBLICATION_REGISTRATION_ID, str_to_c(CHANNEL))
+            .expect("failed to queue add destination");
+
+        assert!(!proxy.find_destination_response(correlation_id).unwrap());
+    }
+
+    #[test]
+    fn should_find_destination_response_without_conductor_lock() {
+        let test = ClientConductorTest::new();
+        let proxy = test.conductor.lock().unwrap().conductor_proxy();
+
+        let correlation_id = proxy
+            .add_destination(PUBLICATION_REGISTRATION_ID, str_to_c(CHANNEL))
+            .expect("failed to queue add destination");
+        test.conductor.lock().unwrap().process_conductor_commands();
+        test.conductor.lock().unwrap().on_operation_success(correlation_id);
+
+        // IC: do_work holds this lock, destination responses must not wait for it
+        let _conductor_guard = test.conductor.lock().unwrap();
+        assert!(proxy.find_destination_response(correlation_id).unwrap());
+        assert_that!(
+            &proxy.find_destination_response(correlation_id + 1).err().unwrap(),
+            has_structure!(AeronError::Generic[any_value()])
+        );
+    }
+
+    #[test]
+    fn should_timeout_destination_response_flagged_by_conductor() {
+        let test = ClientConductorTest::new();
+        let proxy = test.conductor.lock().unwrap().conductor_proxy();
+
+        let correlation_id = proxy
+            .add_destination(PUBLICATION_REGISTRATION_ID, str_to_c(CHANNEL))
+            .expect("failed to queue add destination");
+        test.conductor.lock().unwrap().process_conductor_commands();
+
+        let now_ms = driver_timeout_provider();
+        test.conductor.lock().unwrap().on_check_destination_timeouts(now_ms);
+
+        assert_that!(
+            &proxy.find_destination_response(correlation_id).err().unwrap(),
+            has_structure!(AeronError::DriverTimeout[any_value()])
+        );
+    }
+
+    #[test]
+    fn should_not_panic_on_channel_status_of_counter_id_out_of_range() {
+        let test = ClientConductorTest::new();
+        let proxy = test.conductor.lock().unwrap().conductor_proxy();
+
+        assert_eq!(
+            proxy.channel_status(i32::MAX),
+            status_indicator_reader::NO_ID_ALLOCATED as i64
+        );
+    }
+
     #[test]
     fn should_return_different_ids_for_duplicate_add_counter_calls() {
         let test = ClientConductorTest::new();
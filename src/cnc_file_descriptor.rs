@@ -175,3 +175,48 @@ pub fn pid(cnc_file: &MemoryMappedFile) -> i64 {
 
     meta_data.pid
 }
+
+/**
+ * Length of a CnC file holding buffers of the given total length.
+ */
+pub fn compute_cnc_file_length(total_length_of_buffers: Index) -> Index {
+    *META_DATA_LENGTH + total_length_of_buffers
+}
+
+/**
+ * Fill in the meta data of a new CnC file. The version is written last by signal_cnc_ready() so clients
+ * do not start using the file before it is complete.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn fill_meta_data(
+    cnc_meta_data_buffer: &AtomicBuffer,
+    to_driver_buffer_length: Index,
+    to_clients_buffer_length: Index,
+    counter_metadata_buffer_length: Index,
+    counter_values_buffer_length: Index,
+    error_log_buffer_length: Index,
+    client_liveness_timeout_ns: i64,
+    start_timestamp: i64,
+    pid: i64,
+) {
+    let meta_data = MetaDataDefn {
+        cnc_version: 0,
+        to_driver_buffer_length,
+        to_clients_buffer_length,
+        counter_metadata_buffer_length,
+        counter_values_buffer_length,
+        error_log_buffer_length,
+        client_liveness_timeout: client_liveness_timeout_ns,
+        start_timestamp,
+        pid,
+    };
+
+    cnc_meta_data_buffer.put::<MetaDataDefn>(0, meta_data);
+}
+
+/**
+ * Signal that the CnC file is ready for use by clients by writing the version.
+ */
+pub fn signal_cnc_ready(cnc_meta_data_buffer: &AtomicBuffer) {
+    cnc_meta_data_buffer.put_ordered::<i32>(offset_of!(MetaDataDefn, cnc_version) as Index, CNC_VERSION);
+}
@@ -37,7 +37,7 @@ pub(crate) struct ClientTimeoutDefn {
 
 pub const CLIENT_TIMEOUT_LENGTH: Index = std::mem::size_of::<ClientTimeoutDefn>() as Index;
 
-pub(crate) struct ClientTimeoutFlyweight {
+pub struct ClientTimeoutFlyweight {
     flyweight: Flyweight<ClientTimeoutDefn>,
 }
 
@@ -52,4 +52,13 @@ impl ClientTimeoutFlyweight {
     pub fn client_id(&self) -> i64 {
         unsafe { (*self.flyweight.m_struct).client_id }
     }
+
+    // Setters
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_client_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).client_id = value;
+        }
+    }
 }
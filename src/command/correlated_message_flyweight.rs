@@ -39,8 +39,8 @@ pub(crate) struct CorrelatedMessageDefn {
 
 pub const CORRELATED_MESSAGE_LENGTH: Index = std::mem::size_of::<CorrelatedMessageDefn>() as Index;
 
-pub(crate) struct CorrelatedMessageFlyweight {
-    pub flyweight: Flyweight<CorrelatedMessageDefn>,
+pub struct CorrelatedMessageFlyweight {
+    pub(crate) flyweight: Flyweight<CorrelatedMessageDefn>,
 }
 
 impl CorrelatedMessageFlyweight {
@@ -52,7 +52,12 @@ impl CorrelatedMessageFlyweight {
 
     // Getters
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn client_id(&self) -> i64 {
+        unsafe { (*self.flyweight.m_struct).client_id }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn correlation_id(&self) -> i64 {
         unsafe { (*self.flyweight.m_struct).correlation_id }
     }
@@ -14,7 +14,7 @@
  * limitations under the License.
  */
 
-#[cfg(test)]
+#[cfg(any(test, feature = "embedded-driver"))]
 use std::ffi::CString;
 
 use crate::{
@@ -58,7 +58,7 @@ pub(crate) struct CounterMessageDefn {
 
 pub const COUNTER_MESSAGE_LENGTH: Index = std::mem::size_of::<CounterMessageDefn>() as Index;
 
-pub(crate) struct CounterMessageFlyweight {
+pub struct CounterMessageFlyweight {
     correlated_message_flyweight: CorrelatedMessageFlyweight,
     m_struct: *mut CounterMessageDefn, // This is actually part of above field memory space
 }
@@ -73,7 +73,7 @@ impl CounterMessageFlyweight {
         }
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn type_id(&self) -> i32 {
         unsafe { (*self.m_struct).type_id }
     }
@@ -112,6 +112,19 @@ impl CounterMessageFlyweight {
         length
     }
 
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn key(&self) -> Vec<u8> {
+        let mut key = vec![0u8; self.key_length() as usize];
+
+        self.correlated_message_flyweight.flyweight.get_bytes(
+            self.key_length_offset() + I32_SIZE,
+            key.as_mut_ptr(),
+            key.len() as Index,
+        );
+
+        key
+    }
+
     #[inline]
     pub fn label_length(&self) -> Index {
         self.correlated_message_flyweight
@@ -119,7 +132,7 @@ impl CounterMessageFlyweight {
             .string_get_length(self.label_length_offset())
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn label(&self) -> CString {
         self.correlated_message_flyweight
             .flyweight
@@ -140,7 +153,12 @@ impl CounterMessageFlyweight {
 
     // Parent Getters
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn client_id(&self) -> i64 {
+        self.correlated_message_flyweight.client_id()
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn correlation_id(&self) -> i64 {
         self.correlated_message_flyweight.correlation_id()
     }
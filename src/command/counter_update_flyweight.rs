@@ -40,7 +40,7 @@ pub(crate) struct CounterUpdateDefn {
 
 pub const COUNTER_READY_LENGTH: Index = std::mem::size_of::<CounterUpdateDefn>() as Index;
 
-pub(crate) struct CounterUpdateFlyweight {
+pub struct CounterUpdateFlyweight {
     flyweight: Flyweight<CounterUpdateDefn>,
 }
 
@@ -62,4 +62,20 @@ impl CounterUpdateFlyweight {
     pub fn counter_id(&self) -> i32 {
         unsafe { (*self.flyweight.m_struct).counter_id }
     }
+
+    // Setters
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_correlation_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).correlation_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_counter_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).counter_id = value;
+        }
+    }
 }
@@ -13,6 +13,9 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
+#[cfg(any(test, feature = "embedded-driver"))]
+use std::ffi::CString;
+
 use crate::{
     command::correlated_message_flyweight::{CorrelatedMessageDefn, CorrelatedMessageFlyweight},
     concurrent::atomic_buffer::AtomicBuffer,
@@ -28,7 +31,7 @@ struct DestinationMessageDefn {
     channel_data: [i8; 1],
 }
 
-pub(crate) struct DestinationMessageFlyweight {
+pub struct DestinationMessageFlyweight {
     correlated_message_flyweight: CorrelatedMessageFlyweight,
     m_struct: *mut DestinationMessageDefn, // This is actually part of above field memory space
 }
@@ -45,6 +48,11 @@ impl DestinationMessageFlyweight {
         }
     }
 
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn registration_id(&self) -> i64 {
+        unsafe { (*self.m_struct).registration_id }
+    }
+
     #[inline]
     pub fn set_registration_id(&mut self, value: i64) {
         unsafe {
@@ -52,6 +60,13 @@ impl DestinationMessageFlyweight {
         }
     }
 
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn channel(&self) -> CString {
+        self.correlated_message_flyweight
+            .flyweight
+            .string_get(offset_of!(DestinationMessageDefn, channel_length) as Index)
+    }
+
     #[inline]
     pub fn set_channel(&mut self, value: &[u8]) {
         self.correlated_message_flyweight
@@ -64,6 +79,17 @@ impl DestinationMessageFlyweight {
         unsafe { offset_of!(DestinationMessageDefn, channel_data) as Index + (*self.m_struct).channel_length as Index }
     }
 
+    // Parent Getters
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn client_id(&self) -> i64 {
+        self.correlated_message_flyweight.client_id()
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn correlation_id(&self) -> i64 {
+        self.correlated_message_flyweight.correlation_id()
+    }
+
     // Parent Setters
 
     #[inline]
@@ -93,4 +93,26 @@ impl ErrorResponseFlyweight {
             offset_of!(ErrorResponseDefn, error_message_data) as Index + (*self.flyweight.m_struct).error_message_length as Index
         }
     }
+
+    // Setters
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_offending_command_correlation_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).offending_command_correlation_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_error_code(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).error_code = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_error_message(&mut self, value: &[u8]) {
+        self.flyweight
+            .string_put(offset_of!(ErrorResponseDefn, error_message_length) as Index, value);
+    }
 }
@@ -66,7 +66,7 @@ pub(crate) struct ImageBuffersReadyDefn {
 
 pub const IMAGE_BUFFERS_READY_LENGTH: Index = std::mem::size_of::<ImageBuffersReadyDefn>() as Index;
 
-pub(crate) struct ImageBuffersReadyFlyweight {
+pub struct ImageBuffersReadyFlyweight {
     flyweight: Flyweight<ImageBuffersReadyDefn>,
 }
 
@@ -110,6 +110,62 @@ impl ImageBuffersReadyFlyweight {
     pub fn source_identity(&self) -> CString {
         self.flyweight.string_get(self.source_identity_offset())
     }
+
+    // Setters
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_correlation_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).correlation_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_session_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).session_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_stream_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).stream_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_subscription_registration_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).subscription_registration_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_subscriber_position_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).subscriber_position_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_log_file_name(&mut self, value: &[u8]) {
+        let offset = self.log_file_name_offset();
+        self.flyweight.string_put(offset, value);
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_source_identity(&mut self, value: &[u8]) {
+        let offset = self.source_identity_offset();
+        self.flyweight.string_put(offset, value);
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn length(&self) -> Index {
+        let offset = self.source_identity_offset();
+
+        offset + I32_SIZE + self.flyweight.string_get_length(offset)
+    }
 }
 
 impl ImageBuffersReadyFlyweight {
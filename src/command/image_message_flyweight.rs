@@ -49,7 +49,7 @@ pub(crate) struct ImageMessageDefn {
     channel_data: [i8; 1],
 }
 
-pub(crate) struct ImageMessageFlyweight {
+pub struct ImageMessageFlyweight {
     flyweight: Flyweight<ImageMessageDefn>,
 }
 
@@ -71,4 +71,38 @@ impl ImageMessageFlyweight {
     pub fn subscription_registration_id(&self) -> i64 {
         unsafe { (*self.flyweight.m_struct).subscription_registration_id }
     }
+
+    // Setters
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_correlation_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).correlation_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_subscription_registration_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).subscription_registration_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_stream_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).stream_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_channel(&mut self, value: &[u8]) {
+        self.flyweight
+            .string_put(offset_of!(ImageMessageDefn, channel_length) as Index, value);
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn length(&self) -> Index {
+        unsafe { offset_of!(ImageMessageDefn, channel_data) as Index + (*self.flyweight.m_struct).channel_length as Index }
+    }
 }
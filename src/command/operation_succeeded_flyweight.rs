@@ -37,7 +37,7 @@ pub(crate) struct OperationSucceededDefn {
 
 pub const OPERATION_SUCCEEDED_LENGTH: Index = std::mem::size_of::<OperationSucceededDefn>() as Index;
 
-pub(crate) struct OperationSucceededFlyweight {
+pub struct OperationSucceededFlyweight {
     flyweight: Flyweight<OperationSucceededDefn>,
 }
 
@@ -52,4 +52,13 @@ impl OperationSucceededFlyweight {
     pub fn correlation_id(&self) -> i64 {
         unsafe { (*self.flyweight.m_struct).correlation_id }
     }
+
+    // Setters
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_correlation_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).correlation_id = value;
+        }
+    }
 }
@@ -62,7 +62,7 @@ pub(crate) struct PublicationBuffersReadyDefn {
     log_file_data: [i8; 1],
 }
 
-pub(crate) struct PublicationBuffersReadyFlyweight {
+pub struct PublicationBuffersReadyFlyweight {
     flyweight: Flyweight<PublicationBuffersReadyDefn>,
 }
 
@@ -111,4 +111,61 @@ impl PublicationBuffersReadyFlyweight {
         self.flyweight
             .string_get(offset_of!(PublicationBuffersReadyDefn, log_file_length) as Index)
     }
+
+    // Setters
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_correlation_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).correlation_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_registration_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).registration_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_session_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).session_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_stream_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).stream_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_position_limit_counter_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).position_limit_counter_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_channel_status_indicator_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).channel_status_indicator_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_log_file_name(&mut self, value: &[u8]) {
+        self.flyweight
+            .string_put(offset_of!(PublicationBuffersReadyDefn, log_file_length) as Index, value);
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn length(&self) -> Index {
+        unsafe {
+            offset_of!(PublicationBuffersReadyDefn, log_file_data) as Index + (*self.flyweight.m_struct).log_file_length as Index
+        }
+    }
 }
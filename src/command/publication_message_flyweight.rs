@@ -13,7 +13,7 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-#[cfg(test)]
+#[cfg(any(test, feature = "embedded-driver"))]
 use std::ffi::CString;
 
 use crate::{
@@ -54,7 +54,7 @@ pub(crate) struct PublicationMessageDefn {
     channel_data: [i8; 1],
 }
 
-pub(crate) struct PublicationMessageFlyweight {
+pub struct PublicationMessageFlyweight {
     correlated_message_flyweight: CorrelatedMessageFlyweight,
     m_struct: *mut PublicationMessageDefn, // This is actually part of above field memory space
 }
@@ -71,7 +71,7 @@ impl PublicationMessageFlyweight {
         }
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn stream_id(&self) -> i32 {
         unsafe { (*self.m_struct).stream_id }
     }
@@ -83,7 +83,7 @@ impl PublicationMessageFlyweight {
         }
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn channel(&self) -> CString {
         self.correlated_message_flyweight
             .flyweight
@@ -103,7 +103,12 @@ impl PublicationMessageFlyweight {
     }
 
     // Parent Getters
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn client_id(&self) -> i64 {
+        self.correlated_message_flyweight.client_id()
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn correlation_id(&self) -> i64 {
         self.correlated_message_flyweight.correlation_id()
     }
@@ -45,7 +45,7 @@ pub(crate) struct RemoveMessageDefn {
 
 pub const REMOVE_MESSAGE_LENGTH: Index = std::mem::size_of::<RemoveMessageDefn>() as Index;
 
-pub(crate) struct RemoveMessageFlyweight {
+pub struct RemoveMessageFlyweight {
     correlated_message_flyweight: CorrelatedMessageFlyweight,
     m_struct: *mut RemoveMessageDefn, // This is actually part of above field memory space
 }
@@ -60,7 +60,7 @@ impl RemoveMessageFlyweight {
         }
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn registration_id(&self) -> i64 {
         unsafe { (*self.m_struct).registration_id }
     }
@@ -77,6 +77,17 @@ impl RemoveMessageFlyweight {
         REMOVE_MESSAGE_LENGTH
     }
 
+    // Parent Getters
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn client_id(&self) -> i64 {
+        self.correlated_message_flyweight.client_id()
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn correlation_id(&self) -> i64 {
+        self.correlated_message_flyweight.correlation_id()
+    }
+
     // Parent Setters
     #[inline]
     pub fn set_client_id(&mut self, value: i64) {
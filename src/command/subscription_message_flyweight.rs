@@ -13,7 +13,7 @@
  * See the License for the specific language governing permissions and
  * limitations under the License.
  */
-#[cfg(test)]
+#[cfg(any(test, feature = "embedded-driver"))]
 use std::ffi::CString;
 
 use crate::{
@@ -57,7 +57,7 @@ pub(crate) struct SubscriptionMessageDefn {
     channel_data: [i8; 1],
 }
 
-pub(crate) struct SubscriptionMessageFlyweight {
+pub struct SubscriptionMessageFlyweight {
     correlated_message_flyweight: CorrelatedMessageFlyweight,
     m_struct: *mut SubscriptionMessageDefn,
 }
@@ -74,7 +74,7 @@ impl SubscriptionMessageFlyweight {
         }
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn stream_id(&self) -> i32 {
         unsafe { (*self.m_struct).stream_id }
     }
@@ -93,7 +93,7 @@ impl SubscriptionMessageFlyweight {
         }
     }
 
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn channel(&self) -> CString {
         self.correlated_message_flyweight
             .flyweight
@@ -113,7 +113,12 @@ impl SubscriptionMessageFlyweight {
     }
 
     // Parent Getters
-    #[cfg(test)]
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn client_id(&self) -> i64 {
+        self.correlated_message_flyweight.client_id()
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
     pub fn correlation_id(&self) -> i64 {
         self.correlated_message_flyweight.correlation_id()
     }
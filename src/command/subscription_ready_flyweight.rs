@@ -40,7 +40,7 @@ pub(crate) struct SubscriptionReadyDefn {
 
 pub const SUBSCRIPTION_READY_LENGTH: Index = std::mem::size_of::<SubscriptionReadyDefn>() as Index;
 
-pub(crate) struct SubscriptionReadyFlyweight {
+pub struct SubscriptionReadyFlyweight {
     flyweight: Flyweight<SubscriptionReadyDefn>,
 }
 
@@ -62,4 +62,20 @@ impl SubscriptionReadyFlyweight {
     pub fn channel_status_indicator_id(&self) -> i32 {
         unsafe { (*self.flyweight.m_struct).channel_status_indicator_id }
     }
+
+    // Setters
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_correlation_id(&mut self, value: i64) {
+        unsafe {
+            (*self.flyweight.m_struct).correlation_id = value;
+        }
+    }
+
+    #[cfg(any(test, feature = "embedded-driver"))]
+    pub fn set_channel_status_indicator_id(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).channel_status_indicator_id = value;
+        }
+    }
 }
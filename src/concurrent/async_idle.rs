@@ -0,0 +1,150 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures::future::poll_fn;
+use futures_timer::Delay;
+
+use crate::concurrent::atomics::cpu_pause;
+use crate::concurrent::strategies::{IdleAction, IdlePolicy};
+
+/**
+ * Applies an IdlePolicy from async code. Instead of blocking the thread a yield hands control back to
+ * the executor and a park waits on a timer.
+ */
+pub struct AsyncIdle<P: IdlePolicy> {
+    policy: P,
+    delay: Option<Delay>,
+    yielded: bool,
+}
+
+impl<P: IdlePolicy> AsyncIdle<P> {
+    pub fn new(policy: P) -> Self {
+        Self {
+            policy,
+            delay: None,
+            yielded: false,
+        }
+    }
+
+    pub fn policy(&self) -> &P {
+        &self.policy
+    }
+
+    /**
+     * Reset the policy after work has been done and drop any pending timer.
+     */
+    pub fn reset(&mut self) {
+        self.policy.reset_idle();
+        self.delay = None;
+        self.yielded = false;
+    }
+
+    /**
+     * Poll for the end of the current unit of idle time.
+     *
+     * @return Poll::Ready when it is time to check for work again.
+     */
+    pub fn poll_idle(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        if self.yielded {
+            self.yielded = false;
+            return Poll::Ready(());
+        }
+
+        if let Some(delay) = self.delay.as_mut() {
+            futures::ready!(Pin::new(delay).poll(cx));
+            self.delay = None;
+            return Poll::Ready(());
+        }
+
+        match self.policy.idle_action() {
+            IdleAction::Spin => {
+                cpu_pause();
+                Poll::Ready(())
+            }
+            IdleAction::Yield => {
+                // IC: wake straight away so the executor gets to run other tasks before polling us again
+                self.yielded = true;
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+            IdleAction::Park(period) => {
+                let mut delay = Delay::new(period);
+                match Pin::new(&mut delay).poll(cx) {
+                    Poll::Ready(()) => Poll::Ready(()),
+                    Poll::Pending => {
+                        self.delay = Some(delay);
+                        Poll::Pending
+                    }
+                }
+            }
+        }
+    }
+
+    /**
+     * Wait for one unit of idle time.
+     */
+    pub async fn idle(&mut self) {
+        poll_fn(|cx| self.poll_idle(cx)).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use futures::executor::block_on;
+
+    use crate::concurrent::async_idle::AsyncIdle;
+    use crate::concurrent::strategies::{BackOffIdleStrategy, IdleAction, IdlePolicy};
+
+    #[test]
+    fn should_spin_then_yield_then_park() {
+        let mut policy = BackOffIdleStrategy::new(2, 2, 1_000, 4_000);
+
+        let actions: Vec<IdleAction> = (0..8).map(|_| policy.idle_action()).collect();
+        assert_eq!(
+            actions,
+            vec![
+                IdleAction::Spin,
+                IdleAction::Spin,
+                IdleAction::Spin,
+                IdleAction::Yield,
+                IdleAction::Yield,
+                IdleAction::Yield,
+                IdleAction::Park(Duration::from_nanos(1_000)),
+                IdleAction::Park(Duration::from_nanos(2_000)),
+            ]
+        );
+
+        policy.reset_idle();
+        assert_eq!(policy.idle_action(), IdleAction::Spin);
+    }
+
+    #[test]
+    fn should_complete_idle_through_every_action() {
+        let mut idle = AsyncIdle::new(BackOffIdleStrategy::new(1, 1, 1_000, 1_000_000));
+
+        block_on(async {
+            for _ in 0..6 {
+                idle.idle().await;
+            }
+        });
+    }
+}
@@ -135,7 +135,7 @@ impl AtomicBuffer {
     #[inline]
     pub fn get<T: Copy>(&self, position: Index) -> T {
         self.bounds_check(position, std::mem::size_of::<T>() as Index);
-        unsafe { *(self.at(position) as *mut T) }
+        unsafe { (self.at(position) as *const T).read_unaligned() }
     }
 
     #[inline]
@@ -184,7 +184,7 @@ impl AtomicBuffer {
     #[inline]
     pub fn put<T>(&self, position: Index, val: T) {
         self.bounds_check(position, std::mem::size_of::<T>() as Index);
-        unsafe { *(self.at(position) as *mut T) = val }
+        unsafe { (self.at(position) as *mut T).write_unaligned(val) }
     }
 
     #[inline]
@@ -387,6 +387,20 @@ mod tests {
         assert_eq!(read, to_write)
     }
 
+    #[test]
+    fn atomic_buffer_write_read_unaligned() {
+        let src = AlignedBuffer::with_capacity(32);
+        let buffer = AtomicBuffer::from_aligned(&src);
+
+        // IC: SBE encoded messages place i64 fields at offsets which are not multiples of 8
+        buffer.put::<i64>(3, -0x0102_0304_0506_0708);
+        buffer.put::<i32>(13, 0x0a0b_0c0d);
+
+        assert_eq!(buffer.get::<i64>(3), -0x0102_0304_0506_0708);
+        assert_eq!(buffer.get::<i32>(13), 0x0a0b_0c0d);
+        assert_eq!(buffer.get::<u8>(3), 0xf8);
+    }
+
     #[test]
     fn atomic_buffer_preserves_from_aligned() {
         let buffer = AlignedBuffer::with_capacity(8);
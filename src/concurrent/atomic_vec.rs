@@ -85,8 +85,9 @@ impl<T> AtomicVec<T> {
                 let seq_no = self.begin_change.load(Ordering::Acquire).wrapping_add(1);
                 self.begin_change.store(seq_no, Ordering::Release);
 
+                // IC: the change counters carry on from here, resetting them would leave begin and end apart
+                // and every later load() or take() would spin forever
                 let buf = mem::take(&mut self.buf);
-                *self = Self::new();
 
                 self.end_change.store(seq_no, Ordering::Release);
 
@@ -138,3 +139,22 @@ impl<T: Clone> AtomicVec<T> {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_load_and_take_again_after_take() {
+        let mut vec = AtomicVec::new();
+        vec.add(1);
+        vec.add(2);
+
+        assert_eq!(vec.take(), vec![1, 2]);
+        assert!(vec.load().is_empty());
+        assert!(vec.take().is_empty());
+
+        vec.add(3);
+        assert_eq!(vec.load_val(), vec![3]);
+    }
+}
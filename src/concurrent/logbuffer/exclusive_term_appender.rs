@@ -38,6 +38,14 @@ pub struct ExclusiveTermAppender {
     tail_addr: *const i64,
 }
 
+// SAFETY: term_buffer and tail_addr point into the log buffers of the ExclusivePublication owning the appender,
+// which holds an Arc<LogBuffers> for as long as it lives. The client conductor only unmaps log buffers once it holds
+// the last reference to them, so closing the publication, by the conductor on another thread or by the client
+// closing, never unmaps the log under an offer in progress: the closed flag only stops further offers, and a check
+// of it racing with the close still appends to mapped memory. The appender is not Sync as ExclusivePublication
+// appends through &mut self, so it is used from one thread at a time and may only be moved between threads.
+unsafe impl Send for ExclusiveTermAppender {}
+
 impl ExclusiveTermAppender {
     pub fn new(term_buffer: AtomicBuffer, meta_data_buffer: AtomicBuffer, partition_index: Index) -> Self {
         // This check implemented as assert. Looks like out of bounds here can be reached only
@@ -12,3 +12,6 @@ pub mod reports;
 pub mod ring_buffer;
 pub mod status;
 pub mod strategies;
+
+#[cfg(feature = "async")]
+pub mod async_idle;
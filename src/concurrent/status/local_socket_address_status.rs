@@ -0,0 +1,111 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::concurrent::counters::CountersReader;
+use crate::concurrent::status::status_indicator_reader::CHANNEL_ENDPOINT_ACTIVE;
+use crate::utils::types::{Index, I32_SIZE};
+
+/**
+ * Type id of the counter which holds the local socket address bound by a channel endpoint.
+ */
+pub const LOCAL_SOCKET_ADDRESS_STATUS_TYPE_ID: i32 = 14;
+
+const CHANNEL_STATUS_ID_OFFSET: Index = 0;
+const LOCAL_SOCKET_ADDRESS_LENGTH_OFFSET: Index = CHANNEL_STATUS_ID_OFFSET + I32_SIZE;
+
+/**
+ * Find the list of currently bound local sockets.
+ *
+ * @param counters_reader  for the connected driver.
+ * @param channel_status   value for the channel which aggregates the transports.
+ * @param channel_status_id identity of the counter for the channel which aggregates the transports.
+ * @return the list of active bound local socket addresses.
+ */
+pub fn find_addresses(counters_reader: &CountersReader, channel_status: i64, channel_status_id: i32) -> Vec<String> {
+    let mut addresses = Vec::new();
+
+    if channel_status == CHANNEL_ENDPOINT_ACTIVE {
+        counters_reader.for_each(|counter_id, type_id, key_buffer, _label| {
+            if type_id == LOCAL_SOCKET_ADDRESS_STATUS_TYPE_ID
+                && key_buffer.get::<i32>(CHANNEL_STATUS_ID_OFFSET) == channel_status_id
+                && counters_reader.counter_value(counter_id).unwrap_or_default() as i64 == CHANNEL_ENDPOINT_ACTIVE
+            {
+                let address = key_buffer.get_string(LOCAL_SOCKET_ADDRESS_LENGTH_OFFSET);
+                addresses.push(address.to_string_lossy().into_owned());
+            }
+        });
+    }
+
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::concurrent::atomic_buffer::{AlignedBuffer, AtomicBuffer};
+    use crate::concurrent::counters::{CountersManager, CountersReader, COUNTER_LENGTH, METADATA_LENGTH};
+    use crate::concurrent::status::status_indicator_reader::{CHANNEL_ENDPOINT_ACTIVE, CHANNEL_ENDPOINT_INITIALIZING};
+
+    use super::*;
+
+    const COUNTER_COUNT: i32 = 8;
+    const CHANNEL_STATUS_ID: i32 = 5;
+
+    fn allocate_address(manager: &mut CountersManager, channel_status_id: i32, address: &str, status: i64) {
+        let counter_id = manager
+            .allocate_opt(
+                LOCAL_SOCKET_ADDRESS_STATUS_TYPE_ID,
+                None,
+                Some(|key_buffer: &mut AtomicBuffer| {
+                    key_buffer.put::<i32>(CHANNEL_STATUS_ID_OFFSET, channel_status_id);
+                    key_buffer.put_string(LOCAL_SOCKET_ADDRESS_LENGTH_OFFSET, address.as_bytes());
+                }),
+                "local socket address",
+            )
+            .unwrap();
+        manager.set_counter_value(counter_id, status as u64);
+    }
+
+    #[test]
+    fn should_find_active_addresses_of_channel() {
+        let metadata = AlignedBuffer::with_capacity(COUNTER_COUNT * METADATA_LENGTH);
+        let values = AlignedBuffer::with_capacity(COUNTER_COUNT * COUNTER_LENGTH);
+        let metadata_buffer = AtomicBuffer::from_aligned(&metadata);
+        let values_buffer = AtomicBuffer::from_aligned(&values);
+        let mut manager = CountersManager::new(metadata_buffer, values_buffer);
+
+        allocate_address(&mut manager, CHANNEL_STATUS_ID, "127.0.0.1:40123", CHANNEL_ENDPOINT_ACTIVE);
+        allocate_address(
+            &mut manager,
+            CHANNEL_STATUS_ID,
+            "127.0.0.1:40124",
+            CHANNEL_ENDPOINT_INITIALIZING,
+        );
+        allocate_address(
+            &mut manager,
+            CHANNEL_STATUS_ID + 1,
+            "127.0.0.1:40125",
+            CHANNEL_ENDPOINT_ACTIVE,
+        );
+
+        let reader = CountersReader::new(metadata_buffer, values_buffer);
+
+        assert_eq!(
+            find_addresses(&reader, CHANNEL_ENDPOINT_ACTIVE, CHANNEL_STATUS_ID),
+            vec!["127.0.0.1:40123".to_string()]
+        );
+        assert!(find_addresses(&reader, CHANNEL_ENDPOINT_INITIALIZING, CHANNEL_STATUS_ID).is_empty());
+    }
+}
@@ -1 +1,2 @@
+pub mod local_socket_address_status;
 pub mod status_indicator_reader;
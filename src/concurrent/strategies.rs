@@ -31,6 +31,52 @@ pub trait StrategyMut {
     fn reset(&mut self);
 }
 
+/**
+ * What an idle strategy does next when there is no work. Lets callers which must not block the thread,
+ * e.g. async tasks, apply a strategy by spinning, yielding to their executor or parking on a timer instead.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IdleAction {
+    Spin,
+    Yield,
+    Park(Duration),
+}
+
+impl IdleAction {
+    /**
+     * Take the action on the current thread.
+     */
+    pub fn perform(self) {
+        match self {
+            IdleAction::Spin => cpu_pause(),
+            IdleAction::Yield => std::thread::yield_now(),
+            IdleAction::Park(period) => std::thread::sleep(period),
+        }
+    }
+}
+
+/**
+ * Idle strategy which reports the action to take rather than taking it.
+ */
+pub trait IdlePolicy {
+    /**
+     * Advance the strategy for a unit of idle time.
+     *
+     * @return the action to take before checking for work again.
+     */
+    fn idle_action(&mut self) -> IdleAction;
+
+    /**
+     * Reset the strategy after work has been done.
+     */
+    fn reset_idle(&mut self);
+}
+
+pub const DEFAULT_MAX_SPINS: i32 = 10;
+pub const DEFAULT_MAX_YIELDS: i32 = 5;
+pub const DEFAULT_MIN_PARK_PERIOD_NS: u64 = 1_000;
+pub const DEFAULT_MAX_PARK_PERIOD_NS: u64 = 1_000_000;
+
 const BACK_OFF_STATE_NOT_IDLE: u8 = 0;
 const BACK_OFF_STATE_SPINNING: u8 = 1;
 const BACK_OFF_STATE_YIELDING: u8 = 2;
@@ -47,6 +93,40 @@ pub struct BackOffIdleStrategy {
     max_park_period_ns: u64,
 }
 
+impl BackOffIdleStrategy {
+    /**
+     * Create a strategy which spins, then yields, then parks with an exponentially growing period.
+     *
+     * @param max_spins          to perform before moving to yielding.
+     * @param max_yields         to perform before moving to parking.
+     * @param min_park_period_ns to use when initiating parking.
+     * @param max_park_period_ns to use for end duration when parking.
+     */
+    pub fn new(max_spins: i32, max_yields: i32, min_park_period_ns: u64, max_park_period_ns: u64) -> Self {
+        Self {
+            state: BACK_OFF_STATE_NOT_IDLE,
+            spins: 0,
+            max_spins,
+            yields: 0,
+            max_yields,
+            park_period_ns: min_park_period_ns,
+            min_park_period_ns,
+            max_park_period_ns,
+        }
+    }
+}
+
+impl Default for BackOffIdleStrategy {
+    fn default() -> Self {
+        Self::new(
+            DEFAULT_MAX_SPINS,
+            DEFAULT_MAX_YIELDS,
+            DEFAULT_MIN_PARK_PERIOD_NS,
+            DEFAULT_MAX_PARK_PERIOD_NS,
+        )
+    }
+}
+
 impl StrategyMut for BackOffIdleStrategy {
     fn idle_opt(&mut self, work_count: i32) {
         if work_count > 0 {
@@ -57,41 +137,51 @@ impl StrategyMut for BackOffIdleStrategy {
     }
 
     fn idle(&mut self) {
+        self.idle_action().perform();
+    }
+
+    fn reset(&mut self) {
+        self.spins = 0;
+        self.yields = 0;
+        self.park_period_ns = self.min_park_period_ns;
+        self.state = BACK_OFF_STATE_NOT_IDLE;
+    }
+}
+
+impl IdlePolicy for BackOffIdleStrategy {
+    fn idle_action(&mut self) -> IdleAction {
         match self.state {
             BACK_OFF_STATE_NOT_IDLE => {
                 self.state = BACK_OFF_STATE_SPINNING;
                 self.spins += 1;
+                IdleAction::Spin
             }
             BACK_OFF_STATE_SPINNING => {
-                cpu_pause();
                 self.spins += 1;
                 if self.spins > self.max_spins {
                     self.state = BACK_OFF_STATE_YIELDING;
                     self.yields = 0;
                 }
+                IdleAction::Spin
             }
             BACK_OFF_STATE_YIELDING => {
                 self.yields += 1;
                 if self.yields > self.max_yields {
                     self.state = BACK_OFF_STATE_PARKING;
                     self.park_period_ns = self.min_park_period_ns;
-                } else {
-                    std::thread::yield_now();
                 }
+                IdleAction::Yield
             }
-            BACK_OFF_STATE_PARKING => {}
             _ => {
-                std::thread::sleep(Duration::from_nanos(self.park_period_ns));
+                let period = Duration::from_nanos(self.park_period_ns);
                 self.park_period_ns = std::cmp::min(self.park_period_ns * 2, self.max_park_period_ns);
+                IdleAction::Park(period)
             }
         }
     }
 
-    fn reset(&mut self) {
-        self.spins = 0;
-        self.yields = 0;
-        self.park_period_ns = self.min_park_period_ns;
-        self.state = BACK_OFF_STATE_NOT_IDLE;
+    fn reset_idle(&mut self) {
+        StrategyMut::reset(self);
     }
 }
 
@@ -161,28 +251,61 @@ impl Strategy for SleepingIdleStrategy {
     }
 }
 
-pub struct YieldingIdleStrategy {}
-
-// IC: Archive Context is cloneable
-impl Clone for YieldingIdleStrategy {
-    fn clone(&self) -> Self {
-        self.clone()
+impl IdlePolicy for SleepingIdleStrategy {
+    fn idle_action(&mut self) -> IdleAction {
+        IdleAction::Park(Duration::from_millis(self.duration))
     }
+
+    fn reset_idle(&mut self) {}
 }
 
+#[derive(Clone, Copy, Default)]
+pub struct YieldingIdleStrategy {}
+
 impl Strategy for YieldingIdleStrategy {
     fn idle_opt(&self, work_count: i32) {
-        if 0 == work_count {
+        if work_count > 0 {
             return;
         }
         std::thread::yield_now();
     }
 
     fn idle(&self) {
-        unimplemented!();
+        std::thread::yield_now();
     }
 
-    fn reset(&self) {
-        std::thread::yield_now();
+    fn reset(&self) {}
+}
+
+impl IdlePolicy for YieldingIdleStrategy {
+    fn idle_action(&mut self) -> IdleAction {
+        IdleAction::Yield
+    }
+
+    fn reset_idle(&mut self) {}
+}
+
+impl IdlePolicy for BusySpinIdleStrategy {
+    fn idle_action(&mut self) -> IdleAction {
+        IdleAction::Spin
+    }
+
+    fn reset_idle(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yielding_idle_strategy_should_clone_and_idle() {
+        // IC: clone() used to call itself and idle() was unimplemented
+        let original = YieldingIdleStrategy::default();
+        let strategy = Clone::clone(&original);
+
+        strategy.idle_opt(0);
+        strategy.idle_opt(1);
+        strategy.idle();
+        strategy.reset();
     }
 }
@@ -0,0 +1,253 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::{
+    ffi::CString,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::Sender,
+        Arc,
+    },
+};
+
+use crate::{
+    client_conductor::{DestinationStateDefn, DestinationStates},
+    concurrent::{counters::CountersReader, status::status_indicator_reader},
+    driver_proxy::DriverProxy,
+    image::Image,
+    utils::{
+        errors::{AeronError, GenericError},
+        types::Moment,
+    },
+};
+
+/// Commands queued by resource handles for the conductor to carry out on its next duty cycle.
+pub(crate) enum ConductorCommand {
+    AddDestination {
+        registration_id: i64,
+        correlation_id: i64,
+        channel: CString,
+    },
+    RemoveDestination {
+        registration_id: i64,
+        correlation_id: i64,
+        channel: CString,
+    },
+    AddRcvDestination {
+        registration_id: i64,
+        correlation_id: i64,
+        channel: CString,
+    },
+    RemoveRcvDestination {
+        registration_id: i64,
+        correlation_id: i64,
+        channel: CString,
+    },
+    ReleasePublication(i64),
+    ReleaseExclusivePublication(i64),
+    ReleaseSubscription {
+        registration_id: i64,
+        images: Vec<Image>,
+    },
+    ReleaseCounter(i64),
+}
+
+/**
+ * Handle to the ClientConductor held by Publication, ExclusivePublication, Subscription and Counter.
+ *
+ * Read only queries are answered from the shared CountersReader, destination states and conductor closed flag,
+ * and commands are put on a queue drained by the conductor in do_work(), so none of them wait for the conductor
+ * lock while the conductor is busy with its duty cycle.
+ */
+#[derive(Clone)]
+pub struct ConductorProxy {
+    driver_proxy: Arc<DriverProxy>,
+    counters_reader: Arc<CountersReader>,
+    destination_states: DestinationStates,
+    driver_timeout_ms: Moment,
+    is_closed: Arc<AtomicBool>,
+    command_sender: Sender<ConductorCommand>,
+}
+
+impl ConductorProxy {
+    pub(crate) fn new(
+        driver_proxy: Arc<DriverProxy>,
+        counters_reader: Arc<CountersReader>,
+        destination_states: DestinationStates,
+        driver_timeout_ms: Moment,
+        is_closed: Arc<AtomicBool>,
+        command_sender: Sender<ConductorCommand>,
+    ) -> Self {
+        Self {
+            driver_proxy,
+            counters_reader,
+            destination_states,
+            driver_timeout_ms,
+            is_closed,
+            command_sender,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.is_closed.load(Ordering::Acquire)
+    }
+
+    pub fn ensure_open(&self) -> Result<(), AeronError> {
+        if self.is_closed() {
+            Err(GenericError::ClientConductorClosed.into())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn counters_reader(&self) -> Result<Arc<CountersReader>, AeronError> {
+        self.ensure_open()?;
+        Ok(self.counters_reader.clone())
+    }
+
+    /**
+     * Status of the channel endpoint behind the status indicator counter.
+     *
+     * @return the counter value, or NO_ID_ALLOCATED when the counter id is outside the counters buffer.
+     */
+    pub fn channel_status(&self, counter_id: i32) -> i64 {
+        match counter_id {
+            0 => status_indicator_reader::CHANNEL_ENDPOINT_INITIALIZING,
+            status_indicator_reader::NO_ID_ALLOCATED => status_indicator_reader::CHANNEL_ENDPOINT_ACTIVE,
+            _ => self
+                .counters_reader
+                .counter_value(counter_id)
+                .map_or(status_indicator_reader::NO_ID_ALLOCATED as i64, |value| value as i64),
+        }
+    }
+
+    /**
+     * Queue adding a destination to a multi-destination publication.
+     *
+     * @return correlation id to pass to find_destination_response.
+     */
+    pub fn add_destination(&self, publication_registration_id: i64, endpoint_channel: CString) -> Result<i64, AeronError> {
+        self.send_destination_command(publication_registration_id, |correlation_id| {
+            ConductorCommand::AddDestination {
+                registration_id: publication_registration_id,
+                correlation_id,
+                channel: endpoint_channel,
+            }
+        })
+    }
+
+    /**
+     * Queue removing a destination from a multi-destination publication.
+     *
+     * @return correlation id to pass to find_destination_response.
+     */
+    pub fn remove_destination(&self, publication_registration_id: i64, endpoint_channel: CString) -> Result<i64, AeronError> {
+        self.send_destination_command(publication_registration_id, |correlation_id| {
+            ConductorCommand::RemoveDestination {
+                registration_id: publication_registration_id,
+                correlation_id,
+                channel: endpoint_channel,
+            }
+        })
+    }
+
+    /**
+     * Queue adding a destination to a multi-destination subscription.
+     *
+     * @return correlation id to pass to find_destination_response.
+     */
+    pub fn add_rcv_destination(&self, subscription_registration_id: i64, endpoint_channel: CString) -> Result<i64, AeronError> {
+        self.send_destination_command(subscription_registration_id, |correlation_id| {
+            ConductorCommand::AddRcvDestination {
+                registration_id: subscription_registration_id,
+                correlation_id,
+                channel: endpoint_channel,
+            }
+        })
+    }
+
+    /**
+     * Queue removing a destination from a multi-destination subscription.
+     *
+     * @return correlation id to pass to find_destination_response.
+     */
+    pub fn remove_rcv_destination(
+        &self,
+        subscription_registration_id: i64,
+        endpoint_channel: CString,
+    ) -> Result<i64, AeronError> {
+        self.send_destination_command(subscription_registration_id, |correlation_id| {
+            ConductorCommand::RemoveRcvDestination {
+                registration_id: subscription_registration_id,
+                correlation_id,
+                channel: endpoint_channel,
+            }
+        })
+    }
+
+    /**
+     * Check the driver response to a destination command. Commands still in the queue are reported as awaiting
+     * the response, the conductor flags the ones the driver did not answer within the driver timeout.
+     */
+    pub fn find_destination_response(&self, correlation_id: i64) -> Result<bool, AeronError> {
+        self.ensure_open()?;
+
+        match self.destination_states.lock().expect("Mutex poisoned").get(&correlation_id) {
+            Some(state) => state.response(self.driver_timeout_ms),
+            None => Err(GenericError::UnknownCorrelationId(correlation_id).into()),
+        }
+    }
+
+    pub(crate) fn release_publication(&self, registration_id: i64) {
+        self.send(ConductorCommand::ReleasePublication(registration_id));
+    }
+
+    pub(crate) fn release_exclusive_publication(&self, registration_id: i64) {
+        self.send(ConductorCommand::ReleaseExclusivePublication(registration_id));
+    }
+
+    pub(crate) fn release_subscription(&self, registration_id: i64, images: Vec<Image>) {
+        self.send(ConductorCommand::ReleaseSubscription { registration_id, images });
+    }
+
+    pub(crate) fn release_counter(&self, registration_id: i64) {
+        self.send(ConductorCommand::ReleaseCounter(registration_id));
+    }
+
+    fn send_destination_command(
+        &self,
+        registration_id: i64,
+        command: impl FnOnce(i64) -> ConductorCommand,
+    ) -> Result<i64, AeronError> {
+        self.ensure_open()?;
+
+        let correlation_id = self.driver_proxy.next_correlation_id();
+        self.destination_states
+            .lock()
+            .expect("Mutex poisoned")
+            .insert(correlation_id, DestinationStateDefn::queued(correlation_id, registration_id));
+        self.command_sender
+            .send(command(correlation_id))
+            .map_err(|_| AeronError::from(GenericError::ClientConductorClosed))?;
+
+        Ok(correlation_id)
+    }
+
+    fn send(&self, command: ConductorCommand) {
+        // IC: the receiver only goes away together with the conductor, then there is nothing left to release
+        let _ignored = self.command_sender.send(command);
+    }
+}
@@ -1,123 +1,306 @@
-// use std::collections::HashMap;
-// use std::marker::PhantomData;
-//
-// use agrona::buffer::{AtomicBuffer, BufferBuilder};
-// use agrona::collections::HashMap as AgronaHashMap;
-// use agrona::util::BitUtil;
-//
-// use crate::logbuffer::{ControlledFragmentHandler, Header};
-// use crate::protocol::{DataHeaderFlyweight, FrameDescriptor};
-//
-// struct ControlledFragmentAssembler<'a, F>
-// where
-//     F: ControlledFragmentHandler,
-// {
-//     delegate: F,
-//     builder_by_session_id_map: AgronaHashMap<i32, BufferBuilder<'a>>,
-//     initial_buffer_length: usize,
-//     is_direct_byte_buffer: bool,
-// }
-//
-// impl<'a, F> ControlledFragmentAssembler<'a, F>
-// where
-//     F: ControlledFragmentHandler,
-// {
-//     fn new(delegate: F) -> Self {
-//         ControlledFragmentAssembler {
-//             delegate,
-//             builder_by_session_id_map: HashMap::new(),
-//             initial_buffer_length: 0,
-//             is_direct_byte_buffer: false,
-//         }
-//     }
-//
-//     fn with_initial_buffer_length(delegate: F, initial_buffer_length: usize) -> Self {
-//         ControlledFragmentAssembler {
-//             delegate,
-//             builder_by_session_id_map: HashMap::new(),
-//             initial_buffer_length,
-//             is_direct_byte_buffer: false,
-//         }
-//     }
-//
-//     fn with_direct_byte_buffer(
-//         delegate: F,
-//         initial_buffer_length: usize,
-//         is_direct_byte_buffer: bool,
-//     ) -> Self {
-//         ControlledFragmentAssembler {
-//             delegate,
-//             builder_by_session_id_map: HashMap::new(),
-//             initial_buffer_length,
-//             is_direct_byte_buffer,
-//         }
-//     }
-//
-//     fn delegate(&self) -> &F {
-//         &self.delegate
-//     }
-//
-//     fn is_direct_byte_buffer(&self) -> bool {
-//         self.is_direct_byte_buffer
-//     }
-//
-//     fn on_fragment(
-//         &mut self,
-//         buffer: &AtomicBuffer,
-//         offset: usize,
-//         length: usize,
-//         header: &Header,
-//     ) -> ControlledFragmentHandler::Action {
-//         let flags = header.flags();
-//         let mut action = ControlledFragmentHandler::Action::Continue;
-//
-//         if (flags & FrameDescriptor::UNFRAGMENTED) == FrameDescriptor::UNFRAGMENTED {
-//             action = self.delegate.on_fragment(buffer, offset, length, header);
-//         } else if (flags & FrameDescriptor::BEGIN_FRAG_FLAG) == FrameDescriptor::BEGIN_FRAG_FLAG {
-//             let builder = self.get_buffer_builder(header.session_id());
-//             builder
-//                 .reset()
-//                 .append(buffer, offset, length)
-//                 .next_term_offset(BitUtil::align(
-//                     offset + length + DataHeaderFlyweight::HEADER_LENGTH,
-//                     FrameDescriptor::FRAME_ALIGNMENT,
-//                 ));
-//         } else {
-//             let builder = self.builder_by_session_id_map.get_mut(&header.session_id());
-//             if let Some(builder) = builder {
-//                 if offset == builder.next_term_offset() {
-//                     let limit = builder.limit();
-//
-//                     builder.append(buffer, offset, length);
-//
-//                     if (flags & FrameDescriptor::END_FRAG_FLAG) == FrameDescriptor::END_FRAG_FLAG {
-//                         action = self.delegate.on_fragment(
-//                             builder.buffer(),
-//                             0,
-//                             builder.limit(),
-//                             header,
-//                         );
-//
-//                         if action == ControlledFragmentHandler::Action::Abort {
-//                             builder.limit(limit);
-//                         } else {
-//                             builder.reset();
-//                         }
-//                     } else {
-//                         builder.next_term_offset(BitUtil::align(
-//                             offset + length + DataHeaderFlyweight::HEADER_LENGTH,
-//                             FrameDescriptor::FRAME_ALIGNMENT,
-//                         ));
-//                     }
-//                 } else {
-//                     builder.reset();
-//                 }
-//             }
-//         }
-//
-//         action
-//     }
-//
-//     fn free_session_buffer(&mut self, session_id: i32) -> bool {}
-// }
-//
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::HashMap;
+
+use crate::{
+    buffer_builder::BufferBuilder,
+    concurrent::{
+        atomic_buffer::AtomicBuffer,
+        logbuffer::{data_frame_header, frame_descriptor, header::Header},
+    },
+    image::ControlledPollAction,
+    utils::{errors::AeronError, types::Index},
+};
+
+const DEFAULT_FRAGMENT_ASSEMBLY_BUFFER_LENGTH: isize = 4096;
+
+pub trait ControlledFragment: FnMut(&AtomicBuffer, Index, Index, &Header) -> Result<ControlledPollAction, AeronError> {}
+
+impl<T: FnMut(&AtomicBuffer, Index, Index, &Header) -> Result<ControlledPollAction, AeronError>> ControlledFragment for T {}
+
+/**
+ * A handler that sits in a chain-of-responsibility pattern that reassembles fragmented messages
+ * so that the next handler in the chain only sees whole messages. This is for use with
+ * Subscription::controlled_poll.
+ * <p>
+ * Unfragmented messages are delegated without copy. Fragmented messages are copied to a temporary
+ * buffer for reassembly before delegation.
+ * <p>
+ * The Header passed to the delegate on assembling a message will be that of the last fragment.
+ * <p>
+ * Unlike FragmentAssembler the delegate is not owned by the assembler but passed on each call. This allows
+ * the assembler to be kept alongside the state the delegate updates, so partially assembled messages
+ * survive between polls.
+ */
+pub struct ControlledFragmentAssembler {
+    builder_by_session_id_map: HashMap<i32, BufferBuilder>,
+    initial_buffer_length: isize,
+}
+
+impl Default for ControlledFragmentAssembler {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl ControlledFragmentAssembler {
+    /**
+     * Construct an adapter to reassemble message fragments and delegate on only whole messages.
+     *
+     * @param initialBufferLength to be used for each session.
+     */
+    pub fn new(initial_buffer_length: Option<isize>) -> Self {
+        Self {
+            builder_by_session_id_map: HashMap::new(),
+            initial_buffer_length: initial_buffer_length.unwrap_or(DEFAULT_FRAGMENT_ASSEMBLY_BUFFER_LENGTH),
+        }
+    }
+
+    /**
+     * Free an existing session buffer to reduce memory pressure when an Image goes inactive or no more
+     * large messages are expected.
+     *
+     * @param sessionId to have its buffer freed
+     */
+    pub fn delete_session_buffer(&mut self, session_id: i32) {
+        self.builder_by_session_id_map.remove(&session_id);
+    }
+
+    /**
+     * Reassemble the fragment and hand whole messages to the delegate.
+     *
+     * @param delegate to receive whole messages.
+     * @return the action returned by the delegate or CONTINUE if the fragment was only buffered.
+     */
+    pub fn on_fragment(
+        &mut self,
+        delegate: &mut impl ControlledFragment,
+        buffer: &AtomicBuffer,
+        offset: Index,
+        length: Index,
+        header: &Header,
+    ) -> Result<ControlledPollAction, AeronError> {
+        let flags = header.flags();
+        let mut action = ControlledPollAction::CONTINUE;
+
+        if (flags & frame_descriptor::UNFRAGMENTED) == frame_descriptor::UNFRAGMENTED {
+            action = delegate(buffer, offset, length, header)?;
+        } else if (flags & frame_descriptor::BEGIN_FRAG) == frame_descriptor::BEGIN_FRAG {
+            let initial_buffer_length = self.initial_buffer_length;
+            let builder = self
+                .builder_by_session_id_map
+                .entry(header.session_id())
+                .or_insert_with(|| BufferBuilder::new(initial_buffer_length));
+
+            builder.reset().append(buffer, offset, length, header)?;
+        } else if let Some(builder) = self.builder_by_session_id_map.get_mut(&header.session_id()) {
+            let limit = builder.limit();
+
+            if limit != data_frame_header::LENGTH {
+                builder.append(buffer, offset, length, header)?;
+
+                if flags & frame_descriptor::END_FRAG == frame_descriptor::END_FRAG {
+                    let msg_length = builder.limit() - data_frame_header::LENGTH;
+                    let msg_buffer = AtomicBuffer::new(builder.buffer(), builder.limit());
+
+                    action = delegate(&msg_buffer, data_frame_header::LENGTH, msg_length, header)?;
+
+                    if ControlledPollAction::ABORT == action {
+                        builder.set_limit(limit)?;
+                    } else {
+                        builder.reset();
+                    }
+                }
+            }
+        }
+
+        Ok(action)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::concurrent::{
+        atomic_buffer::{AlignedBuffer, AtomicBuffer},
+        logbuffer::{
+            data_frame_header::{self, DataFrameHeaderDefn},
+            frame_descriptor,
+            header::Header,
+            log_buffer_descriptor,
+        },
+    };
+    use crate::controlled_fragment_assembler::ControlledFragmentAssembler;
+    use crate::image::ControlledPollAction;
+    use crate::utils::{errors::AeronError, types::Index};
+
+    const STREAM_ID: i32 = 10;
+    const SESSION_ID: i32 = 200;
+    const TERM_LENGTH: i32 = log_buffer_descriptor::TERM_MIN_LENGTH;
+    const INITIAL_TERM_ID: i32 = -1234;
+    const ACTIVE_TERM_ID: i32 = INITIAL_TERM_ID + 5;
+    const MTU_LENGTH: Index = 128;
+
+    #[allow(dead_code)]
+    struct ControlledFragmentAssemblerTest {
+        fragment: AlignedBuffer,
+        buffer: AtomicBuffer,
+        header: Header,
+    }
+
+    impl ControlledFragmentAssemblerTest {
+        pub fn new() -> Self {
+            let fragment = AlignedBuffer::with_capacity(TERM_LENGTH);
+            let buffer = AtomicBuffer::from_aligned(&fragment);
+            let mut header = Header::new(INITIAL_TERM_ID, TERM_LENGTH);
+            header.set_buffer(buffer);
+            Self {
+                fragment,
+                buffer,
+                header,
+            }
+        }
+
+        fn fill_frame(&mut self, flags: u8, offset: i32, length: i32, payload_value: u8) {
+            let frame = self.buffer.overlay_struct::<DataFrameHeaderDefn>(offset);
+            unsafe {
+                (*frame).frame_length = data_frame_header::LENGTH + length;
+                (*frame).version = data_frame_header::CURRENT_VERSION;
+                (*frame).flags = flags;
+                (*frame).frame_type = data_frame_header::HDR_TYPE_DATA;
+                (*frame).term_offset = offset;
+                (*frame).session_id = SESSION_ID;
+                (*frame).stream_id = STREAM_ID;
+                (*frame).term_id = ACTIVE_TERM_ID;
+            }
+
+            for i in 0..length {
+                self.buffer.put(i + offset + data_frame_header::LENGTH, payload_value);
+            }
+
+            self.header.set_offset(offset);
+        }
+    }
+
+    #[test]
+    fn should_pass_through_unfragmented_message() {
+        let mut test = ControlledFragmentAssemblerTest::new();
+        let msg_length = 158;
+        test.fill_frame(frame_descriptor::UNFRAGMENTED, 0, msg_length, 1);
+
+        let mut received = Vec::new();
+        let mut delegate = |buffer: &AtomicBuffer, offset: Index, length: Index, _header: &Header| {
+            received.push((offset, length, buffer.get::<u8>(offset)));
+            Ok::<_, AeronError>(ControlledPollAction::CONTINUE)
+        };
+
+        let mut assembler = ControlledFragmentAssembler::default();
+        let action = assembler
+            .on_fragment(
+                &mut delegate,
+                &test.buffer,
+                data_frame_header::LENGTH,
+                msg_length,
+                &test.header,
+            )
+            .unwrap();
+
+        assert!(action == ControlledPollAction::CONTINUE);
+        assert_eq!(received, vec![(data_frame_header::LENGTH, msg_length, 1)]);
+    }
+
+    #[test]
+    fn should_reassemble_from_two_fragments() {
+        let mut test = ControlledFragmentAssemblerTest::new();
+        let msg_length = MTU_LENGTH - data_frame_header::LENGTH;
+
+        let mut received = Vec::new();
+        let mut delegate = |buffer: &AtomicBuffer, offset: Index, length: Index, _header: &Header| {
+            received.push((length, buffer.get::<u8>(offset), buffer.get::<u8>(offset + length - 1)));
+            Ok::<_, AeronError>(ControlledPollAction::CONTINUE)
+        };
+
+        let mut assembler = ControlledFragmentAssembler::default();
+
+        test.fill_frame(frame_descriptor::BEGIN_FRAG, 0, msg_length, 1);
+        assembler
+            .on_fragment(
+                &mut delegate,
+                &test.buffer,
+                data_frame_header::LENGTH,
+                msg_length,
+                &test.header,
+            )
+            .unwrap();
+
+        test.fill_frame(frame_descriptor::END_FRAG, MTU_LENGTH, msg_length, 2);
+        assembler
+            .on_fragment(
+                &mut delegate,
+                &test.buffer,
+                MTU_LENGTH + data_frame_header::LENGTH,
+                msg_length,
+                &test.header,
+            )
+            .unwrap();
+
+        assert_eq!(received, vec![(msg_length * 2, 1, 2)]);
+    }
+
+    #[test]
+    fn should_keep_assembled_message_when_delegate_aborts() {
+        let mut test = ControlledFragmentAssemblerTest::new();
+        let msg_length = MTU_LENGTH - data_frame_header::LENGTH;
+
+        let mut calls = 0;
+        let mut delegate = |_buffer: &AtomicBuffer, _offset: Index, length: Index, _header: &Header| {
+            assert_eq!(length, msg_length * 2);
+            calls += 1;
+            if calls == 1 {
+                Ok::<_, AeronError>(ControlledPollAction::ABORT)
+            } else {
+                Ok(ControlledPollAction::CONTINUE)
+            }
+        };
+
+        let mut assembler = ControlledFragmentAssembler::default();
+
+        test.fill_frame(frame_descriptor::BEGIN_FRAG, 0, msg_length, 1);
+        assembler
+            .on_fragment(
+                &mut delegate,
+                &test.buffer,
+                data_frame_header::LENGTH,
+                msg_length,
+                &test.header,
+            )
+            .unwrap();
+
+        test.fill_frame(frame_descriptor::END_FRAG, MTU_LENGTH, msg_length, 2);
+        let offset = MTU_LENGTH + data_frame_header::LENGTH;
+        let action = assembler
+            .on_fragment(&mut delegate, &test.buffer, offset, msg_length, &test.header)
+            .unwrap();
+        assert!(action == ControlledPollAction::ABORT);
+
+        // The same END fragment is redelivered after an abort
+        let action = assembler
+            .on_fragment(&mut delegate, &test.buffer, offset, msg_length, &test.header)
+            .unwrap();
+        assert!(action == ControlledPollAction::CONTINUE);
+        assert_eq!(calls, 2);
+    }
+}
@@ -16,27 +16,21 @@
 
 use std::ffi::CString;
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
 
-use crate::client_conductor::ClientConductor;
 use crate::concurrent::{atomic_buffer::AtomicBuffer, atomic_counter::AtomicCounter};
+use crate::conductor_proxy::ConductorProxy;
 use crate::utils::errors::AeronError;
 
 pub struct Counter {
     // inherits from AtomicCounter
     atomic_counter: AtomicCounter,
-    client_conductor: Arc<Mutex<ClientConductor>>,
+    client_conductor: ConductorProxy,
     registration_id: i64,
     is_closed: AtomicBool,
 }
 
 impl Counter {
-    pub fn new(
-        client_conductor: Arc<Mutex<ClientConductor>>,
-        buffer: AtomicBuffer,
-        registration_id: i64,
-        counter_id: i32,
-    ) -> Self {
+    pub fn new(client_conductor: ConductorProxy, buffer: AtomicBuffer, registration_id: i64, counter_id: i32) -> Self {
         Self {
             atomic_counter: AtomicCounter::new(buffer, counter_id),
             client_conductor,
@@ -58,14 +52,12 @@ impl Counter {
     }
 
     pub fn state(&self) -> Result<i32, AeronError> {
-        let cc = self.client_conductor.lock().expect("Mutex poisoned");
-        let cr = cc.counters_reader()?;
+        let cr = self.client_conductor.counters_reader()?;
         cr.counter_state(self.atomic_counter.id())
     }
 
     pub fn label(&self) -> Result<CString, AeronError> {
-        let cc = self.client_conductor.lock().expect("Mutex poisoned");
-        let cr = cc.counters_reader()?;
+        let cr = self.client_conductor.counters_reader()?;
         cr.counter_label(self.atomic_counter.id())
     }
 
@@ -77,10 +69,6 @@ impl Counter {
 
 impl Drop for Counter {
     fn drop(&mut self) {
-        let _ignored = self
-            .client_conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .release_counter(self.registration_id);
+        self.client_conductor.release_counter(self.registration_id);
     }
 }
@@ -0,0 +1,244 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::{
+    command::{
+        client_timeout_flyweight::{ClientTimeoutFlyweight, CLIENT_TIMEOUT_LENGTH},
+        control_protocol_events::AeronCommand,
+        counter_update_flyweight::{CounterUpdateFlyweight, COUNTER_READY_LENGTH},
+        error_response_flyweight::ErrorResponseFlyweight,
+        image_buffers_ready_flyweight::ImageBuffersReadyFlyweight,
+        image_message_flyweight::ImageMessageFlyweight,
+        operation_succeeded_flyweight::{OperationSucceededFlyweight, OPERATION_SUCCEEDED_LENGTH},
+        publication_buffers_ready_flyweight::PublicationBuffersReadyFlyweight,
+        subscription_ready_flyweight::{SubscriptionReadyFlyweight, SUBSCRIPTION_READY_LENGTH},
+    },
+    concurrent::{atomic_buffer::AtomicBuffer, broadcast::broadcast_transmitter::BroadcastTransmitter},
+    log,
+    utils::{errors::AeronError, types::Index},
+};
+
+/**
+ * Proxy for sending responses and notifications to clients over the to-clients broadcast buffer.
+ */
+pub(crate) struct ClientProxy {
+    transmitter: BroadcastTransmitter,
+}
+
+impl ClientProxy {
+    pub fn new(transmitter: BroadcastTransmitter) -> Self {
+        Self { transmitter }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn on_publication_ready(
+        &mut self,
+        correlation_id: i64,
+        registration_id: i64,
+        stream_id: i32,
+        session_id: i32,
+        position_limit_counter_id: i32,
+        channel_status_indicator_id: i32,
+        log_file_name: &str,
+        is_exclusive: bool,
+    ) -> Result<(), AeronError> {
+        self.transmit(|buffer, length| {
+            let mut publication_ready = PublicationBuffersReadyFlyweight::new(buffer, 0);
+
+            publication_ready.set_correlation_id(correlation_id);
+            publication_ready.set_registration_id(registration_id);
+            publication_ready.set_stream_id(stream_id);
+            publication_ready.set_session_id(session_id);
+            publication_ready.set_position_limit_counter_id(position_limit_counter_id);
+            publication_ready.set_channel_status_indicator_id(channel_status_indicator_id);
+            publication_ready.set_log_file_name(log_file_name.as_bytes());
+
+            *length = publication_ready.length();
+
+            if is_exclusive {
+                AeronCommand::ResponseOnExclusivePublicationReady
+            } else {
+                AeronCommand::ResponseOnPublicationReady
+            }
+        })
+    }
+
+    pub fn on_subscription_ready(&mut self, correlation_id: i64, channel_status_indicator_id: i32) -> Result<(), AeronError> {
+        self.transmit(|buffer, length| {
+            let mut subscription_ready = SubscriptionReadyFlyweight::new(buffer, 0);
+
+            subscription_ready.set_correlation_id(correlation_id);
+            subscription_ready.set_channel_status_indicator_id(channel_status_indicator_id);
+
+            *length = SUBSCRIPTION_READY_LENGTH;
+
+            AeronCommand::ResponseOnSubscriptionReady
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub fn on_available_image(
+        &mut self,
+        correlation_id: i64,
+        stream_id: i32,
+        session_id: i32,
+        subscription_registration_id: i64,
+        subscriber_position_id: i32,
+        log_file_name: &str,
+        source_identity: &str,
+    ) -> Result<(), AeronError> {
+        self.transmit(|buffer, length| {
+            let mut image_ready = ImageBuffersReadyFlyweight::new(buffer, 0);
+
+            image_ready.set_correlation_id(correlation_id);
+            image_ready.set_stream_id(stream_id);
+            image_ready.set_session_id(session_id);
+            image_ready.set_subscription_registration_id(subscription_registration_id);
+            image_ready.set_subscriber_position_id(subscriber_position_id);
+            image_ready.set_log_file_name(log_file_name.as_bytes());
+            image_ready.set_source_identity(source_identity.as_bytes());
+
+            *length = image_ready.length();
+
+            AeronCommand::ResponseOnAvailableImage
+        })
+    }
+
+    pub fn on_unavailable_image(
+        &mut self,
+        correlation_id: i64,
+        subscription_registration_id: i64,
+        stream_id: i32,
+        channel: &str,
+    ) -> Result<(), AeronError> {
+        self.transmit(|buffer, length| {
+            let mut image_message = ImageMessageFlyweight::new(buffer, 0);
+
+            image_message.set_correlation_id(correlation_id);
+            image_message.set_subscription_registration_id(subscription_registration_id);
+            image_message.set_stream_id(stream_id);
+            image_message.set_channel(channel.as_bytes());
+
+            *length = image_message.length();
+
+            AeronCommand::ResponseOnUnavailableImage
+        })
+    }
+
+    pub fn on_operation_succeeded(&mut self, correlation_id: i64) -> Result<(), AeronError> {
+        self.transmit(|buffer, length| {
+            let mut operation_succeeded = OperationSucceededFlyweight::new(buffer, 0);
+
+            operation_succeeded.set_correlation_id(correlation_id);
+
+            *length = OPERATION_SUCCEEDED_LENGTH;
+
+            AeronCommand::ResponseOnOperationSuccess
+        })
+    }
+
+    pub fn on_error(
+        &mut self,
+        offending_command_correlation_id: i64,
+        error_code: i32,
+        error_message: &str,
+    ) -> Result<(), AeronError> {
+        log!(
+            trace,
+            "Driver error response for {}: {} {}",
+            offending_command_correlation_id,
+            error_code,
+            error_message
+        );
+
+        self.transmit(|buffer, length| {
+            let mut error_response = ErrorResponseFlyweight::new(buffer, 0);
+
+            error_response.set_offending_command_correlation_id(offending_command_correlation_id);
+            error_response.set_error_code(error_code);
+            error_response.set_error_message(error_message.as_bytes());
+
+            *length = error_response.length();
+
+            AeronCommand::ResponseOnError
+        })
+    }
+
+    pub fn on_counter_ready(&mut self, correlation_id: i64, counter_id: i32) -> Result<(), AeronError> {
+        self.transmit(|buffer, length| {
+            let mut counter_update = CounterUpdateFlyweight::new(buffer, 0);
+
+            counter_update.set_correlation_id(correlation_id);
+            counter_update.set_counter_id(counter_id);
+
+            *length = COUNTER_READY_LENGTH;
+
+            AeronCommand::ResponseOnCounterReady
+        })
+    }
+
+    pub fn on_unavailable_counter(&mut self, registration_id: i64, counter_id: i32) -> Result<(), AeronError> {
+        self.transmit(|buffer, length| {
+            let mut counter_update = CounterUpdateFlyweight::new(buffer, 0);
+
+            counter_update.set_correlation_id(registration_id);
+            counter_update.set_counter_id(counter_id);
+
+            *length = COUNTER_READY_LENGTH;
+
+            AeronCommand::ResponseOnUnavailableCounter
+        })
+    }
+
+    pub fn on_client_timeout(&mut self, client_id: i64) -> Result<(), AeronError> {
+        self.transmit(|buffer, length| {
+            let mut client_timeout = ClientTimeoutFlyweight::new(buffer, 0);
+
+            client_timeout.set_client_id(client_id);
+
+            *length = CLIENT_TIMEOUT_LENGTH;
+
+            AeronCommand::ResponseOnClientTimeout
+        })
+    }
+
+    fn transmit(&mut self, filler: impl FnOnce(AtomicBuffer, &mut Index) -> AeronCommand) -> Result<(), AeronError> {
+        let mut message_buffer = ClientProxyResponseBuffer::default();
+
+        let buffer = AtomicBuffer::new(&mut message_buffer.data[0] as *mut u8, message_buffer.data.len() as Index);
+        let mut length = buffer.capacity();
+
+        // Filler returns the msg type and sets the actual msg length via the length param.
+        let msg_type = filler(buffer, &mut length);
+
+        self.transmitter.transmit(msg_type as i32, &buffer, 0, length)?;
+
+        Ok(())
+    }
+}
+
+/// Same alignment as the command buffer of DriverProxy. Responses carry at most a log file name and a
+/// channel or source identity so 4K is plenty.
+#[repr(C, align(16))]
+struct ClientProxyResponseBuffer {
+    data: [u8; 4096],
+}
+
+impl Default for ClientProxyResponseBuffer {
+    fn default() -> Self {
+        Self { data: [0; 4096] }
+    }
+}
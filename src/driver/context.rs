@@ -0,0 +1,309 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::cnc_file_descriptor::CNC_FILE;
+use crate::concurrent::{broadcast::broadcast_buffer_descriptor, ring_buffer};
+use crate::context::Context;
+use crate::utils::types::{Index, Moment};
+
+pub const DEFAULT_TO_DRIVER_BUFFER_LENGTH: Index = 1024 * 1024 + ring_buffer::TRAILER_LENGTH;
+pub const DEFAULT_TO_CLIENTS_BUFFER_LENGTH: Index = 1024 * 1024 + broadcast_buffer_descriptor::TRAILER_LENGTH;
+pub const DEFAULT_COUNTER_VALUES_BUFFER_LENGTH: Index = 1024 * 1024;
+pub const DEFAULT_ERROR_BUFFER_LENGTH: Index = 1024 * 1024;
+pub const DEFAULT_CLIENT_LIVENESS_TIMEOUT_MS: Moment = 10_000;
+pub const DEFAULT_PUBLICATION_LINGER_TIMEOUT_MS: Moment = 5_000;
+pub const DEFAULT_IPC_TERM_BUFFER_LENGTH: Index = 1024 * 1024;
+pub const DEFAULT_TERM_BUFFER_LENGTH: Index = 1024 * 1024;
+pub const DEFAULT_INITIAL_WINDOW_LENGTH: Index = 128 * 1024;
+pub const DEFAULT_STATUS_MESSAGE_TIMEOUT_MS: Moment = 200;
+pub const DEFAULT_IMAGE_LIVENESS_TIMEOUT_MS: Moment = 10_000;
+pub const DEFAULT_PUBLICATION_CONNECTION_TIMEOUT_MS: Moment = 5_000;
+pub const DEFAULT_MTU_LENGTH: Index = 1408;
+pub const DEFAULT_IDLE_SLEEP_MS: Moment = 1;
+
+/**
+ * Configuration for an embedded MediaDriver.
+ * <p>
+ * The defaults follow those of the Java media driver except for the term lengths, which are kept small as
+ * the embedded driver is mostly used for tests.
+ */
+#[derive(Clone, Debug)]
+pub struct DriverContext {
+    aeron_dir: String,
+    dir_delete_on_start: bool,
+    dir_delete_on_shutdown: bool,
+    to_driver_buffer_length: Index,
+    to_clients_buffer_length: Index,
+    counter_values_buffer_length: Index,
+    error_buffer_length: Index,
+    client_liveness_timeout_ms: Moment,
+    publication_linger_timeout_ms: Moment,
+    ipc_term_buffer_length: Index,
+    term_buffer_length: Index,
+    initial_window_length: Index,
+    status_message_timeout_ms: Moment,
+    image_liveness_timeout_ms: Moment,
+    publication_connection_timeout_ms: Moment,
+    mtu_length: Index,
+    idle_sleep_ms: Moment,
+}
+
+impl Default for DriverContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DriverContext {
+    pub fn new() -> Self {
+        Self {
+            aeron_dir: Context::default_aeron_path(),
+            dir_delete_on_start: false,
+            dir_delete_on_shutdown: false,
+            to_driver_buffer_length: DEFAULT_TO_DRIVER_BUFFER_LENGTH,
+            to_clients_buffer_length: DEFAULT_TO_CLIENTS_BUFFER_LENGTH,
+            counter_values_buffer_length: DEFAULT_COUNTER_VALUES_BUFFER_LENGTH,
+            error_buffer_length: DEFAULT_ERROR_BUFFER_LENGTH,
+            client_liveness_timeout_ms: DEFAULT_CLIENT_LIVENESS_TIMEOUT_MS,
+            publication_linger_timeout_ms: DEFAULT_PUBLICATION_LINGER_TIMEOUT_MS,
+            ipc_term_buffer_length: DEFAULT_IPC_TERM_BUFFER_LENGTH,
+            term_buffer_length: DEFAULT_TERM_BUFFER_LENGTH,
+            initial_window_length: DEFAULT_INITIAL_WINDOW_LENGTH,
+            status_message_timeout_ms: DEFAULT_STATUS_MESSAGE_TIMEOUT_MS,
+            image_liveness_timeout_ms: DEFAULT_IMAGE_LIVENESS_TIMEOUT_MS,
+            publication_connection_timeout_ms: DEFAULT_PUBLICATION_CONNECTION_TIMEOUT_MS,
+            mtu_length: DEFAULT_MTU_LENGTH,
+            idle_sleep_ms: DEFAULT_IDLE_SLEEP_MS,
+        }
+    }
+
+    pub fn aeron_dir(&self) -> String {
+        self.aeron_dir.clone()
+    }
+
+    /**
+     * Set the directory in which the driver creates the CnC file and log buffers. Clients connect by setting
+     * the same directory on their Context.
+     *
+     * @param directory to use
+     * @return reference to this DriverContext instance
+     */
+    pub fn set_aeron_dir(&mut self, directory: String) -> &Self {
+        self.aeron_dir = directory;
+        self
+    }
+
+    pub fn cnc_file_name(&self) -> String {
+        self.aeron_dir.clone() + "/" + CNC_FILE
+    }
+
+    pub fn publications_dir(&self) -> String {
+        self.aeron_dir.clone() + "/publications"
+    }
+
+    pub fn images_dir(&self) -> String {
+        self.aeron_dir.clone() + "/images"
+    }
+
+    pub fn dir_delete_on_start(&self) -> bool {
+        self.dir_delete_on_start
+    }
+
+    /**
+     * Delete the aeron directory on start, even if another driver looks to be active in it.
+     */
+    pub fn set_dir_delete_on_start(&mut self, value: bool) -> &Self {
+        self.dir_delete_on_start = value;
+        self
+    }
+
+    pub fn dir_delete_on_shutdown(&self) -> bool {
+        self.dir_delete_on_shutdown
+    }
+
+    /**
+     * Delete the aeron directory when the driver is closed.
+     */
+    pub fn set_dir_delete_on_shutdown(&mut self, value: bool) -> &Self {
+        self.dir_delete_on_shutdown = value;
+        self
+    }
+
+    pub fn to_driver_buffer_length(&self) -> Index {
+        self.to_driver_buffer_length
+    }
+
+    /**
+     * Length of the buffer for commands from clients, a power of two plus the ring buffer trailer.
+     */
+    pub fn set_to_driver_buffer_length(&mut self, length: Index) -> &Self {
+        self.to_driver_buffer_length = length;
+        self
+    }
+
+    pub fn to_clients_buffer_length(&self) -> Index {
+        self.to_clients_buffer_length
+    }
+
+    /**
+     * Length of the buffer for responses to clients, a power of two plus the broadcast buffer trailer.
+     */
+    pub fn set_to_clients_buffer_length(&mut self, length: Index) -> &Self {
+        self.to_clients_buffer_length = length;
+        self
+    }
+
+    pub fn counter_values_buffer_length(&self) -> Index {
+        self.counter_values_buffer_length
+    }
+
+    /**
+     * Length of the counter values buffer. The metadata buffer is sized to match.
+     */
+    pub fn set_counter_values_buffer_length(&mut self, length: Index) -> &Self {
+        self.counter_values_buffer_length = length;
+        self
+    }
+
+    pub fn error_buffer_length(&self) -> Index {
+        self.error_buffer_length
+    }
+
+    pub fn set_error_buffer_length(&mut self, length: Index) -> &Self {
+        self.error_buffer_length = length;
+        self
+    }
+
+    pub fn client_liveness_timeout_ms(&self) -> Moment {
+        self.client_liveness_timeout_ms
+    }
+
+    /**
+     * Time after its last heartbeat at which a client is considered dead and its resources are released.
+     */
+    pub fn set_client_liveness_timeout_ms(&mut self, timeout: Moment) -> &Self {
+        self.client_liveness_timeout_ms = timeout;
+        self
+    }
+
+    pub fn publication_linger_timeout_ms(&self) -> Moment {
+        self.publication_linger_timeout_ms
+    }
+
+    /**
+     * Time a removed publication's log buffer is kept after it has been drained.
+     */
+    pub fn set_publication_linger_timeout_ms(&mut self, timeout: Moment) -> &Self {
+        self.publication_linger_timeout_ms = timeout;
+        self
+    }
+
+    pub fn ipc_term_buffer_length(&self) -> Index {
+        self.ipc_term_buffer_length
+    }
+
+    /**
+     * Term length of the log buffers for aeron:ipc publications.
+     */
+    pub fn set_ipc_term_buffer_length(&mut self, length: Index) -> &Self {
+        self.ipc_term_buffer_length = length;
+        self
+    }
+
+    pub fn term_buffer_length(&self) -> Index {
+        self.term_buffer_length
+    }
+
+    /**
+     * Term length of the log buffers for aeron:udp publications.
+     */
+    pub fn set_term_buffer_length(&mut self, length: Index) -> &Self {
+        self.term_buffer_length = length;
+        self
+    }
+
+    pub fn initial_window_length(&self) -> Index {
+        self.initial_window_length
+    }
+
+    /**
+     * Receiver window advertised in status messages, capped at half the term length of an image.
+     */
+    pub fn set_initial_window_length(&mut self, length: Index) -> &Self {
+        self.initial_window_length = length;
+        self
+    }
+
+    pub fn status_message_timeout_ms(&self) -> Moment {
+        self.status_message_timeout_ms
+    }
+
+    /**
+     * Longest time between status messages sent for an image, even when subscribers are not consuming.
+     */
+    pub fn set_status_message_timeout_ms(&mut self, timeout: Moment) -> &Self {
+        self.status_message_timeout_ms = timeout;
+        self
+    }
+
+    pub fn image_liveness_timeout_ms(&self) -> Moment {
+        self.image_liveness_timeout_ms
+    }
+
+    /**
+     * Time without data or heartbeats after which an image is considered gone.
+     */
+    pub fn set_image_liveness_timeout_ms(&mut self, timeout: Moment) -> &Self {
+        self.image_liveness_timeout_ms = timeout;
+        self
+    }
+
+    pub fn publication_connection_timeout_ms(&self) -> Moment {
+        self.publication_connection_timeout_ms
+    }
+
+    /**
+     * Time without status messages after which a publication is no longer connected to its receiver.
+     */
+    pub fn set_publication_connection_timeout_ms(&mut self, timeout: Moment) -> &Self {
+        self.publication_connection_timeout_ms = timeout;
+        self
+    }
+
+    pub fn mtu_length(&self) -> Index {
+        self.mtu_length
+    }
+
+    /**
+     * Maximum length of a frame, which decides when messages are fragmented.
+     */
+    pub fn set_mtu_length(&mut self, length: Index) -> &Self {
+        self.mtu_length = length;
+        self
+    }
+
+    pub fn idle_sleep_ms(&self) -> Moment {
+        self.idle_sleep_ms
+    }
+
+    /**
+     * Time the conductor, sender and receiver sleep for when a duty cycle found no work.
+     */
+    pub fn set_idle_sleep_ms(&mut self, sleep_ms: Moment) -> &Self {
+        self.idle_sleep_ms = sleep_ms;
+        self
+    }
+}
@@ -0,0 +1,1211 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::net::SocketAddr;
+use std::sync::{mpsc, Arc, Mutex};
+
+use crate::{
+    channel_uri::{ChannelUri, IPC_CHANNEL, IPC_MEDIA, UDP_MEDIA},
+    cnc_file_descriptor,
+    command::{
+        control_protocol_events::AeronCommand,
+        correlated_message_flyweight::CorrelatedMessageFlyweight,
+        counter_message_flyweight::CounterMessageFlyweight,
+        error_response_flyweight::{
+            ERROR_CODE_GENERIC_ERROR, ERROR_CODE_INVALID_CHANNEL, ERROR_CODE_NOT_SUPPORTED, ERROR_CODE_UNKNOWN_COUNTER,
+            ERROR_CODE_UNKNOWN_PUBLICATION, ERROR_CODE_UNKNOWN_SUBSCRIPTION,
+        },
+        publication_message_flyweight::PublicationMessageFlyweight,
+        remove_message_flyweight::RemoveMessageFlyweight,
+        subscription_message_flyweight::SubscriptionMessageFlyweight,
+    },
+    concurrent::{
+        agent_runner::Agent,
+        atomic_buffer::AtomicBuffer,
+        broadcast::broadcast_transmitter::BroadcastTransmitter,
+        counters::{CountersManager, CountersReader},
+        logbuffer::log_buffer_descriptor,
+        ring_buffer::ManyToOneRingBuffer,
+        status::status_indicator_reader::NO_ID_ALLOCATED,
+    },
+    driver::{
+        client_proxy::ClientProxy,
+        context::DriverContext,
+        ipc_publication::{IpcPublication, IpcPublicationState},
+        network_publication::{NetworkPublication, NetworkPublicationState},
+        publication_image::{PublicationImage, PublicationImageState},
+        raw_log::RawLog,
+        receive_channel_endpoint::ReceiveChannelEndpoint,
+        receiver::ReceiverCommand,
+        sender::SenderCommand,
+        stream_counter,
+        udp_channel::UdpChannel,
+    },
+    heartbeat_timestamp::CLIENT_HEARTBEAT_TYPE_ID,
+    log,
+    utils::{
+        bit_utils,
+        errors::AeronError,
+        memory_mapped_file::MemoryMappedFile,
+        misc::unix_time_ms,
+        types::{Index, Moment},
+    },
+};
+
+const COMMAND_LIMIT: i32 = 10;
+
+/**
+ * Requests from the receiver to the conductor.
+ */
+pub(crate) enum ConductorCommand {
+    /// A Setup frame arrived for a session on a subscribed stream which has no image yet.
+    CreatePublicationImage {
+        endpoint: SocketAddr,
+        source_address: SocketAddr,
+        session_id: i32,
+        stream_id: i32,
+        initial_term_id: i32,
+        active_term_id: i32,
+        term_offset: Index,
+        term_length: Index,
+        mtu_length: Index,
+    },
+}
+
+struct AeronClient {
+    client_id: i64,
+    heartbeat_counter_id: i32,
+    time_of_last_keepalive_ms: Moment,
+}
+
+struct PublicationLink {
+    registration_id: i64,
+    client_id: i64,
+    publication_registration_id: i64,
+}
+
+struct SubscriptionLink {
+    registration_id: i64,
+    client_id: i64,
+    stream_id: i32,
+    channel: String,
+    /// Receive channel endpoint of an aeron:udp subscription, None for aeron:ipc.
+    endpoint: Option<SocketAddr>,
+}
+
+struct ReceiveEndpointLink {
+    endpoint: SocketAddr,
+    subscription_count: i32,
+}
+
+struct CounterLink {
+    registration_id: i64,
+    client_id: i64,
+    counter_id: i32,
+}
+
+/**
+ * Driver conductor of the embedded driver. It consumes client commands from the to-driver ring buffer, answers
+ * over the to-clients broadcast buffer and owns every driver side resource: client liveness, log buffers and
+ * counters. Network publications are handed to the sender and receive channel endpoints and images to the
+ * receiver.
+ */
+pub(crate) struct DriverConductor {
+    context: DriverContext,
+    to_driver_commands: Arc<ManyToOneRingBuffer>,
+    client_proxy: ClientProxy,
+    counters_manager: CountersManager,
+    counter_values: AtomicBuffer,
+    clients: Vec<AeronClient>,
+    publication_links: Vec<PublicationLink>,
+    subscription_links: Vec<SubscriptionLink>,
+    counter_links: Vec<CounterLink>,
+    ipc_publications: Vec<IpcPublication>,
+    network_publications: Vec<Arc<Mutex<NetworkPublication>>>,
+    publication_images: Vec<Arc<Mutex<PublicationImage>>>,
+    receive_endpoints: Vec<ReceiveEndpointLink>,
+    sender_commands: mpsc::Sender<SenderCommand>,
+    receiver_commands: mpsc::Sender<ReceiverCommand>,
+    // IC: agents are shared with their runner thread so must be Sync, which a channel receiver is not
+    conductor_commands: Mutex<mpsc::Receiver<ConductorCommand>>,
+    next_session_id: i32,
+    _cnc_file: MemoryMappedFile,
+}
+
+impl DriverConductor {
+    /**
+     * Take over a CnC file whose meta data has been filled in. Clients are not able to connect until the file
+     * is signalled ready, by which time the conductor already heartbeats so the driver is seen as active.
+     */
+    pub fn new(
+        context: DriverContext,
+        cnc_file: MemoryMappedFile,
+        sender_commands: mpsc::Sender<SenderCommand>,
+        receiver_commands: mpsc::Sender<ReceiverCommand>,
+        conductor_commands: mpsc::Receiver<ConductorCommand>,
+    ) -> Result<Self, AeronError> {
+        let to_driver_commands = Arc::new(ManyToOneRingBuffer::new(cnc_file_descriptor::create_to_driver_buffer(
+            &cnc_file,
+        ))?);
+        let transmitter = BroadcastTransmitter::new(cnc_file_descriptor::create_to_clients_buffer(&cnc_file))?;
+        let counter_values = cnc_file_descriptor::create_counter_values_buffer(&cnc_file);
+        let counters_manager =
+            CountersManager::new(cnc_file_descriptor::create_counter_metadata_buffer(&cnc_file), counter_values);
+
+        to_driver_commands.set_consumer_heartbeat_time(unix_time_ms() as i64);
+
+        Ok(Self {
+            context,
+            to_driver_commands,
+            client_proxy: ClientProxy::new(transmitter),
+            counters_manager,
+            counter_values,
+            clients: Vec::new(),
+            publication_links: Vec::new(),
+            subscription_links: Vec::new(),
+            counter_links: Vec::new(),
+            ipc_publications: Vec::new(),
+            network_publications: Vec::new(),
+            publication_images: Vec::new(),
+            receive_endpoints: Vec::new(),
+            sender_commands,
+            receiver_commands,
+            conductor_commands: Mutex::new(conductor_commands),
+            next_session_id: rand::random::<i32>(),
+            _cnc_file: cnc_file,
+        })
+    }
+
+    fn on_command(&mut self, command: AeronCommand, buffer: AtomicBuffer) {
+        let correlated_message = CorrelatedMessageFlyweight::new(buffer, 0);
+        let client_id = correlated_message.client_id();
+        let correlation_id = correlated_message.correlation_id();
+
+        log!(trace, "Driver command {:#x} from client {}", command, client_id);
+
+        if command != AeronCommand::ClientClose {
+            if let Err(err) = self.on_client_activity(client_id) {
+                self.on_command_error(correlation_id, err);
+                return;
+            }
+        }
+
+        let result = match command {
+            AeronCommand::AddPublication => self.on_add_publication(buffer, false),
+            AeronCommand::AddExclusivePublication => self.on_add_publication(buffer, true),
+            AeronCommand::RemovePublication => self.on_remove_publication(buffer),
+            AeronCommand::AddSubscription => self.on_add_subscription(buffer),
+            AeronCommand::RemoveSubscription => self.on_remove_subscription(buffer),
+            AeronCommand::AddCounter => self.on_add_counter(buffer),
+            AeronCommand::RemoveCounter => self.on_remove_counter(buffer),
+            AeronCommand::ClientClose => {
+                self.on_client_close(client_id);
+                Ok(())
+            }
+            // IC: activity was recorded above, which is all a keepalive is for. Termination is not supported
+            // as the driver belongs to the process which launched it.
+            AeronCommand::ClientKeepAlive | AeronCommand::TerminateDriver => Ok(()),
+            _ => Err(AeronError::RegistrationException(
+                ERROR_CODE_NOT_SUPPORTED,
+                format!("command {:#x} is not supported by the embedded driver", command),
+            )),
+        };
+
+        if let Err(err) = result {
+            self.on_command_error(correlation_id, err);
+        }
+    }
+
+    fn on_command_error(&mut self, correlation_id: i64, err: AeronError) {
+        let (code, message) = match err {
+            AeronError::RegistrationException(code, message) => (code, message),
+            err => (ERROR_CODE_GENERIC_ERROR, err.to_string()),
+        };
+
+        if let Err(err) = self.client_proxy.on_error(correlation_id, code, &message) {
+            log!(error, "Failed to send error response for {}: {:?}", correlation_id, err);
+        }
+    }
+
+    fn on_client_activity(&mut self, client_id: i64) -> Result<(), AeronError> {
+        let now_ms = unix_time_ms();
+
+        if let Some(client) = self.clients.iter_mut().find(|client| client.client_id == client_id) {
+            client.time_of_last_keepalive_ms = now_ms;
+            return Ok(());
+        }
+
+        let heartbeat_counter_id = self.counters_manager.allocate_opt(
+            CLIENT_HEARTBEAT_TYPE_ID,
+            Some(&client_id.to_le_bytes()),
+            Option::<fn(&mut AtomicBuffer)>::None,
+            &format!("client-heartbeat: {}", client_id),
+        )?;
+        self.counter_values
+            .put_ordered::<i64>(CountersReader::counter_offset(heartbeat_counter_id), now_ms as i64);
+
+        self.clients.push(AeronClient {
+            client_id,
+            heartbeat_counter_id,
+            time_of_last_keepalive_ms: now_ms,
+        });
+
+        Ok(())
+    }
+
+    fn on_add_publication(&mut self, buffer: AtomicBuffer, is_exclusive: bool) -> Result<(), AeronError> {
+        let message = PublicationMessageFlyweight::new(buffer, 0);
+        let correlation_id = message.correlation_id();
+        let client_id = message.client_id();
+        let stream_id = message.stream_id();
+        let channel = message.channel().to_string_lossy().into_owned();
+
+        if Self::channel_media(&channel)? == UDP_MEDIA {
+            return self.on_add_network_publication(correlation_id, client_id, stream_id, channel, is_exclusive);
+        }
+
+        let existing = if is_exclusive {
+            None
+        } else {
+            self.ipc_publications.iter().position(|publication| {
+                !publication.is_exclusive() && publication.is_accepting_subscriptions() && publication.stream_id() == stream_id
+            })
+        };
+
+        let index = match existing {
+            Some(index) => index,
+            None => self.new_ipc_publication(correlation_id, stream_id, &channel, is_exclusive)?,
+        };
+
+        let publication = &mut self.ipc_publications[index];
+        publication.inc_ref();
+
+        self.publication_links.push(PublicationLink {
+            registration_id: correlation_id,
+            client_id,
+            publication_registration_id: publication.registration_id(),
+        });
+
+        self.client_proxy.on_publication_ready(
+            correlation_id,
+            publication.registration_id(),
+            stream_id,
+            publication.session_id(),
+            publication.publisher_limit_id(),
+            NO_ID_ALLOCATED,
+            publication.log_file_name(),
+            is_exclusive,
+        )?;
+
+        if existing.is_none() {
+            self.link_subscriptions(index)?;
+        }
+
+        Ok(())
+    }
+
+    fn on_add_network_publication(
+        &mut self,
+        correlation_id: i64,
+        client_id: i64,
+        stream_id: i32,
+        channel: String,
+        is_exclusive: bool,
+    ) -> Result<(), AeronError> {
+        let udp_channel = UdpChannel::parse(&channel)?;
+
+        let existing = if is_exclusive {
+            None
+        } else {
+            self.network_publications
+                .iter()
+                .find(|publication| {
+                    let publication = publication.lock().expect("Mutex poisoned");
+                    !publication.is_exclusive()
+                        && publication.state() == NetworkPublicationState::Active
+                        && publication.stream_id() == stream_id
+                        && publication.endpoint() == udp_channel.endpoint()
+                })
+                .cloned()
+        };
+
+        let publication = match existing {
+            Some(publication) => publication,
+            None => self.new_network_publication(correlation_id, stream_id, &udp_channel, is_exclusive)?,
+        };
+
+        let mut publication = publication.lock().expect("Mutex poisoned");
+        publication.inc_ref();
+
+        self.publication_links.push(PublicationLink {
+            registration_id: correlation_id,
+            client_id,
+            publication_registration_id: publication.registration_id(),
+        });
+
+        self.client_proxy.on_publication_ready(
+            correlation_id,
+            publication.registration_id(),
+            stream_id,
+            publication.session_id(),
+            publication.publisher_limit_id(),
+            NO_ID_ALLOCATED,
+            publication.log_file_name(),
+            is_exclusive,
+        )
+    }
+
+    fn new_network_publication(
+        &mut self,
+        registration_id: i64,
+        stream_id: i32,
+        udp_channel: &UdpChannel,
+        is_exclusive: bool,
+    ) -> Result<Arc<Mutex<NetworkPublication>>, AeronError> {
+        let socket = udp_channel.open_send_socket()?;
+        let session_id = self.next_session_id();
+        let channel = udp_channel.original_uri();
+
+        let initial_term_id = rand::random::<i32>();
+        let raw_log = RawLog::create(
+            format!("{}/{}.logbuffer", self.context.publications_dir(), registration_id),
+            self.context.term_buffer_length(),
+            self.context.mtu_length(),
+            initial_term_id,
+            session_id,
+            stream_id,
+        )?;
+
+        let publisher_limit_id = stream_counter::allocate(
+            &mut self.counters_manager,
+            "pub-lmt",
+            stream_counter::PUBLISHER_LIMIT_TYPE_ID,
+            registration_id,
+            session_id,
+            stream_id,
+            channel,
+        )
+        .inspect_err(|_| raw_log.delete())?;
+
+        let sender_position_id = stream_counter::allocate(
+            &mut self.counters_manager,
+            "snd-pos",
+            stream_counter::SENDER_POSITION_TYPE_ID,
+            registration_id,
+            session_id,
+            stream_id,
+            channel,
+        )
+        .inspect_err(|_| {
+            self.counters_manager.free(publisher_limit_id);
+            raw_log.delete();
+        })?;
+
+        let publication = Arc::new(Mutex::new(NetworkPublication::new(
+            registration_id,
+            session_id,
+            stream_id,
+            is_exclusive,
+            raw_log,
+            socket,
+            udp_channel.endpoint(),
+            self.counter_values,
+            publisher_limit_id,
+            sender_position_id,
+            initial_term_id,
+            self.context.mtu_length(),
+            self.context.publication_connection_timeout_ms(),
+        )));
+
+        self.network_publications.push(publication.clone());
+        self.send_to_sender(SenderCommand::AddPublication(publication.clone()));
+
+        Ok(publication)
+    }
+
+    fn new_ipc_publication(
+        &mut self,
+        registration_id: i64,
+        stream_id: i32,
+        channel: &str,
+        is_exclusive: bool,
+    ) -> Result<usize, AeronError> {
+        let session_id = self.next_session_id();
+
+        let initial_term_id = rand::random::<i32>();
+        let raw_log = RawLog::create(
+            format!("{}/{}.logbuffer", self.context.publications_dir(), registration_id),
+            self.context.ipc_term_buffer_length(),
+            self.context.mtu_length(),
+            initial_term_id,
+            session_id,
+            stream_id,
+        )?;
+
+        let publisher_limit_id = match stream_counter::allocate(
+            &mut self.counters_manager,
+            "pub-lmt",
+            stream_counter::PUBLISHER_LIMIT_TYPE_ID,
+            registration_id,
+            session_id,
+            stream_id,
+            channel,
+        ) {
+            Ok(counter_id) => counter_id,
+            Err(err) => {
+                raw_log.delete();
+                return Err(err);
+            }
+        };
+
+        self.ipc_publications.push(IpcPublication::new(
+            registration_id,
+            session_id,
+            stream_id,
+            channel.to_string(),
+            is_exclusive,
+            raw_log,
+            initial_term_id,
+            publisher_limit_id,
+        ));
+
+        Ok(self.ipc_publications.len() - 1)
+    }
+
+    fn on_remove_publication(&mut self, buffer: AtomicBuffer) -> Result<(), AeronError> {
+        let message = RemoveMessageFlyweight::new(buffer, 0);
+        let registration_id = message.registration_id();
+
+        let index = self
+            .publication_links
+            .iter()
+            .position(|link| link.registration_id == registration_id && link.client_id == message.client_id())
+            .ok_or_else(|| {
+                AeronError::RegistrationException(
+                    ERROR_CODE_UNKNOWN_PUBLICATION,
+                    format!(
+                        "unknown publication client_id={} registration_id={}",
+                        message.client_id(),
+                        registration_id
+                    ),
+                )
+            })?;
+
+        let link = self.publication_links.remove(index);
+        self.release_publication(link.publication_registration_id);
+
+        self.client_proxy.on_operation_succeeded(message.correlation_id())
+    }
+
+    fn release_publication(&mut self, publication_registration_id: i64) {
+        if let Some(publication) = self
+            .ipc_publications
+            .iter_mut()
+            .find(|publication| publication.registration_id() == publication_registration_id)
+        {
+            publication.dec_ref();
+            return;
+        }
+
+        for publication in &self.network_publications {
+            let mut publication = publication.lock().expect("Mutex poisoned");
+            if publication.registration_id() == publication_registration_id {
+                publication.dec_ref();
+                return;
+            }
+        }
+    }
+
+    fn on_add_subscription(&mut self, buffer: AtomicBuffer) -> Result<(), AeronError> {
+        let message = SubscriptionMessageFlyweight::new(buffer, 0);
+        let registration_id = message.correlation_id();
+        let stream_id = message.stream_id();
+        let channel = message.channel().to_string_lossy().into_owned();
+
+        let endpoint = if Self::channel_media(&channel)? == UDP_MEDIA {
+            let udp_channel = UdpChannel::parse(&channel)?;
+            self.add_receive_endpoint_subscription(&udp_channel, stream_id)?;
+            Some(udp_channel.endpoint())
+        } else {
+            None
+        };
+
+        self.subscription_links.push(SubscriptionLink {
+            registration_id,
+            client_id: message.client_id(),
+            stream_id,
+            channel,
+            endpoint,
+        });
+
+        self.client_proxy.on_subscription_ready(registration_id, NO_ID_ALLOCATED)?;
+
+        match endpoint {
+            None => {
+                for index in 0..self.ipc_publications.len() {
+                    let publication = &self.ipc_publications[index];
+                    if publication.is_accepting_subscriptions() && publication.stream_id() == stream_id {
+                        self.link_subscriber(index, registration_id)?;
+                    }
+                }
+            }
+            Some(endpoint) => {
+                let images: Vec<_> = self
+                    .publication_images
+                    .iter()
+                    .filter(|image| {
+                        let image = image.lock().expect("Mutex poisoned");
+                        image.state() == PublicationImageState::Active
+                            && image.endpoint() == endpoint
+                            && image.stream_id() == stream_id
+                    })
+                    .cloned()
+                    .collect();
+
+                for image in images {
+                    self.link_image_subscriber(&image, registration_id)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Open the receive channel endpoint for a subscription's address unless another subscription already has,
+     * then register interest in the stream with the receiver.
+     */
+    fn add_receive_endpoint_subscription(&mut self, udp_channel: &UdpChannel, stream_id: i32) -> Result<(), AeronError> {
+        let endpoint = udp_channel.endpoint();
+
+        match self.receive_endpoints.iter_mut().find(|link| link.endpoint == endpoint) {
+            Some(link) => link.subscription_count += 1,
+            None => {
+                let socket = udp_channel.open_receive_socket()?;
+                self.send_to_receiver(ReceiverCommand::AddEndpoint(ReceiveChannelEndpoint::new(endpoint, socket)));
+                self.receive_endpoints.push(ReceiveEndpointLink {
+                    endpoint,
+                    subscription_count: 1,
+                });
+            }
+        }
+
+        self.send_to_receiver(ReceiverCommand::AddSubscription { endpoint, stream_id });
+
+        Ok(())
+    }
+
+    fn remove_receive_endpoint_subscription(&mut self, endpoint: SocketAddr, stream_id: i32) {
+        self.send_to_receiver(ReceiverCommand::RemoveSubscription { endpoint, stream_id });
+
+        if let Some(index) = self.receive_endpoints.iter().position(|link| link.endpoint == endpoint) {
+            self.receive_endpoints[index].subscription_count -= 1;
+
+            if self.receive_endpoints[index].subscription_count == 0 {
+                self.receive_endpoints.remove(index);
+                self.send_to_receiver(ReceiverCommand::RemoveEndpoint { endpoint });
+            }
+        }
+    }
+
+    fn on_remove_subscription(&mut self, buffer: AtomicBuffer) -> Result<(), AeronError> {
+        let message = RemoveMessageFlyweight::new(buffer, 0);
+        let registration_id = message.registration_id();
+
+        let index = self
+            .subscription_links
+            .iter()
+            .position(|link| link.registration_id == registration_id && link.client_id == message.client_id())
+            .ok_or_else(|| {
+                AeronError::RegistrationException(
+                    ERROR_CODE_UNKNOWN_SUBSCRIPTION,
+                    format!(
+                        "unknown subscription client_id={} registration_id={}",
+                        message.client_id(),
+                        registration_id
+                    ),
+                )
+            })?;
+
+        let link = self.subscription_links.remove(index);
+        self.release_subscription(link);
+
+        self.client_proxy.on_operation_succeeded(message.correlation_id())
+    }
+
+    /**
+     * Link the subscriptions on the stream of a new publication to it.
+     */
+    fn link_subscriptions(&mut self, publication_index: usize) -> Result<(), AeronError> {
+        let stream_id = self.ipc_publications[publication_index].stream_id();
+        let registration_ids: Vec<i64> = self
+            .subscription_links
+            .iter()
+            .filter(|link| link.endpoint.is_none() && link.stream_id == stream_id)
+            .map(|link| link.registration_id)
+            .collect();
+
+        for registration_id in registration_ids {
+            self.link_subscriber(publication_index, registration_id)?;
+        }
+
+        Ok(())
+    }
+
+    fn link_subscriber(&mut self, publication_index: usize, subscription_registration_id: i64) -> Result<(), AeronError> {
+        let publication = &mut self.ipc_publications[publication_index];
+
+        let counter_id = stream_counter::allocate(
+            &mut self.counters_manager,
+            "sub-pos",
+            stream_counter::SUBSCRIBER_POSITION_TYPE_ID,
+            subscription_registration_id,
+            publication.session_id(),
+            publication.stream_id(),
+            publication.channel(),
+        )?;
+
+        let join_position = publication.add_subscriber(subscription_registration_id, counter_id);
+        self.counter_values
+            .put_ordered::<i64>(CountersReader::counter_offset(counter_id), join_position);
+
+        self.client_proxy.on_available_image(
+            publication.registration_id(),
+            publication.stream_id(),
+            publication.session_id(),
+            subscription_registration_id,
+            counter_id,
+            publication.log_file_name(),
+            IPC_CHANNEL,
+        )
+    }
+
+    fn link_image_subscriber(
+        &mut self,
+        image: &Arc<Mutex<PublicationImage>>,
+        subscription_registration_id: i64,
+    ) -> Result<(), AeronError> {
+        let mut image = image.lock().expect("Mutex poisoned");
+
+        let counter_id = stream_counter::allocate(
+            &mut self.counters_manager,
+            "sub-pos",
+            stream_counter::SUBSCRIBER_POSITION_TYPE_ID,
+            subscription_registration_id,
+            image.session_id(),
+            image.stream_id(),
+            image.channel(),
+        )?;
+
+        let join_position = image.add_subscriber(subscription_registration_id, counter_id);
+        self.counter_values
+            .put_ordered::<i64>(CountersReader::counter_offset(counter_id), join_position);
+
+        self.client_proxy.on_available_image(
+            image.correlation_id(),
+            image.stream_id(),
+            image.session_id(),
+            subscription_registration_id,
+            counter_id,
+            image.log_file_name(),
+            &image.source_identity(),
+        )
+    }
+
+    fn release_subscription(&mut self, link: SubscriptionLink) {
+        self.unlink_subscriber(link.registration_id);
+
+        if let Some(endpoint) = link.endpoint {
+            self.remove_receive_endpoint_subscription(endpoint, link.stream_id);
+        }
+    }
+
+    fn unlink_subscriber(&mut self, subscription_registration_id: i64) {
+        for publication in self.ipc_publications.iter_mut() {
+            if let Some(counter_id) = publication.remove_subscriber(subscription_registration_id) {
+                self.counters_manager.free(counter_id);
+            }
+        }
+
+        for image in &self.publication_images {
+            let mut image = image.lock().expect("Mutex poisoned");
+            if let Some(counter_id) = image.remove_subscriber(subscription_registration_id) {
+                self.counters_manager.free(counter_id);
+            }
+        }
+    }
+
+    fn on_add_counter(&mut self, buffer: AtomicBuffer) -> Result<(), AeronError> {
+        let message = CounterMessageFlyweight::new(buffer, 0);
+        let registration_id = message.correlation_id();
+        let key = message.key();
+        let label = message.label().to_string_lossy().into_owned();
+
+        let counter_id =
+            self.counters_manager
+                .allocate_opt(message.type_id(), Some(&key), Option::<fn(&mut AtomicBuffer)>::None, &label)?;
+
+        self.counter_links.push(CounterLink {
+            registration_id,
+            client_id: message.client_id(),
+            counter_id,
+        });
+
+        self.client_proxy.on_counter_ready(registration_id, counter_id)
+    }
+
+    fn on_remove_counter(&mut self, buffer: AtomicBuffer) -> Result<(), AeronError> {
+        let message = RemoveMessageFlyweight::new(buffer, 0);
+        let registration_id = message.registration_id();
+
+        let index = self
+            .counter_links
+            .iter()
+            .position(|link| link.registration_id == registration_id && link.client_id == message.client_id())
+            .ok_or_else(|| {
+                AeronError::RegistrationException(
+                    ERROR_CODE_UNKNOWN_COUNTER,
+                    format!(
+                        "unknown counter client_id={} registration_id={}",
+                        message.client_id(),
+                        registration_id
+                    ),
+                )
+            })?;
+
+        let link = self.counter_links.remove(index);
+
+        self.client_proxy.on_operation_succeeded(message.correlation_id())?;
+        self.release_counter(link)
+    }
+
+    fn release_counter(&mut self, link: CounterLink) -> Result<(), AeronError> {
+        self.counters_manager.free(link.counter_id);
+        self.client_proxy
+            .on_unavailable_counter(link.registration_id, link.counter_id)
+    }
+
+    fn on_client_close(&mut self, client_id: i64) {
+        if let Some(index) = self.clients.iter().position(|client| client.client_id == client_id) {
+            let client = self.clients.remove(index);
+            self.release_client_resources(client);
+        }
+    }
+
+    /**
+     * Free everything a client registered, as when it closes or times out.
+     */
+    fn release_client_resources(&mut self, client: AeronClient) {
+        self.counters_manager.free(client.heartbeat_counter_id);
+
+        let (publication_links, remaining): (Vec<_>, Vec<_>) = self
+            .publication_links
+            .drain(..)
+            .partition(|link| link.client_id == client.client_id);
+        self.publication_links = remaining;
+        for link in publication_links {
+            self.release_publication(link.publication_registration_id);
+        }
+
+        let (subscription_links, remaining): (Vec<_>, Vec<_>) = self
+            .subscription_links
+            .drain(..)
+            .partition(|link| link.client_id == client.client_id);
+        self.subscription_links = remaining;
+        for link in subscription_links {
+            self.release_subscription(link);
+        }
+
+        let (counter_links, remaining): (Vec<_>, Vec<_>) = self
+            .counter_links
+            .drain(..)
+            .partition(|link| link.client_id == client.client_id);
+        self.counter_links = remaining;
+        for link in counter_links {
+            if let Err(err) = self.release_counter(link) {
+                log!(error, "Failed to send unavailable counter: {:?}", err);
+            }
+        }
+    }
+
+    fn check_client_liveness(&mut self, now_ms: Moment) -> Result<i32, AeronError> {
+        let liveness_timeout_ms = self.context.client_liveness_timeout_ms();
+        let counter_values = self.counter_values;
+
+        let (timed_out, alive): (Vec<_>, Vec<_>) = self.clients.drain(..).partition(|client| {
+            let heartbeat_ms =
+                counter_values.get_volatile::<i64>(CountersReader::counter_offset(client.heartbeat_counter_id)) as Moment;
+
+            now_ms > client.time_of_last_keepalive_ms.max(heartbeat_ms) + liveness_timeout_ms
+        });
+        self.clients = alive;
+
+        let work_count = timed_out.len() as i32;
+        for client in timed_out {
+            log!(debug, "Client {} timed out", client.client_id);
+
+            self.client_proxy.on_client_timeout(client.client_id)?;
+            self.release_client_resources(client);
+        }
+
+        Ok(work_count)
+    }
+
+    fn on_publication_time_events(&mut self, now_ms: Moment) -> Result<i32, AeronError> {
+        let linger_timeout_ms = self.context.publication_linger_timeout_ms();
+        let mut work_count = 0;
+
+        for publication in self.ipc_publications.iter_mut() {
+            work_count += publication.update_publisher_limit(&self.counter_values);
+
+            if publication.on_time_event(now_ms, linger_timeout_ms, &self.counter_values) {
+                for position in publication.subscriber_positions() {
+                    self.client_proxy.on_unavailable_image(
+                        publication.registration_id(),
+                        position.subscription_registration_id,
+                        publication.stream_id(),
+                        publication.channel(),
+                    )?;
+                }
+                work_count += 1;
+            }
+        }
+
+        let (done, active): (Vec<_>, Vec<_>) = self
+            .ipc_publications
+            .drain(..)
+            .partition(|publication| publication.state() == IpcPublicationState::Done);
+        self.ipc_publications = active;
+
+        for publication in done {
+            self.counters_manager.free(publication.publisher_limit_id());
+            for position in publication.subscriber_positions() {
+                self.counters_manager.free(position.counter_id);
+            }
+            publication.close();
+            work_count += 1;
+        }
+
+        Ok(work_count)
+    }
+
+    fn on_network_publication_time_events(&mut self, now_ms: Moment) -> i32 {
+        let linger_timeout_ms = self.context.publication_linger_timeout_ms();
+        let mut work_count = 0;
+
+        for publication in &self.network_publications {
+            let mut publication = publication.lock().expect("Mutex poisoned");
+            work_count += publication.update_publisher_limit();
+            publication.on_time_event(now_ms, linger_timeout_ms);
+        }
+
+        let (done, active): (Vec<_>, Vec<_>) = self
+            .network_publications
+            .drain(..)
+            .partition(|publication| publication.lock().expect("Mutex poisoned").state() == NetworkPublicationState::Done);
+        self.network_publications = active;
+
+        for publication in done {
+            let publication = publication.lock().expect("Mutex poisoned");
+            self.send_to_sender(SenderCommand::RemovePublication {
+                registration_id: publication.registration_id(),
+            });
+            self.counters_manager.free(publication.publisher_limit_id());
+            self.counters_manager.free(publication.sender_position_id());
+            publication.close();
+            work_count += 1;
+        }
+
+        work_count
+    }
+
+    fn on_image_time_events(&mut self, now_ms: Moment) -> Result<i32, AeronError> {
+        let liveness_timeout_ms = self.context.image_liveness_timeout_ms();
+        let linger_timeout_ms = self.context.publication_linger_timeout_ms();
+        let mut work_count = 0;
+
+        for image in &self.publication_images {
+            let mut image = image.lock().expect("Mutex poisoned");
+
+            if image.on_time_event(now_ms, liveness_timeout_ms, linger_timeout_ms) {
+                for position in image.subscriber_positions() {
+                    self.client_proxy.on_unavailable_image(
+                        image.correlation_id(),
+                        position.subscription_registration_id,
+                        image.stream_id(),
+                        image.channel(),
+                    )?;
+                }
+
+                // IC: the receiver stops feeding the image straight away, the log lingers for subscribers
+                // which are still reading it
+                if self
+                    .receiver_commands
+                    .send(ReceiverCommand::RemoveImage {
+                        endpoint: image.endpoint(),
+                        correlation_id: image.correlation_id(),
+                    })
+                    .is_err()
+                {
+                    log!(error, "Receiver has stopped, image {} not removed", image.correlation_id());
+                }
+                work_count += 1;
+            }
+        }
+
+        let (done, active): (Vec<_>, Vec<_>) = self
+            .publication_images
+            .drain(..)
+            .partition(|image| image.lock().expect("Mutex poisoned").state() == PublicationImageState::Done);
+        self.publication_images = active;
+
+        for image in done {
+            let image = image.lock().expect("Mutex poisoned");
+            self.counters_manager.free(image.receiver_hwm_id());
+            self.counters_manager.free(image.receiver_position_id());
+            for position in image.subscriber_positions() {
+                self.counters_manager.free(position.counter_id);
+            }
+            image.close();
+            work_count += 1;
+        }
+
+        Ok(work_count)
+    }
+
+    fn on_conductor_command(&mut self, command: ConductorCommand, now_ms: Moment) {
+        let result = match command {
+            ConductorCommand::CreatePublicationImage {
+                endpoint,
+                source_address,
+                session_id,
+                stream_id,
+                initial_term_id,
+                active_term_id,
+                term_offset,
+                term_length,
+                mtu_length,
+            } => self.on_create_publication_image(
+                endpoint,
+                source_address,
+                session_id,
+                stream_id,
+                initial_term_id,
+                active_term_id,
+                term_offset,
+                term_length,
+                mtu_length,
+                now_ms,
+            ),
+        };
+
+        if let Err(err) = result {
+            log!(error, "Failed to create publication image: {:?}", err);
+        }
+    }
+
+    /**
+     * Create the image of a session which sent a Setup frame to a receive channel endpoint and link the
+     * subscriptions on its stream to it, then hand it to the receiver to be fed.
+     */
+    #[allow(clippy::too_many_arguments)]
+    fn on_create_publication_image(
+        &mut self,
+        endpoint: SocketAddr,
+        source_address: SocketAddr,
+        session_id: i32,
+        stream_id: i32,
+        initial_term_id: i32,
+        active_term_id: i32,
+        term_offset: Index,
+        term_length: Index,
+        mtu_length: Index,
+        now_ms: Moment,
+    ) -> Result<(), AeronError> {
+        // IC: Setup frames keep coming until the receiver has been handed the image
+        let is_known = self.publication_images.iter().any(|image| {
+            let image = image.lock().expect("Mutex poisoned");
+            image.state() == PublicationImageState::Active
+                && image.endpoint() == endpoint
+                && image.session_id() == session_id
+                && image.stream_id() == stream_id
+        });
+
+        let subscriptions: Vec<(i64, String)> = self
+            .subscription_links
+            .iter()
+            .filter(|link| link.endpoint == Some(endpoint) && link.stream_id == stream_id)
+            .map(|link| (link.registration_id, link.channel.clone()))
+            .collect();
+
+        if is_known || subscriptions.is_empty() {
+            return Ok(());
+        }
+
+        let correlation_id = self.to_driver_commands.next_correlation_id();
+        let channel = subscriptions[0].1.clone();
+
+        let raw_log = RawLog::create(
+            format!("{}/{}.logbuffer", self.context.images_dir(), correlation_id),
+            term_length,
+            mtu_length,
+            initial_term_id,
+            session_id,
+            stream_id,
+        )?;
+
+        let receiver_hwm_id = stream_counter::allocate(
+            &mut self.counters_manager,
+            "rcv-hwm",
+            stream_counter::RECEIVER_HWM_TYPE_ID,
+            correlation_id,
+            session_id,
+            stream_id,
+            &channel,
+        )
+        .inspect_err(|_| raw_log.delete())?;
+
+        let receiver_position_id = stream_counter::allocate(
+            &mut self.counters_manager,
+            "rcv-pos",
+            stream_counter::RECEIVER_POSITION_TYPE_ID,
+            correlation_id,
+            session_id,
+            stream_id,
+            &channel,
+        )
+        .inspect_err(|_| {
+            self.counters_manager.free(receiver_hwm_id);
+            raw_log.delete();
+        })?;
+
+        let join_position = log_buffer_descriptor::compute_position(
+            active_term_id,
+            term_offset,
+            bit_utils::number_of_trailing_zeroes(term_length),
+            initial_term_id,
+        );
+
+        let image = Arc::new(Mutex::new(PublicationImage::new(
+            correlation_id,
+            session_id,
+            stream_id,
+            channel,
+            endpoint,
+            source_address,
+            raw_log,
+            self.counter_values,
+            receiver_hwm_id,
+            receiver_position_id,
+            initial_term_id,
+            join_position,
+            self.context.initial_window_length(),
+            self.context.status_message_timeout_ms(),
+            now_ms,
+        )));
+        self.publication_images.push(image.clone());
+
+        for (registration_id, _) in subscriptions {
+            self.link_image_subscriber(&image, registration_id)?;
+        }
+
+        self.send_to_receiver(ReceiverCommand::AddImage { endpoint, image });
+
+        Ok(())
+    }
+
+    fn next_session_id(&mut self) -> i32 {
+        let session_id = self.next_session_id;
+        self.next_session_id = self.next_session_id.wrapping_add(1);
+        session_id
+    }
+
+    fn send_to_sender(&self, command: SenderCommand) {
+        if self.sender_commands.send(command).is_err() {
+            log!(error, "Sender has stopped, dropping command");
+        }
+    }
+
+    fn send_to_receiver(&self, command: ReceiverCommand) {
+        if self.receiver_commands.send(command).is_err() {
+            log!(error, "Receiver has stopped, dropping command");
+        }
+    }
+
+    fn channel_media(channel: &str) -> Result<String, AeronError> {
+        let media = ChannelUri::parse(channel)
+            .map_err(|err| AeronError::RegistrationException(ERROR_CODE_INVALID_CHANNEL, err.to_string()))?
+            .lock()
+            .expect("Mutex poisoned")
+            .media();
+
+        if media != IPC_MEDIA && media != UDP_MEDIA {
+            return Err(AeronError::RegistrationException(
+                ERROR_CODE_INVALID_CHANNEL,
+                format!(
+                    "only {} and aeron:{} channels are supported by the embedded driver: {}",
+                    IPC_CHANNEL, UDP_MEDIA, channel
+                ),
+            ));
+        }
+
+        Ok(media)
+    }
+}
+
+impl Agent for DriverConductor {
+    fn on_start(&mut self) -> Result<(), AeronError> {
+        self.to_driver_commands.set_consumer_heartbeat_time(unix_time_ms() as i64);
+        Ok(())
+    }
+
+    fn do_work(&mut self) -> Result<i32, AeronError> {
+        let now_ms = unix_time_ms();
+        self.to_driver_commands.set_consumer_heartbeat_time(now_ms as i64);
+
+        let to_driver_commands = self.to_driver_commands.clone();
+        let mut work_count = to_driver_commands.read(|command, buffer| self.on_command(command, buffer), COMMAND_LIMIT);
+
+        let conductor_commands: Vec<_> = self.conductor_commands.lock().expect("Mutex poisoned").try_iter().collect();
+        work_count += conductor_commands.len() as i32;
+        for command in conductor_commands {
+            self.on_conductor_command(command, now_ms);
+        }
+
+        work_count += self.check_client_liveness(now_ms)?;
+        work_count += self.on_publication_time_events(now_ms)?;
+        work_count += self.on_network_publication_time_events(now_ms);
+        work_count += self.on_image_time_events(now_ms)?;
+
+        Ok(work_count)
+    }
+
+    fn on_close(&mut self) -> Result<(), AeronError> {
+        for publication in self.ipc_publications.drain(..) {
+            publication.close();
+        }
+
+        for publication in self.network_publications.drain(..) {
+            publication.lock().expect("Mutex poisoned").close();
+        }
+
+        for image in self.publication_images.drain(..) {
+            image.lock().expect("Mutex poisoned").close();
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,289 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::concurrent::{atomic_buffer::AtomicBuffer, counters::CountersReader, logbuffer::log_buffer_descriptor};
+use crate::driver::raw_log::RawLog;
+use crate::utils::{bit_utils, types::Moment};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum IpcPublicationState {
+    /// Publishers hold a reference to the publication.
+    Active,
+    /// All publishers have gone, waiting for subscribers to consume what is left in the log.
+    Draining,
+    /// Subscribers have been told the image is gone, the log is kept until the deadline.
+    Linger { deadline_ms: Moment },
+    /// Ready to have its counters freed and log deleted.
+    Done,
+}
+
+/**
+ * Position of a subscriber to an IpcPublication.
+ */
+pub(crate) struct SubscriberPosition {
+    pub subscription_registration_id: i64,
+    pub counter_id: i32,
+}
+
+/**
+ * Publication over aeron:ipc. Publishers and subscribers share the log buffer directly so the driver only
+ * moves the publisher limit along behind the slowest subscriber and cleans the terms that have been consumed.
+ */
+pub(crate) struct IpcPublication {
+    registration_id: i64,
+    session_id: i32,
+    stream_id: i32,
+    channel: String,
+    is_exclusive: bool,
+    raw_log: RawLog,
+    position_bits_to_shift: i32,
+    initial_term_id: i32,
+    term_window_length: i64,
+    publisher_limit_id: i32,
+    subscriber_positions: Vec<SubscriberPosition>,
+    consumer_position: i64,
+    clean_position: i64,
+    ref_count: i32,
+    state: IpcPublicationState,
+}
+
+impl IpcPublication {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        registration_id: i64,
+        session_id: i32,
+        stream_id: i32,
+        channel: String,
+        is_exclusive: bool,
+        raw_log: RawLog,
+        initial_term_id: i32,
+        publisher_limit_id: i32,
+    ) -> Self {
+        let term_length = raw_log.term_length();
+
+        Self {
+            registration_id,
+            session_id,
+            stream_id,
+            channel,
+            is_exclusive,
+            raw_log,
+            position_bits_to_shift: bit_utils::number_of_trailing_zeroes(term_length),
+            initial_term_id,
+            term_window_length: term_length as i64,
+            publisher_limit_id,
+            subscriber_positions: Vec::new(),
+            consumer_position: 0,
+            clean_position: 0,
+            ref_count: 0,
+            state: IpcPublicationState::Active,
+        }
+    }
+
+    pub fn registration_id(&self) -> i64 {
+        self.registration_id
+    }
+
+    pub fn session_id(&self) -> i32 {
+        self.session_id
+    }
+
+    pub fn stream_id(&self) -> i32 {
+        self.stream_id
+    }
+
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        self.is_exclusive
+    }
+
+    pub fn log_file_name(&self) -> &str {
+        self.raw_log.file_name()
+    }
+
+    pub fn publisher_limit_id(&self) -> i32 {
+        self.publisher_limit_id
+    }
+
+    pub fn subscriber_positions(&self) -> &[SubscriberPosition] {
+        &self.subscriber_positions
+    }
+
+    pub fn state(&self) -> IpcPublicationState {
+        self.state
+    }
+
+    pub fn is_accepting_subscriptions(&self) -> bool {
+        self.state == IpcPublicationState::Active
+    }
+
+    pub fn inc_ref(&mut self) {
+        self.ref_count += 1;
+    }
+
+    /**
+     * Drop a publisher's reference. The last one to go starts the publication draining, with the end of stream
+     * at the current producer position.
+     */
+    pub fn dec_ref(&mut self) {
+        self.ref_count -= 1;
+
+        if self.ref_count == 0 && self.state == IpcPublicationState::Active {
+            log_buffer_descriptor::set_end_of_stream_position(&self.raw_log.meta_data(), self.producer_position());
+            self.state = IpcPublicationState::Draining;
+        }
+    }
+
+    /**
+     * Position the publisher has written up to.
+     */
+    pub fn producer_position(&self) -> i64 {
+        let raw_tail = log_buffer_descriptor::raw_tail_volatile(&self.raw_log.meta_data());
+        let term_offset = log_buffer_descriptor::term_offset(raw_tail, self.raw_log.term_length() as i64);
+
+        log_buffer_descriptor::compute_position(
+            log_buffer_descriptor::term_id(raw_tail),
+            term_offset,
+            self.position_bits_to_shift,
+            self.initial_term_id,
+        )
+    }
+
+    /**
+     * Add a subscriber which joins at the current producer position.
+     *
+     * @return the join position the subscriber position counter has to be set to.
+     */
+    pub fn add_subscriber(&mut self, subscription_registration_id: i64, counter_id: i32) -> i64 {
+        self.subscriber_positions.push(SubscriberPosition {
+            subscription_registration_id,
+            counter_id,
+        });
+        log_buffer_descriptor::set_is_connected(&self.raw_log.meta_data(), true);
+
+        self.producer_position()
+    }
+
+    /**
+     * Remove the subscriber of a subscription.
+     *
+     * @return the id of its position counter, which is for the caller to free, if it was subscribed.
+     */
+    pub fn remove_subscriber(&mut self, subscription_registration_id: i64) -> Option<i32> {
+        let index = self
+            .subscriber_positions
+            .iter()
+            .position(|position| position.subscription_registration_id == subscription_registration_id)?;
+        let position = self.subscriber_positions.remove(index);
+
+        if self.subscriber_positions.is_empty() {
+            log_buffer_descriptor::set_is_connected(&self.raw_log.meta_data(), false);
+        }
+
+        Some(position.counter_id)
+    }
+
+    /**
+     * Move the publisher limit to a window beyond the slowest subscriber, or hold it at the consumer position
+     * when there are no subscribers, and clean terms which every subscriber has moved past.
+     *
+     * @return the amount of work done.
+     */
+    pub fn update_publisher_limit(&mut self, counter_values: &AtomicBuffer) -> i32 {
+        if self.state != IpcPublicationState::Active {
+            return 0;
+        }
+
+        let limit_offset = CountersReader::counter_offset(self.publisher_limit_id);
+        let publisher_limit = counter_values.get_volatile::<i64>(limit_offset);
+        let term_length = self.raw_log.term_length() as i64;
+
+        if self.subscriber_positions.is_empty() {
+            if publisher_limit > self.consumer_position {
+                counter_values.put_ordered::<i64>(limit_offset, self.consumer_position);
+                self.clean_buffer_to(self.consumer_position - term_length);
+                return 1;
+            }
+
+            return 0;
+        }
+
+        let mut min_subscriber_position = i64::MAX;
+        let mut max_subscriber_position = self.consumer_position;
+
+        for position in &self.subscriber_positions {
+            let value = counter_values.get_volatile::<i64>(CountersReader::counter_offset(position.counter_id));
+            min_subscriber_position = min_subscriber_position.min(value);
+            max_subscriber_position = max_subscriber_position.max(value);
+        }
+
+        self.consumer_position = max_subscriber_position;
+        self.clean_buffer_to(min_subscriber_position - term_length);
+
+        let new_limit = min_subscriber_position + self.term_window_length;
+        if new_limit != publisher_limit {
+            counter_values.put_ordered::<i64>(limit_offset, new_limit);
+            return 1;
+        }
+
+        0
+    }
+
+    /**
+     * Progress draining and lingering.
+     *
+     * @return true when the publication has just been drained, at which point subscribers should be told
+     * its images are unavailable.
+     */
+    pub fn on_time_event(&mut self, now_ms: Moment, linger_timeout_ms: Moment, counter_values: &AtomicBuffer) -> bool {
+        match self.state {
+            IpcPublicationState::Draining if self.is_drained(counter_values) => {
+                self.state = IpcPublicationState::Linger {
+                    deadline_ms: now_ms + linger_timeout_ms,
+                };
+                return true;
+            }
+            IpcPublicationState::Linger { deadline_ms } if now_ms >= deadline_ms => {
+                self.state = IpcPublicationState::Done;
+            }
+            _ => {}
+        }
+
+        false
+    }
+
+    /**
+     * Release the log buffer file. Counters are freed by the caller.
+     */
+    pub fn close(&self) {
+        self.raw_log.delete();
+    }
+
+    fn is_drained(&self, counter_values: &AtomicBuffer) -> bool {
+        let producer_position = self.producer_position();
+
+        self.subscriber_positions.iter().all(|position| {
+            counter_values.get_volatile::<i64>(CountersReader::counter_offset(position.counter_id)) >= producer_position
+        })
+    }
+
+    fn clean_buffer_to(&mut self, position: i64) {
+        self.clean_position += self.raw_log.clean(self.clean_position, position, self.position_bits_to_shift) as i64;
+    }
+}
@@ -0,0 +1,172 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::concurrent::{atomic_buffer::AtomicBuffer, logbuffer::term_scan};
+use crate::utils::types::{Index, Moment};
+
+/**
+ * Delay before the first NAK for a gap, giving frames which are merely reordered time to arrive.
+ */
+const NAK_DELAY_MS: Moment = 1;
+
+/**
+ * Delay before a gap which has been NAKed is NAKed again.
+ */
+const NAK_RETRY_TIMEOUT_MS: Moment = 20;
+
+/**
+ * Range of a term which has not been received.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Gap {
+    pub term_id: i32,
+    pub term_offset: Index,
+    pub length: Index,
+}
+
+/**
+ * Detects gaps in a term being rebuilt and decides when to NAK them.
+ */
+#[derive(Default)]
+pub(crate) struct LossDetector {
+    active_gap: Option<Gap>,
+    deadline_ms: Moment,
+}
+
+impl LossDetector {
+    /**
+     * Scan a term from the rebuild offset up to the high-water mark offset for the first gap.
+     *
+     * @return the offset up to which the term is complete and the gap to NAK, if one is due.
+     */
+    pub fn scan(
+        &mut self,
+        term_buffer: &AtomicBuffer,
+        term_id: i32,
+        rebuild_offset: Index,
+        hwm_offset: Index,
+        now_ms: Moment,
+    ) -> (Index, Option<Gap>) {
+        let mut gap = None;
+        let rebuild_offset = term_scan::scan_for_gap(
+            term_buffer,
+            term_id,
+            rebuild_offset,
+            hwm_offset,
+            |term_id, _buffer, term_offset, length| {
+                gap = Some(Gap {
+                    term_id,
+                    term_offset,
+                    length,
+                })
+            },
+        );
+
+        (rebuild_offset, self.on_gap(gap, now_ms))
+    }
+
+    fn on_gap(&mut self, gap: Option<Gap>, now_ms: Moment) -> Option<Gap> {
+        let gap = match gap {
+            Some(gap) => gap,
+            None => {
+                self.active_gap = None;
+                return None;
+            }
+        };
+
+        // IC: the gap is the same one for as long as it starts at the same place, it only shrinks or grows as
+        // frames arrive around it
+        match self.active_gap {
+            Some(active) if active.term_id == gap.term_id && active.term_offset == gap.term_offset => {}
+            _ => self.deadline_ms = now_ms + NAK_DELAY_MS,
+        }
+        self.active_gap = Some(gap);
+
+        if now_ms >= self.deadline_ms {
+            self.deadline_ms = now_ms + NAK_RETRY_TIMEOUT_MS;
+            return Some(gap);
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::{
+        atomic_buffer::AlignedBuffer,
+        logbuffer::{data_frame_header, log_buffer_descriptor},
+    };
+
+    const TERM_ID: i32 = 7;
+    const FRAME_LENGTH: Index = 64;
+
+    fn put_frame(term_buffer: &AtomicBuffer, term_offset: Index) {
+        term_buffer.put::<u16>(
+            *data_frame_header::TYPE_FIELD_OFFSET + term_offset,
+            data_frame_header::HDR_TYPE_DATA,
+        );
+        term_buffer.put_ordered::<i32>(term_offset, FRAME_LENGTH);
+    }
+
+    #[test]
+    fn should_nak_gap_after_delay_and_retry() {
+        let aligned = AlignedBuffer::with_capacity(log_buffer_descriptor::TERM_MIN_LENGTH);
+        let term_buffer = AtomicBuffer::from_aligned(&aligned);
+        term_buffer.set_memory(0, term_buffer.capacity(), 0);
+
+        put_frame(&term_buffer, 0);
+        put_frame(&term_buffer, 2 * FRAME_LENGTH);
+        let hwm_offset = 3 * FRAME_LENGTH;
+
+        let mut loss_detector = LossDetector::default();
+        let expected_gap = Gap {
+            term_id: TERM_ID,
+            term_offset: FRAME_LENGTH,
+            length: FRAME_LENGTH,
+        };
+
+        assert_eq!(
+            loss_detector.scan(&term_buffer, TERM_ID, 0, hwm_offset, 100),
+            (FRAME_LENGTH, None)
+        );
+        assert_eq!(
+            loss_detector.scan(&term_buffer, TERM_ID, FRAME_LENGTH, hwm_offset, 100 + NAK_DELAY_MS),
+            (FRAME_LENGTH, Some(expected_gap))
+        );
+        assert_eq!(
+            loss_detector.scan(&term_buffer, TERM_ID, FRAME_LENGTH, hwm_offset, 101 + NAK_DELAY_MS),
+            (FRAME_LENGTH, None)
+        );
+        assert_eq!(
+            loss_detector.scan(
+                &term_buffer,
+                TERM_ID,
+                FRAME_LENGTH,
+                hwm_offset,
+                100 + NAK_DELAY_MS + NAK_RETRY_TIMEOUT_MS
+            ),
+            (FRAME_LENGTH, Some(expected_gap))
+        );
+
+        put_frame(&term_buffer, FRAME_LENGTH);
+        assert_eq!(
+            loss_detector.scan(&term_buffer, TERM_ID, FRAME_LENGTH, hwm_offset, 200),
+            (hwm_offset, None)
+        );
+    }
+}
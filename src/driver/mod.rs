@@ -0,0 +1,240 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+//! In-process media driver, enabled by the `embedded-driver` feature.
+//!
+//! The driver creates the CnC file in its aeron directory and runs a conductor thread which serves clients
+//! connecting to that directory, just like an external media driver would, plus sender and receiver threads
+//! for network publications and images. `aeron:ipc` and unicast `aeron:udp` channels are supported.
+
+use std::fs;
+use std::path::Path;
+use std::sync::{mpsc, Arc, Mutex};
+
+use crate::{
+    cnc_file_descriptor,
+    concurrent::{
+        agent_runner::{Agent, AgentRunner, AgentStopper},
+        counters,
+        ring_buffer::ManyToOneRingBuffer,
+        strategies::SleepingIdleStrategy,
+    },
+    driver::{context::DriverContext, driver_conductor::DriverConductor, receiver::Receiver, sender::Sender},
+    log,
+    utils::{
+        errors::{AeronError, IllegalStateError},
+        memory_mapped_file::MemoryMappedFile,
+        misc::unix_time_ms,
+        types::{Index, Moment},
+    },
+};
+
+mod client_proxy;
+pub mod context;
+mod driver_conductor;
+mod ipc_publication;
+mod loss_detector;
+mod network_publication;
+mod publication_image;
+mod raw_log;
+mod receive_channel_endpoint;
+mod receiver;
+mod sender;
+pub mod stream_counter;
+mod udp_channel;
+
+const CONDUCTOR_AGENT_NAME: &str = "aeron-driver-conductor";
+const SENDER_AGENT_NAME: &str = "aeron-driver-sender";
+const RECEIVER_AGENT_NAME: &str = "aeron-driver-receiver";
+
+fn default_error_handler(error: AeronError) {
+    log!(error, "Driver agent error: {:?}", error);
+}
+
+/**
+ * Media driver running inside the process which launched it. Dropping the driver stops it.
+ */
+pub struct MediaDriver {
+    context: DriverContext,
+    conductor_stopper: Option<AgentStopper>,
+    sender_stopper: Option<AgentStopper>,
+    receiver_stopper: Option<AgentStopper>,
+}
+
+impl MediaDriver {
+    /**
+     * Create the aeron directory and CnC file then start the conductor, sender and receiver threads. Clients can
+     * connect as soon as this returns.
+     *
+     * @param context for the driver.
+     * @return the running driver or the error which stopped it from starting, e.g. another driver being active
+     * in the same directory.
+     */
+    pub fn launch(context: DriverContext) -> Result<Self, AeronError> {
+        Self::prepare_dir(&context)?;
+
+        let cnc_file = MemoryMappedFile::create_new(context.cnc_file_name(), 0, cnc_file_length(&context))?;
+        let cnc_meta_data = cnc_file.atomic_buffer(0, *cnc_file_descriptor::META_DATA_LENGTH);
+
+        cnc_file_descriptor::fill_meta_data(
+            &cnc_meta_data,
+            context.to_driver_buffer_length(),
+            context.to_clients_buffer_length(),
+            counters_metadata_buffer_length(&context),
+            context.counter_values_buffer_length(),
+            context.error_buffer_length(),
+            (context.client_liveness_timeout_ms() * 1_000_000) as i64,
+            unix_time_ms() as i64,
+            std::process::id() as i64,
+        );
+
+        let (sender_commands, sender_rx) = mpsc::channel();
+        let (receiver_commands, receiver_rx) = mpsc::channel();
+        let (conductor_commands, conductor_rx) = mpsc::channel();
+
+        let conductor = DriverConductor::new(context.clone(), cnc_file, sender_commands, receiver_commands, conductor_rx)?;
+        cnc_file_descriptor::signal_cnc_ready(&cnc_meta_data);
+
+        let mut driver = Self {
+            context: context.clone(),
+            conductor_stopper: None,
+            sender_stopper: None,
+            receiver_stopper: None,
+        };
+
+        // IC: the sender and receiver go first so nothing the conductor hands them waits on a thread which is
+        // not running yet. Should a start fail, dropping the driver stops whatever did start.
+        driver.sender_stopper = Some(Self::start_agent(&context, Sender::new(sender_rx), SENDER_AGENT_NAME)?);
+        driver.receiver_stopper = Some(Self::start_agent(
+            &context,
+            Receiver::new(receiver_rx, conductor_commands),
+            RECEIVER_AGENT_NAME,
+        )?);
+        driver.conductor_stopper = Some(Self::start_agent(&context, conductor, CONDUCTOR_AGENT_NAME)?);
+
+        Ok(driver)
+    }
+
+    pub fn context(&self) -> &DriverContext {
+        &self.context
+    }
+
+    pub fn aeron_dir(&self) -> String {
+        self.context.aeron_dir()
+    }
+
+    /**
+     * Stop the conductor, sender and receiver, deleting the aeron directory if the context asks for it. Called
+     * on drop.
+     */
+    pub fn close(&mut self) {
+        // IC: the sender and receiver update counters in the CnC file owned by the conductor, so they stop first
+        let mut stoppers = [
+            self.sender_stopper.take(),
+            self.receiver_stopper.take(),
+            self.conductor_stopper.take(),
+        ];
+
+        let mut was_running = false;
+        for stopper in stoppers.iter_mut().flatten() {
+            stopper.stop();
+            was_running = true;
+        }
+
+        if was_running && self.context.dir_delete_on_shutdown() {
+            let _ignored = fs::remove_dir_all(self.context.aeron_dir());
+        }
+    }
+
+    fn start_agent<A: Agent + Send + Sync + 'static>(
+        context: &DriverContext,
+        agent: A,
+        name: &str,
+    ) -> Result<AgentStopper, AeronError> {
+        let runner = AgentRunner::new(
+            Arc::new(Mutex::new(agent)),
+            Arc::new(SleepingIdleStrategy::new(context.idle_sleep_ms())),
+            Box::new(default_error_handler),
+            name,
+        );
+
+        AgentRunner::start(runner)
+    }
+
+    fn prepare_dir(context: &DriverContext) -> Result<(), AeronError> {
+        let aeron_dir = context.aeron_dir();
+
+        if Path::new(&aeron_dir).exists() {
+            if !context.dir_delete_on_start() && Self::is_driver_active(context) {
+                return Err(IllegalStateError::DriverAlreadyActive(aeron_dir).into());
+            }
+
+            fs::remove_dir_all(&aeron_dir).map_err(AeronError::MemMappedFileError)?;
+        }
+
+        fs::create_dir_all(context.publications_dir()).map_err(AeronError::MemMappedFileError)?;
+        fs::create_dir_all(context.images_dir()).map_err(AeronError::MemMappedFileError)
+    }
+
+    /**
+     * Is a driver heartbeating in the CnC file of the directory.
+     */
+    fn is_driver_active(context: &DriverContext) -> bool {
+        let cnc_file_name = context.cnc_file_name();
+
+        match MemoryMappedFile::get_file_size(&cnc_file_name) {
+            Ok(size) if size as Index > *cnc_file_descriptor::META_DATA_LENGTH => {}
+            _ => return false,
+        }
+
+        let cnc_file = match MemoryMappedFile::map_existing(cnc_file_name, false) {
+            Ok(cnc_file) => cnc_file,
+            Err(_) => return false,
+        };
+
+        if cnc_file_descriptor::cnc_version_volatile(&cnc_file) == 0 {
+            return false;
+        }
+
+        match ManyToOneRingBuffer::new(cnc_file_descriptor::create_to_driver_buffer(&cnc_file)) {
+            Ok(to_driver) => {
+                let heartbeat_ms = to_driver.consumer_heartbeat_time() as Moment;
+                heartbeat_ms + context.client_liveness_timeout_ms() > unix_time_ms()
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+impl Drop for MediaDriver {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+fn cnc_file_length(context: &DriverContext) -> Index {
+    cnc_file_descriptor::compute_cnc_file_length(
+        context.to_driver_buffer_length()
+            + context.to_clients_buffer_length()
+            + counters_metadata_buffer_length(context)
+            + context.counter_values_buffer_length()
+            + context.error_buffer_length(),
+    )
+}
+
+fn counters_metadata_buffer_length(context: &DriverContext) -> Index {
+    context.counter_values_buffer_length() * (counters::METADATA_LENGTH / counters::COUNTER_LENGTH)
+}
@@ -0,0 +1,816 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::io::ErrorKind;
+use std::net::{SocketAddr, UdpSocket};
+
+use crate::{
+    concurrent::{
+        atomic_buffer::AtomicBuffer,
+        counters::CountersReader,
+        logbuffer::{data_frame_header, log_buffer_descriptor, term_scan},
+    },
+    driver::{raw_log::RawLog, udp_channel::DatagramBuffer},
+    log,
+    protocol::{
+        data_header_flyweight::{self, DataHeaderFlyweight},
+        header_flyweight::HEADER_DEFN_SIZE,
+        nak_flyweight::{NakFlyweight, NAK_DEFN_SIZE},
+        setup_flyweight::SetupFlyweight,
+        status_message_flyweight::{self, StatusMessageFlyweight, STATUS_MESSAGE_DEFN_SIZE},
+    },
+    utils::{
+        bit_utils,
+        types::{Index, Moment},
+    },
+};
+
+/**
+ * Interval at which Setup frames are sent until a receiver answers with a status message.
+ */
+const SETUP_TIMEOUT_MS: Moment = 100;
+
+/**
+ * Time without data being sent after which a heartbeat is sent to keep the image alive.
+ */
+const HEARTBEAT_TIMEOUT_MS: Moment = 100;
+
+/**
+ * Most datagrams sent for a publication in one duty cycle so control frames are not left waiting.
+ */
+const MAX_DATAGRAMS_PER_SEND: i32 = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum NetworkPublicationState {
+    /// Publishers hold a reference to the publication.
+    Active,
+    /// All publishers have gone, waiting for the sender to send what is left in the log.
+    Draining,
+    /// Everything has been sent, end of stream heartbeats and retransmits go on until the deadline.
+    Linger { deadline_ms: Moment },
+    /// Ready to have its counters freed and log deleted.
+    Done,
+}
+
+/**
+ * Publication over aeron:udp to a unicast endpoint.
+ * <p>
+ * The sender sends Setup frames until the receiver answers with a status message, then sends the log as it is
+ * written up to the window the receiver advertised, resending what receivers ask for in NAKs. The conductor
+ * moves the publisher limit along behind the sender position and handles the publication's lifecycle.
+ */
+pub(crate) struct NetworkPublication {
+    registration_id: i64,
+    session_id: i32,
+    stream_id: i32,
+    is_exclusive: bool,
+    raw_log: RawLog,
+    socket: UdpSocket,
+    endpoint: SocketAddr,
+    counter_values: AtomicBuffer,
+    publisher_limit_id: i32,
+    sender_position_id: i32,
+    position_bits_to_shift: i32,
+    initial_term_id: i32,
+    term_window_length: i64,
+    mtu_length: Index,
+    connection_timeout_ms: Moment,
+    sender_position: i64,
+    sender_limit: i64,
+    clean_position: i64,
+    time_of_last_send_ms: Moment,
+    time_of_last_setup_ms: Moment,
+    time_of_last_status_message_ms: Moment,
+    is_connected: bool,
+    should_send_setup: bool,
+    ref_count: i32,
+    state: NetworkPublicationState,
+    frame_buffer: Box<DatagramBuffer>,
+}
+
+impl NetworkPublication {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        registration_id: i64,
+        session_id: i32,
+        stream_id: i32,
+        is_exclusive: bool,
+        raw_log: RawLog,
+        socket: UdpSocket,
+        endpoint: SocketAddr,
+        counter_values: AtomicBuffer,
+        publisher_limit_id: i32,
+        sender_position_id: i32,
+        initial_term_id: i32,
+        mtu_length: Index,
+        connection_timeout_ms: Moment,
+    ) -> Self {
+        let term_length = raw_log.term_length();
+
+        Self {
+            registration_id,
+            session_id,
+            stream_id,
+            is_exclusive,
+            raw_log,
+            socket,
+            endpoint,
+            counter_values,
+            publisher_limit_id,
+            sender_position_id,
+            position_bits_to_shift: bit_utils::number_of_trailing_zeroes(term_length),
+            initial_term_id,
+            term_window_length: (term_length / 2) as i64,
+            mtu_length,
+            connection_timeout_ms,
+            sender_position: 0,
+            sender_limit: 0,
+            clean_position: 0,
+            time_of_last_send_ms: 0,
+            time_of_last_setup_ms: 0,
+            time_of_last_status_message_ms: 0,
+            is_connected: false,
+            should_send_setup: true,
+            ref_count: 0,
+            state: NetworkPublicationState::Active,
+            frame_buffer: DatagramBuffer::new(),
+        }
+    }
+
+    pub fn registration_id(&self) -> i64 {
+        self.registration_id
+    }
+
+    pub fn session_id(&self) -> i32 {
+        self.session_id
+    }
+
+    pub fn stream_id(&self) -> i32 {
+        self.stream_id
+    }
+
+    pub fn is_exclusive(&self) -> bool {
+        self.is_exclusive
+    }
+
+    pub fn endpoint(&self) -> SocketAddr {
+        self.endpoint
+    }
+
+    pub fn log_file_name(&self) -> &str {
+        self.raw_log.file_name()
+    }
+
+    pub fn publisher_limit_id(&self) -> i32 {
+        self.publisher_limit_id
+    }
+
+    pub fn sender_position_id(&self) -> i32 {
+        self.sender_position_id
+    }
+
+    pub fn state(&self) -> NetworkPublicationState {
+        self.state
+    }
+
+    pub fn inc_ref(&mut self) {
+        self.ref_count += 1;
+    }
+
+    /**
+     * Drop a publisher's reference. The last one to go starts the publication draining, with the end of stream
+     * at the current producer position.
+     */
+    pub fn dec_ref(&mut self) {
+        self.ref_count -= 1;
+
+        if self.ref_count == 0 && self.state == NetworkPublicationState::Active {
+            log_buffer_descriptor::set_end_of_stream_position(&self.raw_log.meta_data(), self.producer_position());
+            self.state = NetworkPublicationState::Draining;
+        }
+    }
+
+    /**
+     * Position the publisher has written up to.
+     */
+    pub fn producer_position(&self) -> i64 {
+        let raw_tail = log_buffer_descriptor::raw_tail_volatile(&self.raw_log.meta_data());
+        let term_offset = log_buffer_descriptor::term_offset(raw_tail, self.raw_log.term_length() as i64);
+
+        log_buffer_descriptor::compute_position(
+            log_buffer_descriptor::term_id(raw_tail),
+            term_offset,
+            self.position_bits_to_shift,
+            self.initial_term_id,
+        )
+    }
+
+    /**
+     * Move the publisher limit to a window beyond the sender position while a receiver is connected, holding it
+     * at the sender position otherwise, and clean terms which have been sent and can no longer be asked for.
+     *
+     * @return the amount of work done.
+     */
+    pub fn update_publisher_limit(&mut self) -> i32 {
+        if self.state != NetworkPublicationState::Active {
+            return 0;
+        }
+
+        let limit_offset = CountersReader::counter_offset(self.publisher_limit_id);
+        let publisher_limit = self.counter_values.get_volatile::<i64>(limit_offset);
+
+        let new_limit = if self.is_connected {
+            self.sender_position + self.term_window_length
+        } else {
+            self.sender_position
+        };
+
+        self.clean_buffer_to(self.sender_position - self.raw_log.term_length() as i64);
+
+        if new_limit != publisher_limit {
+            self.counter_values.put_ordered::<i64>(limit_offset, new_limit);
+            return 1;
+        }
+
+        0
+    }
+
+    /**
+     * Progress draining and lingering.
+     */
+    pub fn on_time_event(&mut self, now_ms: Moment, linger_timeout_ms: Moment) {
+        match self.state {
+            NetworkPublicationState::Draining if self.sender_position >= self.producer_position() => {
+                self.state = NetworkPublicationState::Linger {
+                    deadline_ms: now_ms + linger_timeout_ms,
+                };
+            }
+            NetworkPublicationState::Linger { deadline_ms } if now_ms >= deadline_ms => {
+                self.state = NetworkPublicationState::Done;
+            }
+            _ => {}
+        }
+    }
+
+    /**
+     * Sender duty cycle for the publication: process status messages and NAKs then send Setup frames, data and
+     * heartbeats as needed.
+     *
+     * @return the amount of work done.
+     */
+    pub fn send(&mut self, now_ms: Moment) -> i32 {
+        if self.state == NetworkPublicationState::Done {
+            return 0;
+        }
+
+        let mut work_count = self.poll_control_frames(now_ms);
+
+        if self.is_connected && now_ms > self.time_of_last_status_message_ms + self.connection_timeout_ms {
+            log!(
+                debug,
+                "Publication {} lost its receiver at {}",
+                self.registration_id,
+                self.endpoint
+            );
+
+            self.is_connected = false;
+            self.should_send_setup = true;
+            log_buffer_descriptor::set_is_connected(&self.raw_log.meta_data(), false);
+        }
+
+        if self.should_send_setup {
+            work_count += self.send_setup_frame(now_ms);
+        }
+
+        if self.is_connected {
+            let bytes_sent = self.send_data(now_ms);
+            work_count += bytes_sent;
+
+            if bytes_sent == 0 {
+                work_count += self.send_heartbeat(now_ms);
+            }
+        }
+
+        work_count
+    }
+
+    /**
+     * Release the log buffer file. Counters are freed by the caller.
+     */
+    pub fn close(&self) {
+        self.raw_log.delete();
+    }
+
+    fn poll_control_frames(&mut self, now_ms: Moment) -> i32 {
+        let mut work_count = 0;
+
+        loop {
+            let length = match self.socket.recv_from(self.frame_buffer.as_mut_slice()) {
+                Ok((length, _source)) => length as Index,
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    log!(debug, "Publication {} failed to receive: {}", self.registration_id, err);
+                    break;
+                }
+            };
+
+            work_count += 1;
+            if length < HEADER_DEFN_SIZE {
+                continue;
+            }
+
+            let buffer = self.frame_buffer.atomic_buffer();
+            match buffer.get::<u16>(*data_frame_header::TYPE_FIELD_OFFSET) {
+                data_frame_header::HDR_TYPE_SM if length >= STATUS_MESSAGE_DEFN_SIZE => {
+                    self.on_status_message(&StatusMessageFlyweight::new(buffer, 0), now_ms)
+                }
+                data_frame_header::HDR_TYPE_NAK if length >= NAK_DEFN_SIZE => self.on_nak(&NakFlyweight::new(buffer, 0)),
+                _ => {}
+            }
+        }
+
+        work_count
+    }
+
+    fn on_status_message(&mut self, status_message: &StatusMessageFlyweight, now_ms: Moment) {
+        if status_message.session_id() != self.session_id || status_message.stream_id() != self.stream_id {
+            return;
+        }
+
+        if status_message.flags() & status_message_flyweight::SEND_SETUP_FLAG != 0 {
+            self.should_send_setup = true;
+            return;
+        }
+
+        let consumption_position = log_buffer_descriptor::compute_position(
+            status_message.consumption_term_id(),
+            status_message.consumption_term_offset(),
+            self.position_bits_to_shift,
+            self.initial_term_id,
+        );
+        self.sender_limit = self
+            .sender_limit
+            .max(consumption_position + status_message.receiver_window() as i64);
+        self.time_of_last_status_message_ms = now_ms;
+
+        if !self.is_connected {
+            log!(debug, "Publication {} connected to {}", self.registration_id, self.endpoint);
+
+            self.is_connected = true;
+            self.should_send_setup = false;
+            log_buffer_descriptor::set_is_connected(&self.raw_log.meta_data(), true);
+        }
+    }
+
+    /**
+     * Resend the frames in the range a receiver is missing, as long as the range has been sent and has not been
+     * cleaned since. NAKs for a range which can not be in a term are ignored.
+     */
+    fn on_nak(&mut self, nak: &NakFlyweight) {
+        if nak.session_id() != self.session_id || nak.stream_id() != self.stream_id {
+            return;
+        }
+
+        let term_length = self.raw_log.term_length();
+        if nak.term_offset() < 0 || nak.term_offset() >= term_length || nak.length() <= 0 {
+            return;
+        }
+
+        let position = log_buffer_descriptor::compute_position(
+            nak.term_id(),
+            nak.term_offset(),
+            self.position_bits_to_shift,
+            self.initial_term_id,
+        );
+
+        if position < self.sender_position - term_length as i64 || position >= self.sender_position {
+            return;
+        }
+
+        let term_buffer = self.term_buffer(position);
+        let limit_offset = nak
+            .term_offset()
+            .saturating_add(nak.length())
+            .min(term_length)
+            .min(nak.term_offset() + (self.sender_position - position) as Index);
+        let mut offset = nak.term_offset();
+
+        while offset < limit_offset {
+            let scan_outcome = term_scan::scan_for_availability(&term_buffer, offset, self.mtu_length.min(limit_offset - offset));
+            let available = term_scan::available(scan_outcome);
+            if available <= 0 {
+                break;
+            }
+
+            if !self.send_to_endpoint(term_buffer.as_sub_slice(offset, available)) {
+                break;
+            }
+
+            offset += available + term_scan::padding(scan_outcome);
+        }
+    }
+
+    fn send_setup_frame(&mut self, now_ms: Moment) -> i32 {
+        if now_ms < self.time_of_last_setup_ms + SETUP_TIMEOUT_MS {
+            return 0;
+        }
+
+        let mut setup = SetupFlyweight::new(self.frame_buffer.atomic_buffer(), 0);
+        setup.set_frame_length(SetupFlyweight::header_length());
+        setup.set_version(data_frame_header::CURRENT_VERSION);
+        setup.set_flags(0);
+        setup.set_header_type(data_frame_header::HDR_TYPE_SETUP);
+        setup.set_term_offset(self.term_offset(self.sender_position));
+        setup.set_session_id(self.session_id);
+        setup.set_stream_id(self.stream_id);
+        setup.set_initial_term_id(self.initial_term_id);
+        setup.set_action_term_id(self.term_id(self.sender_position));
+        setup.set_term_length(self.raw_log.term_length());
+        setup.set_mtu(self.mtu_length);
+
+        self.send_frame(SetupFlyweight::header_length());
+        self.time_of_last_setup_ms = now_ms;
+
+        1
+    }
+
+    fn send_data(&mut self, now_ms: Moment) -> i32 {
+        let limit = self.sender_limit.min(self.producer_position());
+        let mut bytes_sent = 0;
+        let mut datagrams_sent = 0;
+
+        while self.sender_position < limit && datagrams_sent < MAX_DATAGRAMS_PER_SEND {
+            let term_buffer = self.term_buffer(self.sender_position);
+            let term_offset = self.term_offset(self.sender_position);
+            let max_length = (limit - self.sender_position).min(self.mtu_length as i64) as Index;
+
+            let scan_outcome = term_scan::scan_for_availability(&term_buffer, term_offset, max_length);
+            let available = term_scan::available(scan_outcome);
+            if available <= 0 {
+                break;
+            }
+
+            if !self.send_to_endpoint(term_buffer.as_sub_slice(term_offset, available)) {
+                break;
+            }
+
+            bytes_sent += available;
+            datagrams_sent += 1;
+            self.sender_position += (available + term_scan::padding(scan_outcome)) as i64;
+        }
+
+        if bytes_sent > 0 {
+            self.counter_values
+                .put_ordered::<i64>(CountersReader::counter_offset(self.sender_position_id), self.sender_position);
+            self.time_of_last_send_ms = now_ms;
+        }
+
+        bytes_sent
+    }
+
+    /**
+     * Send an empty data frame at the sender position, flagged as the end of the stream once a drained
+     * publication has been sent in full.
+     */
+    fn send_heartbeat(&mut self, now_ms: Moment) -> i32 {
+        if now_ms < self.time_of_last_send_ms + HEARTBEAT_TIMEOUT_MS {
+            return 0;
+        }
+
+        let is_end_of_stream = self.state != NetworkPublicationState::Active && self.sender_position >= self.producer_position();
+
+        let mut heartbeat = DataHeaderFlyweight::new(self.frame_buffer.atomic_buffer(), 0);
+        heartbeat.set_frame_length(0);
+        heartbeat.set_version(data_frame_header::CURRENT_VERSION);
+        heartbeat.set_flags(if is_end_of_stream {
+            data_header_flyweight::BEGIN_END_AND_EOS_FLAGS
+        } else {
+            data_header_flyweight::BEGIN_AND_END_FLAGS
+        });
+        heartbeat.set_header_type(data_frame_header::HDR_TYPE_DATA);
+        heartbeat.set_term_offset(self.term_offset(self.sender_position));
+        heartbeat.set_session_id(self.session_id);
+        heartbeat.set_stream_id(self.stream_id);
+        heartbeat.set_term_id(self.term_id(self.sender_position));
+        heartbeat.set_reserved_value(0);
+
+        self.send_frame(DataHeaderFlyweight::header_length());
+        self.time_of_last_send_ms = now_ms;
+
+        1
+    }
+
+    fn send_frame(&mut self, length: Index) {
+        if let Err(err) = self.socket.send_to(self.frame_buffer.as_slice(length), self.endpoint) {
+            log!(
+                debug,
+                "Publication {} failed to send to {}: {}",
+                self.registration_id,
+                self.endpoint,
+                err
+            );
+        }
+    }
+
+    /**
+     * @return false if the socket could not take the datagram and sending should stop for this duty cycle.
+     */
+    fn send_to_endpoint(&self, datagram: &[u8]) -> bool {
+        match self.socket.send_to(datagram, self.endpoint) {
+            Ok(_) => true,
+            Err(err) if err.kind() == ErrorKind::WouldBlock => false,
+            Err(err) => {
+                log!(
+                    debug,
+                    "Publication {} failed to send to {}: {}",
+                    self.registration_id,
+                    self.endpoint,
+                    err
+                );
+                false
+            }
+        }
+    }
+
+    fn term_buffer(&self, position: i64) -> AtomicBuffer {
+        self.raw_log.term_buffer(log_buffer_descriptor::index_by_position(
+            position,
+            self.position_bits_to_shift,
+        ))
+    }
+
+    fn term_id(&self, position: i64) -> i32 {
+        self.initial_term_id
+            .wrapping_add((position >> self.position_bits_to_shift) as i32)
+    }
+
+    fn term_offset(&self, position: i64) -> Index {
+        (position & (self.raw_log.term_length() - 1) as i64) as Index
+    }
+
+    fn clean_buffer_to(&mut self, position: i64) {
+        self.clean_position += self.raw_log.clean(self.clean_position, position, self.position_bits_to_shift) as i64;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{concurrent::atomic_buffer::AlignedBuffer, driver::publication_image::PublicationImage};
+
+    const TERM_LENGTH: Index = log_buffer_descriptor::TERM_MIN_LENGTH;
+    const INITIAL_TERM_ID: i32 = 5;
+    const SESSION_ID: i32 = 21;
+    const STREAM_ID: i32 = 1002;
+    const PUBLISHER_LIMIT_ID: i32 = 0;
+    const SENDER_POSITION_ID: i32 = 1;
+    const RECEIVER_HWM_ID: i32 = 2;
+    const RECEIVER_POSITION_ID: i32 = 3;
+    const FRAME_LENGTH: Index = 64;
+    const FRAME_COUNT: Index = 3;
+    const NOW_MS: Moment = 1000;
+
+    struct TestLink {
+        publication: NetworkPublication,
+        publication_address: SocketAddr,
+        receiver_socket: UdpSocket,
+        counter_values: AtomicBuffer,
+        _counters: AlignedBuffer,
+    }
+
+    fn log_file_name(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("{}-{}.logbuffer", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /**
+     * Publication with FRAME_COUNT frames written to its log, connected to a receiver socket owned by the test.
+     * The MTU is one frame so each frame goes in its own datagram.
+     */
+    fn connected_publication(name: &str) -> TestLink {
+        let counters = AlignedBuffer::with_capacity(CountersReader::counter_offset(4));
+        let counter_values = AtomicBuffer::from_aligned(&counters);
+
+        let receiver_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        receiver_socket.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let publication_socket = UdpSocket::bind("127.0.0.1:0").unwrap();
+        publication_socket.set_nonblocking(true).unwrap();
+        let publication_address = publication_socket.local_addr().unwrap();
+
+        let raw_log = RawLog::create(
+            log_file_name(name),
+            TERM_LENGTH,
+            FRAME_LENGTH,
+            INITIAL_TERM_ID,
+            SESSION_ID,
+            STREAM_ID,
+        )
+        .unwrap();
+
+        let term_buffer = raw_log.term_buffer(0);
+        for index in 0..FRAME_COUNT {
+            let mut header = DataHeaderFlyweight::new(term_buffer, index * FRAME_LENGTH);
+            header.set_version(data_frame_header::CURRENT_VERSION);
+            header.set_flags(data_header_flyweight::BEGIN_AND_END_FLAGS);
+            header.set_header_type(data_frame_header::HDR_TYPE_DATA);
+            header.set_term_offset(index * FRAME_LENGTH);
+            header.set_session_id(SESSION_ID);
+            header.set_stream_id(STREAM_ID);
+            header.set_term_id(INITIAL_TERM_ID);
+            header.set_frame_length(FRAME_LENGTH);
+        }
+        raw_log.meta_data().put_ordered::<i64>(
+            *log_buffer_descriptor::TERM_TAIL_COUNTER_OFFSET,
+            ((INITIAL_TERM_ID as i64) << 32) | (FRAME_COUNT * FRAME_LENGTH) as i64,
+        );
+
+        let mut publication = NetworkPublication::new(
+            1,
+            SESSION_ID,
+            STREAM_ID,
+            false,
+            raw_log,
+            publication_socket,
+            receiver_socket.local_addr().unwrap(),
+            counter_values,
+            PUBLISHER_LIMIT_ID,
+            SENDER_POSITION_ID,
+            INITIAL_TERM_ID,
+            FRAME_LENGTH,
+            5000,
+        );
+
+        let mut frame_buffer = DatagramBuffer::new();
+        let mut status_message = StatusMessageFlyweight::new(frame_buffer.atomic_buffer(), 0);
+        status_message.set_frame_length(StatusMessageFlyweight::header_length());
+        status_message.set_version(data_frame_header::CURRENT_VERSION);
+        status_message.set_flags(0);
+        status_message.set_header_type(data_frame_header::HDR_TYPE_SM);
+        status_message.set_session_id(SESSION_ID);
+        status_message.set_stream_id(STREAM_ID);
+        status_message.set_consumption_term_id(INITIAL_TERM_ID);
+        status_message.set_consumption_term_offset(0);
+        status_message.set_receiver_window(TERM_LENGTH / 2);
+        receiver_socket
+            .send_to(
+                frame_buffer.as_slice(StatusMessageFlyweight::header_length()),
+                publication_address,
+            )
+            .unwrap();
+
+        assert!(publication.send(NOW_MS) > 0);
+
+        TestLink {
+            publication,
+            publication_address,
+            receiver_socket,
+            counter_values,
+            _counters: counters,
+        }
+    }
+
+    fn receive_frame(socket: &UdpSocket) -> (AlignedBuffer, Index) {
+        let mut datagram = [0_u8; 1024];
+        let (length, _source) = socket.recv_from(&mut datagram).expect("no datagram received");
+        let aligned = AlignedBuffer::with_capacity(1024);
+        AtomicBuffer::from_aligned(&aligned).put_bytes(0, &datagram[..length]);
+
+        (aligned, length as Index)
+    }
+
+    fn send_nak(link: &TestLink, term_offset: i32, length: i32) {
+        let mut frame_buffer = DatagramBuffer::new();
+        let mut nak = NakFlyweight::new(frame_buffer.atomic_buffer(), 0);
+        nak.set_frame_length(NakFlyweight::header_length());
+        nak.set_version(data_frame_header::CURRENT_VERSION);
+        nak.set_flags(0);
+        nak.set_header_type(data_frame_header::HDR_TYPE_NAK);
+        nak.set_session_id(SESSION_ID);
+        nak.set_stream_id(STREAM_ID);
+        nak.set_term_id(INITIAL_TERM_ID);
+        nak.set_term_offset(term_offset);
+        nak.set_length(length);
+
+        link.receiver_socket
+            .send_to(frame_buffer.as_slice(NakFlyweight::header_length()), link.publication_address)
+            .unwrap();
+    }
+
+    fn received_term_offsets(socket: &UdpSocket) -> Vec<i32> {
+        socket.set_nonblocking(true).unwrap();
+
+        let mut frame_buffer = DatagramBuffer::new();
+        let mut term_offsets = Vec::new();
+        while socket.recv_from(frame_buffer.as_mut_slice()).is_ok() {
+            term_offsets.push(DataHeaderFlyweight::new(frame_buffer.atomic_buffer(), 0).term_offset());
+        }
+
+        term_offsets
+    }
+
+    #[test]
+    fn should_fill_gap_of_dropped_datagram_from_nak_retransmit() {
+        let mut link = connected_publication("network-publication-nak");
+        let receiver_address = link.receiver_socket.local_addr().unwrap();
+        let receiver_log = RawLog::create(
+            log_file_name("network-publication-nak-image"),
+            TERM_LENGTH,
+            FRAME_LENGTH,
+            INITIAL_TERM_ID,
+            SESSION_ID,
+            STREAM_ID,
+        )
+        .unwrap();
+        let mut image = PublicationImage::new(
+            2,
+            SESSION_ID,
+            STREAM_ID,
+            "aeron:udp?endpoint=127.0.0.1:0".to_string(),
+            receiver_address,
+            link.publication_address,
+            receiver_log,
+            link.counter_values,
+            RECEIVER_HWM_ID,
+            RECEIVER_POSITION_ID,
+            INITIAL_TERM_ID,
+            0,
+            TERM_LENGTH / 2,
+            200,
+            NOW_MS,
+        );
+
+        for index in 0..FRAME_COUNT {
+            let (frame, length) = receive_frame(&link.receiver_socket);
+            // IC: lose the second datagram on the way
+            if index != 1 {
+                image.insert_packet(&AtomicBuffer::from_aligned(&frame), length, NOW_MS);
+            }
+        }
+
+        let receiver_position = CountersReader::counter_offset(RECEIVER_POSITION_ID);
+        let mut now_ms = NOW_MS;
+        while image.process_control(&link.receiver_socket, now_ms) == 0 {
+            now_ms += 1;
+            assert!(now_ms < NOW_MS + 100, "gap was never NAKed");
+        }
+        assert_eq!(link.counter_values.get::<i64>(receiver_position), FRAME_LENGTH as i64);
+
+        link.publication.send(now_ms);
+        let (frame, length) = receive_frame(&link.receiver_socket);
+        assert_eq!(
+            DataHeaderFlyweight::new(AtomicBuffer::from_aligned(&frame), 0).term_offset(),
+            FRAME_LENGTH
+        );
+        image.insert_packet(&AtomicBuffer::from_aligned(&frame), length, now_ms);
+        image.process_control(&link.receiver_socket, now_ms);
+
+        assert_eq!(
+            link.counter_values.get::<i64>(receiver_position),
+            (FRAME_COUNT * FRAME_LENGTH) as i64
+        );
+
+        image.close();
+        link.publication.close();
+    }
+
+    #[test]
+    fn should_ignore_naks_outside_the_term() {
+        let mut link = connected_publication("network-publication-bad-nak");
+        for _ in 0..FRAME_COUNT {
+            receive_frame(&link.receiver_socket);
+        }
+
+        send_nak(&link, -FRAME_LENGTH, FRAME_LENGTH);
+        send_nak(&link, FRAME_LENGTH, 0);
+        send_nak(&link, FRAME_LENGTH, -FRAME_LENGTH);
+        send_nak(&link, TERM_LENGTH, FRAME_LENGTH);
+        link.publication.send(NOW_MS);
+        assert_eq!(received_term_offsets(&link.receiver_socket), Vec::<i32>::new());
+
+        send_nak(&link, FRAME_LENGTH, i32::MAX);
+        link.publication.send(NOW_MS);
+        assert_eq!(
+            received_term_offsets(&link.receiver_socket),
+            vec![FRAME_LENGTH, 2 * FRAME_LENGTH]
+        );
+
+        link.publication.close();
+    }
+}
@@ -0,0 +1,574 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::net::{SocketAddr, UdpSocket};
+
+use crate::{
+    concurrent::{
+        atomic_buffer::AtomicBuffer,
+        counters::CountersReader,
+        logbuffer::{data_frame_header, frame_descriptor, log_buffer_descriptor},
+    },
+    driver::{
+        ipc_publication::SubscriberPosition,
+        loss_detector::{Gap, LossDetector},
+        raw_log::RawLog,
+        udp_channel::DatagramBuffer,
+    },
+    log,
+    protocol::{
+        data_header_flyweight::{self, DataHeaderFlyweight},
+        nak_flyweight::NakFlyweight,
+        status_message_flyweight::StatusMessageFlyweight,
+    },
+    utils::{
+        bit_utils,
+        types::{Index, Moment, I32_SIZE},
+    },
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PublicationImageState {
+    /// Receiving from the publication.
+    Active,
+    /// Subscribers have been told the image is gone, the log is kept until the deadline.
+    Linger { deadline_ms: Moment },
+    /// Ready to have its counters freed and log deleted.
+    Done,
+}
+
+/**
+ * Image of a network publication being rebuilt from the frames a receive channel endpoint gets.
+ * <p>
+ * The receiver inserts frames into the log, tracks how far the log has been rebuilt without gaps, NAKs gaps and
+ * sends status messages carrying the position of the slowest subscriber back to the publication. The conductor
+ * links subscribers to the image and handles its lifecycle.
+ */
+pub(crate) struct PublicationImage {
+    correlation_id: i64,
+    session_id: i32,
+    stream_id: i32,
+    channel: String,
+    endpoint: SocketAddr,
+    source_address: SocketAddr,
+    raw_log: RawLog,
+    counter_values: AtomicBuffer,
+    receiver_hwm_id: i32,
+    receiver_position_id: i32,
+    subscriber_positions: Vec<SubscriberPosition>,
+    position_bits_to_shift: i32,
+    initial_term_id: i32,
+    window_length: i32,
+    status_message_timeout_ms: Moment,
+    hwm_position: i64,
+    rebuild_position: i64,
+    clean_position: i64,
+    last_status_message_position: i64,
+    time_of_last_status_message_ms: Moment,
+    time_of_last_packet_ms: Moment,
+    end_of_stream_position: Option<i64>,
+    loss_detector: LossDetector,
+    state: PublicationImageState,
+    frame_buffer: Box<DatagramBuffer>,
+}
+
+impl PublicationImage {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        correlation_id: i64,
+        session_id: i32,
+        stream_id: i32,
+        channel: String,
+        endpoint: SocketAddr,
+        source_address: SocketAddr,
+        raw_log: RawLog,
+        counter_values: AtomicBuffer,
+        receiver_hwm_id: i32,
+        receiver_position_id: i32,
+        initial_term_id: i32,
+        join_position: i64,
+        window_length: i32,
+        status_message_timeout_ms: Moment,
+        now_ms: Moment,
+    ) -> Self {
+        let term_length = raw_log.term_length();
+
+        let image = Self {
+            correlation_id,
+            session_id,
+            stream_id,
+            channel,
+            endpoint,
+            source_address,
+            raw_log,
+            counter_values,
+            receiver_hwm_id,
+            receiver_position_id,
+            subscriber_positions: Vec::new(),
+            position_bits_to_shift: bit_utils::number_of_trailing_zeroes(term_length),
+            initial_term_id,
+            window_length: window_length.min(term_length / 2),
+            status_message_timeout_ms,
+            hwm_position: join_position,
+            rebuild_position: join_position,
+            clean_position: join_position,
+            last_status_message_position: join_position,
+            time_of_last_status_message_ms: 0,
+            time_of_last_packet_ms: now_ms,
+            end_of_stream_position: None,
+            loss_detector: LossDetector::default(),
+            state: PublicationImageState::Active,
+            frame_buffer: DatagramBuffer::new(),
+        };
+
+        image.put_counter(receiver_hwm_id, join_position);
+        image.put_counter(receiver_position_id, join_position);
+
+        image
+    }
+
+    pub fn correlation_id(&self) -> i64 {
+        self.correlation_id
+    }
+
+    pub fn session_id(&self) -> i32 {
+        self.session_id
+    }
+
+    pub fn stream_id(&self) -> i32 {
+        self.stream_id
+    }
+
+    pub fn channel(&self) -> &str {
+        &self.channel
+    }
+
+    /**
+     * Address of the receive channel endpoint the image is fed from.
+     */
+    pub fn endpoint(&self) -> SocketAddr {
+        self.endpoint
+    }
+
+    pub fn source_identity(&self) -> String {
+        self.source_address.to_string()
+    }
+
+    pub fn log_file_name(&self) -> &str {
+        self.raw_log.file_name()
+    }
+
+    pub fn receiver_hwm_id(&self) -> i32 {
+        self.receiver_hwm_id
+    }
+
+    pub fn receiver_position_id(&self) -> i32 {
+        self.receiver_position_id
+    }
+
+    pub fn subscriber_positions(&self) -> &[SubscriberPosition] {
+        &self.subscriber_positions
+    }
+
+    pub fn state(&self) -> PublicationImageState {
+        self.state
+    }
+
+    /**
+     * Add a subscriber which joins at the position the image has been rebuilt up to.
+     *
+     * @return the join position the subscriber position counter has to be set to.
+     */
+    pub fn add_subscriber(&mut self, subscription_registration_id: i64, counter_id: i32) -> i64 {
+        self.subscriber_positions.push(SubscriberPosition {
+            subscription_registration_id,
+            counter_id,
+        });
+
+        self.rebuild_position
+    }
+
+    /**
+     * Remove the subscriber of a subscription.
+     *
+     * @return the id of its position counter, which is for the caller to free, if it was subscribed.
+     */
+    pub fn remove_subscriber(&mut self, subscription_registration_id: i64) -> Option<i32> {
+        let index = self
+            .subscriber_positions
+            .iter()
+            .position(|position| position.subscription_registration_id == subscription_registration_id)?;
+
+        Some(self.subscriber_positions.remove(index).counter_id)
+    }
+
+    /**
+     * Insert a data frame, or a batch of them, into the log if it falls within the window advertised in the last
+     * status message. Heartbeats only move the high-water mark and may carry the end of the stream. Frames with
+     * an offset or length which can not be in the term are dropped.
+     *
+     * @return the number of bytes processed.
+     */
+    pub fn insert_packet(&mut self, packet: &AtomicBuffer, length: Index, now_ms: Moment) -> Index {
+        if self.state != PublicationImageState::Active {
+            return 0;
+        }
+
+        let header = DataHeaderFlyweight::new(*packet, 0);
+        let term_offset = header.term_offset();
+        let is_heartbeat = header.frame_length() == 0;
+
+        if !is_valid_frame(&header, length, is_heartbeat) {
+            log!(
+                debug,
+                "Image {} dropped malformed frame: term_offset {}, frame_length {}, length {}",
+                self.correlation_id,
+                term_offset,
+                header.frame_length(),
+                length
+            );
+            return length;
+        }
+        let packet_position = log_buffer_descriptor::compute_position(
+            header.term_id(),
+            term_offset,
+            self.position_bits_to_shift,
+            self.initial_term_id,
+        );
+        let proposed_position = if is_heartbeat {
+            packet_position
+        } else {
+            packet_position + length as i64
+        };
+
+        if packet_position < self.last_status_message_position
+            || proposed_position > self.last_status_message_position + self.window_length as i64
+            || term_offset + length > self.raw_log.term_length()
+        {
+            return length;
+        }
+
+        if is_heartbeat {
+            if header.flags() & data_header_flyweight::EOS_FLAG != 0 && self.end_of_stream_position.is_none() {
+                self.end_of_stream_position = Some(packet_position);
+                log_buffer_descriptor::set_end_of_stream_position(&self.raw_log.meta_data(), packet_position);
+            }
+        } else {
+            let term_buffer = self.term_buffer(packet_position);
+
+            // IC: the frame length of the first frame goes in last so subscribers never see part of the batch
+            if term_buffer.get_volatile::<i32>(term_offset) == 0 {
+                term_buffer.copy_from(term_offset + I32_SIZE, packet, I32_SIZE, length - I32_SIZE);
+                term_buffer.put_ordered::<i32>(term_offset, header.frame_length());
+            }
+        }
+
+        self.time_of_last_packet_ms = now_ms;
+
+        if proposed_position > self.hwm_position {
+            self.hwm_position = proposed_position;
+            self.put_counter(self.receiver_hwm_id, proposed_position);
+        }
+
+        length
+    }
+
+    /**
+     * Receiver duty cycle for the image: track how far the log has been rebuilt, NAK gaps and send status
+     * messages when subscribers have moved on or the status message timeout has passed.
+     *
+     * @return the amount of work done.
+     */
+    pub fn process_control(&mut self, socket: &UdpSocket, now_ms: Moment) -> i32 {
+        if self.state != PublicationImageState::Active {
+            return 0;
+        }
+
+        let mut work_count = 0;
+
+        if let Some(gap) = self.track_rebuild(now_ms) {
+            self.send_nak(socket, gap);
+            work_count += 1;
+        }
+
+        if let Some(min_subscriber_position) = self.min_subscriber_position() {
+            if now_ms >= self.time_of_last_status_message_ms + self.status_message_timeout_ms
+                || min_subscriber_position >= self.last_status_message_position + (self.window_length / 4) as i64
+            {
+                self.send_status_message(socket, min_subscriber_position);
+                self.last_status_message_position = min_subscriber_position;
+                self.time_of_last_status_message_ms = now_ms;
+                work_count += 1;
+            }
+
+            self.clean_buffer_to(min_subscriber_position - self.raw_log.term_length() as i64);
+        }
+
+        work_count
+    }
+
+    /**
+     * Deactivate the image when its publication has gone, its stream has ended and been consumed, or nothing
+     * subscribes to it any more, then progress lingering.
+     *
+     * @return true when the image has just been deactivated, at which point subscribers should be told it is
+     * unavailable.
+     */
+    pub fn on_time_event(&mut self, now_ms: Moment, liveness_timeout_ms: Moment, linger_timeout_ms: Moment) -> bool {
+        match self.state {
+            PublicationImageState::Active
+                if self.subscriber_positions.is_empty()
+                    || self.is_drained()
+                    || now_ms > self.time_of_last_packet_ms + liveness_timeout_ms =>
+            {
+                self.state = PublicationImageState::Linger {
+                    deadline_ms: now_ms + linger_timeout_ms,
+                };
+                return true;
+            }
+            PublicationImageState::Linger { deadline_ms } if now_ms >= deadline_ms => {
+                self.state = PublicationImageState::Done;
+            }
+            _ => {}
+        }
+
+        false
+    }
+
+    /**
+     * Release the log buffer file. Counters are freed by the caller.
+     */
+    pub fn close(&self) {
+        self.raw_log.delete();
+    }
+
+    fn is_drained(&self) -> bool {
+        match self.end_of_stream_position {
+            Some(end_of_stream_position) => {
+                self.rebuild_position >= end_of_stream_position
+                    && self.min_subscriber_position().unwrap_or(end_of_stream_position) >= end_of_stream_position
+            }
+            None => false,
+        }
+    }
+
+    fn track_rebuild(&mut self, now_ms: Moment) -> Option<Gap> {
+        if self.rebuild_position >= self.hwm_position {
+            return None;
+        }
+
+        let term_length = self.raw_log.term_length();
+        let rebuild_term_count = self.rebuild_position >> self.position_bits_to_shift;
+        let rebuild_term_offset = (self.rebuild_position & (term_length - 1) as i64) as Index;
+        let hwm_offset = if rebuild_term_count == self.hwm_position >> self.position_bits_to_shift {
+            (self.hwm_position & (term_length - 1) as i64) as Index
+        } else {
+            term_length
+        };
+
+        let (rebuild_offset, gap) = self.loss_detector.scan(
+            &self.term_buffer(self.rebuild_position),
+            self.initial_term_id.wrapping_add(rebuild_term_count as i32),
+            rebuild_term_offset,
+            hwm_offset,
+            now_ms,
+        );
+
+        if rebuild_offset > rebuild_term_offset {
+            self.rebuild_position += (rebuild_offset - rebuild_term_offset) as i64;
+            self.put_counter(self.receiver_position_id, self.rebuild_position);
+        }
+
+        gap
+    }
+
+    fn send_status_message(&mut self, socket: &UdpSocket, position: i64) {
+        let term_length = self.raw_log.term_length();
+
+        let mut status_message = StatusMessageFlyweight::new(self.frame_buffer.atomic_buffer(), 0);
+        status_message.set_frame_length(StatusMessageFlyweight::header_length());
+        status_message.set_version(data_frame_header::CURRENT_VERSION);
+        status_message.set_flags(0);
+        status_message.set_header_type(data_frame_header::HDR_TYPE_SM);
+        status_message.set_session_id(self.session_id);
+        status_message.set_stream_id(self.stream_id);
+        status_message.set_consumption_term_id(
+            self.initial_term_id
+                .wrapping_add((position >> self.position_bits_to_shift) as i32),
+        );
+        status_message.set_consumption_term_offset((position & (term_length - 1) as i64) as i32);
+        status_message.set_receiver_window(self.window_length);
+
+        self.send_frame(socket, StatusMessageFlyweight::header_length());
+    }
+
+    fn send_nak(&mut self, socket: &UdpSocket, gap: Gap) {
+        let mut nak = NakFlyweight::new(self.frame_buffer.atomic_buffer(), 0);
+        nak.set_frame_length(NakFlyweight::header_length());
+        nak.set_version(data_frame_header::CURRENT_VERSION);
+        nak.set_flags(0);
+        nak.set_header_type(data_frame_header::HDR_TYPE_NAK);
+        nak.set_session_id(self.session_id);
+        nak.set_stream_id(self.stream_id);
+        nak.set_term_id(gap.term_id);
+        nak.set_term_offset(gap.term_offset);
+        nak.set_length(gap.length);
+
+        self.send_frame(socket, NakFlyweight::header_length());
+    }
+
+    fn send_frame(&mut self, socket: &UdpSocket, length: Index) {
+        if let Err(err) = socket.send_to(self.frame_buffer.as_slice(length), self.source_address) {
+            log!(
+                debug,
+                "Image {} failed to send to {}: {}",
+                self.correlation_id,
+                self.source_address,
+                err
+            );
+        }
+    }
+
+    fn min_subscriber_position(&self) -> Option<i64> {
+        self.subscriber_positions
+            .iter()
+            .map(|position| {
+                self.counter_values
+                    .get_volatile::<i64>(CountersReader::counter_offset(position.counter_id))
+            })
+            .min()
+    }
+
+    fn term_buffer(&self, position: i64) -> AtomicBuffer {
+        self.raw_log.term_buffer(log_buffer_descriptor::index_by_position(
+            position,
+            self.position_bits_to_shift,
+        ))
+    }
+
+    fn put_counter(&self, counter_id: i32, value: i64) {
+        self.counter_values
+            .put_ordered::<i64>(CountersReader::counter_offset(counter_id), value);
+    }
+
+    fn clean_buffer_to(&mut self, position: i64) {
+        self.clean_position += self.raw_log.clean(self.clean_position, position, self.position_bits_to_shift) as i64;
+    }
+}
+
+// IC: offset and lengths come straight off the network, the term buffer only checks the end of a write
+fn is_valid_frame(header: &DataHeaderFlyweight, length: Index, is_heartbeat: bool) -> bool {
+    let term_offset = header.term_offset();
+    let frame_length = header.frame_length();
+
+    term_offset >= 0
+        && term_offset % frame_descriptor::FRAME_ALIGNMENT == 0
+        && (is_heartbeat || (DataHeaderFlyweight::header_length() <= frame_length && frame_length <= length))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AlignedBuffer;
+
+    const TERM_LENGTH: Index = log_buffer_descriptor::TERM_MIN_LENGTH;
+    const INITIAL_TERM_ID: i32 = 3;
+    const SESSION_ID: i32 = 11;
+    const STREAM_ID: i32 = 1001;
+    const HWM_ID: i32 = 0;
+    const RECEIVER_POSITION_ID: i32 = 1;
+    const FRAME_LENGTH: Index = 64;
+    // IC: near the end of the first term so a negative offset in the next term falls within the window
+    const JOIN_POSITION: i64 = (TERM_LENGTH - 2 * FRAME_LENGTH) as i64;
+
+    fn new_image(counter_values: AtomicBuffer) -> PublicationImage {
+        let file_name = std::env::temp_dir()
+            .join(format!("publication-image-{}.logbuffer", std::process::id()))
+            .to_string_lossy()
+            .into_owned();
+        let raw_log = RawLog::create(file_name, TERM_LENGTH, 1408, INITIAL_TERM_ID, SESSION_ID, STREAM_ID).unwrap();
+        let address = "127.0.0.1:40456".parse().unwrap();
+
+        PublicationImage::new(
+            1,
+            SESSION_ID,
+            STREAM_ID,
+            "aeron:udp?endpoint=127.0.0.1:40456".to_string(),
+            address,
+            address,
+            raw_log,
+            counter_values,
+            HWM_ID,
+            RECEIVER_POSITION_ID,
+            INITIAL_TERM_ID,
+            JOIN_POSITION,
+            TERM_LENGTH / 2,
+            200,
+            0,
+        )
+    }
+
+    fn put_header(packet: &AtomicBuffer, term_id: i32, term_offset: i32, frame_length: i32) {
+        let mut header = DataHeaderFlyweight::new(*packet, 0);
+        header.set_frame_length(frame_length);
+        header.set_flags(data_header_flyweight::BEGIN_AND_END_FLAGS);
+        header.set_header_type(data_frame_header::HDR_TYPE_DATA);
+        header.set_term_offset(term_offset);
+        header.set_session_id(SESSION_ID);
+        header.set_stream_id(STREAM_ID);
+        header.set_term_id(term_id);
+    }
+
+    #[test]
+    fn should_drop_malformed_frames() {
+        let counters = AlignedBuffer::with_capacity(CountersReader::counter_offset(2));
+        let counter_values = AtomicBuffer::from_aligned(&counters);
+        let packet_buffer = AlignedBuffer::with_capacity(FRAME_LENGTH);
+        let packet = AtomicBuffer::from_aligned(&packet_buffer);
+        let mut image = new_image(counter_values);
+        let join_offset = JOIN_POSITION as i32;
+
+        for (term_id, term_offset, frame_length) in &[
+            (INITIAL_TERM_ID + 1, -FRAME_LENGTH, FRAME_LENGTH),
+            (INITIAL_TERM_ID, join_offset + 8, FRAME_LENGTH),
+            (INITIAL_TERM_ID, join_offset, 2 * FRAME_LENGTH),
+            (INITIAL_TERM_ID, join_offset, DataHeaderFlyweight::header_length() - 1),
+            (INITIAL_TERM_ID, join_offset, -FRAME_LENGTH),
+        ] {
+            put_header(&packet, *term_id, *term_offset, *frame_length);
+
+            assert_eq!(image.insert_packet(&packet, FRAME_LENGTH, 100), FRAME_LENGTH);
+            assert_eq!(
+                counter_values.get::<i64>(CountersReader::counter_offset(HWM_ID)),
+                JOIN_POSITION
+            );
+        }
+
+        let term_buffer = image.term_buffer(JOIN_POSITION);
+        assert_eq!(term_buffer.get::<i32>(join_offset), 0);
+        assert_eq!(term_buffer.get::<i32>(TERM_LENGTH - FRAME_LENGTH), 0);
+
+        put_header(&packet, INITIAL_TERM_ID, join_offset, FRAME_LENGTH);
+        assert_eq!(image.insert_packet(&packet, FRAME_LENGTH, 100), FRAME_LENGTH);
+        assert_eq!(term_buffer.get::<i32>(join_offset), FRAME_LENGTH);
+        assert_eq!(
+            counter_values.get::<i64>(CountersReader::counter_offset(HWM_ID)),
+            JOIN_POSITION + FRAME_LENGTH as i64
+        );
+
+        image.close();
+    }
+}
@@ -0,0 +1,157 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::fs;
+
+use crate::concurrent::{
+    atomic_buffer::AtomicBuffer,
+    logbuffer::{
+        data_frame_header::{self, DataFrameHeaderDefn},
+        frame_descriptor,
+        log_buffer_descriptor::{self, LOG_META_DATA_LENGTH, PARTITION_COUNT},
+    },
+};
+use crate::utils::{
+    errors::AeronError,
+    memory_mapped_file::MemoryMappedFile,
+    types::{Index, I64_SIZE},
+};
+
+pub const PAGE_SIZE: Index = 4 * 1024;
+
+/**
+ * A log buffer file created by the driver: three term buffers followed by the log meta data, as laid out by
+ * log_buffer_descriptor.
+ */
+pub(crate) struct RawLog {
+    file_name: String,
+    term_length: Index,
+    term_buffers: [AtomicBuffer; PARTITION_COUNT as usize],
+    meta_data: AtomicBuffer,
+    _mapped_file: MemoryMappedFile,
+}
+
+impl RawLog {
+    /**
+     * Create and map a new log file then initialise its meta data so clients can map it straight away.
+     */
+    pub fn create(
+        file_name: String,
+        term_length: Index,
+        mtu_length: Index,
+        initial_term_id: i32,
+        session_id: i32,
+        stream_id: i32,
+    ) -> Result<Self, AeronError> {
+        log_buffer_descriptor::check_term_length(term_length)?;
+
+        let log_length = PARTITION_COUNT * term_length + LOG_META_DATA_LENGTH;
+        let mapped_file = MemoryMappedFile::create_new(file_name.as_str(), 0, log_length)?;
+
+        let term_buffers = [
+            mapped_file.atomic_buffer(0, term_length),
+            mapped_file.atomic_buffer(term_length, term_length),
+            mapped_file.atomic_buffer(2 * term_length, term_length),
+        ];
+        let meta_data = mapped_file.atomic_buffer(PARTITION_COUNT * term_length, LOG_META_DATA_LENGTH);
+
+        // IC: the tail of partition i starts at the term id it had i terms before the initial one so that
+        // rotation into it finds the expected term id
+        log_buffer_descriptor::initialize_tail_with_term_id(&meta_data, 0, initial_term_id);
+        for partition_index in 1..PARTITION_COUNT {
+            let expected_term_id = initial_term_id + partition_index - PARTITION_COUNT;
+            log_buffer_descriptor::initialize_tail_with_term_id(&meta_data, partition_index, expected_term_id);
+        }
+
+        meta_data.put::<i32>(*log_buffer_descriptor::LOG_INITIAL_TERM_ID_OFFSET, initial_term_id);
+        meta_data.put::<i32>(*log_buffer_descriptor::LOG_MTU_LENGTH_OFFSET, mtu_length);
+        meta_data.put::<i32>(*log_buffer_descriptor::LOG_TERM_LENGTH_OFFSET, term_length);
+        meta_data.put::<i32>(*log_buffer_descriptor::LOG_PAGE_SIZE_OFFSET, PAGE_SIZE);
+        meta_data.put::<i32>(
+            *log_buffer_descriptor::LOG_DEFAULT_FRAME_HEADER_LENGTH_OFFSET,
+            data_frame_header::LENGTH,
+        );
+        meta_data.put::<DataFrameHeaderDefn>(
+            log_buffer_descriptor::LOG_DEFAULT_FRAME_HEADER_OFFSET,
+            DataFrameHeaderDefn {
+                frame_length: 0,
+                version: data_frame_header::CURRENT_VERSION,
+                flags: frame_descriptor::UNFRAGMENTED,
+                frame_type: data_frame_header::HDR_TYPE_DATA,
+                term_offset: 0,
+                session_id,
+                stream_id,
+                term_id: 0,
+                reserved_value: 0,
+            },
+        );
+        log_buffer_descriptor::set_end_of_stream_position(&meta_data, i64::MAX);
+        log_buffer_descriptor::set_active_term_count_ordered(&meta_data, 0);
+
+        Ok(Self {
+            file_name,
+            term_length,
+            term_buffers,
+            meta_data,
+            _mapped_file: mapped_file,
+        })
+    }
+
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn term_length(&self) -> Index {
+        self.term_length
+    }
+
+    pub fn term_buffer(&self, partition_index: Index) -> AtomicBuffer {
+        self.term_buffers[partition_index as usize]
+    }
+
+    pub fn meta_data(&self) -> AtomicBuffer {
+        self.meta_data
+    }
+
+    /**
+     * Zero the term buffers from a clean position up to, at most, the end of its term so they can be reused
+     * once the log wraps. The frame length word is zeroed last as readers rely on it.
+     *
+     * @return the number of bytes cleaned.
+     */
+    pub fn clean(&self, clean_position: i64, position: i64, position_bits_to_shift: i32) -> Index {
+        if position <= clean_position {
+            return 0;
+        }
+
+        let dirty_term =
+            self.term_buffers[log_buffer_descriptor::index_by_position(clean_position, position_bits_to_shift) as usize];
+        let term_offset = (clean_position & (self.term_length - 1) as i64) as Index;
+        let length = (position - clean_position).min((self.term_length - term_offset) as i64) as Index;
+
+        dirty_term.set_memory(term_offset + I64_SIZE, length - I64_SIZE, 0);
+        dirty_term.put_ordered::<i64>(term_offset, 0);
+
+        length
+    }
+
+    /**
+     * Remove the log file. Clients which still have it mapped keep their mapping.
+     */
+    pub fn delete(&self) {
+        let _ignored = fs::remove_file(&self.file_name);
+    }
+}
@@ -0,0 +1,321 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::io::ErrorKind;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::{mpsc, Arc, Mutex};
+
+use crate::{
+    concurrent::{atomic_buffer::AtomicBuffer, logbuffer::data_frame_header},
+    driver::{driver_conductor::ConductorCommand, publication_image::PublicationImage, udp_channel::DatagramBuffer},
+    log,
+    protocol::{
+        data_header_flyweight::DataHeaderFlyweight,
+        header_flyweight::HEADER_DEFN_SIZE,
+        setup_flyweight::SetupFlyweight,
+        status_message_flyweight::{self, StatusMessageFlyweight},
+    },
+    utils::types::{Index, Moment},
+};
+
+/**
+ * Time for which a session is not asked for a Setup frame again, or not asked to be turned into an image again.
+ */
+const PENDING_SETUP_TIMEOUT_MS: Moment = 100;
+
+/**
+ * Most datagrams read from the socket in one duty cycle.
+ */
+const MAX_DATAGRAMS_PER_POLL: i32 = 64;
+
+struct StreamInterest {
+    stream_id: i32,
+    subscription_count: i32,
+}
+
+struct ImageEntry {
+    correlation_id: i64,
+    session_id: i32,
+    stream_id: i32,
+    image: Arc<Mutex<PublicationImage>>,
+}
+
+struct PendingSetup {
+    session_id: i32,
+    stream_id: i32,
+    deadline_ms: Moment,
+    is_image_requested: bool,
+}
+
+/**
+ * Socket bound to the endpoint of an aeron:udp subscription channel. Frames for the streams subscribed to are
+ * dispatched to the image of their session, Setup frames for new sessions are passed on to the conductor to
+ * create images for.
+ */
+pub(crate) struct ReceiveChannelEndpoint {
+    endpoint: SocketAddr,
+    socket: UdpSocket,
+    stream_interests: Vec<StreamInterest>,
+    images: Vec<ImageEntry>,
+    pending_setups: Vec<PendingSetup>,
+    receive_buffer: Box<DatagramBuffer>,
+    frame_buffer: Box<DatagramBuffer>,
+}
+
+impl ReceiveChannelEndpoint {
+    pub fn new(endpoint: SocketAddr, socket: UdpSocket) -> Self {
+        Self {
+            endpoint,
+            socket,
+            stream_interests: Vec::new(),
+            images: Vec::new(),
+            pending_setups: Vec::new(),
+            receive_buffer: DatagramBuffer::new(),
+            frame_buffer: DatagramBuffer::new(),
+        }
+    }
+
+    pub fn endpoint(&self) -> SocketAddr {
+        self.endpoint
+    }
+
+    pub fn add_subscription(&mut self, stream_id: i32) {
+        match self
+            .stream_interests
+            .iter_mut()
+            .find(|interest| interest.stream_id == stream_id)
+        {
+            Some(interest) => interest.subscription_count += 1,
+            None => self.stream_interests.push(StreamInterest {
+                stream_id,
+                subscription_count: 1,
+            }),
+        }
+    }
+
+    /**
+     * Drop a subscription to a stream. Once the stream has no subscriptions left its frames are ignored and its
+     * images no longer fed.
+     */
+    pub fn remove_subscription(&mut self, stream_id: i32) {
+        if let Some(index) = self
+            .stream_interests
+            .iter()
+            .position(|interest| interest.stream_id == stream_id)
+        {
+            self.stream_interests[index].subscription_count -= 1;
+
+            if self.stream_interests[index].subscription_count == 0 {
+                self.stream_interests.remove(index);
+                self.images.retain(|entry| entry.stream_id != stream_id);
+            }
+        }
+    }
+
+    pub fn add_image(&mut self, image: Arc<Mutex<PublicationImage>>) {
+        let (correlation_id, session_id, stream_id) = {
+            let image = image.lock().expect("Mutex poisoned");
+            (image.correlation_id(), image.session_id(), image.stream_id())
+        };
+
+        self.pending_setups
+            .retain(|pending| pending.session_id != session_id || pending.stream_id != stream_id);
+        self.images.push(ImageEntry {
+            correlation_id,
+            session_id,
+            stream_id,
+            image,
+        });
+    }
+
+    pub fn remove_image(&mut self, correlation_id: i64) {
+        self.images.retain(|entry| entry.correlation_id != correlation_id);
+    }
+
+    /**
+     * Read the datagrams waiting on the socket and dispatch them.
+     *
+     * @return the amount of work done.
+     */
+    pub fn poll(&mut self, conductor_commands: &mpsc::Sender<ConductorCommand>, now_ms: Moment) -> i32 {
+        self.pending_setups.retain(|pending| pending.deadline_ms > now_ms);
+
+        let mut work_count = 0;
+
+        while work_count < MAX_DATAGRAMS_PER_POLL {
+            let (length, source_address) = match self.socket.recv_from(self.receive_buffer.as_mut_slice()) {
+                Ok((length, source_address)) => (length as Index, source_address),
+                Err(err) if err.kind() == ErrorKind::WouldBlock => break,
+                Err(err) => {
+                    log!(debug, "Receive endpoint {} failed to receive: {}", self.endpoint, err);
+                    break;
+                }
+            };
+
+            work_count += 1;
+            if length < HEADER_DEFN_SIZE {
+                continue;
+            }
+
+            let buffer = self.receive_buffer.atomic_buffer();
+            match buffer.get::<u16>(*data_frame_header::TYPE_FIELD_OFFSET) {
+                data_frame_header::HDR_TYPE_DATA | data_frame_header::HDR_TYPE_PAD
+                    if length >= DataHeaderFlyweight::header_length() =>
+                {
+                    self.on_data_packet(&buffer, length, source_address, now_ms)
+                }
+                data_frame_header::HDR_TYPE_SETUP if length >= SetupFlyweight::header_length() => {
+                    self.on_setup(&buffer, source_address, conductor_commands, now_ms)
+                }
+                _ => {}
+            }
+        }
+
+        work_count
+    }
+
+    /**
+     * Rebuild, NAK and send status messages for each image.
+     *
+     * @return the amount of work done.
+     */
+    pub fn process_images(&mut self, now_ms: Moment) -> i32 {
+        let socket = &self.socket;
+
+        self.images
+            .iter()
+            .map(|entry| entry.image.lock().expect("Mutex poisoned").process_control(socket, now_ms))
+            .sum()
+    }
+
+    fn on_data_packet(&mut self, buffer: &AtomicBuffer, length: Index, source_address: SocketAddr, now_ms: Moment) {
+        let header = DataHeaderFlyweight::new(*buffer, 0);
+        let session_id = header.session_id();
+        let stream_id = header.stream_id();
+
+        if !self.is_subscribed(stream_id) {
+            return;
+        }
+
+        if let Some(entry) = self
+            .images
+            .iter()
+            .find(|entry| entry.session_id == session_id && entry.stream_id == stream_id)
+        {
+            entry
+                .image
+                .lock()
+                .expect("Mutex poisoned")
+                .insert_packet(buffer, length, now_ms);
+            return;
+        }
+
+        // IC: data for a session without an image means its Setup frames were missed, e.g. the subscription came
+        // after the publication connected elsewhere, so ask for one
+        if !self.is_setup_pending(session_id, stream_id, false) {
+            self.send_setup_eliciting_status_message(session_id, stream_id, source_address);
+            self.pending_setups.push(PendingSetup {
+                session_id,
+                stream_id,
+                deadline_ms: now_ms + PENDING_SETUP_TIMEOUT_MS,
+                is_image_requested: false,
+            });
+        }
+    }
+
+    fn on_setup(
+        &mut self,
+        buffer: &AtomicBuffer,
+        source_address: SocketAddr,
+        conductor_commands: &mpsc::Sender<ConductorCommand>,
+        now_ms: Moment,
+    ) {
+        let setup = SetupFlyweight::new(*buffer, 0);
+        let session_id = setup.session_id();
+        let stream_id = setup.stream_id();
+
+        if !self.is_subscribed(stream_id)
+            || self
+                .images
+                .iter()
+                .any(|entry| entry.session_id == session_id && entry.stream_id == stream_id)
+            || self.is_setup_pending(session_id, stream_id, true)
+        {
+            return;
+        }
+
+        self.pending_setups
+            .retain(|pending| pending.session_id != session_id || pending.stream_id != stream_id);
+        self.pending_setups.push(PendingSetup {
+            session_id,
+            stream_id,
+            deadline_ms: now_ms + PENDING_SETUP_TIMEOUT_MS,
+            is_image_requested: true,
+        });
+
+        let command = ConductorCommand::CreatePublicationImage {
+            endpoint: self.endpoint,
+            source_address,
+            session_id,
+            stream_id,
+            initial_term_id: setup.initial_term_id(),
+            active_term_id: setup.action_term_id(),
+            term_offset: setup.term_offset(),
+            term_length: setup.term_length(),
+            mtu_length: setup.mtu(),
+        };
+
+        if conductor_commands.send(command).is_err() {
+            log!(debug, "Conductor gone, dropping setup for session {}", session_id);
+        }
+    }
+
+    fn is_subscribed(&self, stream_id: i32) -> bool {
+        self.stream_interests.iter().any(|interest| interest.stream_id == stream_id)
+    }
+
+    fn is_setup_pending(&self, session_id: i32, stream_id: i32, only_image_requested: bool) -> bool {
+        self.pending_setups.iter().any(|pending| {
+            pending.session_id == session_id
+                && pending.stream_id == stream_id
+                && (pending.is_image_requested || !only_image_requested)
+        })
+    }
+
+    fn send_setup_eliciting_status_message(&mut self, session_id: i32, stream_id: i32, source_address: SocketAddr) {
+        let mut status_message = StatusMessageFlyweight::new(self.frame_buffer.atomic_buffer(), 0);
+        status_message.set_frame_length(StatusMessageFlyweight::header_length());
+        status_message.set_version(data_frame_header::CURRENT_VERSION);
+        status_message.set_flags(status_message_flyweight::SEND_SETUP_FLAG);
+        status_message.set_header_type(data_frame_header::HDR_TYPE_SM);
+        status_message.set_session_id(session_id);
+        status_message.set_stream_id(stream_id);
+        status_message.set_consumption_term_id(0);
+        status_message.set_consumption_term_offset(0);
+        status_message.set_receiver_window(0);
+
+        let length = StatusMessageFlyweight::header_length();
+        if let Err(err) = self.socket.send_to(self.frame_buffer.as_slice(length), source_address) {
+            log!(
+                debug,
+                "Receive endpoint {} failed to send to {}: {}",
+                self.endpoint,
+                source_address,
+                err
+            );
+        }
+    }
+}
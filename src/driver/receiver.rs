@@ -0,0 +1,139 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::net::SocketAddr;
+use std::sync::{mpsc, Arc, Mutex};
+
+use crate::{
+    concurrent::agent_runner::Agent,
+    driver::{
+        driver_conductor::ConductorCommand, publication_image::PublicationImage, receive_channel_endpoint::ReceiveChannelEndpoint,
+    },
+    utils::{errors::AeronError, misc::unix_time_ms},
+};
+
+/**
+ * Commands from the conductor to the receiver. Endpoints are identified by the address they are bound to.
+ */
+pub(crate) enum ReceiverCommand {
+    AddEndpoint(ReceiveChannelEndpoint),
+    RemoveEndpoint {
+        endpoint: SocketAddr,
+    },
+    AddSubscription {
+        endpoint: SocketAddr,
+        stream_id: i32,
+    },
+    RemoveSubscription {
+        endpoint: SocketAddr,
+        stream_id: i32,
+    },
+    AddImage {
+        endpoint: SocketAddr,
+        image: Arc<Mutex<PublicationImage>>,
+    },
+    RemoveImage {
+        endpoint: SocketAddr,
+        correlation_id: i64,
+    },
+}
+
+/**
+ * Agent which reads the receive channel endpoints opened by the conductor, feeding their images and sending
+ * status messages and NAKs back to the publications.
+ */
+pub(crate) struct Receiver {
+    // IC: agents are shared with their runner thread so must be Sync, which a channel receiver is not
+    commands: Mutex<mpsc::Receiver<ReceiverCommand>>,
+    conductor_commands: mpsc::Sender<ConductorCommand>,
+    endpoints: Vec<ReceiveChannelEndpoint>,
+}
+
+impl Receiver {
+    pub fn new(commands: mpsc::Receiver<ReceiverCommand>, conductor_commands: mpsc::Sender<ConductorCommand>) -> Self {
+        Self {
+            commands: Mutex::new(commands),
+            conductor_commands,
+            endpoints: Vec::new(),
+        }
+    }
+
+    fn on_command(&mut self, command: ReceiverCommand) {
+        match command {
+            ReceiverCommand::AddEndpoint(endpoint) => self.endpoints.push(endpoint),
+            ReceiverCommand::RemoveEndpoint { endpoint } => self
+                .endpoints
+                .retain(|channel_endpoint| channel_endpoint.endpoint() != endpoint),
+            ReceiverCommand::AddSubscription { endpoint, stream_id } => {
+                if let Some(channel_endpoint) = self.find_endpoint(endpoint) {
+                    channel_endpoint.add_subscription(stream_id);
+                }
+            }
+            ReceiverCommand::RemoveSubscription { endpoint, stream_id } => {
+                if let Some(channel_endpoint) = self.find_endpoint(endpoint) {
+                    channel_endpoint.remove_subscription(stream_id);
+                }
+            }
+            ReceiverCommand::AddImage { endpoint, image } => {
+                if let Some(channel_endpoint) = self.find_endpoint(endpoint) {
+                    channel_endpoint.add_image(image);
+                }
+            }
+            ReceiverCommand::RemoveImage {
+                endpoint,
+                correlation_id,
+            } => {
+                if let Some(channel_endpoint) = self.find_endpoint(endpoint) {
+                    channel_endpoint.remove_image(correlation_id);
+                }
+            }
+        }
+    }
+
+    fn find_endpoint(&mut self, endpoint: SocketAddr) -> Option<&mut ReceiveChannelEndpoint> {
+        self.endpoints
+            .iter_mut()
+            .find(|channel_endpoint| channel_endpoint.endpoint() == endpoint)
+    }
+}
+
+impl Agent for Receiver {
+    fn on_start(&mut self) -> Result<(), AeronError> {
+        Ok(())
+    }
+
+    fn do_work(&mut self) -> Result<i32, AeronError> {
+        let commands: Vec<_> = self.commands.lock().expect("Mutex poisoned").try_iter().collect();
+        let mut work_count = commands.len() as i32;
+
+        for command in commands {
+            self.on_command(command);
+        }
+
+        let now_ms = unix_time_ms();
+        for endpoint in self.endpoints.iter_mut() {
+            work_count += endpoint.poll(&self.conductor_commands, now_ms);
+            work_count += endpoint.process_images(now_ms);
+        }
+
+        Ok(work_count)
+    }
+
+    fn on_close(&mut self) -> Result<(), AeronError> {
+        self.endpoints.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::sync::{mpsc, Arc, Mutex};
+
+use crate::{
+    concurrent::agent_runner::Agent,
+    driver::network_publication::NetworkPublication,
+    utils::{errors::AeronError, misc::unix_time_ms},
+};
+
+/**
+ * Commands from the conductor to the sender.
+ */
+pub(crate) enum SenderCommand {
+    AddPublication(Arc<Mutex<NetworkPublication>>),
+    RemovePublication { registration_id: i64 },
+}
+
+/**
+ * Agent which sends the network publications created by the conductor and processes the status messages and
+ * NAKs coming back from their receivers.
+ */
+pub(crate) struct Sender {
+    // IC: agents are shared with their runner thread so must be Sync, which a channel receiver is not
+    commands: Mutex<mpsc::Receiver<SenderCommand>>,
+    publications: Vec<(i64, Arc<Mutex<NetworkPublication>>)>,
+}
+
+impl Sender {
+    pub fn new(commands: mpsc::Receiver<SenderCommand>) -> Self {
+        Self {
+            commands: Mutex::new(commands),
+            publications: Vec::new(),
+        }
+    }
+
+    fn on_command(&mut self, command: SenderCommand) {
+        match command {
+            SenderCommand::AddPublication(publication) => {
+                let registration_id = publication.lock().expect("Mutex poisoned").registration_id();
+                self.publications.push((registration_id, publication));
+            }
+            SenderCommand::RemovePublication { registration_id } => {
+                self.publications.retain(|(id, _)| *id != registration_id);
+            }
+        }
+    }
+}
+
+impl Agent for Sender {
+    fn on_start(&mut self) -> Result<(), AeronError> {
+        Ok(())
+    }
+
+    fn do_work(&mut self) -> Result<i32, AeronError> {
+        let commands: Vec<_> = self.commands.lock().expect("Mutex poisoned").try_iter().collect();
+        let mut work_count = commands.len() as i32;
+
+        for command in commands {
+            self.on_command(command);
+        }
+
+        let now_ms = unix_time_ms();
+        for (_, publication) in &self.publications {
+            work_count += publication.lock().expect("Mutex poisoned").send(now_ms);
+        }
+
+        Ok(work_count)
+    }
+
+    fn on_close(&mut self) -> Result<(), AeronError> {
+        self.publications.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use crate::concurrent::{
+    atomic_buffer::AtomicBuffer,
+    counters::{CountersManager, MAX_KEY_LENGTH, MAX_LABEL_LENGTH},
+};
+use crate::utils::{
+    errors::AeronError,
+    types::{Index, I32_SIZE, I64_SIZE},
+};
+
+/**
+ * Type id of the counter holding the position limit of a publication.
+ */
+pub const PUBLISHER_LIMIT_TYPE_ID: i32 = 1;
+
+/**
+ * Type id of the counter holding the position a network publication has sent up to.
+ */
+pub const SENDER_POSITION_TYPE_ID: i32 = 2;
+
+/**
+ * Type id of the counter holding the highest position an image has received data for.
+ */
+pub const RECEIVER_HWM_TYPE_ID: i32 = 3;
+
+/**
+ * Type id of the counter holding the position a subscriber has consumed up to.
+ */
+pub const SUBSCRIBER_POSITION_TYPE_ID: i32 = 4;
+
+/**
+ * Type id of the counter holding the position up to which an image has been received without gaps.
+ */
+pub const RECEIVER_POSITION_TYPE_ID: i32 = 5;
+
+pub const REGISTRATION_ID_OFFSET: Index = 0;
+pub const SESSION_ID_OFFSET: Index = REGISTRATION_ID_OFFSET + I64_SIZE;
+pub const STREAM_ID_OFFSET: Index = SESSION_ID_OFFSET + I32_SIZE;
+pub const CHANNEL_OFFSET: Index = STREAM_ID_OFFSET + I32_SIZE;
+
+/**
+ * Allocate a counter for a stream. The key holds the registration id, session id, stream id and as much of the
+ * channel as fits, the same layout as the counters of the Java media driver.
+ *
+ * @return the id of the allocated counter.
+ */
+pub fn allocate(
+    counters_manager: &mut CountersManager,
+    name: &str,
+    type_id: i32,
+    registration_id: i64,
+    session_id: i32,
+    stream_id: i32,
+    channel: &str,
+) -> Result<i32, AeronError> {
+    let max_channel_length = (MAX_KEY_LENGTH - CHANNEL_OFFSET - I32_SIZE) as usize;
+    let key_channel = &channel.as_bytes()[..channel.len().min(max_channel_length)];
+
+    let mut label = format!("{}: {} {} {} {}", name, registration_id, session_id, stream_id, channel);
+    label.truncate(MAX_LABEL_LENGTH as usize);
+
+    counters_manager.allocate_opt(
+        type_id,
+        None,
+        Some(|key_buffer: &mut AtomicBuffer| {
+            key_buffer.put::<i64>(REGISTRATION_ID_OFFSET, registration_id);
+            key_buffer.put::<i32>(SESSION_ID_OFFSET, session_id);
+            key_buffer.put::<i32>(STREAM_ID_OFFSET, stream_id);
+            key_buffer.put_string(CHANNEL_OFFSET, key_channel);
+        }),
+        &label,
+    )
+}
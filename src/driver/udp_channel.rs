@@ -0,0 +1,185 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, ToSocketAddrs, UdpSocket};
+
+use crate::{
+    channel_uri::{ChannelUri, ENDPOINT_PARAM_NAME, MDC_CONTROL_PARAM_NAME, UDP_MEDIA},
+    command::error_response_flyweight::{ERROR_CODE_GENERIC_ERROR, ERROR_CODE_INVALID_CHANNEL},
+    concurrent::atomic_buffer::AtomicBuffer,
+    utils::{errors::AeronError, types::Index},
+};
+
+/**
+ * Largest payload of a UDP datagram.
+ */
+pub const MAX_UDP_PAYLOAD_LENGTH: Index = 65504;
+
+/**
+ * An aeron:udp unicast channel resolved to the socket address of its endpoint.
+ */
+pub(crate) struct UdpChannel {
+    original_uri: String,
+    endpoint: SocketAddr,
+}
+
+impl UdpChannel {
+    /**
+     * Parse a channel and resolve its endpoint. Multicast and multi-destination channels are rejected as only
+     * unicast is supported.
+     */
+    pub fn parse(channel: &str) -> Result<Self, AeronError> {
+        let uri = ChannelUri::parse(channel).map_err(|err| invalid_channel(err.to_string()))?;
+        let uri = uri.lock().expect("Mutex poisoned");
+
+        if uri.media() != UDP_MEDIA {
+            return Err(invalid_channel(format!("not a udp channel: {}", channel)));
+        }
+
+        if uri.contains_key(MDC_CONTROL_PARAM_NAME) {
+            return Err(invalid_channel(format!(
+                "multi-destination channels are not supported: {}",
+                channel
+            )));
+        }
+
+        let endpoint_param = uri.get(ENDPOINT_PARAM_NAME);
+        if endpoint_param.is_empty() {
+            return Err(invalid_channel(format!("{} must be set: {}", ENDPOINT_PARAM_NAME, channel)));
+        }
+
+        let endpoint = endpoint_param
+            .to_socket_addrs()
+            .map_err(|err| invalid_channel(format!("could not resolve {}: {}", endpoint_param, err)))?
+            .next()
+            .ok_or_else(|| invalid_channel(format!("could not resolve {}", endpoint_param)))?;
+
+        if endpoint.ip().is_multicast() {
+            return Err(invalid_channel(format!("multicast channels are not supported: {}", channel)));
+        }
+
+        Ok(Self {
+            original_uri: channel.to_string(),
+            endpoint,
+        })
+    }
+
+    pub fn original_uri(&self) -> &str {
+        &self.original_uri
+    }
+
+    pub fn endpoint(&self) -> SocketAddr {
+        self.endpoint
+    }
+
+    /**
+     * Open a non-blocking socket on an ephemeral port for sending to the endpoint.
+     */
+    pub fn open_send_socket(&self) -> Result<UdpSocket, AeronError> {
+        let bind_address: SocketAddr = if self.endpoint.is_ipv4() {
+            (Ipv4Addr::UNSPECIFIED, 0).into()
+        } else {
+            (Ipv6Addr::UNSPECIFIED, 0).into()
+        };
+
+        open_socket(bind_address)
+    }
+
+    /**
+     * Open a non-blocking socket bound to the endpoint for receiving from it.
+     */
+    pub fn open_receive_socket(&self) -> Result<UdpSocket, AeronError> {
+        open_socket(self.endpoint)
+    }
+}
+
+fn open_socket(bind_address: SocketAddr) -> Result<UdpSocket, AeronError> {
+    let socket = UdpSocket::bind(bind_address).map_err(|err| {
+        AeronError::RegistrationException(
+            ERROR_CODE_GENERIC_ERROR,
+            format!("could not bind socket to {}: {}", bind_address, err),
+        )
+    })?;
+
+    socket.set_nonblocking(true).map_err(|err| {
+        AeronError::RegistrationException(
+            ERROR_CODE_GENERIC_ERROR,
+            format!("could not make socket on {} non-blocking: {}", bind_address, err),
+        )
+    })?;
+
+    Ok(socket)
+}
+
+fn invalid_channel(message: String) -> AeronError {
+    AeronError::RegistrationException(ERROR_CODE_INVALID_CHANNEL, message)
+}
+
+/**
+ * Buffer for a single datagram. It is aligned so that frame flyweights can be overlaid on it.
+ */
+#[repr(C, align(16))]
+pub(crate) struct DatagramBuffer {
+    data: [u8; MAX_UDP_PAYLOAD_LENGTH as usize],
+}
+
+impl DatagramBuffer {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            data: [0; MAX_UDP_PAYLOAD_LENGTH as usize],
+        })
+    }
+
+    pub fn atomic_buffer(&mut self) -> AtomicBuffer {
+        AtomicBuffer::wrap_slice(&mut self.data)
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    pub fn as_slice(&self, length: Index) -> &[u8] {
+        &self.data[..length as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_resolve_unicast_endpoint() {
+        let channel = UdpChannel::parse("aeron:udp?endpoint=127.0.0.1:40456").unwrap();
+
+        assert_eq!(channel.endpoint(), "127.0.0.1:40456".parse::<SocketAddr>().unwrap());
+        assert_eq!(channel.original_uri(), "aeron:udp?endpoint=127.0.0.1:40456");
+    }
+
+    #[test]
+    fn should_reject_unsupported_channels() {
+        for channel in &[
+            "aeron:ipc",
+            "aeron:udp",
+            "aeron:udp?endpoint=224.10.9.9:40456",
+            "aeron:udp?control=127.0.0.1:40457|control-mode=dynamic",
+        ] {
+            match UdpChannel::parse(channel) {
+                Err(AeronError::RegistrationException(code, _)) => assert_eq!(code, ERROR_CODE_INVALID_CHANNEL),
+                _ => panic!("{} must be rejected", channel),
+            }
+        }
+    }
+}
@@ -55,6 +55,10 @@ impl DriverProxy {
         self.client_id
     }
 
+    pub fn next_correlation_id(&self) -> i64 {
+        self.to_driver_command_buffer.next_correlation_id()
+    }
+
     pub fn add_publication(&self, channel: CString, stream_id: i32) -> Result<i64, AeronError> {
         let correlation_id = self.to_driver_command_buffer.next_correlation_id();
 
@@ -160,9 +164,12 @@ impl DriverProxy {
         })
     }
 
-    pub fn add_destination(&self, publication_registration_id: i64, channel: CString) -> Result<i64, AeronError> {
-        let correlation_id = self.to_driver_command_buffer.next_correlation_id();
-
+    pub fn add_destination(
+        &self,
+        publication_registration_id: i64,
+        correlation_id: i64,
+        channel: CString,
+    ) -> Result<(), AeronError> {
         self.write_command_to_driver(|buffer, length| {
             let mut add_message = DestinationMessageFlyweight::new(buffer, 0);
 
@@ -174,14 +181,15 @@ impl DriverProxy {
             *length = add_message.length();
 
             Ok(AeronCommand::AddDestination)
-        })?;
-
-        Ok(correlation_id)
+        })
     }
 
-    pub fn remove_destination(&self, publication_registration_id: i64, channel: CString) -> Result<i64, AeronError> {
-        let correlation_id = self.to_driver_command_buffer.next_correlation_id();
-
+    pub fn remove_destination(
+        &self,
+        publication_registration_id: i64,
+        correlation_id: i64,
+        channel: CString,
+    ) -> Result<(), AeronError> {
         self.write_command_to_driver(|buffer, length| {
             let mut remove_message = DestinationMessageFlyweight::new(buffer, 0);
 
@@ -193,14 +201,15 @@ impl DriverProxy {
             *length = remove_message.length();
 
             Ok(AeronCommand::RemoveDestination)
-        })?;
-
-        Ok(correlation_id)
+        })
     }
 
-    pub fn add_rcv_destination(&self, subscription_registration_id: i64, channel: CString) -> Result<i64, AeronError> {
-        let correlation_id = self.to_driver_command_buffer.next_correlation_id();
-
+    pub fn add_rcv_destination(
+        &self,
+        subscription_registration_id: i64,
+        correlation_id: i64,
+        channel: CString,
+    ) -> Result<(), AeronError> {
         self.write_command_to_driver(|buffer, length| {
             let mut add_message = DestinationMessageFlyweight::new(buffer, 0);
 
@@ -212,14 +221,15 @@ impl DriverProxy {
             *length = add_message.length();
 
             Ok(AeronCommand::AddRcvDestination)
-        })?;
-
-        Ok(correlation_id)
+        })
     }
 
-    pub fn remove_rcv_destination(&self, subscription_registration_id: i64, channel: CString) -> Result<i64, AeronError> {
-        let correlation_id = self.to_driver_command_buffer.next_correlation_id();
-
+    pub fn remove_rcv_destination(
+        &self,
+        subscription_registration_id: i64,
+        correlation_id: i64,
+        channel: CString,
+    ) -> Result<(), AeronError> {
         self.write_command_to_driver(|buffer, length| {
             let mut remove_message = DestinationMessageFlyweight::new(buffer, 0);
 
@@ -231,9 +241,7 @@ impl DriverProxy {
             *length = remove_message.length();
 
             Ok(AeronCommand::RemoveRcvDestination)
-        })?;
-
-        Ok(correlation_id)
+        })
     }
 
     pub fn add_counter(&self, type_id: i32, key: &[u8], label: CString) -> Result<i64, AeronError> {
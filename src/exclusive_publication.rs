@@ -18,13 +18,12 @@ use std::{
     ffi::CString,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
+        Arc,
     },
 };
 
 use crate::utils::errors::{IllegalArgumentError, IllegalStateError};
 use crate::{
-    client_conductor::ClientConductor,
     concurrent::{
         atomic_buffer::AtomicBuffer,
         logbuffer::{
@@ -38,7 +37,10 @@ use crate::{
         },
         position::{ReadablePosition, UnsafeBufferPosition},
         status::status_indicator_reader,
+        strategies::IdlePolicy,
     },
+    conductor_proxy::ConductorProxy,
+    offer_retry_policy::OfferRetryPolicy,
     utils::{bit_utils::number_of_trailing_zeroes, errors::AeronError, log_buffers::LogBuffers, types::Index},
 };
 
@@ -55,6 +57,8 @@ use crate::{
  * The APIs used try claim and offer are non-blocking.
  *
  * <b>Note:</b> ExclusivePublication instances are NOT threadsafe for offer and try claim methods but are for others.
+ * Aeron#find_exclusive_publication hands out the only handle, which sends through &mut self and can be moved to the
+ * publishing thread. Dropping it releases the publication with the client conductor.
  *
  * @see Aeron#addExclusivePublication(String, int)
  * @see BufferClaim
@@ -62,7 +66,7 @@ use crate::{
 
 #[allow(dead_code)]
 pub struct ExclusivePublication {
-    conductor: Arc<Mutex<ClientConductor>>,
+    conductor: ConductorProxy,
     log_meta_data_buffer: AtomicBuffer,
     channel: CString,
     registration_id: i64,
@@ -81,9 +85,10 @@ pub struct ExclusivePublication {
 
     publication_limit: UnsafeBufferPosition,
     channel_status_id: i32,
-    is_closed: AtomicBool, // default to false
+    is_closed: Arc<AtomicBool>, // default to false, shared with the client conductor
 
     // The LogBuffers object must be dropped when last ref to it goes out of scope.
+    // IC: holding it keeps the log mapped for the appenders until this handle is dropped, even once closed
     log_buffers: Arc<LogBuffers>,
 
     // it was unique_ptr on TermAppender's
@@ -94,7 +99,7 @@ pub struct ExclusivePublication {
 impl ExclusivePublication {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        conductor: Arc<Mutex<ClientConductor>>,
+        conductor: ConductorProxy,
         channel: CString,
         registration_id: i64,
         stream_id: i32,
@@ -141,7 +146,7 @@ impl ExclusivePublication {
             term_begin_position: 0,
             publication_limit,
             channel_status_id,
-            is_closed: AtomicBool::from(false),
+            is_closed: Arc::new(AtomicBool::from(false)),
             log_buffers,
             header_writer: HeaderWriter::new(log_buffer_descriptor::default_frame_header(&log_md_buffer)),
             appenders,
@@ -450,6 +455,28 @@ impl ExclusivePublication {
         self.offer_part(buffer, 0, buffer.capacity())
     }
 
+    /**
+     * Blocking publish of a buffer containing a message. BackPressured and AdminAction are retried, applying the
+     * idle strategy between attempts, for as long as the retry policy allows.
+     *
+     * @param buffer        containing message.
+     * @param offset        offset in the buffer at which the encoded message begins.
+     * @param length        in bytes of the encoded message.
+     * @param idle_strategy to apply between attempts.
+     * @param retry_policy  deciding which errors are retried and for how long.
+     * @return The new stream position, otherwise the error of the last attempt.
+     */
+    pub fn offer_blocking(
+        &mut self,
+        buffer: AtomicBuffer,
+        offset: Index,
+        length: Index,
+        idle_strategy: &mut impl IdlePolicy,
+        retry_policy: &OfferRetryPolicy,
+    ) -> Result<i64, AeronError> {
+        retry_policy.retry(idle_strategy, || self.offer_part(buffer, offset, length))
+    }
+
     /**
      * Non-blocking publish of buffers containing a message.
      *
@@ -476,6 +503,26 @@ impl ExclusivePublication {
     //    offer(buffers, buffers + length, reserved_value_supplier)
     //}
 
+    /**
+     * Blocking claim of a range in the publication log, see try_claim. BackPressured and AdminAction are retried,
+     * applying the idle strategy between attempts, for as long as the retry policy allows.
+     *
+     * @param length        of the range to claim, in bytes.
+     * @param buffer_claim  to be populated if the claim succeeds.
+     * @param idle_strategy to apply between attempts.
+     * @param retry_policy  deciding which errors are retried and for how long.
+     * @return The new stream position, otherwise the error of the last attempt.
+     */
+    pub fn try_claim_blocking(
+        &mut self,
+        length: Index,
+        buffer_claim: &mut BufferClaim,
+        idle_strategy: &mut impl IdlePolicy,
+        retry_policy: &OfferRetryPolicy,
+    ) -> Result<i64, AeronError> {
+        retry_policy.retry(idle_strategy, || self.try_claim(length, buffer_claim))
+    }
+
     /**
      * Try to claim a range in the publication log into which a message can be written with zero copy semantics.
      * Once the message has been written then {@link BufferClaim#commit()} should be called thus making it available.
@@ -520,10 +567,7 @@ impl ExclusivePublication {
             return Err(IllegalStateError::PublicationClosed.into());
         }
 
-        self.conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .add_destination(self.registration_id, endpoint_channel)
+        self.conductor.add_destination(self.registration_id, endpoint_channel)
     }
 
     /**
@@ -536,10 +580,7 @@ impl ExclusivePublication {
             return Err(IllegalStateError::PublicationClosed.into());
         }
 
-        self.conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .remove_destination(self.registration_id, endpoint_channel)
+        self.conductor.remove_destination(self.registration_id, endpoint_channel)
     }
 
     /**
@@ -552,16 +593,18 @@ impl ExclusivePublication {
             return status_indicator_reader::NO_ID_ALLOCATED as i64;
         }
 
-        self.conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .channel_status(self.channel_status_id)
+        self.conductor.channel_status(self.channel_status_id)
     }
 
     pub fn close(&self) {
         self.is_closed.store(true, Ordering::Release);
     }
 
+    // IC: the conductor keeps this flag instead of the handle so it can close the publication with the client
+    pub(crate) fn closed_flag(&self) -> Arc<AtomicBool> {
+        self.is_closed.clone()
+    }
+
     fn new_position(&mut self, resulting_offset: Index) -> Result<i64, AeronError> {
         if resulting_offset > 0 {
             self.term_offset = resulting_offset;
@@ -631,18 +674,16 @@ impl ExclusivePublication {
 impl Drop for ExclusivePublication {
     fn drop(&mut self) {
         self.is_closed.store(true, Ordering::Release);
-        let _unused = self
-            .conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .release_exclusive_publication(self.registration_id);
+        self.conductor.release_exclusive_publication(self.registration_id);
     }
 }
 
 #[cfg(test)]
 mod tests {
     use std::ffi::CString;
-    use std::sync::{Arc, Mutex};
+    use std::sync::atomic::Ordering;
+    use std::sync::{Arc, Barrier, Mutex};
+    use std::thread;
 
     use lazy_static::lazy_static;
 
@@ -801,6 +842,7 @@ mod tests {
             let publication_limit =
                 UnsafeBufferPosition::new(conductor_guard.counter_values_buffer(), PUBLICATION_LIMIT_COUNTER_ID);
             let channel_status_indicator = StatusIndicatorReader::new(conductor_guard.counter_values_buffer(), NO_ID_ALLOCATED);
+            let conductor_proxy = conductor_guard.conductor_proxy();
             drop(conductor_guard);
 
             let log_meta_data_buffer = log_buffers.atomic_buffer(log_buffer_descriptor::LOG_META_DATA_SECTION_INDEX);
@@ -836,7 +878,7 @@ mod tests {
                 publication_limit: publication_limit.clone(),
                 channel_status_indicator,
                 publication: ExclusivePublication::new(
-                    conductor,
+                    conductor_proxy,
                     (*CHANNEL).clone(),
                     CORRELATION_ID,
                     STREAM_ID,
@@ -850,7 +892,7 @@ mod tests {
 
         fn create_pub(&mut self) {
             self.publication = ExclusivePublication::new(
-                self.conductor.clone(),
+                self.conductor.lock().unwrap().conductor_proxy(),
                 (*CHANNEL).clone(),
                 CORRELATION_ID,
                 STREAM_ID,
@@ -862,6 +904,12 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_be_movable_to_publisher_thread() {
+        fn assert_send<T: Send>() {}
+        assert_send::<ExclusivePublication>();
+    }
+
     #[test]
     fn should_report_initial_position() {
         let test = ExclusivePublicationTest::new();
@@ -919,6 +967,48 @@ mod tests {
         assert_eq!(offer_result.unwrap_err(), AeronError::PublicationClosed);
     }
 
+    #[test]
+    fn should_refuse_offers_once_closed_from_another_thread() {
+        let mut test = ExclusivePublicationTest::new();
+        test.publication_limit.set(TERM_MIN_LENGTH as i64);
+        let closed = test.publication.closed_flag();
+        let src_buffer = test.src_buffer;
+        let offering = Barrier::new(2);
+
+        thread::scope(|scope| {
+            let publication = &mut test.publication;
+            let offering = &offering;
+            let publisher = scope.spawn(move || {
+                let mut offers = 0;
+                loop {
+                    match publication.offer(src_buffer) {
+                        Err(AeronError::PublicationClosed) => return offers,
+                        _ => offers += 1,
+                    }
+                    if offers == 1 {
+                        offering.wait();
+                    }
+                }
+            });
+
+            // IC: the conductor closes a publication through the shared flag while the publisher may be offering
+            offering.wait();
+            closed.store(true, Ordering::Release);
+            assert!(publisher.join().unwrap() > 0);
+        });
+
+        // IC: closing stops offers but the handle still holds the log, so the conductor can't unmap it under an offer
+        assert_eq!(Arc::strong_count(&test.log_buffers), 2);
+        assert_eq!(
+            test.publication.offer(test.src_buffer).unwrap_err(),
+            AeronError::PublicationClosed
+        );
+        assert_eq!(
+            test.publication.try_claim(1024, &mut BufferClaim::default()).unwrap_err(),
+            AeronError::PublicationClosed
+        );
+    }
+
     #[test]
     fn should_ensure_the_publication_is_open_before_claim() {
         let mut test = ExclusivePublicationTest::new();
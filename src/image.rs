@@ -310,6 +310,26 @@ impl Image {
             )
     }
 
+    /**
+     * The number of observed active transports within the image liveness timeout.
+     *
+     * If the image is closed, then this is 0. This may also be 0 if no actual datagrams have arrived. IPC
+     * Images also will be 0.
+     *
+     * @return number of active transports. 0 if Image is closed, no datagrams yet, or IPC.
+     */
+    pub fn active_transport_count(&self) -> i32 {
+        if self.is_closed() {
+            return 0;
+        }
+
+        log_buffer_descriptor::active_transport_count(
+            &self
+                .log_buffers
+                .atomic_buffer(log_buffer_descriptor::LOG_META_DATA_SECTION_INDEX),
+        )
+    }
+
     /**
      * Poll for new messages in a stream. If new messages are found beyond the last consumed position then they
      * will be delivered via the fragment_handler_t up to a limited number of fragments as specified.
@@ -1168,6 +1188,30 @@ mod tests {
         assert_eq!(image.position(), initial_position);
     }
 
+    #[test]
+    fn should_report_active_transport_count_until_closed() {
+        let log_buf = AlignedBuffer::with_capacity(LOG_BUFFER_LENGTH);
+        let src_buf = AlignedBuffer::with_capacity(SRC_BUFFER_LENGTH);
+        let cnt_buf = AlignedBuffer::with_capacity(COUNTER_VALUES_BUFFER_LENGTH);
+        let image_test = ImageTest::new(&log_buf, &src_buf, &cnt_buf);
+
+        log_buffer_descriptor::set_active_transport_count(&image_test.log_meta_data_buffer, 2);
+        let mut image = Image::create(
+            SESSION_ID,
+            CORRELATION_ID,
+            SUBSCRIPTION_REGISTRATION_ID,
+            CString::new(SOURCE_IDENTITY).unwrap(),
+            &image_test.subscriber_position,
+            image_test.log_buffers.clone(),
+            Box::new(error_handler),
+        );
+
+        assert_eq!(image.active_transport_count(), 2);
+
+        image.close();
+        assert_eq!(image.active_transport_count(), 0);
+    }
+
     #[test]
     fn should_ensure_image_is_open_before_poll() {
         let log_buf = AlignedBuffer::with_capacity(LOG_BUFFER_LENGTH);
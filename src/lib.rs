@@ -27,8 +27,11 @@ pub mod client_conductor;
 pub mod cnc_file_descriptor;
 pub mod command;
 pub mod concurrent;
+pub mod conductor_proxy;
 pub mod context;
 pub mod counter;
+#[cfg(feature = "embedded-driver")]
+pub mod driver;
 pub mod driver_listener_adapter;
 pub mod driver_proxy;
 pub mod example_config;
@@ -36,11 +39,16 @@ pub mod exclusive_publication;
 pub mod fragment_assembler;
 pub mod heartbeat_timestamp;
 pub mod image;
+pub mod offer_retry_policy;
 pub mod protocol;
 pub mod publication;
+#[cfg(feature = "async")]
+pub mod publication_sink;
 pub mod subscription;
+#[cfg(feature = "async")]
+pub mod subscription_stream;
 pub mod utils;
 
 pub mod archive;
 pub mod agrona;
-// mod controlled_fragment_assembler;
\ No newline at end of file
+pub mod controlled_fragment_assembler;
\ No newline at end of file
@@ -0,0 +1,195 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::time::{Duration, Instant};
+
+use crate::concurrent::strategies::IdlePolicy;
+use crate::utils::errors::AeronError;
+
+/**
+ * Decides how long the blocking offer and try_claim variants of Publication and ExclusivePublication keep
+ * retrying.
+ * <p>
+ * BackPressured and AdminAction are always retried until the retry limit or the timeout, if set, is reached.
+ * NotConnected fails straight away unless retry_not_connected is set. PublicationClosed and MaxPositionExceeded
+ * can never succeed on a retry so they always fail straight away.
+ * <p>
+ * When retries run out the error from the last attempt is returned.
+ */
+#[derive(Clone, Debug, Default)]
+pub struct OfferRetryPolicy {
+    max_retries: Option<u64>,
+    timeout: Option<Duration>,
+    retry_not_connected: bool,
+}
+
+impl OfferRetryPolicy {
+    /**
+     * Policy which retries back pressure and admin actions for as long as it takes.
+     */
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn max_retries(&self) -> Option<u64> {
+        self.max_retries
+    }
+
+    /**
+     * Give up after this many retries of the first attempt.
+     */
+    pub fn set_max_retries(&mut self, max_retries: u64) -> &Self {
+        self.max_retries = Some(max_retries);
+        self
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /**
+     * Give up once this long has passed since the first attempt.
+     */
+    pub fn set_timeout(&mut self, timeout: Duration) -> &Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    pub fn retry_not_connected(&self) -> bool {
+        self.retry_not_connected
+    }
+
+    /**
+     * Retry NotConnected, i.e. wait for a subscriber to connect, rather than failing.
+     */
+    pub fn set_retry_not_connected(&mut self, retry_not_connected: bool) -> &Self {
+        self.retry_not_connected = retry_not_connected;
+        self
+    }
+
+    /**
+     * Is the error one which this policy retries.
+     */
+    pub fn is_retryable(&self, error: &AeronError) -> bool {
+        match error {
+            AeronError::BackPressured | AeronError::AdminAction => true,
+            AeronError::NotConnected => self.retry_not_connected,
+            _ => false,
+        }
+    }
+
+    /**
+     * Make attempts until one succeeds, fails with an error which is not retried or the retries run out. The idle
+     * strategy is applied between attempts and reset afterwards.
+     *
+     * @param idle_strategy to apply between attempts.
+     * @param attempt       to make, e.g. an offer.
+     * @return the result of the last attempt.
+     */
+    pub fn retry<T>(
+        &self,
+        idle_strategy: &mut impl IdlePolicy,
+        mut attempt: impl FnMut() -> Result<T, AeronError>,
+    ) -> Result<T, AeronError> {
+        let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+        let mut retries: u64 = 0;
+
+        let result = loop {
+            match attempt() {
+                Err(error) if self.is_retryable(&error) => {
+                    let out_of_retries = self.max_retries.is_some_and(|max_retries| retries >= max_retries);
+                    let out_of_time = deadline.is_some_and(|deadline| Instant::now() >= deadline);
+
+                    if out_of_retries || out_of_time {
+                        break Err(error);
+                    }
+
+                    retries += 1;
+                    idle_strategy.idle_action().perform();
+                }
+                result => break result,
+            }
+        };
+
+        idle_strategy.reset_idle();
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use crate::concurrent::strategies::BusySpinIdleStrategy;
+    use crate::offer_retry_policy::OfferRetryPolicy;
+    use crate::utils::errors::AeronError;
+
+    fn attempts(mut results: Vec<Result<i64, AeronError>>) -> impl FnMut() -> Result<i64, AeronError> {
+        results.reverse();
+        move || results.pop().unwrap_or(Err(AeronError::BackPressured))
+    }
+
+    #[test]
+    fn should_retry_back_pressure_until_success() {
+        let policy = OfferRetryPolicy::new();
+        let mut idle = BusySpinIdleStrategy::default();
+
+        let result = policy.retry(
+            &mut idle,
+            attempts(vec![Err(AeronError::BackPressured), Err(AeronError::AdminAction), Ok(128)]),
+        );
+        assert_eq!(result.unwrap(), 128);
+    }
+
+    #[test]
+    fn should_fail_fast_unless_policy_retries_not_connected() {
+        let mut policy = OfferRetryPolicy::new();
+        let mut idle = BusySpinIdleStrategy::default();
+
+        let result = policy.retry(&mut idle, attempts(vec![Err(AeronError::NotConnected), Ok(64)]));
+        assert!(matches!(result, Err(AeronError::NotConnected)));
+
+        policy.set_retry_not_connected(true);
+        let result = policy.retry(&mut idle, attempts(vec![Err(AeronError::NotConnected), Ok(64)]));
+        assert_eq!(result.unwrap(), 64);
+
+        let result = policy.retry(&mut idle, attempts(vec![Err(AeronError::PublicationClosed), Ok(64)]));
+        assert!(matches!(result, Err(AeronError::PublicationClosed)));
+
+        let result = policy.retry(&mut idle, attempts(vec![Err(AeronError::MaxPositionExceeded), Ok(64)]));
+        assert!(matches!(result, Err(AeronError::MaxPositionExceeded)));
+    }
+
+    #[test]
+    fn should_give_up_after_max_retries_or_timeout() {
+        let mut policy = OfferRetryPolicy::new();
+        policy.set_max_retries(3);
+        let mut idle = BusySpinIdleStrategy::default();
+
+        let mut count = 0;
+        let result = policy.retry(&mut idle, || -> Result<i64, AeronError> {
+            count += 1;
+            Err(AeronError::BackPressured)
+        });
+        assert!(matches!(result, Err(AeronError::BackPressured)));
+        assert_eq!(count, 4);
+
+        let mut policy = OfferRetryPolicy::new();
+        policy.set_timeout(Duration::from_millis(10));
+        let result = policy.retry(&mut idle, attempts(vec![]));
+        assert!(matches!(result, Err(AeronError::BackPressured)));
+    }
+}
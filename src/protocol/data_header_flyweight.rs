@@ -20,6 +20,12 @@ use crate::utils::types::Index;
 
 pub const DATA_HEADER_DEFN_SIZE: Index = std::mem::size_of::<DataHeaderDefn>() as Index;
 
+pub const BEGIN_FLAG: u8 = 0x80;
+pub const END_FLAG: u8 = 0x40;
+pub const EOS_FLAG: u8 = 0x20;
+pub const BEGIN_AND_END_FLAGS: u8 = BEGIN_FLAG | END_FLAG;
+pub const BEGIN_END_AND_EOS_FLAGS: u8 = BEGIN_FLAG | END_FLAG | EOS_FLAG;
+
 /**
  * HeaderFlyweight for Data Header
  * <p>
@@ -34,10 +40,9 @@ pub struct DataHeaderDefn {
     session_id: i32,
     stream_id: i32,
     term_id: i32,
-    data: [u8; 1],
+    reserved_value: i64,
 }
 
-#[allow(dead_code)]
 pub struct DataHeaderFlyweight {
     header_flyweight: HeaderFlyweight,
     m_struct: *mut DataHeaderDefn, // This is actually part of above field memory space
@@ -74,16 +79,21 @@ impl DataHeaderFlyweight {
         unsafe { (*self.m_struct).term_offset }
     }
 
+    #[inline]
+    pub fn reserved_value(&self) -> i64 {
+        unsafe { (*self.m_struct).reserved_value }
+    }
+
     #[inline]
     pub fn data(&self) -> *const u8 {
-        unsafe { (*self.m_struct).data.as_ptr() }
+        unsafe { (self.m_struct as *const u8).add(DATA_HEADER_DEFN_SIZE as usize) }
     }
 
     // Setters
     #[inline]
     pub fn set_session_id(&mut self, value: i32) {
         unsafe {
-            (*self.m_struct).stream_id = value;
+            (*self.m_struct).session_id = value;
         }
     }
 
@@ -108,8 +118,88 @@ impl DataHeaderFlyweight {
         }
     }
 
+    #[inline]
+    pub fn set_reserved_value(&mut self, value: i64) {
+        unsafe {
+            (*self.m_struct).reserved_value = value;
+        }
+    }
+
     #[inline]
     pub const fn header_length() -> Index {
         DATA_HEADER_DEFN_SIZE
     }
+
+    // Parent Getters
+    #[inline]
+    pub fn frame_length(&self) -> i32 {
+        self.header_flyweight.frame_length()
+    }
+
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.header_flyweight.version()
+    }
+
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.header_flyweight.flags()
+    }
+
+    #[inline]
+    pub fn header_type(&self) -> u16 {
+        self.header_flyweight.header_type()
+    }
+
+    // Parent Setters
+    #[inline]
+    pub fn set_frame_length(&mut self, value: i32) {
+        self.header_flyweight.set_frame_length(value);
+    }
+
+    #[inline]
+    pub fn set_version(&mut self, value: u8) {
+        self.header_flyweight.set_version(value);
+    }
+
+    #[inline]
+    pub fn set_flags(&mut self, value: u8) {
+        self.header_flyweight.set_flags(value);
+    }
+
+    #[inline]
+    pub fn set_header_type(&mut self, value: u16) {
+        self.header_flyweight.set_header_type(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AlignedBuffer;
+    use crate::concurrent::logbuffer::data_frame_header;
+
+    #[test]
+    fn should_match_data_frame_header_layout() {
+        assert_eq!(DataHeaderFlyweight::header_length(), data_frame_header::LENGTH);
+
+        let aligned = AlignedBuffer::with_capacity(64);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+        let mut flyweight = DataHeaderFlyweight::new(buffer, 0);
+
+        flyweight.set_session_id(11);
+        flyweight.set_stream_id(22);
+        flyweight.set_term_id(33);
+        flyweight.set_term_offset(64);
+
+        let header = buffer.get::<data_frame_header::DataFrameHeaderDefn>(0);
+        assert_eq!({ header.session_id }, 11);
+        assert_eq!({ header.stream_id }, 22);
+        assert_eq!({ header.term_id }, 33);
+        assert_eq!({ header.term_offset }, 64);
+        assert_eq!(
+            flyweight.data(),
+            unsafe { buffer.buffer().add(data_frame_header::LENGTH as usize) } as *const u8
+        );
+    }
 }
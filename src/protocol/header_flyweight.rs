@@ -27,9 +27,9 @@ pub const HEADER_DEFN_SIZE: Index = std::mem::size_of::<HeaderDefn>() as Index;
 #[derive(Copy, Clone)]
 pub struct HeaderDefn {
     frame_length: i32,
-    version: i8,
-    flags: i8,
-    h_type: i16,
+    version: u8,
+    flags: u8,
+    h_type: u16,
 }
 
 pub(crate) struct HeaderFlyweight {
@@ -42,4 +42,54 @@ impl HeaderFlyweight {
             flyweight: Flyweight::new(buffer, offset),
         }
     }
+
+    // Getters
+    #[inline]
+    pub fn frame_length(&self) -> i32 {
+        unsafe { (*self.flyweight.m_struct).frame_length }
+    }
+
+    #[inline]
+    pub fn version(&self) -> u8 {
+        unsafe { (*self.flyweight.m_struct).version }
+    }
+
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        unsafe { (*self.flyweight.m_struct).flags }
+    }
+
+    #[inline]
+    pub fn header_type(&self) -> u16 {
+        unsafe { (*self.flyweight.m_struct).h_type }
+    }
+
+    // Setters
+    #[inline]
+    pub fn set_frame_length(&mut self, value: i32) {
+        unsafe {
+            (*self.flyweight.m_struct).frame_length = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_version(&mut self, value: u8) {
+        unsafe {
+            (*self.flyweight.m_struct).version = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_flags(&mut self, value: u8) {
+        unsafe {
+            (*self.flyweight.m_struct).flags = value;
+        }
+    }
+
+    #[inline]
+    pub fn set_header_type(&mut self, value: u16) {
+        unsafe {
+            (*self.flyweight.m_struct).h_type = value;
+        }
+    }
 }
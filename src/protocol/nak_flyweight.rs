@@ -37,7 +37,6 @@ pub struct NakDefn {
     length: i32,
 }
 
-#[allow(dead_code)]
 pub struct NakFlyweight {
     header_flyweight: HeaderFlyweight,
     m_struct: *mut NakDefn, // This is actually part of above field memory space
@@ -54,6 +53,11 @@ impl NakFlyweight {
     }
 
     // Getters
+    #[inline]
+    pub fn session_id(&self) -> i32 {
+        unsafe { (*self.m_struct).session_id }
+    }
+
     #[inline]
     pub fn stream_id(&self) -> i32 {
         unsafe { (*self.m_struct).stream_id }
@@ -66,7 +70,7 @@ impl NakFlyweight {
 
     #[inline]
     pub fn term_offset(&self) -> i32 {
-        unsafe { (*self.m_struct).term_id }
+        unsafe { (*self.m_struct).term_offset }
     }
 
     #[inline]
@@ -75,6 +79,13 @@ impl NakFlyweight {
     }
 
     // Setters
+    #[inline]
+    pub fn set_session_id(&mut self, value: i32) {
+        unsafe {
+            (*self.m_struct).session_id = value;
+        }
+    }
+
     #[inline]
     pub fn set_stream_id(&mut self, value: i32) {
         unsafe {
@@ -92,7 +103,7 @@ impl NakFlyweight {
     #[inline]
     pub fn set_term_offset(&mut self, value: i32) {
         unsafe {
-            (*self.m_struct).term_id = value;
+            (*self.m_struct).term_offset = value;
         }
     }
 
@@ -107,4 +118,70 @@ impl NakFlyweight {
     pub const fn header_length() -> Index {
         NAK_DEFN_SIZE
     }
+
+    // Parent Getters
+    #[inline]
+    pub fn frame_length(&self) -> i32 {
+        self.header_flyweight.frame_length()
+    }
+
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.header_flyweight.version()
+    }
+
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.header_flyweight.flags()
+    }
+
+    #[inline]
+    pub fn header_type(&self) -> u16 {
+        self.header_flyweight.header_type()
+    }
+
+    // Parent Setters
+    #[inline]
+    pub fn set_frame_length(&mut self, value: i32) {
+        self.header_flyweight.set_frame_length(value);
+    }
+
+    #[inline]
+    pub fn set_version(&mut self, value: u8) {
+        self.header_flyweight.set_version(value);
+    }
+
+    #[inline]
+    pub fn set_flags(&mut self, value: u8) {
+        self.header_flyweight.set_flags(value);
+    }
+
+    #[inline]
+    pub fn set_header_type(&mut self, value: u16) {
+        self.header_flyweight.set_header_type(value);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::concurrent::atomic_buffer::AlignedBuffer;
+
+    #[test]
+    fn should_keep_term_id_and_term_offset_apart() {
+        let aligned = AlignedBuffer::with_capacity(64);
+        let mut flyweight = NakFlyweight::new(AtomicBuffer::from_aligned(&aligned), 0);
+
+        flyweight.set_session_id(1);
+        flyweight.set_stream_id(2);
+        flyweight.set_term_id(3);
+        flyweight.set_term_offset(4);
+        flyweight.set_length(5);
+
+        assert_eq!(flyweight.session_id(), 1);
+        assert_eq!(flyweight.stream_id(), 2);
+        assert_eq!(flyweight.term_id(), 3);
+        assert_eq!(flyweight.term_offset(), 4);
+        assert_eq!(flyweight.length(), 5);
+    }
 }
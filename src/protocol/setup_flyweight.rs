@@ -39,7 +39,6 @@ pub struct SetupDefn {
     mtu: i32,
 }
 
-#[allow(dead_code)]
 pub struct SetupFlyweight {
     header_flyweight: HeaderFlyweight,
     m_struct: *mut SetupDefn, // This is actually part of above field memory space
@@ -145,4 +144,46 @@ impl SetupFlyweight {
     pub const fn header_length() -> Index {
         SETUP_DEFN_SIZE
     }
+
+    // Parent Getters
+    #[inline]
+    pub fn frame_length(&self) -> i32 {
+        self.header_flyweight.frame_length()
+    }
+
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.header_flyweight.version()
+    }
+
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.header_flyweight.flags()
+    }
+
+    #[inline]
+    pub fn header_type(&self) -> u16 {
+        self.header_flyweight.header_type()
+    }
+
+    // Parent Setters
+    #[inline]
+    pub fn set_frame_length(&mut self, value: i32) {
+        self.header_flyweight.set_frame_length(value);
+    }
+
+    #[inline]
+    pub fn set_version(&mut self, value: u8) {
+        self.header_flyweight.set_version(value);
+    }
+
+    #[inline]
+    pub fn set_flags(&mut self, value: u8) {
+        self.header_flyweight.set_flags(value);
+    }
+
+    #[inline]
+    pub fn set_header_type(&mut self, value: u16) {
+        self.header_flyweight.set_header_type(value);
+    }
 }
@@ -20,6 +20,11 @@ use crate::utils::types::Index;
 
 pub const STATUS_MESSAGE_DEFN_SIZE: Index = std::mem::size_of::<StatusMessageDefn>() as Index;
 
+/**
+ * Flag asking the publication to send a Setup frame, used by receivers which have data but no image.
+ */
+pub const SEND_SETUP_FLAG: u8 = 0x80;
+
 /**
  * Flow/Congestion control message to send feedback from subscriptions to publications.
  *
@@ -57,7 +62,6 @@ pub struct StatusMessageDefn {
     receiver_window: i32,
 }
 
-#[allow(dead_code)]
 pub struct StatusMessageFlyweight {
     header_flyweight: HeaderFlyweight,
     m_struct: *mut StatusMessageDefn, // This is actually part of above field memory space
@@ -139,4 +143,46 @@ impl StatusMessageFlyweight {
     pub const fn header_length() -> Index {
         STATUS_MESSAGE_DEFN_SIZE
     }
+
+    // Parent Getters
+    #[inline]
+    pub fn frame_length(&self) -> i32 {
+        self.header_flyweight.frame_length()
+    }
+
+    #[inline]
+    pub fn version(&self) -> u8 {
+        self.header_flyweight.version()
+    }
+
+    #[inline]
+    pub fn flags(&self) -> u8 {
+        self.header_flyweight.flags()
+    }
+
+    #[inline]
+    pub fn header_type(&self) -> u16 {
+        self.header_flyweight.header_type()
+    }
+
+    // Parent Setters
+    #[inline]
+    pub fn set_frame_length(&mut self, value: i32) {
+        self.header_flyweight.set_frame_length(value);
+    }
+
+    #[inline]
+    pub fn set_version(&mut self, value: u8) {
+        self.header_flyweight.set_version(value);
+    }
+
+    #[inline]
+    pub fn set_flags(&mut self, value: u8) {
+        self.header_flyweight.set_flags(value);
+    }
+
+    #[inline]
+    pub fn set_header_type(&mut self, value: u16) {
+        self.header_flyweight.set_header_type(value);
+    }
 }
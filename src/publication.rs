@@ -18,12 +18,11 @@ use std::{
     ffi::CString,
     sync::{
         atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
+        Arc,
     },
 };
 
 use crate::{
-    client_conductor::ClientConductor,
     concurrent::{
         atomic_buffer::AtomicBuffer,
         logbuffer::{
@@ -35,8 +34,11 @@ use crate::{
         },
         position::{ReadablePosition, UnsafeBufferPosition},
         status::status_indicator_reader,
+        strategies::IdlePolicy,
     },
+    conductor_proxy::ConductorProxy,
     log,
+    offer_retry_policy::OfferRetryPolicy,
     utils::{
         bit_utils::number_of_trailing_zeroes,
         errors::{AeronError, IllegalArgumentError, IllegalStateError},
@@ -59,14 +61,17 @@ pub trait BulkPubSize {
  * <p>
  * The APIs used to send are all non-blocking.
  * <p>
- * Note: Publication instances are threadsafe and can be shared between publisher threads.
+ * Note: Publication instances are threadsafe and can be shared between publisher threads. Aeron#find_publication
+ * hands out an Arc<Publication> and all send methods take &self, so no lock is needed around them. The publication
+ * is released with the client conductor once the last handle is dropped. Channel status and destination commands go
+ * through a ConductorProxy and do not wait for the client conductor lock either.
  * @see Aeron#add_publication
  * @see Aeron#findPublication
  */
 
 #[allow(dead_code)]
 pub struct Publication {
-    conductor: Arc<Mutex<ClientConductor>>,
+    conductor: ConductorProxy,
     log_meta_data_buffer: AtomicBuffer,
     channel: CString,
     registration_id: i64,
@@ -93,7 +98,7 @@ pub struct Publication {
 impl Publication {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        conductor: Arc<Mutex<ClientConductor>>,
+        conductor: ConductorProxy,
         channel: CString,
         registration_id: i64,
         original_registration_id: i64,
@@ -441,6 +446,28 @@ impl Publication {
         self.offer_part(buffer, 0, buffer.capacity())
     }
 
+    /**
+     * Blocking publish of a buffer containing a message. BackPressured and AdminAction are retried, applying the
+     * idle strategy between attempts, for as long as the retry policy allows.
+     *
+     * @param buffer        containing message.
+     * @param offset        offset in the buffer at which the encoded message begins.
+     * @param length        in bytes of the encoded message.
+     * @param idle_strategy to apply between attempts.
+     * @param retry_policy  deciding which errors are retried and for how long.
+     * @return The new stream position, otherwise the error of the last attempt.
+     */
+    pub fn offer_blocking(
+        &self,
+        buffer: AtomicBuffer,
+        offset: Index,
+        length: Index,
+        idle_strategy: &mut impl IdlePolicy,
+        retry_policy: &OfferRetryPolicy,
+    ) -> Result<u64, AeronError> {
+        retry_policy.retry(idle_strategy, || self.offer_part(buffer, offset, length))
+    }
+
     /**
      * Non-blocking publish of buffers containing a message.
      *
@@ -476,7 +503,7 @@ impl Publication {
      * {@link #ADMIN_ACTION} or {@link #CLOSED}.
      */
     pub fn offer_bulk(
-        &mut self,
+        &self,
         buffers: Vec<AtomicBuffer>,
         reserved_value_supplier: OnReservedValueSupplier,
     ) -> Result<u64, AeronError> {
@@ -489,7 +516,7 @@ impl Publication {
         if !self.is_closed() {
             let limit = self.publication_limit.get_volatile();
             let term_count = log_buffer_descriptor::active_term_count(&self.log_meta_data_buffer);
-            let term_appender = &mut self.appenders[(log_buffer_descriptor::index_by_term_count(term_count as i64)) as usize];
+            let term_appender = &self.appenders[(log_buffer_descriptor::index_by_term_count(term_count as i64)) as usize];
             let raw_tail = term_appender.raw_tail_volatile();
             let term_offset = raw_tail & 0xFFFF_FFFF;
             let term_id = log_buffer_descriptor::term_id(raw_tail);
@@ -544,6 +571,26 @@ impl Publication {
         }
     }
 
+    /**
+     * Blocking claim of a range in the publication log, see try_claim. BackPressured and AdminAction are retried,
+     * applying the idle strategy between attempts, for as long as the retry policy allows.
+     *
+     * @param length        of the range to claim, in bytes.
+     * @param buffer_claim  to be populated if the claim succeeds.
+     * @param idle_strategy to apply between attempts.
+     * @param retry_policy  deciding which errors are retried and for how long.
+     * @return The new stream position, otherwise the error of the last attempt.
+     */
+    pub fn try_claim_blocking(
+        &self,
+        length: Index,
+        buffer_claim: &mut BufferClaim,
+        idle_strategy: &mut impl IdlePolicy,
+        retry_policy: &OfferRetryPolicy,
+    ) -> Result<u64, AeronError> {
+        retry_policy.retry(idle_strategy, || self.try_claim(length, buffer_claim))
+    }
+
     /**
      * Try to claim a range in the publication log into which a message can be written with zero copy semantics.
      * Once the message has been written then {@link BufferClaim#commit(&self)} should be called thus making it available.
@@ -557,13 +604,13 @@ impl Publication {
      * @throws IllegalArgumentException if the length is greater than max payload length within an MTU.
      * @see BufferClaim::commit
      */
-    pub fn try_claim(&mut self, length: Index, buffer_claim: &mut BufferClaim) -> Result<u64, AeronError> {
+    pub fn try_claim(&self, length: Index, buffer_claim: &mut BufferClaim) -> Result<u64, AeronError> {
         self.check_payload_length(length)?;
 
         if !self.is_closed() {
             let limit = self.publication_limit.get_volatile();
             let term_count = log_buffer_descriptor::active_term_count(&self.log_meta_data_buffer);
-            let term_appender = &mut self.appenders[log_buffer_descriptor::index_by_term_count(term_count as i64) as usize];
+            let term_appender = &self.appenders[log_buffer_descriptor::index_by_term_count(term_count as i64) as usize];
             let raw_tail = term_appender.raw_tail_volatile();
             let term_offset = raw_tail & 0xFFFF_FFFF;
             let term_id = log_buffer_descriptor::term_id(raw_tail);
@@ -598,14 +645,12 @@ impl Publication {
      * @param endpoint_channel for the destination to add
      * @    correlation id for the add command
      */
-    pub fn add_destination(&mut self, endpoint_channel: CString) -> Result<i64, AeronError> {
+    pub fn add_destination(&self, endpoint_channel: CString) -> Result<i64, AeronError> {
         if self.is_closed() {
             return Err(IllegalStateError::PublicationClosed.into());
         }
 
         self.conductor
-            .lock()
-            .expect("Mutex poisoned")
             .add_destination(self.original_registration_id, endpoint_channel)
     }
 
@@ -615,14 +660,12 @@ impl Publication {
      * @param endpoint_channel for the destination to remove
      * @    correlation id for the remove command
      */
-    pub fn remove_destination(&mut self, endpoint_channel: CString) -> Result<i64, AeronError> {
+    pub fn remove_destination(&self, endpoint_channel: CString) -> Result<i64, AeronError> {
         if self.is_closed() {
             return Err(IllegalStateError::PublicationClosed.into());
         }
 
         self.conductor
-            .lock()
-            .expect("Mutex poisoned")
             .remove_destination(self.original_registration_id, endpoint_channel)
     }
 
@@ -645,11 +688,8 @@ impl Publication {
      * or Publication::remove_destination
      * @    true for added or false if not.
      */
-    pub fn find_destination_response(&mut self, correlation_id: i64) -> Result<bool, AeronError> {
-        self.conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .find_destination_response(correlation_id)
+    pub fn find_destination_response(&self, correlation_id: i64) -> Result<bool, AeronError> {
+        self.conductor.find_destination_response(correlation_id)
     }
 
     /**
@@ -662,10 +702,7 @@ impl Publication {
             return status_indicator_reader::NO_ID_ALLOCATED as i64;
         }
 
-        self.conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .channel_status(self.channel_status_id)
+        self.conductor.channel_status(self.channel_status_id)
     }
 
     pub fn close(&self) {
@@ -674,14 +711,7 @@ impl Publication {
 
     pub fn release(&self) {
         self.is_closed.store(true, Ordering::Release);
-        if let Err(err) = self
-            .conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .release_publication(self.registration_id)
-        {
-            log!(error, "Release publication error: {:?}", err);
-        }
+        self.conductor.release_publication(self.registration_id);
     }
 
     fn new_position(
@@ -916,6 +946,7 @@ mod tests {
             let publication_limit =
                 UnsafeBufferPosition::new(conductor_guard.counter_values_buffer(), PUBLICATION_LIMIT_COUNTER_ID);
             let channel_status_indicator = StatusIndicatorReader::new(conductor_guard.counter_values_buffer(), NO_ID_ALLOCATED);
+            let conductor_proxy = conductor_guard.conductor_proxy();
             drop(conductor_guard);
 
             let log_meta_data_buffer = log_buffers.atomic_buffer(log_buffer_descriptor::LOG_META_DATA_SECTION_INDEX);
@@ -955,7 +986,7 @@ mod tests {
                 publication_limit: publication_limit.clone(),
                 channel_status_indicator,
                 publication: Publication::new(
-                    conductor,
+                    conductor_proxy,
                     (*CHANNEL).clone(),
                     CORRELATION_ID,
                     ORIGINAL_REGISTRATION_ID,
@@ -969,6 +1000,12 @@ mod tests {
         }
     }
 
+    #[test]
+    fn should_be_shareable_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Publication>();
+    }
+
     #[test]
     fn should_report_initial_position() {
         let test = PublicationTest::new();
@@ -1029,7 +1066,7 @@ mod tests {
 
     #[test]
     fn should_ensure_the_publication_is_open_before_claim() {
-        let mut test = PublicationTest::new();
+        let test = PublicationTest::new();
         let mut buffer_claim = BufferClaim::default();
 
         test.publication.close();
@@ -1131,7 +1168,7 @@ mod tests {
 
     #[test]
     fn should_rotate_when_claim_trips() {
-        let mut test = PublicationTest::new();
+        let test = PublicationTest::new();
         let active_index = log_buffer_descriptor::index_by_term(TERM_ID_1, TERM_ID_1);
         let initial_position = TERM_MIN_LENGTH - LENGTH;
 
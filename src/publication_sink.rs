@@ -0,0 +1,359 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::ffi::CString;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures::future::poll_fn;
+use futures::sink::Sink;
+
+use crate::{
+    aeron::Aeron,
+    concurrent::{
+        async_idle::AsyncIdle,
+        atomic_buffer::AtomicBuffer,
+        strategies::{BackOffIdleStrategy, IdlePolicy},
+    },
+    exclusive_publication::ExclusivePublication,
+    publication::Publication,
+    utils::{
+        errors::{AeronError, GenericError},
+        types::Index,
+    },
+};
+
+/**
+ * Non-blocking offer common to Publication and ExclusivePublication.
+ */
+pub trait OfferPublication {
+    /**
+     * Non-blocking publish of a buffer containing a message.
+     *
+     * @return the new stream position or the reason the offer failed.
+     */
+    fn offer_part(&mut self, buffer: AtomicBuffer, offset: Index, length: Index) -> Result<i64, AeronError>;
+
+    fn is_connected(&self) -> bool;
+
+    fn is_closed(&self) -> bool;
+}
+
+impl OfferPublication for Arc<Publication> {
+    fn offer_part(&mut self, buffer: AtomicBuffer, offset: Index, length: Index) -> Result<i64, AeronError> {
+        Publication::offer_part(self, buffer, offset, length).map(|position| position as i64)
+    }
+
+    fn is_connected(&self) -> bool {
+        Publication::is_connected(self)
+    }
+
+    fn is_closed(&self) -> bool {
+        Publication::is_closed(self)
+    }
+}
+
+impl OfferPublication for ExclusivePublication {
+    fn offer_part(&mut self, buffer: AtomicBuffer, offset: Index, length: Index) -> Result<i64, AeronError> {
+        ExclusivePublication::offer_part(self, buffer, offset, length)
+    }
+
+    fn is_connected(&self) -> bool {
+        ExclusivePublication::is_connected(self)
+    }
+
+    fn is_closed(&self) -> bool {
+        ExclusivePublication::is_closed(self)
+    }
+}
+
+/**
+ * What an offer does when the publication has no connected subscribers.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotConnectedPolicy {
+    /// Keep retrying with back-off until a subscriber connects.
+    AwaitConnected,
+    /// Fail the offer with AeronError::NotConnected.
+    Fail,
+}
+
+/**
+ * Adapts a Publication or ExclusivePublication to async code, either through offer() or as a Sink of messages.
+ * The sink owns its handle: a clone of the Arc<Publication>, or the ExclusivePublication itself.
+ * <p>
+ * BackPressured and AdminAction are retried, backing off according to the IdlePolicy. NotConnected is retried
+ * or returned according to the NotConnectedPolicy. Any other error, such as PublicationClosed, is returned.
+ * <p>
+ * The Sink holds a single message: poll_ready() only completes once the previous message has been offered.
+ */
+pub struct PublicationSink<T: OfferPublication, P: IdlePolicy = BackOffIdleStrategy> {
+    publication: T,
+    not_connected_policy: NotConnectedPolicy,
+    idle: AsyncIdle<P>,
+    pending: Option<Vec<u8>>,
+}
+
+impl<T: OfferPublication> PublicationSink<T, BackOffIdleStrategy> {
+    /**
+     * Wrap a publication, backing off with the default BackOffIdleStrategy when an offer has to be retried.
+     *
+     * @param publication          to offer messages to.
+     * @param not_connected_policy for offers made while no subscriber is connected.
+     */
+    pub fn new(publication: T, not_connected_policy: NotConnectedPolicy) -> Self {
+        Self::with_idle_policy(publication, not_connected_policy, BackOffIdleStrategy::default())
+    }
+}
+
+impl<T: OfferPublication, P: IdlePolicy> PublicationSink<T, P> {
+    /**
+     * Wrap a publication with the given policy for backing off between retries.
+     *
+     * @param publication          to offer messages to.
+     * @param not_connected_policy for offers made while no subscriber is connected.
+     * @param idle_policy          deciding between spinning, yielding and parking before a retry.
+     */
+    pub fn with_idle_policy(publication: T, not_connected_policy: NotConnectedPolicy, idle_policy: P) -> Self {
+        Self {
+            publication,
+            not_connected_policy,
+            idle: AsyncIdle::new(idle_policy),
+            pending: None,
+        }
+    }
+
+    pub fn publication(&self) -> &T {
+        &self.publication
+    }
+
+    pub fn into_publication(self) -> T {
+        self.publication
+    }
+
+    pub fn not_connected_policy(&self) -> NotConnectedPolicy {
+        self.not_connected_policy
+    }
+
+    /**
+     * Offer a message, waiting out back pressure.
+     *
+     * @param message to be offered.
+     * @return the new stream position or the error which could not be retried.
+     */
+    pub async fn offer(&mut self, message: &[u8]) -> Result<i64, AeronError> {
+        let Self {
+            publication,
+            not_connected_policy,
+            idle,
+            ..
+        } = self;
+
+        poll_fn(|cx| poll_offer(publication, *not_connected_policy, idle, cx, message)).await
+    }
+
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), AeronError>> {
+        let Self {
+            publication,
+            not_connected_policy,
+            idle,
+            pending,
+        } = self;
+
+        if let Some(message) = pending {
+            let result = futures::ready!(poll_offer(publication, *not_connected_policy, idle, cx, message));
+            *pending = None;
+            result?;
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+fn poll_offer<T: OfferPublication, P: IdlePolicy>(
+    publication: &mut T,
+    not_connected_policy: NotConnectedPolicy,
+    idle: &mut AsyncIdle<P>,
+    cx: &mut Context<'_>,
+    message: &[u8],
+) -> Poll<Result<i64, AeronError>> {
+    // IC: offer only ever reads from the buffer
+    let buffer = AtomicBuffer::new(message.as_ptr() as *mut u8, message.len() as Index);
+
+    loop {
+        let result = publication.offer_part(buffer, 0, buffer.capacity());
+
+        match result {
+            Err(AeronError::BackPressured) | Err(AeronError::AdminAction) => {}
+            Err(AeronError::NotConnected) if not_connected_policy == NotConnectedPolicy::AwaitConnected => {}
+            result => {
+                idle.reset();
+                return Poll::Ready(result);
+            }
+        }
+
+        futures::ready!(idle.poll_idle(cx));
+    }
+}
+
+impl<T: OfferPublication + Unpin, P: IdlePolicy + Unpin, B: AsRef<[u8]>> Sink<B> for PublicationSink<T, P> {
+    type Error = AeronError;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), AeronError>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: B) -> Result<(), AeronError> {
+        self.get_mut().pending = Some(item.as_ref().to_vec());
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), AeronError>> {
+        self.get_mut().poll_pending(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), AeronError>> {
+        // IC: closing the sink leaves the publication open, it is released when the sink is dropped
+        self.get_mut().poll_pending(cx)
+    }
+}
+
+/**
+ * Add a Publication and wait for the media driver to register it.
+ *
+ * @param aeron     client to add the publication with.
+ * @param channel   for the publication.
+ * @param stream_id for the publication.
+ * @return the ready Publication or the error reported for its registration.
+ */
+pub async fn add_publication(aeron: &mut Aeron, channel: CString, stream_id: i32) -> Result<Arc<Publication>, AeronError> {
+    let registration_id = aeron.add_publication(channel, stream_id)?;
+    let mut idle = AsyncIdle::new(BackOffIdleStrategy::default());
+
+    loop {
+        match aeron.find_publication(registration_id) {
+            Err(AeronError::PublicationNotReady(_)) => idle.idle().await,
+            result => return result,
+        }
+    }
+}
+
+/**
+ * Add an ExclusivePublication and wait for the media driver to register it.
+ *
+ * @param aeron     client to add the publication with.
+ * @param channel   for the publication.
+ * @param stream_id for the publication.
+ * @return the ready ExclusivePublication or the error reported for its registration.
+ */
+pub async fn add_exclusive_publication(
+    aeron: &mut Aeron,
+    channel: CString,
+    stream_id: i32,
+) -> Result<ExclusivePublication, AeronError> {
+    let registration_id = aeron.add_exclusive_publication(channel, stream_id)?;
+    let mut idle = AsyncIdle::new(BackOffIdleStrategy::default());
+
+    loop {
+        match aeron.find_exclusive_publication(registration_id) {
+            Err(AeronError::Generic(GenericError::ExclusivePublicationNotReadyYet { .. })) => idle.idle().await,
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::VecDeque;
+
+    use futures::executor::block_on;
+    use futures::sink::SinkExt;
+
+    use crate::concurrent::atomic_buffer::AtomicBuffer;
+    use crate::publication_sink::{NotConnectedPolicy, OfferPublication, PublicationSink};
+    use crate::utils::{errors::AeronError, types::Index};
+
+    #[derive(Default)]
+    struct ScriptedPublication {
+        failures: VecDeque<AeronError>,
+        offered: Vec<Vec<u8>>,
+    }
+
+    impl OfferPublication for ScriptedPublication {
+        fn offer_part(&mut self, buffer: AtomicBuffer, offset: Index, length: Index) -> Result<i64, AeronError> {
+            match self.failures.pop_front() {
+                Some(err) => Err(err),
+                None => {
+                    self.offered.push(buffer.as_sub_slice(offset, length).to_vec());
+                    Ok(self.offered.len() as i64 * 64)
+                }
+            }
+        }
+
+        fn is_connected(&self) -> bool {
+            true
+        }
+
+        fn is_closed(&self) -> bool {
+            false
+        }
+    }
+
+    fn scripted(failures: Vec<AeronError>) -> ScriptedPublication {
+        ScriptedPublication {
+            failures: failures.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_retry_back_pressure_and_admin_action() {
+        let publication = scripted(vec![
+            AeronError::BackPressured,
+            AeronError::AdminAction,
+            AeronError::NotConnected,
+            AeronError::BackPressured,
+        ]);
+        let mut sink = PublicationSink::new(publication, NotConnectedPolicy::AwaitConnected);
+
+        assert_eq!(block_on(sink.offer(b"hello")).unwrap(), 64);
+        assert_eq!(sink.publication().offered, vec![b"hello".to_vec()]);
+    }
+
+    #[test]
+    fn should_fail_when_not_connected_or_closed() {
+        let publication = scripted(vec![AeronError::NotConnected, AeronError::PublicationClosed]);
+        let mut sink = PublicationSink::new(publication, NotConnectedPolicy::Fail);
+
+        assert!(matches!(block_on(sink.offer(b"a")), Err(AeronError::NotConnected)));
+        assert!(matches!(block_on(sink.offer(b"a")), Err(AeronError::PublicationClosed)));
+        assert!(sink.publication().offered.is_empty());
+    }
+
+    #[test]
+    fn should_send_messages_through_sink() {
+        let publication = scripted(vec![AeronError::BackPressured]);
+        let mut sink = PublicationSink::new(publication, NotConnectedPolicy::Fail);
+
+        block_on(async {
+            sink.send(vec![1u8, 2, 3]).await.unwrap();
+            sink.send(&[4u8][..]).await.unwrap();
+        });
+
+        assert_eq!(sink.publication().offered, vec![vec![1, 2, 3], vec![4]]);
+    }
+}
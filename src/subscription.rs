@@ -16,27 +16,25 @@
 
 use std::{
     ffi::CString,
-    sync::{
-        atomic::{AtomicBool, Ordering},
-        Arc, Mutex,
-    },
+    sync::atomic::{AtomicBool, Ordering},
 };
 
 use crate::utils::errors::{GenericError, IllegalStateError};
 use crate::{
-    client_conductor::ClientConductor,
+    channel_uri::{ChannelUri, ENDPOINT_PARAM_NAME},
     concurrent::{
         atomic_buffer::AtomicBuffer,
         atomic_vec::AtomicVec,
         logbuffer::{header::Header, term_scan::BlockHandler},
-        status::status_indicator_reader,
+        status::{local_socket_address_status, status_indicator_reader},
     },
+    conductor_proxy::ConductorProxy,
     image::{ControlledPollAction, Image},
     utils::{errors::AeronError, types::Index},
 };
 
 pub struct Subscription {
-    conductor: Arc<Mutex<ClientConductor>>,
+    conductor: ConductorProxy,
     channel: CString,
     channel_status_id: i32,
     round_robin_index: Index,
@@ -50,7 +48,7 @@ pub struct Subscription {
 
 impl Subscription {
     pub fn new(
-        conductor: Arc<Mutex<ClientConductor>>,
+        conductor: ConductorProxy,
         registration_id: i64,
         channel: CString,
         stream_id: i32,
@@ -111,8 +109,6 @@ impl Subscription {
 
         if let Ok(endpoint_channel_cstr) = CString::new(endpoint_channel) {
             self.conductor
-                .lock()
-                .expect("Mutex poisoned")
                 .add_rcv_destination(self.registration_id, endpoint_channel_cstr)
         } else {
             Err(GenericError::StringToCStringConversionFailed.into())
@@ -126,8 +122,6 @@ impl Subscription {
 
         if let Ok(endpoint_channel_cstr) = CString::new(endpoint_channel) {
             self.conductor
-                .lock()
-                .expect("Mutex poisoned")
                 .remove_rcv_destination(self.registration_id, endpoint_channel_cstr)
         } else {
             Err(GenericError::StringToCStringConversionFailed.into())
@@ -135,10 +129,7 @@ impl Subscription {
     }
 
     pub fn find_destination_response(&self, correlation_id: i64) -> Result<bool, AeronError> {
-        self.conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .find_destination_response(correlation_id)
+        self.conductor.find_destination_response(correlation_id)
     }
 
     pub fn channel_status(&self) -> i64 {
@@ -146,10 +137,61 @@ impl Subscription {
             return status_indicator_reader::NO_ID_ALLOCATED as i64;
         }
 
-        self.conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .channel_status(self.channel_status_id)
+        self.conductor.channel_status(self.channel_status_id)
+    }
+
+    /**
+     * Fetches the local socket addresses for this subscription. If the channel is not
+     * CHANNEL_ENDPOINT_ACTIVE, then this will return an empty list.
+     *
+     * @return the list of active bound local socket addresses.
+     */
+    pub fn local_socket_addresses(&self) -> Vec<String> {
+        let channel_status = self.channel_status();
+        let counters_reader = self.conductor.counters_reader();
+
+        match counters_reader {
+            Ok(counters_reader) => {
+                local_socket_address_status::find_addresses(&counters_reader, channel_status, self.channel_status_id)
+            }
+            Err(_) => Vec::new(),
+        }
+    }
+
+    /**
+     * Resolve channel endpoint and replace it with the port from the ephemeral range when 0 was provided. If there
+     * are no addresses, or if there is more than one, returned from local_socket_addresses() then the original
+     * channel is returned.
+     * <p>
+     * If the channel is not CHANNEL_ENDPOINT_ACTIVE, then None will be returned.
+     *
+     * @return channel URI string with an endpoint being resolved to the allocated port.
+     */
+    pub fn try_resolve_channel_endpoint_port(&self) -> Option<String> {
+        let channel = self.channel.to_string_lossy().into_owned();
+
+        if status_indicator_reader::CHANNEL_ENDPOINT_ACTIVE != self.channel_status() {
+            return None;
+        }
+
+        let local_socket_addresses = self.local_socket_addresses();
+        if local_socket_addresses.len() == 1 {
+            if let Ok(uri) = ChannelUri::parse(&channel) {
+                let mut uri = uri.lock().expect("Mutex poisoned");
+                let endpoint = uri.get(ENDPOINT_PARAM_NAME).to_string();
+
+                if endpoint.ends_with(":0") {
+                    let resolved_endpoint = &local_socket_addresses[0];
+                    let port_index = resolved_endpoint.rfind(':').unwrap_or(0);
+                    let new_endpoint = format!("{}{}", &endpoint[..endpoint.len() - 2], &resolved_endpoint[port_index..]);
+                    uri.put(ENDPOINT_PARAM_NAME, new_endpoint);
+
+                    return Some(uri.to_string());
+                }
+            }
+        }
+
+        Some(channel)
     }
 
     /**
@@ -239,7 +281,7 @@ impl Subscription {
      */
     pub fn controlled_poll(
         &mut self,
-        fragment_handler: impl FnMut(&AtomicBuffer, Index, Index, &Header) -> Result<ControlledPollAction, AeronError> + Copy,
+        mut fragment_handler: impl FnMut(&AtomicBuffer, Index, Index, &Header) -> Result<ControlledPollAction, AeronError>,
         fragment_limit: i32,
     ) -> i32 {
         let image_list = self.image_list.load_mut();
@@ -259,7 +301,7 @@ impl Subscription {
                 fragments_read += image_list
                     .get_mut(i)
                     .expect("Error getting element from Image vec")
-                    .controlled_poll(fragment_handler, fragment_limit - fragments_read);
+                    .controlled_poll(&mut fragment_handler, fragment_limit - fragments_read);
             }
         }
 
@@ -268,7 +310,7 @@ impl Subscription {
                 fragments_read += image_list
                     .get_mut(i)
                     .expect("Error getting element from Image vec")
-                    .controlled_poll(fragment_handler, fragment_limit - fragments_read);
+                    .controlled_poll(&mut fragment_handler, fragment_limit - fragments_read);
             }
         }
 
@@ -404,11 +446,7 @@ impl Drop for Subscription {
     fn drop(&mut self) {
         let list = self.image_list.take();
 
-        let _unused = self
-            .conductor
-            .lock()
-            .expect("Mutex poisoned")
-            .release_subscription(self.registration_id, list);
+        self.conductor.release_subscription(self.registration_id, list);
     }
 }
 
@@ -0,0 +1,360 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+use std::collections::{HashMap, VecDeque};
+use std::ffi::CString;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+use futures::future::poll_fn;
+use futures::stream::Stream;
+
+use crate::{
+    aeron::Aeron,
+    buffer_builder::BufferBuilder,
+    concurrent::{
+        async_idle::AsyncIdle,
+        atomic_buffer::AtomicBuffer,
+        logbuffer::{data_frame_header, frame_descriptor, header::Header},
+        strategies::{BackOffIdleStrategy, IdlePolicy},
+    },
+    subscription::Subscription,
+    utils::{errors::AeronError, types::Index},
+};
+
+const DEFAULT_FRAGMENT_ASSEMBLY_BUFFER_LENGTH: isize = 4096;
+
+/**
+ * Whole message taken from a SubscriptionStream. Fields other than data are those of the last fragment.
+ */
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReceivedMessage {
+    pub session_id: i32,
+    pub stream_id: i32,
+    pub position: i64,
+    pub reserved_value: i64,
+    pub data: Vec<u8>,
+}
+
+impl ReceivedMessage {
+    fn new(buffer: &AtomicBuffer, offset: Index, length: Index, header: &Header) -> Self {
+        Self {
+            session_id: header.session_id(),
+            stream_id: header.stream_id(),
+            position: header.position(),
+            reserved_value: header.reserved_value(),
+            data: buffer.as_sub_slice(offset, length).to_vec(),
+        }
+    }
+}
+
+/**
+ * Per session reassembly with the same semantics as FragmentAssembler, but taking the delegate on each
+ * fragment so the session buffers can outlive a single poll.
+ */
+struct SessionAssembler {
+    builder_by_session_id_map: HashMap<i32, BufferBuilder>,
+}
+
+impl SessionAssembler {
+    fn new() -> Self {
+        Self {
+            builder_by_session_id_map: HashMap::new(),
+        }
+    }
+
+    fn on_fragment(
+        &mut self,
+        buffer: &AtomicBuffer,
+        offset: Index,
+        length: Index,
+        header: &Header,
+        delegate: &mut impl FnMut(&AtomicBuffer, Index, Index, &Header),
+    ) {
+        let flags = header.flags();
+        if (flags & frame_descriptor::UNFRAGMENTED) == frame_descriptor::UNFRAGMENTED {
+            delegate(buffer, offset, length, header);
+        } else if (flags & frame_descriptor::BEGIN_FRAG) == frame_descriptor::BEGIN_FRAG {
+            let builder = self
+                .builder_by_session_id_map
+                .entry(header.session_id())
+                .or_insert_with(|| BufferBuilder::new(DEFAULT_FRAGMENT_ASSEMBLY_BUFFER_LENGTH));
+
+            builder.reset().append(buffer, offset, length, header).expect("append failed");
+        } else if let Some(builder) = self.builder_by_session_id_map.get_mut(&header.session_id()) {
+            if builder.limit() != data_frame_header::LENGTH {
+                builder.append(buffer, offset, length, header).expect("append failed");
+
+                if flags & frame_descriptor::END_FRAG == frame_descriptor::END_FRAG {
+                    let msg_length = builder.limit() - data_frame_header::LENGTH;
+                    let msg_buffer = AtomicBuffer::new(builder.buffer(), builder.limit());
+
+                    delegate(&msg_buffer, data_frame_header::LENGTH, msg_length, header);
+
+                    builder.reset();
+                }
+            }
+        }
+    }
+}
+
+/**
+ * Adapts a Subscription to async code. As a Stream it yields owned, reassembled messages and ends once the
+ * subscription is closed. poll_with() hands borrowed messages to a handler instead, without copying
+ * unfragmented ones. When there is nothing to read the IdlePolicy decides whether to poll again straight
+ * away, yield to the executor or park on a timer.
+ * <p>
+ * Messages already taken for the Stream are not seen by poll_with() so use one or the other on a given instance.
+ */
+pub struct SubscriptionStream<P: IdlePolicy = BackOffIdleStrategy> {
+    subscription: Arc<Mutex<Subscription>>,
+    fragment_limit: i32,
+    assembler: SessionAssembler,
+    received: VecDeque<ReceivedMessage>,
+    idle: AsyncIdle<P>,
+}
+
+impl SubscriptionStream<BackOffIdleStrategy> {
+    /**
+     * Wrap a subscription, backing off with the default BackOffIdleStrategy when idle.
+     *
+     * @param subscription   to read messages from.
+     * @param fragment_limit for each poll of the subscription.
+     */
+    pub fn new(subscription: Arc<Mutex<Subscription>>, fragment_limit: i32) -> Self {
+        Self::with_idle_policy(subscription, fragment_limit, BackOffIdleStrategy::default())
+    }
+}
+
+impl<P: IdlePolicy> SubscriptionStream<P> {
+    /**
+     * Wrap a subscription with the given policy for when it is idle.
+     *
+     * @param subscription   to read messages from.
+     * @param fragment_limit for each poll of the subscription.
+     * @param idle_policy    deciding between spinning, yielding and parking when there are no messages.
+     */
+    pub fn with_idle_policy(subscription: Arc<Mutex<Subscription>>, fragment_limit: i32, idle_policy: P) -> Self {
+        Self {
+            subscription,
+            fragment_limit,
+            assembler: SessionAssembler::new(),
+            received: VecDeque::new(),
+            idle: AsyncIdle::new(idle_policy),
+        }
+    }
+
+    pub fn subscription(&self) -> Arc<Mutex<Subscription>> {
+        self.subscription.clone()
+    }
+
+    /**
+     * Free the reassembly buffer of a session, e.g. once its Image has gone.
+     *
+     * @param session_id to have its buffer freed.
+     */
+    pub fn delete_session_buffer(&mut self, session_id: i32) {
+        self.assembler.builder_by_session_id_map.remove(&session_id);
+    }
+
+    /**
+     * Wait for fragments and pass whole messages to the handler. The buffer is only valid for the duration of
+     * the call.
+     *
+     * @param handler for whole messages.
+     * @return the number of fragments read or 0 if the subscription is closed.
+     */
+    pub async fn poll_with(&mut self, handler: &mut impl FnMut(&AtomicBuffer, Index, Index, &Header)) -> i32 {
+        poll_fn(|cx| {
+            let Self {
+                subscription,
+                fragment_limit,
+                assembler,
+                idle,
+                ..
+            } = self;
+
+            loop {
+                let fragments_read = {
+                    let mut subscription = subscription.lock().expect("Mutex poisoned");
+                    if subscription.is_closed() {
+                        return Poll::Ready(0);
+                    }
+
+                    subscription.poll(
+                        &mut |buffer: &AtomicBuffer, offset, length, header: &Header| {
+                            assembler.on_fragment(buffer, offset, length, header, handler)
+                        },
+                        *fragment_limit,
+                    )
+                };
+
+                if fragments_read > 0 {
+                    idle.reset();
+                    return Poll::Ready(fragments_read);
+                }
+
+                futures::ready!(idle.poll_idle(cx));
+            }
+        })
+        .await
+    }
+}
+
+impl<P: IdlePolicy + Unpin> Stream for SubscriptionStream<P> {
+    type Item = ReceivedMessage;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<ReceivedMessage>> {
+        let Self {
+            subscription,
+            fragment_limit,
+            assembler,
+            received,
+            idle,
+        } = self.get_mut();
+
+        loop {
+            if let Some(message) = received.pop_front() {
+                return Poll::Ready(Some(message));
+            }
+
+            let fragments_read = {
+                let mut subscription = subscription.lock().expect("Mutex poisoned");
+                if subscription.is_closed() {
+                    return Poll::Ready(None);
+                }
+
+                subscription.poll(
+                    &mut |buffer: &AtomicBuffer, offset, length, header: &Header| {
+                        assembler.on_fragment(buffer, offset, length, header, &mut |buffer, offset, length, header| {
+                            received.push_back(ReceivedMessage::new(buffer, offset, length, header))
+                        })
+                    },
+                    *fragment_limit,
+                )
+            };
+
+            if fragments_read > 0 {
+                // IC: a poll may only have read the start of a message so go round again before idling
+                idle.reset();
+                continue;
+            }
+
+            futures::ready!(idle.poll_idle(cx));
+        }
+    }
+}
+
+/**
+ * Add a Subscription and wait for the media driver to register it, parking on a timer between checks rather
+ * than spinning the thread.
+ *
+ * @param aeron     client to add the subscription with.
+ * @param channel   for the subscription.
+ * @param stream_id for the subscription.
+ * @return the ready Subscription or the error reported for its registration.
+ */
+pub async fn add_subscription(
+    aeron: &mut Aeron,
+    channel: CString,
+    stream_id: i32,
+) -> Result<Arc<Mutex<Subscription>>, AeronError> {
+    let registration_id = aeron.add_subscription(channel, stream_id)?;
+    let mut idle = AsyncIdle::new(BackOffIdleStrategy::default());
+
+    loop {
+        match aeron.find_subscription(registration_id) {
+            Err(AeronError::SubscriptionNotReady(_)) => idle.idle().await,
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::concurrent::{
+        atomic_buffer::{AlignedBuffer, AtomicBuffer},
+        logbuffer::{
+            data_frame_header::{self, DataFrameHeaderDefn},
+            frame_descriptor,
+            header::Header,
+            log_buffer_descriptor,
+        },
+    };
+    use crate::subscription_stream::{ReceivedMessage, SessionAssembler};
+    use crate::utils::types::Index;
+
+    const TERM_LENGTH: i32 = log_buffer_descriptor::TERM_MIN_LENGTH;
+    const INITIAL_TERM_ID: i32 = 7;
+    const FRAGMENT_LENGTH: Index = 64;
+
+    fn fill_frame(buffer: &AtomicBuffer, flags: u8, offset: Index, session_id: i32, payload_value: u8) {
+        let frame = buffer.overlay_struct::<DataFrameHeaderDefn>(offset);
+        unsafe {
+            (*frame).frame_length = data_frame_header::LENGTH + FRAGMENT_LENGTH;
+            (*frame).version = data_frame_header::CURRENT_VERSION;
+            (*frame).flags = flags;
+            (*frame).frame_type = data_frame_header::HDR_TYPE_DATA;
+            (*frame).term_offset = offset;
+            (*frame).session_id = session_id;
+            (*frame).stream_id = 10;
+            (*frame).term_id = INITIAL_TERM_ID;
+        }
+
+        for i in 0..FRAGMENT_LENGTH {
+            buffer.put(offset + data_frame_header::LENGTH + i, payload_value);
+        }
+    }
+
+    #[test]
+    fn should_reassemble_interleaved_sessions() {
+        let aligned = AlignedBuffer::with_capacity(TERM_LENGTH);
+        let buffer = AtomicBuffer::from_aligned(&aligned);
+        let mut header = Header::new(INITIAL_TERM_ID, TERM_LENGTH);
+        header.set_buffer(buffer);
+
+        let frame_length = data_frame_header::LENGTH + FRAGMENT_LENGTH;
+        fill_frame(&buffer, frame_descriptor::BEGIN_FRAG, 0, 1, 1);
+        fill_frame(&buffer, frame_descriptor::UNFRAGMENTED, frame_length, 2, 9);
+        fill_frame(&buffer, frame_descriptor::END_FRAG, frame_length * 2, 1, 2);
+        // IC: a middle fragment without a beginning is dropped
+        fill_frame(&buffer, 0, frame_length * 3, 3, 5);
+
+        let mut assembler = SessionAssembler::new();
+        let mut received = Vec::new();
+
+        for i in 0..4 {
+            header.set_offset(frame_length * i);
+            assembler.on_fragment(
+                &buffer,
+                frame_length * i + data_frame_header::LENGTH,
+                FRAGMENT_LENGTH,
+                &header,
+                &mut |buffer, offset, length, header| received.push(ReceivedMessage::new(buffer, offset, length, header)),
+            );
+        }
+
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].session_id, 2);
+        assert_eq!(received[0].data, vec![9; FRAGMENT_LENGTH as usize]);
+        assert_eq!(received[1].session_id, 1);
+        assert_eq!(received[1].data.len(), FRAGMENT_LENGTH as usize * 2);
+        assert!(received[1].data[..FRAGMENT_LENGTH as usize].iter().all(|b| *b == 1));
+        assert!(received[1].data[FRAGMENT_LENGTH as usize..].iter().all(|b| *b == 2));
+        assert_eq!(received[1].position, i64::from(frame_length * 3));
+    }
+}
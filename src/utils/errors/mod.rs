@@ -17,6 +17,7 @@
 use std::hash::{Hash, Hasher};
 use std::io;
 
+use io_aeron_archive_codecs::control_response_code::ControlResponseCode;
 use thiserror::Error;
 
 use crate::channel_uri::State;
@@ -42,6 +43,8 @@ pub enum AeronError {
     IllegalState(#[from] IllegalStateError),
     #[error("MemMappedFileError: {0}")]
     MemMappedFileError(io::Error),
+    #[error("PropertiesFileError: {0}")]
+    PropertiesFileError(io::Error),
     #[error("DriverTimeout: {0}")]
     DriverTimeout(#[from] DriverInteractionError),
     #[error("ReentrantException: Client cannot be invoked within callback")]
@@ -68,14 +71,24 @@ pub enum AeronError {
     MaxPositionExceeded,
     #[error("Unknown code {0} on getting position")]
     UnknownCode(i64),
+    #[error("Archive error: {0}")]
+    Archive(#[from] ArchiveError),
 }
 
 #[derive(Error, Debug)]
 pub enum IllegalStateError {
     #[error("Action possibly delayed: expected_term_id={expected_term_id} term_id={term_id}")]
     ActionPossiblyDelayed { term_id: i32, expected_term_id: i32 },
+    #[error("Catalog descriptor at offset {offset} is corrupted: length={length}")]
+    CatalogDescriptorCorrupted { offset: Index, length: Index },
+    #[error("Catalog file is too short for its header: length={0}")]
+    CatalogFileTooShort(Index),
+    #[error("Catalog version {version} is not supported, expected major version {expected_major}")]
+    CatalogVersionNotSupported { version: String, expected_major: u8 },
     #[error("Couldn't write command to driver")]
     CouldNotWriteCommandToDriver,
+    #[error("Active media driver detected in {0}")]
+    DriverAlreadyActive(String),
     #[error("Encountered '{c}' within media definition at index {index} in '{uri}'")]
     EncounteredCharacterWithinMediaDefinition { c: char, index: usize, uri: String },
     #[error("Empty key is not allowed at index {index} in '{uri}'")]
@@ -86,10 +99,16 @@ pub enum IllegalStateError {
     InvalidEndOfKey { index: usize, uri: String },
     #[error("Length overflow: {0}")]
     LengthOverflow(i32),
+    #[error("Mark file field at offset {offset} runs past the end of the file: length={length}")]
+    MarkFileFieldCorrupted { offset: usize, length: usize },
+    #[error("Mark file is too short for its header: length={0}")]
+    MarkFileTooShort(Index),
     #[error("Max capacity was reached: {0}")]
     MaxCapacityReached(Index),
     #[error("Max frame length must be a multiple of {frame_alignment} , length = {length}")]
     MaxFrameLengthMustBeMultipleOfFrameAlignment { length: i32, frame_alignment: Index },
+    #[error("Image closed unexpectedly while merging replay")]
+    MergeImageClosed,
     #[error("Page size is not a power of 2, length= {0}")]
     PageSizeIsNotPowerOfTwo(i32),
     #[error("Page size is greater than max size of {page_max_size}, size= {page_size}")]
@@ -98,6 +117,8 @@ pub enum IllegalStateError {
     PageSizeLessThanMinPossibleSize { page_size: i32, page_min_size: Index },
     #[error("Publication is closed")]
     PublicationClosed,
+    #[error("Segment frame at position {position} is corrupted: frame_length={frame_length}")]
+    SegmentFrameCorrupted { position: i64, frame_length: i32 },
     #[error("Subscription is closed")]
     SubscriptionClosed,
     #[error("Term length is greater than max size of {term_max_length} , length= {term_length}")]
@@ -108,6 +129,114 @@ pub enum IllegalStateError {
     TermLengthIsNotPowerOfTwo(i32),
 }
 
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("Archive client is closed")]
+    ClientClosed,
+    #[error("Connection to the archive has been closed")]
+    ConnectionClosed,
+    #[error("Connection to the archive is no longer available")]
+    ConnectionNotAvailable,
+    #[error("Offer failed due to max position being reached")]
+    MaxPositionExceeded,
+    #[error("ReplayMerge no progress: state={0}")]
+    MergeNoProgress(&'static str),
+    #[error("Failed to send {0} request")]
+    RequestNotSent(&'static str),
+    #[error("Subscription to archive is not connected")]
+    ResponseChannelNotConnected,
+    #[error("Timeout {action} - correlation_id={correlation_id}")]
+    Timeout { action: &'static str, correlation_id: i64 },
+    #[error("Response for correlation_id={correlation_id}, error_code={error_code:?}: {message}")]
+    Response {
+        correlation_id: i64,
+        error_code: ArchiveErrorCode,
+        message: String,
+    },
+    #[error("Unexpected response code {code:?} - correlation_id={correlation_id}")]
+    UnexpectedResponse { correlation_id: i64, code: ControlResponseCode },
+}
+
+impl ArchiveError {
+    /**
+     * Error code sent by the archive if this error came from an archive response.
+     */
+    pub fn error_code(&self) -> Option<ArchiveErrorCode> {
+        match self {
+            ArchiveError::Response { error_code, .. } => Some(*error_code),
+            _ => None,
+        }
+    }
+}
+
+// IC: error codes the archive puts in the relevant_id of an ERROR control response, codes this client does not
+// know yet are kept as Unknown so they can still be told apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveErrorCode {
+    Generic,
+    ActiveListing,
+    ActiveRecording,
+    ActiveSubscription,
+    UnknownSubscription,
+    UnknownRecording,
+    UnknownReplay,
+    MaxReplays,
+    MaxRecordings,
+    InvalidExtension,
+    AuthenticationRejected,
+    StorageSpace,
+    UnknownReplication,
+    UnauthorisedAction,
+    Unknown(i64),
+}
+
+impl ArchiveErrorCode {
+    /**
+     * Code as sent by the archive in the relevant_id of a control response.
+     */
+    pub fn code(self) -> i64 {
+        match self {
+            ArchiveErrorCode::Generic => 0,
+            ArchiveErrorCode::ActiveListing => 1,
+            ArchiveErrorCode::ActiveRecording => 2,
+            ArchiveErrorCode::ActiveSubscription => 3,
+            ArchiveErrorCode::UnknownSubscription => 4,
+            ArchiveErrorCode::UnknownRecording => 5,
+            ArchiveErrorCode::UnknownReplay => 6,
+            ArchiveErrorCode::MaxReplays => 7,
+            ArchiveErrorCode::MaxRecordings => 8,
+            ArchiveErrorCode::InvalidExtension => 9,
+            ArchiveErrorCode::AuthenticationRejected => 10,
+            ArchiveErrorCode::StorageSpace => 11,
+            ArchiveErrorCode::UnknownReplication => 12,
+            ArchiveErrorCode::UnauthorisedAction => 13,
+            ArchiveErrorCode::Unknown(code) => code,
+        }
+    }
+}
+
+impl From<i64> for ArchiveErrorCode {
+    fn from(code: i64) -> Self {
+        match code {
+            0 => ArchiveErrorCode::Generic,
+            1 => ArchiveErrorCode::ActiveListing,
+            2 => ArchiveErrorCode::ActiveRecording,
+            3 => ArchiveErrorCode::ActiveSubscription,
+            4 => ArchiveErrorCode::UnknownSubscription,
+            5 => ArchiveErrorCode::UnknownRecording,
+            6 => ArchiveErrorCode::UnknownReplay,
+            7 => ArchiveErrorCode::MaxReplays,
+            8 => ArchiveErrorCode::MaxRecordings,
+            9 => ArchiveErrorCode::InvalidExtension,
+            10 => ArchiveErrorCode::AuthenticationRejected,
+            11 => ArchiveErrorCode::StorageSpace,
+            12 => ArchiveErrorCode::UnknownReplication,
+            13 => ArchiveErrorCode::UnauthorisedAction,
+            _ => ArchiveErrorCode::Unknown(code),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum DriverInteractionError {
     #[error("CnC file is created but not initialised: {file_name}")]
@@ -151,6 +280,12 @@ pub enum IllegalArgumentError {
     InvalidMedia(String),
     #[error("Invalid prefix: {0}")]
     InvalidPrefix(String),
+    #[error("Invalid value for {name}: '{value}'")]
+    InvalidPropertyValue { name: &'static str, value: String },
+    #[error("Invalid segment file name: {0}")]
+    InvalidSegmentFileName(String),
+    #[error("IPC merging is not supported")]
+    IpcMergingNotSupported,
     #[error("Key length is out of bounds: length= {key_length}, limit= {limit}")]
     KeyLengthIsOutOfBounds { key_length: usize, limit: Index },
     #[error("Label length is out of bounds: length= {label_length}, limit= {limit}")]
@@ -173,6 +308,14 @@ pub enum IllegalArgumentError {
     },
     #[error("No more input found, state={state:?}")]
     NoMoreInputFound { state: State },
+    #[error("{name}={value} is out of range, expected {expected}")]
+    PropertyOutOfRange {
+        name: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+    #[error("Subscription URI must have 'control-mode=manual' uri={uri}")]
+    SubscriptionMustUseManualControlMode { uri: String },
     #[error("Term offset is not in range 0-1g: {0}")]
     TermOffsetNotInRange(u32),
     #[error("Term offset is not a multiple of FRAME_ALIGNMENT= {frame_alignment}: offset= {term_offset}")]
@@ -211,8 +354,8 @@ pub enum GenericError {
     CounterNotFound,
     #[error("{0}")]
     Custom(String),
-    #[error("Exclusive publication already dropped")]
-    ExclusivePublicationAlreadyDropped,
+    #[error("Exclusive publication already taken, its only handle was handed out before")]
+    ExclusivePublicationAlreadyTaken,
     #[error("Exclusive publication not found")]
     ExclusivePublicationNotFound,
     #[error("Exclusive publication not ready yet, status {status:?}")]
@@ -249,7 +392,7 @@ impl Eq for AeronError {}
 impl Hash for AeronError {
     fn hash<H: Hasher>(&self, state: &mut H) {
         match self {
-            AeronError::MemMappedFileError(err) => err.kind().hash(state),
+            AeronError::MemMappedFileError(err) | AeronError::PropertiesFileError(err) => err.kind().hash(state),
             err => err.hash(state),
         }
     }
@@ -268,3 +411,19 @@ pub enum AeronErrorCode {
     MalformedCommand = 11,
     ErrorNotSupplied = 12,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_keep_code_of_unknown_archive_error() {
+        assert_eq!(ArchiveErrorCode::from(5), ArchiveErrorCode::UnknownRecording);
+        assert_eq!(ArchiveErrorCode::from(0), ArchiveErrorCode::Generic);
+        assert_eq!(ArchiveErrorCode::from(99), ArchiveErrorCode::Unknown(99));
+
+        for code in -1..=14 {
+            assert_eq!(ArchiveErrorCode::from(code).code(), code);
+        }
+    }
+}
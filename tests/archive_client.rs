@@ -0,0 +1,1262 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![cfg(feature = "embedded-driver")]
+
+extern crate aeron_rs;
+
+use std::net::UdpSocket;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use aeron_rs::{
+    aeron::Aeron,
+    archive::client::{
+        aeron_archive::{AeronArchive, AsyncConnect, AsyncConnectState, NULL_POSITION},
+        context::Context as ArchiveContext,
+        credentials_supplier::CredentialsSupplier,
+        recording_descriptor_consumer::RecordingDescriptor,
+        replication_params::ReplicationParams,
+    },
+    context::{Context, NULL_VALUE},
+    driver::{context::DriverContext, MediaDriver},
+    utils::errors::{AeronError, ArchiveError, ArchiveErrorCode},
+};
+use io_aeron_archive_codecs::*;
+use tempfile::TempDir;
+
+use crate::mock_archive::{ArchiveRequest, MockArchive, ScriptedResponse};
+
+mod mock_archive;
+
+const CONTROL_CHANNEL: &str = "aeron:ipc";
+const CONTROL_STREAM_ID: i32 = 1010;
+const CONTROL_RESPONSE_STREAM_ID: i32 = 1020;
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_millis(50);
+const MESSAGE_TIMEOUT: Duration = Duration::from_millis(200);
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// Embedded media driver in a temporary directory with a client and a mock archive connected to it, so the tests
+// need no external aeronmd and can run in parallel. Fields drop in order: the mock archive closes before the driver.
+struct TestArchive {
+    mock: MockArchive,
+    aeron: Arc<Mutex<Aeron>>,
+    _driver: MediaDriver,
+    _dir: TempDir,
+}
+
+fn launch_archive() -> TestArchive {
+    let dir = tempfile::tempdir().unwrap();
+    let aeron_dir = dir.path().join("aeron").to_str().unwrap().to_string();
+
+    let mut driver_context = DriverContext::new();
+    driver_context.set_aeron_dir(aeron_dir.clone());
+    driver_context.set_dir_delete_on_shutdown(true);
+    let driver = MediaDriver::launch(driver_context).expect("Error launching embedded driver");
+
+    let mut context = Context::new();
+    context.set_aeron_dir(aeron_dir.clone());
+    let aeron = Arc::new(Mutex::new(Aeron::new(context).expect("Error creating Aeron instance")));
+    let mock = MockArchive::launch(&aeron_dir, CONTROL_CHANNEL, CONTROL_STREAM_ID);
+
+    TestArchive {
+        mock,
+        aeron,
+        _driver: driver,
+        _dir: dir,
+    }
+}
+
+fn archive_context(aeron: &Arc<Mutex<Aeron>>) -> ArchiveContext {
+    let mut context = ArchiveContext::new();
+    context.set_aeron(aeron.clone());
+    context.set_control_request_channel(String::from(CONTROL_CHANNEL));
+    context.set_control_request_stream_id(CONTROL_STREAM_ID);
+    context.set_control_response_channel(String::from(CONTROL_CHANNEL));
+    context.set_control_response_stream_id(CONTROL_RESPONSE_STREAM_ID);
+    context
+}
+
+// Polls the connect attempt like an agent duty cycle would, until it either connects or fails.
+fn poll_connect(async_connect: &mut AsyncConnect) -> Result<AeronArchive, AeronError> {
+    loop {
+        if let Some(archive) = async_connect.poll()? {
+            return Ok(archive);
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn free_udp_port() -> u16 {
+    UdpSocket::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+// Sends a user name and answers the challenge by appending the nonce the archive sent.
+struct ChallengedCredentials;
+
+impl CredentialsSupplier for ChallengedCredentials {
+    fn encoded_credentials(&self) -> Vec<u8> {
+        b"admin".to_vec()
+    }
+
+    fn on_challenge(&self, encoded_challenge: &[u8]) -> Vec<u8> {
+        [b"admin:".as_ref(), encoded_challenge].concat()
+    }
+}
+
+// Requests without a response, like keep-alives, are only seen by the mock some time after they were sent.
+fn await_request(mock: &MockArchive, mut matcher: impl FnMut(&ArchiveRequest) -> bool) -> ArchiveRequest {
+    let deadline = Instant::now() + TIMEOUT;
+
+    loop {
+        if let Some(request) = mock.requests().into_iter().find(|request| matcher(request)) {
+            return request;
+        }
+        assert!(Instant::now() < deadline, "timed out waiting for request");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+// Each call only returns once the mock answered it, so its request is the last one the mock received.
+fn last_request(mock: &MockArchive) -> ArchiveRequest {
+    mock.requests().pop().expect("No request received")
+}
+
+fn assert_error_response(result: Result<impl std::fmt::Debug, AeronError>, request: &ArchiveRequest, expected: ArchiveErrorCode) {
+    match result {
+        Err(AeronError::Archive(ArchiveError::Response {
+            correlation_id,
+            error_code,
+            message,
+        })) => {
+            assert_eq!(Some(correlation_id), request.correlation_id());
+            assert_eq!(error_code, expected);
+            assert_eq!(message, format!("{:?}", expected));
+        }
+        result => panic!("Unexpected result {:?}", result),
+    }
+}
+
+fn error_response(error_code: ArchiveErrorCode) -> Vec<ScriptedResponse> {
+    vec![ScriptedResponse::error(error_code.code(), &format!("{:?}", error_code))]
+}
+
+fn recording_descriptor(recording_id: i64) -> RecordingDescriptor {
+    RecordingDescriptor {
+        recording_id,
+        start_position: 0,
+        stop_position: 4096,
+        initial_term_id: 3,
+        segment_file_length: 128 * 1024 * 1024,
+        term_buffer_length: 64 * 1024,
+        mtu_length: 1408,
+        session_id: 77,
+        stream_id: 1001,
+        stripped_channel: String::from("aeron:ipc"),
+        original_channel: String::from("aeron:ipc?alias=test"),
+        source_identity: String::from("aeron:ipc"),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_archive_connect_and_list_recording() {
+    let test = launch_archive();
+    let mock = &test.mock;
+
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+    assert_eq!(archive.control_session_id(), 1);
+
+    mock.script(
+        list_recording_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::Descriptor(recording_descriptor(5))],
+    );
+    mock.script(
+        list_recording_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::recording_unknown(6)],
+    );
+
+    let descriptor = archive.list_recording(5).unwrap().expect("Recording 5 not listed");
+    assert_eq!(descriptor.stop_position, 4096);
+    assert_eq!(descriptor.original_channel, "aeron:ipc?alias=test");
+    assert!(archive.list_recording(6).unwrap().is_none());
+
+    mock.script(start_recording_request_codec::SBE_TEMPLATE_ID, vec![ScriptedResponse::ok(42)]);
+    let subscription_id = archive
+        .start_recording("aeron:ipc?alias=rec", 1001, SourceLocation::LOCAL)
+        .unwrap();
+    assert_eq!(subscription_id, 42);
+
+    let requests = mock.requests();
+    assert!(matches!(
+        requests[0],
+        ArchiveRequest::Connect {
+            response_stream_id: CONTROL_RESPONSE_STREAM_ID,
+            ..
+        }
+    ));
+    assert!(requests.iter().any(|request| matches!(
+        request,
+        ArchiveRequest::StartRecording { stream_id: 1001, channel, .. } if channel == "aeron:ipc?alias=rec"
+    )));
+
+    archive.close();
+}
+
+#[test]
+fn test_archive_error_response_and_recording_signals() {
+    let test = launch_archive();
+    let mock = &test.mock;
+
+    let signals = Arc::new(Mutex::new(Vec::new()));
+    let mut context = archive_context(&test.aeron);
+    {
+        let signals = signals.clone();
+        context.set_recording_signal_consumer(
+            move |_control_session_id, correlation_id, recording_id, _subscription_id, position, signal| {
+                signals.lock().unwrap().push((correlation_id, recording_id, position, signal));
+            },
+        );
+    }
+
+    let mut archive = AeronArchive::connect(context).expect("Error connecting to archive");
+
+    mock.script(
+        replicate_request_2_codec::SBE_TEMPLATE_ID,
+        vec![
+            ScriptedResponse::Signal {
+                recording_id: 9,
+                subscription_id: 3,
+                position: 0,
+                signal: RecordingSignal::REPLICATE,
+            },
+            ScriptedResponse::ok(11),
+        ],
+    );
+    let replication_id = archive
+        .replicate(5, CONTROL_STREAM_ID, CONTROL_CHANNEL, &ReplicationParams::default())
+        .unwrap();
+    assert_eq!(replication_id, 11);
+    assert_eq!(signals.lock().unwrap()[0].1, 9);
+    assert!(signals.lock().unwrap()[0].3 == RecordingSignal::REPLICATE);
+
+    mock.script(
+        truncate_recording_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::error(5, "recording active")],
+    );
+    match archive.truncate_recording(5, 0) {
+        Err(AeronError::Archive(ArchiveError::Response { error_code, message, .. })) => {
+            assert_eq!(error_code, ArchiveErrorCode::UnknownRecording);
+            assert_eq!(message, "recording active");
+        }
+        result => panic!("Unexpected result {:?}", result),
+    }
+
+    mock.script(
+        truncate_recording_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::error(99, "newer archive")],
+    );
+    let error = archive.truncate_recording(5, 0).unwrap_err();
+    assert!(matches!(
+        error,
+        AeronError::Archive(ArchiveError::Response {
+            error_code: ArchiveErrorCode::Unknown(99),
+            ..
+        })
+    ));
+
+    mock.script(
+        stop_recording_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ControlResponse {
+            code: ControlResponseCode::SUBSCRIPTION_UNKNOWN,
+            relevant_id: 0,
+            error_message: String::new(),
+        }],
+    );
+    let error = archive.stop_recording("aeron:ipc?alias=rec", 1001).unwrap_err();
+    assert!(matches!(
+        error,
+        AeronError::Archive(ArchiveError::UnexpectedResponse {
+            code: ControlResponseCode::SUBSCRIPTION_UNKNOWN,
+            ..
+        })
+    ));
+
+    archive.close();
+}
+
+#[test]
+fn test_archive_keep_alive_after_interval() {
+    let test = launch_archive();
+    let mock = &test.mock;
+
+    let mut context = archive_context(&test.aeron);
+    context.set_keep_alive_interval_ns(KEEP_ALIVE_INTERVAL.as_nanos() as i64);
+    let mut archive = AeronArchive::connect(context).expect("Error connecting to archive");
+
+    // IC: a request counts as activity, so nothing is sent until the interval has passed after it
+    mock.script(
+        list_recording_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::recording_unknown(5)],
+    );
+    assert!(archive.list_recording(5).unwrap().is_none());
+    assert!(!archive.keep_alive_if_required().unwrap());
+
+    thread::sleep(KEEP_ALIVE_INTERVAL * 2);
+    assert_eq!(archive.poll_for_recording_signals().unwrap(), 0);
+    assert!(!archive.keep_alive_if_required().unwrap());
+
+    await_request(
+        mock,
+        |request| matches!(request, ArchiveRequest::KeepAlive { control_session_id, .. } if *control_session_id == 1),
+    );
+
+    archive.close();
+}
+
+#[test]
+fn test_async_connect_times_out_sending_connect_when_archive_not_subscribed() {
+    let test = launch_archive();
+
+    // IC: nothing subscribes to this stream, so the control request publication never gets connected
+    let mut context = archive_context(&test.aeron);
+    context.set_control_request_stream_id(CONTROL_STREAM_ID + 1);
+    context.set_message_timeout_ns(MESSAGE_TIMEOUT.as_nanos() as i64);
+    let mut async_connect = AeronArchive::async_connect(context).unwrap();
+
+    let error = poll_connect(&mut async_connect).err().expect("Connected without an archive");
+    assert_eq!(async_connect.state(), AsyncConnectState::SendConnect);
+    assert!(matches!(
+        error,
+        AeronError::Archive(ArchiveError::Timeout {
+            action: "sending connect request",
+            ..
+        })
+    ));
+    assert!(test.mock.requests().is_empty());
+}
+
+#[test]
+fn test_async_connect_with_udp_response_channel() {
+    let test = launch_archive();
+    let response_port = free_udp_port();
+
+    let mut context = archive_context(&test.aeron);
+    context.set_control_response_channel(format!("aeron:udp?endpoint=127.0.0.1:{}", response_port));
+    let mut async_connect = AeronArchive::async_connect(context).unwrap();
+    assert_eq!(async_connect.state(), AsyncConnectState::CreatePublication);
+
+    let mut archive = poll_connect(&mut async_connect).expect("Error connecting to archive");
+    assert_eq!(async_connect.state(), AsyncConnectState::Done);
+    assert_eq!(archive.control_session_id(), 1);
+
+    // IC: the responses to later requests come back on the same subscription
+    test.mock.script(
+        list_recording_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::Descriptor(recording_descriptor(5))],
+    );
+    assert!(archive.list_recording(5).unwrap().is_some());
+
+    match &test.mock.requests()[0] {
+        ArchiveRequest::Connect {
+            response_stream_id,
+            response_channel: requested_channel,
+            encoded_credentials,
+            ..
+        } => {
+            assert_eq!(*response_stream_id, CONTROL_RESPONSE_STREAM_ID);
+            // IC: conclude() adds the control term buffer parameters to the channel
+            assert!(requested_channel.starts_with("aeron:udp?"));
+            assert!(requested_channel.contains(&format!("endpoint=127.0.0.1:{}", response_port)));
+            assert!(encoded_credentials.is_empty());
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    archive.close();
+}
+
+#[test]
+fn test_async_connect_answers_challenge_with_credentials() {
+    let test = launch_archive();
+    let mock = &test.mock;
+
+    mock.script(
+        auth_connect_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::Challenge {
+            encoded_challenge: b"nonce".to_vec(),
+        }],
+    );
+
+    let mut context = archive_context(&test.aeron);
+    context.set_credentials_supplier(ChallengedCredentials);
+    let archive = AeronArchive::connect(context).expect("Error connecting to archive");
+    assert_eq!(archive.control_session_id(), 1);
+
+    let requests = mock.requests();
+    let connect_correlation_id = match &requests[0] {
+        ArchiveRequest::Connect {
+            correlation_id,
+            encoded_credentials,
+            ..
+        } => {
+            assert_eq!(encoded_credentials.as_slice(), b"admin");
+            *correlation_id
+        }
+        request => panic!("Unexpected request {:?}", request),
+    };
+    match &requests[1] {
+        ArchiveRequest::ChallengeResponse {
+            control_session_id,
+            correlation_id,
+            encoded_credentials,
+        } => {
+            assert_eq!(*control_session_id, 1);
+            assert_ne!(*correlation_id, connect_correlation_id);
+            assert_eq!(encoded_credentials.as_slice(), b"admin:nonce");
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    archive.close();
+}
+
+#[test]
+fn test_async_connect_fails_on_error_response() {
+    let test = launch_archive();
+    let mock = &test.mock;
+
+    mock.script(
+        auth_connect_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::error(
+            ArchiveErrorCode::AuthenticationRejected.code(),
+            "bad credentials",
+        )],
+    );
+
+    let mut async_connect = AeronArchive::async_connect(archive_context(&test.aeron)).unwrap();
+    match poll_connect(&mut async_connect) {
+        Err(AeronError::Archive(ArchiveError::Response { error_code, message, .. })) => {
+            assert_eq!(error_code, ArchiveErrorCode::AuthenticationRejected);
+            assert_eq!(message, "bad credentials");
+        }
+        result => panic!("Unexpected result {:?}", result.map(|archive| archive.control_session_id())),
+    }
+    assert_eq!(async_connect.state(), AsyncConnectState::AwaitResponse);
+
+    // IC: the session the archive opened for the rejected connect is closed again
+    await_request(mock, |request| {
+        matches!(request, ArchiveRequest::CloseSession { control_session_id: 1 })
+    });
+}
+
+#[test]
+fn test_async_connect_times_out_awaiting_response() {
+    let test = launch_archive();
+
+    // IC: the mock opens the session but never answers the connect request
+    test.mock.script(auth_connect_request_codec::SBE_TEMPLATE_ID, vec![]);
+
+    let mut context = archive_context(&test.aeron);
+    context.set_message_timeout_ns(MESSAGE_TIMEOUT.as_nanos() as i64);
+    let mut async_connect = AeronArchive::async_connect(context).unwrap();
+
+    let error = poll_connect(&mut async_connect).err().expect("Connected without a response");
+    assert_eq!(async_connect.state(), AsyncConnectState::AwaitResponse);
+    assert!(matches!(
+        error,
+        AeronError::Archive(ArchiveError::Timeout {
+            action: "awaiting connect response",
+            ..
+        })
+    ));
+    assert!(matches!(test.mock.requests()[0], ArchiveRequest::Connect { .. }));
+}
+
+#[test]
+fn test_recording_requests() {
+    let test = launch_archive();
+    let mock = &test.mock;
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+
+    mock.script(start_recording_request_codec::SBE_TEMPLATE_ID, vec![ScriptedResponse::ok(42)]);
+    assert_eq!(
+        archive
+            .start_recording("aeron:udp?endpoint=localhost:40123", 1001, SourceLocation::REMOTE)
+            .unwrap(),
+        42
+    );
+    match last_request(mock) {
+        ArchiveRequest::StartRecording {
+            control_session_id,
+            stream_id,
+            source_location,
+            channel,
+            ..
+        } => {
+            assert_eq!(control_session_id, 1);
+            assert_eq!(stream_id, 1001);
+            assert_eq!(source_location, SourceLocation::REMOTE);
+            assert_eq!(channel, "aeron:udp?endpoint=localhost:40123");
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    mock.script(
+        extend_recording_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(43)],
+    );
+    assert_eq!(
+        archive
+            .extend_recording(7, "aeron:ipc?alias=ext", 1002, SourceLocation::LOCAL)
+            .unwrap(),
+        43
+    );
+    match last_request(mock) {
+        ArchiveRequest::ExtendRecording {
+            control_session_id,
+            recording_id,
+            stream_id,
+            source_location,
+            channel,
+            ..
+        } => {
+            assert_eq!(control_session_id, 1);
+            assert_eq!(recording_id, 7);
+            assert_eq!(stream_id, 1002);
+            assert_eq!(source_location, SourceLocation::LOCAL);
+            assert_eq!(channel, "aeron:ipc?alias=ext");
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    archive.stop_recording("aeron:ipc?alias=ext", 1002).unwrap();
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::StopRecording { control_session_id: 1, stream_id: 1002, channel, .. } if channel == "aeron:ipc?alias=ext"
+    ));
+
+    archive.stop_recording_by_subscription_id(42).unwrap();
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::StopRecordingSubscription {
+            control_session_id: 1,
+            subscription_id: 42,
+            ..
+        }
+    ));
+
+    // IC: the relevant id of the response tells whether the recording was active
+    mock.script(
+        stop_recording_by_identity_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(1)],
+    );
+    mock.script(
+        stop_recording_by_identity_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(0)],
+    );
+    assert!(archive.stop_recording_by_identity(7).unwrap());
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::StopRecordingByIdentity {
+            control_session_id: 1,
+            recording_id: 7,
+            ..
+        }
+    ));
+    assert!(!archive.stop_recording_by_identity(7).unwrap());
+
+    // IC: correlation ids are unique across the requests of a session
+    let mut correlation_ids: Vec<_> = mock.requests().iter().filter_map(ArchiveRequest::correlation_id).collect();
+    let request_count = correlation_ids.len();
+    correlation_ids.sort_unstable();
+    correlation_ids.dedup();
+    assert_eq!(correlation_ids.len(), request_count);
+
+    archive.close();
+}
+
+#[test]
+fn test_recording_error_responses() {
+    let test = launch_archive();
+    let mock = &test.mock;
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+
+    mock.script(
+        start_recording_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::ActiveSubscription),
+    );
+    let result = archive.start_recording("aeron:ipc", 1001, SourceLocation::LOCAL);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::ActiveSubscription);
+
+    mock.script(
+        extend_recording_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::ActiveRecording),
+    );
+    let result = archive.extend_recording(7, "aeron:ipc", 1001, SourceLocation::LOCAL);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::ActiveRecording);
+
+    mock.script(
+        stop_recording_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownSubscription),
+    );
+    let result = archive.stop_recording("aeron:ipc", 1001);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownSubscription);
+
+    mock.script(
+        stop_recording_subscription_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownSubscription),
+    );
+    let result = archive.stop_recording_by_subscription_id(42);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownSubscription);
+
+    mock.script(
+        stop_recording_by_identity_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownRecording),
+    );
+    let result = archive.stop_recording_by_identity(7);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownRecording);
+
+    // IC: the session stays usable after error responses
+    mock.script(start_recording_request_codec::SBE_TEMPLATE_ID, vec![ScriptedResponse::ok(42)]);
+    assert_eq!(archive.start_recording("aeron:ipc", 1001, SourceLocation::LOCAL).unwrap(), 42);
+
+    archive.close();
+}
+
+#[test]
+fn test_segment_requests() {
+    let test = launch_archive();
+    let mock = &test.mock;
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+
+    // IC: the relevant id of the response is the count of segment files the archive deleted or attached
+    mock.script(purge_recording_request_codec::SBE_TEMPLATE_ID, vec![ScriptedResponse::ok(4)]);
+    assert_eq!(archive.purge_recording(7).unwrap(), 4);
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::PurgeRecording {
+            control_session_id: 1,
+            recording_id: 7,
+            ..
+        }
+    ));
+
+    archive.detach_segments(7, 256 * 1024).unwrap();
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::DetachSegments {
+            control_session_id: 1,
+            recording_id: 7,
+            new_start_position: 262_144,
+            ..
+        }
+    ));
+
+    mock.script(
+        delete_detached_segments_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(2)],
+    );
+    assert_eq!(archive.delete_detached_segments(7).unwrap(), 2);
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::DeleteDetachedSegments {
+            control_session_id: 1,
+            recording_id: 7,
+            ..
+        }
+    ));
+
+    mock.script(purge_segments_request_codec::SBE_TEMPLATE_ID, vec![ScriptedResponse::ok(3)]);
+    assert_eq!(archive.purge_segments(7, 512 * 1024).unwrap(), 3);
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::PurgeSegments {
+            control_session_id: 1,
+            recording_id: 7,
+            new_start_position: 524_288,
+            ..
+        }
+    ));
+
+    mock.script(attach_segments_request_codec::SBE_TEMPLATE_ID, vec![ScriptedResponse::ok(5)]);
+    assert_eq!(archive.attach_segments(7).unwrap(), 5);
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::AttachSegments {
+            control_session_id: 1,
+            recording_id: 7,
+            ..
+        }
+    ));
+
+    mock.script(migrate_segments_request_codec::SBE_TEMPLATE_ID, vec![ScriptedResponse::ok(6)]);
+    assert_eq!(archive.migrate_segments(8, 7).unwrap(), 6);
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::MigrateSegments {
+            control_session_id: 1,
+            src_recording_id: 8,
+            dst_recording_id: 7,
+            ..
+        }
+    ));
+
+    archive.close();
+}
+
+#[test]
+fn test_segment_error_responses() {
+    let test = launch_archive();
+    let mock = &test.mock;
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+
+    mock.script(
+        purge_recording_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::ActiveRecording),
+    );
+    let result = archive.purge_recording(7);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::ActiveRecording);
+
+    mock.script(
+        detach_segments_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::Generic),
+    );
+    let result = archive.detach_segments(7, 1000);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::Generic);
+
+    mock.script(
+        delete_detached_segments_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownRecording),
+    );
+    let result = archive.delete_detached_segments(7);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownRecording);
+
+    mock.script(
+        purge_segments_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::Generic),
+    );
+    let result = archive.purge_segments(7, 1000);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::Generic);
+
+    mock.script(
+        attach_segments_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownRecording),
+    );
+    let result = archive.attach_segments(7);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownRecording);
+
+    mock.script(
+        migrate_segments_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::ActiveRecording),
+    );
+    let result = archive.migrate_segments(8, 7);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::ActiveRecording);
+
+    // IC: the session stays usable after error responses
+    mock.script(attach_segments_request_codec::SBE_TEMPLATE_ID, vec![ScriptedResponse::ok(1)]);
+    assert_eq!(archive.attach_segments(7).unwrap(), 1);
+
+    archive.close();
+}
+
+#[test]
+fn test_replay_requests() {
+    let test = launch_archive();
+    let mock = &test.mock;
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+
+    let replay_session_id = (3_i64 << 32) | 0x55;
+    mock.script(
+        replay_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(replay_session_id)],
+    );
+    assert_eq!(
+        archive.start_replay(5, 1024, 4096, "aeron:ipc?alias=replay", 2001).unwrap(),
+        replay_session_id
+    );
+    match last_request(mock) {
+        ArchiveRequest::Replay {
+            control_session_id,
+            recording_id,
+            position,
+            length,
+            replay_stream_id,
+            replay_channel,
+            ..
+        } => {
+            assert_eq!(control_session_id, 1);
+            assert_eq!(recording_id, 5);
+            assert_eq!(position, 1024);
+            assert_eq!(length, 4096);
+            assert_eq!(replay_stream_id, 2001);
+            assert_eq!(replay_channel, "aeron:ipc?alias=replay");
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    mock.script(
+        bounded_replay_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(replay_session_id)],
+    );
+    assert_eq!(
+        archive
+            .start_bounded_replay(5, 0, i64::MAX, 12, "aeron:ipc?alias=bounded", 2002)
+            .unwrap(),
+        replay_session_id
+    );
+    match last_request(mock) {
+        ArchiveRequest::BoundedReplay {
+            control_session_id,
+            recording_id,
+            position,
+            length,
+            limit_counter_id,
+            replay_stream_id,
+            replay_channel,
+            ..
+        } => {
+            assert_eq!(control_session_id, 1);
+            assert_eq!(recording_id, 5);
+            assert_eq!(position, 0);
+            assert_eq!(length, i64::MAX);
+            assert_eq!(limit_counter_id, 12);
+            assert_eq!(replay_stream_id, 2002);
+            assert_eq!(replay_channel, "aeron:ipc?alias=bounded");
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    archive.stop_replay(replay_session_id).unwrap();
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::StopReplay { control_session_id: 1, replay_session_id: id, .. } if id == replay_session_id
+    ));
+
+    archive.stop_all_replays(5).unwrap();
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::StopAllReplays {
+            control_session_id: 1,
+            recording_id: 5,
+            ..
+        }
+    ));
+
+    // IC: NULL_VALUE stops the replays of all recordings
+    archive.stop_all_replays(NULL_VALUE).unwrap();
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::StopAllReplays { control_session_id: 1, recording_id, .. } if recording_id == NULL_VALUE
+    ));
+
+    // IC: the subscription only takes the replayed image, i.e. the lower 32 bits of the replay session id
+    mock.script(
+        replay_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(replay_session_id)],
+    );
+    let subscription = archive.replay(5, 0, 4096, "aeron:ipc?alias=replay", 2001).unwrap();
+    let subscription = subscription.lock().unwrap();
+    assert_eq!(subscription.stream_id(), 2001);
+    let channel = subscription.channel().into_string().unwrap();
+    assert!(channel.starts_with("aeron:ipc?"));
+    assert!(channel.contains("alias=replay"));
+    assert!(channel.contains(&format!("session-id={}", 0x55)));
+    drop(subscription);
+
+    archive.close();
+}
+
+#[test]
+fn test_replay_error_responses() {
+    let test = launch_archive();
+    let mock = &test.mock;
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+
+    mock.script(
+        replay_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownRecording),
+    );
+    let result = archive.start_replay(5, 0, 4096, "aeron:ipc", 2001);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownRecording);
+
+    mock.script(
+        bounded_replay_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::MaxReplays),
+    );
+    let result = archive.start_bounded_replay(5, 0, 4096, 12, "aeron:ipc", 2001);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::MaxReplays);
+
+    mock.script(
+        stop_replay_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownReplay),
+    );
+    let result = archive.stop_replay(7);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownReplay);
+
+    mock.script(
+        stop_all_replays_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownRecording),
+    );
+    let result = archive.stop_all_replays(5);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownRecording);
+
+    // IC: replay() hands back the error before it adds the replay subscription
+    mock.script(
+        replay_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownRecording),
+    );
+    let result = archive.replay(5, 0, 4096, "aeron:ipc", 2001).map(|_| ());
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownRecording);
+
+    archive.close();
+}
+
+#[test]
+fn test_replication_requests() {
+    let test = launch_archive();
+    let mock = &test.mock;
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+
+    // IC: the defaults ask for a new recording which follows the source until it stops
+    mock.script(replicate_request_2_codec::SBE_TEMPLATE_ID, vec![ScriptedResponse::ok(11)]);
+    assert_eq!(
+        archive
+            .replicate(5, 1010, "aeron:udp?endpoint=src:8010", &ReplicationParams::default())
+            .unwrap(),
+        11
+    );
+    match last_request(mock) {
+        ArchiveRequest::Replicate {
+            control_session_id,
+            src_recording_id,
+            src_control_stream_id,
+            src_control_channel,
+            params,
+            ..
+        } => {
+            assert_eq!(control_session_id, 1);
+            assert_eq!(src_recording_id, 5);
+            assert_eq!(src_control_stream_id, 1010);
+            assert_eq!(src_control_channel, "aeron:udp?endpoint=src:8010");
+            assert_eq!(params, ReplicationParams::default());
+            assert_eq!(params.stop_position(), NULL_POSITION);
+            assert_eq!(params.dst_recording_id(), NULL_VALUE);
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    let mut params = ReplicationParams::default();
+    params.set_stop_position(8192);
+    params.set_dst_recording_id(9);
+    params.set_live_destination(String::from("aeron:udp?endpoint=localhost:9020"));
+    params.set_replication_channel(String::from("aeron:udp?endpoint=localhost:9030"));
+    params.set_channel_tag_id(101);
+    params.set_subscription_tag_id(102);
+    params.set_file_io_max_length(4096);
+    mock.script(replicate_request_2_codec::SBE_TEMPLATE_ID, vec![ScriptedResponse::ok(12)]);
+    assert_eq!(archive.replicate(5, 1010, "aeron:ipc", &params).unwrap(), 12);
+    match last_request(mock) {
+        ArchiveRequest::Replicate {
+            params: requested_params,
+            ..
+        } => assert_eq!(requested_params, params),
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    mock.script(
+        tagged_replicate_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(13)],
+    );
+    assert_eq!(
+        archive
+            .tagged_replicate(5, 9, 101, 102, 1010, "aeron:ipc", "aeron:udp?endpoint=localhost:9020")
+            .unwrap(),
+        13
+    );
+    match last_request(mock) {
+        ArchiveRequest::TaggedReplicate {
+            control_session_id,
+            src_recording_id,
+            dst_recording_id,
+            channel_tag_id,
+            subscription_tag_id,
+            src_control_stream_id,
+            src_control_channel,
+            live_destination,
+            ..
+        } => {
+            assert_eq!(control_session_id, 1);
+            assert_eq!(src_recording_id, 5);
+            assert_eq!(dst_recording_id, 9);
+            assert_eq!(channel_tag_id, 101);
+            assert_eq!(subscription_tag_id, 102);
+            assert_eq!(src_control_stream_id, 1010);
+            assert_eq!(src_control_channel, "aeron:ipc");
+            assert_eq!(live_destination, "aeron:udp?endpoint=localhost:9020");
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    archive.stop_replication(13).unwrap();
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::StopReplication {
+            control_session_id: 1,
+            replication_id: 13,
+            ..
+        }
+    ));
+
+    archive.close();
+}
+
+#[test]
+fn test_replication_error_responses() {
+    let test = launch_archive();
+    let mock = &test.mock;
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+
+    mock.script(
+        replicate_request_2_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownRecording),
+    );
+    let result = archive.replicate(5, 1010, "aeron:ipc", &ReplicationParams::default());
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownRecording);
+
+    mock.script(
+        tagged_replicate_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::ActiveRecording),
+    );
+    let result = archive.tagged_replicate(5, 9, 101, 102, 1010, "aeron:ipc", "");
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::ActiveRecording);
+
+    mock.script(
+        stop_replication_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::UnknownReplication),
+    );
+    let result = archive.stop_replication(13);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::UnknownReplication);
+
+    archive.close();
+}
+
+#[test]
+fn test_listing_requests() {
+    let test = launch_archive();
+    let mock = &test.mock;
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+
+    // IC: the listing is complete once record count descriptors arrived, without a terminating response
+    mock.script(
+        list_recordings_request_codec::SBE_TEMPLATE_ID,
+        (5..8)
+            .map(|id| ScriptedResponse::Descriptor(recording_descriptor(id)))
+            .collect(),
+    );
+    let descriptors = archive.list_recordings(5, 3).unwrap();
+    let recording_ids: Vec<_> = descriptors.iter().map(|descriptor| descriptor.recording_id).collect();
+    assert_eq!(recording_ids, vec![5, 6, 7]);
+    assert!(matches!(
+        last_request(mock),
+        ArchiveRequest::ListRecordings {
+            control_session_id: 1,
+            from_recording_id: 5,
+            record_count: 3,
+            ..
+        }
+    ));
+
+    // IC: RECORDING_UNKNOWN ends a listing which runs out of recordings before the record count
+    mock.script(
+        list_recordings_request_codec::SBE_TEMPLATE_ID,
+        vec![
+            ScriptedResponse::Descriptor(recording_descriptor(8)),
+            ScriptedResponse::Descriptor(recording_descriptor(9)),
+            ScriptedResponse::recording_unknown(10),
+        ],
+    );
+    let mut recording_ids = Vec::new();
+    let count = archive
+        .list_recordings_with(8, 10, &mut |descriptor: RecordingDescriptor| {
+            recording_ids.push(descriptor.recording_id)
+        })
+        .unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(recording_ids, vec![8, 9]);
+
+    mock.script(
+        list_recordings_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::recording_unknown(20)],
+    );
+    assert!(archive.list_recordings(20, 10).unwrap().is_empty());
+
+    mock.script(
+        list_recordings_for_uri_request_codec::SBE_TEMPLATE_ID,
+        vec![
+            ScriptedResponse::Descriptor(recording_descriptor(3)),
+            ScriptedResponse::recording_unknown(4),
+        ],
+    );
+    let descriptors = archive.list_recordings_for_uri(0, 5, "alias=test", 1001).unwrap();
+    assert_eq!(descriptors.len(), 1);
+    assert_eq!(descriptors[0].recording_id, 3);
+    assert_eq!(descriptors[0].original_channel, "aeron:ipc?alias=test");
+    match last_request(mock) {
+        ArchiveRequest::ListRecordingsForUri {
+            control_session_id,
+            from_recording_id,
+            record_count,
+            stream_id,
+            channel,
+            ..
+        } => {
+            assert_eq!(control_session_id, 1);
+            assert_eq!(from_recording_id, 0);
+            assert_eq!(record_count, 5);
+            assert_eq!(stream_id, 1001);
+            assert_eq!(channel, "alias=test");
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    mock.script(
+        find_last_matching_recording_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(9)],
+    );
+    mock.script(
+        find_last_matching_recording_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(NULL_VALUE)],
+    );
+    assert_eq!(archive.find_last_matching_recording(2, "alias=test", 1001, 77).unwrap(), 9);
+    match last_request(mock) {
+        ArchiveRequest::FindLastMatchingRecording {
+            control_session_id,
+            min_recording_id,
+            session_id,
+            stream_id,
+            channel,
+            ..
+        } => {
+            assert_eq!(control_session_id, 1);
+            assert_eq!(min_recording_id, 2);
+            assert_eq!(session_id, 77);
+            assert_eq!(stream_id, 1001);
+            assert_eq!(channel, "alias=test");
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+    assert_eq!(
+        archive.find_last_matching_recording(2, "alias=other", 1001, 77).unwrap(),
+        NULL_VALUE
+    );
+
+    archive.close();
+}
+
+#[test]
+fn test_listing_extends_deadline_for_each_descriptor() {
+    let test = launch_archive();
+    let mock = &test.mock;
+
+    let mut context = archive_context(&test.aeron);
+    context.set_message_timeout_ns(MESSAGE_TIMEOUT.as_nanos() as i64);
+    let mut archive = AeronArchive::connect(context).expect("Error connecting to archive");
+
+    // IC: the whole listing takes longer than the message timeout, but no gap between descriptors does
+    let pause = MESSAGE_TIMEOUT / 2;
+    mock.script(
+        list_recordings_request_codec::SBE_TEMPLATE_ID,
+        (5..9)
+            .flat_map(|id| {
+                vec![
+                    ScriptedResponse::Pause(pause),
+                    ScriptedResponse::Descriptor(recording_descriptor(id)),
+                ]
+            })
+            .collect(),
+    );
+    let started = Instant::now();
+    assert_eq!(archive.list_recordings(5, 4).unwrap().len(), 4);
+    assert!(started.elapsed() > MESSAGE_TIMEOUT);
+
+    mock.script(
+        list_recordings_request_codec::SBE_TEMPLATE_ID,
+        vec![
+            ScriptedResponse::Descriptor(recording_descriptor(5)),
+            ScriptedResponse::Pause(MESSAGE_TIMEOUT * 2),
+            ScriptedResponse::Descriptor(recording_descriptor(6)),
+        ],
+    );
+    let mut recording_ids = Vec::new();
+    let result = archive.list_recordings_with(5, 2, &mut |descriptor: RecordingDescriptor| {
+        recording_ids.push(descriptor.recording_id)
+    });
+    assert!(matches!(
+        result,
+        Err(AeronError::Archive(ArchiveError::Timeout {
+            action: "awaiting recording descriptors",
+            ..
+        }))
+    ));
+    assert_eq!(recording_ids, vec![5]);
+
+    // IC: let the mock send the late descriptor while the response subscription is still there to take it
+    thread::sleep(MESSAGE_TIMEOUT * 2);
+    archive.close();
+}
+
+#[test]
+fn test_listing_error_responses() {
+    let test = launch_archive();
+    let mock = &test.mock;
+    let mut archive = AeronArchive::connect(archive_context(&test.aeron)).expect("Error connecting to archive");
+
+    // IC: an error after some descriptors still fails the listing
+    mock.script(
+        list_recordings_request_codec::SBE_TEMPLATE_ID,
+        [
+            vec![ScriptedResponse::Descriptor(recording_descriptor(5))],
+            error_response(ArchiveErrorCode::ActiveListing),
+        ]
+        .concat(),
+    );
+    let result = archive.list_recordings(5, 3);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::ActiveListing);
+
+    mock.script(
+        list_recordings_for_uri_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::ActiveListing),
+    );
+    let result = archive.list_recordings_for_uri(0, 5, "alias=test", 1001);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::ActiveListing);
+
+    mock.script(
+        find_last_matching_recording_request_codec::SBE_TEMPLATE_ID,
+        error_response(ArchiveErrorCode::Generic),
+    );
+    let result = archive.find_last_matching_recording(2, "alias=test", 1001, 77);
+    assert_error_response(result, &last_request(mock), ArchiveErrorCode::Generic);
+
+    mock.script(
+        list_recordings_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::Descriptor(recording_descriptor(5))],
+    );
+    assert_eq!(archive.list_recordings(5, 1).unwrap().len(), 1);
+
+    archive.close();
+}
@@ -0,0 +1,304 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![cfg(feature = "embedded-driver")]
+
+extern crate aeron_rs;
+
+use std::ffi::CString;
+use std::net::UdpSocket;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use aeron_rs::{
+    aeron::Aeron,
+    concurrent::{
+        atomic_buffer::{AlignedBuffer, AtomicBuffer},
+        logbuffer::{buffer_claim::BufferClaim, header::Header},
+        strategies::BusySpinIdleStrategy,
+    },
+    context::Context,
+    driver::{context::DriverContext, MediaDriver},
+    offer_retry_policy::OfferRetryPolicy,
+    utils::{
+        errors::{AeronError, IllegalStateError},
+        types::Index,
+    },
+};
+
+const IPC_CHANNEL: &str = "aeron:ipc";
+const STREAM_ID: i32 = 1001;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// Unlike the other integration tests these need no external aeronmd: each test launches its own driver in a
+// temporary directory so they can run in parallel.
+fn driver_context(aeron_dir: &str) -> DriverContext {
+    let mut driver_context = DriverContext::new();
+    driver_context.set_aeron_dir(aeron_dir.to_string());
+    driver_context.set_dir_delete_on_shutdown(true);
+    driver_context
+}
+
+fn launch_driver(aeron_dir: &str) -> MediaDriver {
+    MediaDriver::launch(driver_context(aeron_dir)).expect("Error launching embedded driver")
+}
+
+fn connect(aeron_dir: &str) -> Aeron {
+    let mut context = Context::new();
+    context.set_aeron_dir(aeron_dir.to_string());
+
+    Aeron::new(context).expect("Error creating Aeron instance")
+}
+
+fn await_condition(mut condition: impl FnMut() -> bool) {
+    let deadline = Instant::now() + TIMEOUT;
+
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+// Publishes message_count messages of message_length bytes, polling whenever the publication is back pressured,
+// and checks they all arrive in order.
+fn exchange_messages(aeron: &mut Aeron, channel: &str, message_count: i64, message_length: Index) {
+    let subscription_id = aeron.add_subscription(CString::new(channel).unwrap(), STREAM_ID).unwrap();
+    let publication_id = aeron.add_publication(CString::new(channel).unwrap(), STREAM_ID).unwrap();
+
+    let mut subscription = None;
+    await_condition(|| {
+        subscription = aeron.find_subscription(subscription_id).ok();
+        subscription.is_some()
+    });
+    let subscription = subscription.unwrap();
+
+    let mut publication = None;
+    await_condition(|| {
+        publication = aeron.find_publication(publication_id).ok();
+        publication.is_some()
+    });
+    let publication = publication.unwrap();
+
+    await_condition(|| publication.is_connected());
+    await_condition(|| subscription.lock().unwrap().is_connected());
+
+    let buffer = AlignedBuffer::with_capacity(message_length);
+    let src_buffer = AtomicBuffer::from_aligned(&buffer);
+
+    let mut received = Vec::new();
+    let mut handler = |buffer: &AtomicBuffer, offset: Index, length: Index, _header: &Header| {
+        assert_eq!(length, message_length);
+        received.push(buffer.get::<i64>(offset));
+    };
+
+    let mut fragments_read = 0;
+    for i in 0..message_count {
+        src_buffer.put::<i64>(0, i);
+        await_condition(|| {
+            if publication.offer_part(src_buffer, 0, message_length).is_ok() {
+                return true;
+            }
+            fragments_read += subscription.lock().unwrap().poll(&mut handler, 10) as i64;
+            false
+        });
+    }
+
+    await_condition(|| {
+        fragments_read += subscription.lock().unwrap().poll(&mut handler, 10) as i64;
+        fragments_read == message_count
+    });
+
+    assert_eq!(received, (0..message_count).collect::<Vec<i64>>());
+}
+
+fn free_udp_port() -> u16 {
+    UdpSocket::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port()
+}
+
+#[test]
+fn should_exchange_messages_over_ipc() {
+    let dir = tempfile::tempdir().unwrap();
+    let aeron_dir = dir.path().join("aeron").to_str().unwrap().to_string();
+    let _driver = launch_driver(&aeron_dir);
+    let mut aeron = connect(&aeron_dir);
+
+    exchange_messages(&mut aeron, IPC_CHANNEL, 100, 8);
+}
+
+#[test]
+fn should_exchange_messages_over_udp_unicast() {
+    let dir = tempfile::tempdir().unwrap();
+    let aeron_dir = dir.path().join("aeron").to_str().unwrap().to_string();
+
+    // IC: small terms so the messages wrap through every partition several times
+    let mut driver_context = driver_context(&aeron_dir);
+    driver_context.set_term_buffer_length(64 * 1024);
+    let _driver = MediaDriver::launch(driver_context).expect("Error launching embedded driver");
+    let mut aeron = connect(&aeron_dir);
+
+    let channel = format!("aeron:udp?endpoint=127.0.0.1:{}", free_udp_port());
+    exchange_messages(&mut aeron, &channel, 5_000, 200);
+}
+
+#[test]
+fn should_retry_blocking_claim_until_back_pressure_clears() {
+    let dir = tempfile::tempdir().unwrap();
+    let aeron_dir = dir.path().join("aeron").to_str().unwrap().to_string();
+
+    // IC: the publication window of IPC is a term, so a small term fills up after a few messages
+    let mut driver_context = driver_context(&aeron_dir);
+    driver_context.set_ipc_term_buffer_length(64 * 1024);
+    let _driver = MediaDriver::launch(driver_context).expect("Error launching embedded driver");
+    let mut aeron = connect(&aeron_dir);
+
+    let subscription_id = aeron.add_subscription(CString::new(IPC_CHANNEL).unwrap(), STREAM_ID).unwrap();
+    let publication_id = aeron
+        .add_exclusive_publication(CString::new(IPC_CHANNEL).unwrap(), STREAM_ID)
+        .unwrap();
+
+    let mut subscription = None;
+    await_condition(|| {
+        subscription = aeron.find_subscription(subscription_id).ok();
+        subscription.is_some()
+    });
+    let subscription = subscription.unwrap();
+
+    let mut publication = None;
+    await_condition(|| {
+        publication = aeron.find_exclusive_publication(publication_id).ok();
+        publication.is_some()
+    });
+    let mut publication = publication.unwrap();
+    await_condition(|| publication.is_connected());
+
+    let message_length: Index = 1024;
+    let buffer = AlignedBuffer::with_capacity(message_length);
+    let src_buffer = AtomicBuffer::from_aligned(&buffer);
+
+    let mut message_count = 0;
+    loop {
+        src_buffer.put::<i64>(0, message_count);
+        match publication.offer_part(src_buffer, 0, message_length) {
+            Ok(_) => message_count += 1,
+            // IC: filling the window fills the term, which then rotates
+            Err(AeronError::AdminAction) => continue,
+            Err(AeronError::BackPressured) => break,
+            Err(error) => panic!("Unexpected error {:?}", error),
+        }
+    }
+
+    let mut buffer_claim = BufferClaim::default();
+    let mut idle_strategy = BusySpinIdleStrategy::default();
+
+    // IC: nothing is consumed, so the retries run out and the back pressure of the last attempt is returned
+    let mut retry_policy = OfferRetryPolicy::new();
+    retry_policy.set_max_retries(10);
+    let result = publication.try_claim_blocking(message_length, &mut buffer_claim, &mut idle_strategy, &retry_policy);
+    assert!(matches!(result, Err(AeronError::BackPressured)));
+
+    let poll_thread = thread::spawn(move || {
+        let mut received = Vec::new();
+        let mut handler = |buffer: &AtomicBuffer, offset: Index, _length: Index, _header: &Header| {
+            received.push(buffer.get::<i64>(offset));
+        };
+
+        let mut fragments_read = 0;
+        await_condition(|| {
+            fragments_read += subscription.lock().unwrap().poll(&mut handler, 10) as i64;
+            fragments_read > message_count
+        });
+        received
+    });
+
+    let mut retry_policy = OfferRetryPolicy::new();
+    retry_policy.set_timeout(TIMEOUT);
+    publication
+        .try_claim_blocking(message_length, &mut buffer_claim, &mut idle_strategy, &retry_policy)
+        .expect("Claim still back pressured while the subscription consumes");
+    buffer_claim.buffer().put::<i64>(buffer_claim.offset(), message_count);
+    buffer_claim.commit();
+
+    let received = poll_thread.join().unwrap();
+    assert_eq!(received, (0..=message_count).collect::<Vec<i64>>());
+}
+
+#[test]
+fn should_fail_blocking_offer_without_subscriber() {
+    let dir = tempfile::tempdir().unwrap();
+    let aeron_dir = dir.path().join("aeron").to_str().unwrap().to_string();
+    let _driver = launch_driver(&aeron_dir);
+    let mut aeron = connect(&aeron_dir);
+
+    let publication_id = aeron.add_publication(CString::new(IPC_CHANNEL).unwrap(), STREAM_ID).unwrap();
+    let mut publication = None;
+    await_condition(|| {
+        publication = aeron.find_publication(publication_id).ok();
+        publication.is_some()
+    });
+    let publication = publication.unwrap();
+
+    let buffer = AlignedBuffer::with_capacity(8);
+    let src_buffer = AtomicBuffer::from_aligned(&buffer);
+    let mut idle_strategy = BusySpinIdleStrategy::default();
+
+    let result = publication.offer_blocking(src_buffer, 0, 8, &mut idle_strategy, &OfferRetryPolicy::new());
+    assert!(matches!(result, Err(AeronError::NotConnected)));
+
+    // IC: waiting for a subscriber gives up once the timeout has passed
+    let timeout = Duration::from_millis(50);
+    let mut retry_policy = OfferRetryPolicy::new();
+    retry_policy.set_retry_not_connected(true);
+    retry_policy.set_timeout(timeout);
+    let started = Instant::now();
+    let result = publication.offer_blocking(src_buffer, 0, 8, &mut idle_strategy, &retry_policy);
+    assert!(matches!(result, Err(AeronError::NotConnected)));
+    assert!(started.elapsed() >= timeout);
+}
+
+#[test]
+fn should_reject_unsupported_channel() {
+    let dir = tempfile::tempdir().unwrap();
+    let aeron_dir = dir.path().join("aeron").to_str().unwrap().to_string();
+    let _driver = launch_driver(&aeron_dir);
+    let mut aeron = connect(&aeron_dir);
+
+    let publication_id = aeron
+        .add_publication(CString::new("aeron:udp?endpoint=224.0.1.1:40456").unwrap(), STREAM_ID)
+        .unwrap();
+
+    let mut result = aeron.find_publication(publication_id);
+    await_condition(|| {
+        result = aeron.find_publication(publication_id);
+        !matches!(result, Err(AeronError::PublicationNotReady(_)))
+    });
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn should_not_launch_over_active_driver() {
+    let dir = tempfile::tempdir().unwrap();
+    let aeron_dir = dir.path().join("aeron").to_str().unwrap().to_string();
+    let _driver = launch_driver(&aeron_dir);
+
+    let mut driver_context = DriverContext::new();
+    driver_context.set_aeron_dir(aeron_dir.clone());
+
+    match MediaDriver::launch(driver_context) {
+        Err(AeronError::IllegalState(IllegalStateError::DriverAlreadyActive(dir))) => assert_eq!(dir, aeron_dir),
+        _ => panic!("second driver must not launch"),
+    }
+}
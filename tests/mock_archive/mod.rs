@@ -0,0 +1,1025 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// In-process stand-in for an archive which answers control requests with scripted responses, so archive client
+// code can be tested against the embedded media driver without a Java archive.
+
+#![allow(dead_code)]
+
+use std::collections::{HashMap, VecDeque};
+use std::ffi::CString;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use aeron_rs::{
+    aeron::Aeron,
+    archive::client::{recording_descriptor_consumer::RecordingDescriptor, replication_params::ReplicationParams},
+    concurrent::{atomic_buffer::AtomicBuffer, logbuffer::header::Header},
+    context::Context,
+    exclusive_publication::ExclusivePublication,
+    utils::types::Index,
+};
+use io_aeron_archive_codecs::*;
+
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+const FRAGMENT_LIMIT: i32 = 10;
+
+/**
+ * Control request received by the mock, decoded with the archive codecs.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum ArchiveRequest {
+    Connect {
+        correlation_id: i64,
+        response_stream_id: i32,
+        response_channel: String,
+        encoded_credentials: Vec<u8>,
+    },
+    ChallengeResponse {
+        control_session_id: i64,
+        correlation_id: i64,
+        encoded_credentials: Vec<u8>,
+    },
+    KeepAlive {
+        control_session_id: i64,
+        correlation_id: i64,
+    },
+    CloseSession {
+        control_session_id: i64,
+    },
+    StartRecording {
+        control_session_id: i64,
+        correlation_id: i64,
+        stream_id: i32,
+        source_location: SourceLocation,
+        channel: String,
+    },
+    ExtendRecording {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+        stream_id: i32,
+        source_location: SourceLocation,
+        channel: String,
+    },
+    StopRecording {
+        control_session_id: i64,
+        correlation_id: i64,
+        stream_id: i32,
+        channel: String,
+    },
+    StopRecordingSubscription {
+        control_session_id: i64,
+        correlation_id: i64,
+        subscription_id: i64,
+    },
+    StopRecordingByIdentity {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+    },
+    PurgeRecording {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+    },
+    DetachSegments {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+        new_start_position: i64,
+    },
+    DeleteDetachedSegments {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+    },
+    PurgeSegments {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+        new_start_position: i64,
+    },
+    AttachSegments {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+    },
+    MigrateSegments {
+        control_session_id: i64,
+        correlation_id: i64,
+        src_recording_id: i64,
+        dst_recording_id: i64,
+    },
+    Replay {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+        position: i64,
+        length: i64,
+        replay_stream_id: i32,
+        replay_channel: String,
+    },
+    BoundedReplay {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+        position: i64,
+        length: i64,
+        limit_counter_id: i32,
+        replay_stream_id: i32,
+        replay_channel: String,
+    },
+    StopReplay {
+        control_session_id: i64,
+        correlation_id: i64,
+        replay_session_id: i64,
+    },
+    StopAllReplays {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+    },
+    // IC: the optional parameters of a replicate request are decoded back into the ReplicationParams they came from
+    Replicate {
+        control_session_id: i64,
+        correlation_id: i64,
+        src_recording_id: i64,
+        src_control_stream_id: i32,
+        src_control_channel: String,
+        params: ReplicationParams,
+    },
+    TaggedReplicate {
+        control_session_id: i64,
+        correlation_id: i64,
+        src_recording_id: i64,
+        dst_recording_id: i64,
+        channel_tag_id: i64,
+        subscription_tag_id: i64,
+        src_control_stream_id: i32,
+        src_control_channel: String,
+        live_destination: String,
+    },
+    StopReplication {
+        control_session_id: i64,
+        correlation_id: i64,
+        replication_id: i64,
+    },
+    ListRecordings {
+        control_session_id: i64,
+        correlation_id: i64,
+        from_recording_id: i64,
+        record_count: i32,
+    },
+    ListRecording {
+        control_session_id: i64,
+        correlation_id: i64,
+        recording_id: i64,
+    },
+    ListRecordingsForUri {
+        control_session_id: i64,
+        correlation_id: i64,
+        from_recording_id: i64,
+        record_count: i32,
+        stream_id: i32,
+        channel: String,
+    },
+    FindLastMatchingRecording {
+        control_session_id: i64,
+        correlation_id: i64,
+        min_recording_id: i64,
+        session_id: i32,
+        stream_id: i32,
+        channel: String,
+    },
+    // IC: every other control request starts with the control session id and the correlation id
+    Other {
+        template_id: u16,
+        control_session_id: i64,
+        correlation_id: i64,
+    },
+}
+
+impl ArchiveRequest {
+    pub fn control_session_id(&self) -> Option<i64> {
+        match self {
+            ArchiveRequest::ChallengeResponse { control_session_id, .. }
+            | ArchiveRequest::KeepAlive { control_session_id, .. }
+            | ArchiveRequest::CloseSession { control_session_id }
+            | ArchiveRequest::StartRecording { control_session_id, .. }
+            | ArchiveRequest::ExtendRecording { control_session_id, .. }
+            | ArchiveRequest::StopRecording { control_session_id, .. }
+            | ArchiveRequest::StopRecordingSubscription { control_session_id, .. }
+            | ArchiveRequest::StopRecordingByIdentity { control_session_id, .. }
+            | ArchiveRequest::PurgeRecording { control_session_id, .. }
+            | ArchiveRequest::DetachSegments { control_session_id, .. }
+            | ArchiveRequest::DeleteDetachedSegments { control_session_id, .. }
+            | ArchiveRequest::PurgeSegments { control_session_id, .. }
+            | ArchiveRequest::AttachSegments { control_session_id, .. }
+            | ArchiveRequest::MigrateSegments { control_session_id, .. }
+            | ArchiveRequest::Replay { control_session_id, .. }
+            | ArchiveRequest::BoundedReplay { control_session_id, .. }
+            | ArchiveRequest::StopReplay { control_session_id, .. }
+            | ArchiveRequest::StopAllReplays { control_session_id, .. }
+            | ArchiveRequest::Replicate { control_session_id, .. }
+            | ArchiveRequest::TaggedReplicate { control_session_id, .. }
+            | ArchiveRequest::StopReplication { control_session_id, .. }
+            | ArchiveRequest::ListRecordings { control_session_id, .. }
+            | ArchiveRequest::ListRecording { control_session_id, .. }
+            | ArchiveRequest::ListRecordingsForUri { control_session_id, .. }
+            | ArchiveRequest::FindLastMatchingRecording { control_session_id, .. }
+            | ArchiveRequest::Other { control_session_id, .. } => Some(*control_session_id),
+            ArchiveRequest::Connect { .. } => None,
+        }
+    }
+
+    pub fn correlation_id(&self) -> Option<i64> {
+        match self {
+            ArchiveRequest::Connect { correlation_id, .. }
+            | ArchiveRequest::ChallengeResponse { correlation_id, .. }
+            | ArchiveRequest::KeepAlive { correlation_id, .. }
+            | ArchiveRequest::StartRecording { correlation_id, .. }
+            | ArchiveRequest::ExtendRecording { correlation_id, .. }
+            | ArchiveRequest::StopRecording { correlation_id, .. }
+            | ArchiveRequest::StopRecordingSubscription { correlation_id, .. }
+            | ArchiveRequest::StopRecordingByIdentity { correlation_id, .. }
+            | ArchiveRequest::PurgeRecording { correlation_id, .. }
+            | ArchiveRequest::DetachSegments { correlation_id, .. }
+            | ArchiveRequest::DeleteDetachedSegments { correlation_id, .. }
+            | ArchiveRequest::PurgeSegments { correlation_id, .. }
+            | ArchiveRequest::AttachSegments { correlation_id, .. }
+            | ArchiveRequest::MigrateSegments { correlation_id, .. }
+            | ArchiveRequest::Replay { correlation_id, .. }
+            | ArchiveRequest::BoundedReplay { correlation_id, .. }
+            | ArchiveRequest::StopReplay { correlation_id, .. }
+            | ArchiveRequest::StopAllReplays { correlation_id, .. }
+            | ArchiveRequest::Replicate { correlation_id, .. }
+            | ArchiveRequest::TaggedReplicate { correlation_id, .. }
+            | ArchiveRequest::StopReplication { correlation_id, .. }
+            | ArchiveRequest::ListRecordings { correlation_id, .. }
+            | ArchiveRequest::ListRecording { correlation_id, .. }
+            | ArchiveRequest::ListRecordingsForUri { correlation_id, .. }
+            | ArchiveRequest::FindLastMatchingRecording { correlation_id, .. }
+            | ArchiveRequest::Other { correlation_id, .. } => Some(*correlation_id),
+            ArchiveRequest::CloseSession { .. } => None,
+        }
+    }
+}
+
+/**
+ * Message sent back on the response channel of a session. The control session id and the correlation id are
+ * filled in from the request being answered.
+ */
+#[derive(Clone, Debug)]
+pub enum ScriptedResponse {
+    Challenge {
+        encoded_challenge: Vec<u8>,
+    },
+    ControlResponse {
+        code: ControlResponseCode,
+        relevant_id: i64,
+        error_message: String,
+    },
+    Descriptor(RecordingDescriptor),
+    Signal {
+        recording_id: i64,
+        subscription_id: i64,
+        position: i64,
+        signal: RecordingSignal,
+    },
+    // IC: nothing is sent, the mock waits before sending the responses after it
+    Pause(Duration),
+}
+
+impl ScriptedResponse {
+    pub fn ok(relevant_id: i64) -> Self {
+        ScriptedResponse::ControlResponse {
+            code: ControlResponseCode::OK,
+            relevant_id,
+            error_message: String::new(),
+        }
+    }
+
+    pub fn error(relevant_id: i64, error_message: &str) -> Self {
+        ScriptedResponse::ControlResponse {
+            code: ControlResponseCode::ERROR,
+            relevant_id,
+            error_message: error_message.to_string(),
+        }
+    }
+
+    pub fn recording_unknown(recording_id: i64) -> Self {
+        ScriptedResponse::ControlResponse {
+            code: ControlResponseCode::RECORDING_UNKNOWN,
+            relevant_id: recording_id,
+            error_message: String::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    requests: Vec<ArchiveRequest>,
+    scripts: HashMap<u16, VecDeque<Vec<ScriptedResponse>>>,
+}
+
+struct Sessions {
+    aeron: Aeron,
+    next_control_session_id: i64,
+    publications: HashMap<i64, ExclusivePublication>,
+    buffer: Vec<u8>,
+}
+
+/**
+ * Archive stand-in which polls the control request channel on its own thread. Connect requests open a session
+ * with a response publication, other requests are answered with the responses scripted for their template id
+ * or with an OK ControlResponse when nothing is scripted.
+ */
+pub struct MockArchive {
+    shared: Arc<Mutex<Shared>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl MockArchive {
+    /**
+     * Start the archive thread, which connects its own Aeron client to the media driver like an archive process
+     * would, and wait until it is subscribed to the control request channel.
+     *
+     * @param aeron_dir         of the media driver to connect to.
+     * @param control_channel   on which requests are received.
+     * @param control_stream_id on which requests are received.
+     */
+    pub fn launch(aeron_dir: &str, control_channel: &str, control_stream_id: i32) -> Self {
+        let shared = Arc::new(Mutex::new(Shared::default()));
+        let running = Arc::new(AtomicBool::new(true));
+        let (ready_sender, ready_receiver) = mpsc::channel();
+
+        let thread = {
+            let shared = shared.clone();
+            let running = running.clone();
+            let control_channel = CString::new(control_channel).unwrap();
+            let aeron_dir = aeron_dir.to_string();
+
+            // IC: Aeron is not Send, so the client is created on and stays with the archive thread
+            thread::spawn(move || {
+                let mut context = Context::new();
+                context.set_aeron_dir(aeron_dir);
+                let mut aeron = Aeron::new(context).expect("Error creating Aeron instance for mock archive");
+                let registration_id = aeron
+                    .add_subscription(control_channel, control_stream_id)
+                    .expect("Error adding control request subscription");
+                let subscription = await_resource(|| aeron.find_subscription(registration_id));
+                ready_sender.send(()).unwrap();
+
+                let mut sessions = Sessions {
+                    aeron,
+                    next_control_session_id: 1,
+                    publications: HashMap::new(),
+                    buffer: vec![0u8; 4096],
+                };
+
+                while running.load(Ordering::Acquire) {
+                    let mut requests = Vec::new();
+                    subscription.lock().unwrap().poll(
+                        &mut |buffer: &AtomicBuffer, offset: Index, length: Index, _header: &Header| {
+                            if let Some(request) = decode_request(buffer.as_sub_slice(offset, length)) {
+                                requests.push(request);
+                            }
+                        },
+                        FRAGMENT_LIMIT,
+                    );
+
+                    if requests.is_empty() {
+                        thread::sleep(Duration::from_millis(1));
+                    }
+
+                    for request in requests {
+                        sessions.on_request(&shared, request);
+                    }
+                }
+            })
+        };
+
+        ready_receiver
+            .recv_timeout(RESPONSE_TIMEOUT)
+            .expect("Mock archive failed to subscribe to the control request channel");
+
+        Self {
+            shared,
+            running,
+            thread: Some(thread),
+        }
+    }
+
+    /**
+     * Script the responses to the next request with a template id, e.g. list_recording_request_codec::SBE_TEMPLATE_ID.
+     * Calling this again queues the responses for the following request with the same template id.
+     */
+    pub fn script(&self, template_id: u16, responses: Vec<ScriptedResponse>) {
+        self.shared
+            .lock()
+            .unwrap()
+            .scripts
+            .entry(template_id)
+            .or_default()
+            .push_back(responses);
+    }
+
+    /**
+     * Requests received so far in order of arrival.
+     */
+    pub fn requests(&self) -> Vec<ArchiveRequest> {
+        self.shared.lock().unwrap().requests.clone()
+    }
+
+    /**
+     * Stop answering requests and wait for the polling thread to finish.
+     */
+    pub fn close(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.join().expect("Mock archive thread panicked");
+        }
+    }
+}
+
+impl Drop for MockArchive {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl Sessions {
+    fn on_request(&mut self, shared: &Mutex<Shared>, request: ArchiveRequest) {
+        let template_id = template_id_of(&request);
+        let scripted = {
+            let mut shared = shared.lock().unwrap();
+            shared.requests.push(request.clone());
+            shared.scripts.get_mut(&template_id).and_then(|queue| queue.pop_front())
+        };
+
+        let (control_session_id, correlation_id) = match &request {
+            ArchiveRequest::Connect {
+                correlation_id,
+                response_stream_id,
+                response_channel,
+                ..
+            } => {
+                let control_session_id = self.next_control_session_id;
+                self.next_control_session_id += 1;
+                self.open_session(control_session_id, response_channel, *response_stream_id);
+
+                let responses = scripted.unwrap_or_else(|| vec![ScriptedResponse::ok(control_session_id)]);
+                self.respond(control_session_id, *correlation_id, &responses);
+                return;
+            }
+            // IC: the archive does not answer keep alives and closes the session without a response
+            ArchiveRequest::KeepAlive { .. } => return,
+            ArchiveRequest::CloseSession { control_session_id } => {
+                self.publications.remove(control_session_id);
+                return;
+            }
+            request => (
+                request.control_session_id().expect("Request of a session"),
+                request.correlation_id().expect("Request of a session"),
+            ),
+        };
+
+        let responses = scripted.unwrap_or_else(|| vec![ScriptedResponse::ok(0)]);
+        self.respond(control_session_id, correlation_id, &responses);
+    }
+
+    fn open_session(&mut self, control_session_id: i64, response_channel: &str, response_stream_id: i32) {
+        let registration_id = self
+            .aeron
+            .add_exclusive_publication(CString::new(response_channel).unwrap(), response_stream_id)
+            .expect("Error adding response publication");
+        let publication = await_resource(|| self.aeron.find_exclusive_publication(registration_id));
+
+        self.publications.insert(control_session_id, publication);
+    }
+
+    fn respond(&mut self, control_session_id: i64, correlation_id: i64, responses: &[ScriptedResponse]) {
+        let publication = match self.publications.get_mut(&control_session_id) {
+            Some(publication) => publication,
+            None => return,
+        };
+
+        for response in responses {
+            if let ScriptedResponse::Pause(duration) = response {
+                thread::sleep(*duration);
+                continue;
+            }
+
+            let length = encode_response(&mut self.buffer, control_session_id, correlation_id, response);
+            let deadline = Instant::now() + RESPONSE_TIMEOUT;
+
+            while publication
+                .offer_part(AtomicBuffer::wrap_slice(&mut self.buffer), 0, length as Index)
+                .is_err()
+            {
+                if Instant::now() > deadline {
+                    panic!("Mock archive could not send response to session {}", control_session_id);
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+}
+
+fn await_resource<T, E>(mut find: impl FnMut() -> Result<T, E>) -> T {
+    let deadline = Instant::now() + RESPONSE_TIMEOUT;
+
+    loop {
+        if let Ok(resource) = find() {
+            return resource;
+        }
+        if Instant::now() > deadline {
+            panic!("Mock archive timed out waiting for the media driver");
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn template_id_of(request: &ArchiveRequest) -> u16 {
+    match request {
+        ArchiveRequest::Connect { .. } => auth_connect_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::ChallengeResponse { .. } => challenge_response_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::KeepAlive { .. } => keep_alive_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::CloseSession { .. } => close_session_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::StartRecording { .. } => start_recording_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::ExtendRecording { .. } => extend_recording_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::StopRecording { .. } => stop_recording_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::StopRecordingSubscription { .. } => stop_recording_subscription_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::StopRecordingByIdentity { .. } => stop_recording_by_identity_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::PurgeRecording { .. } => purge_recording_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::DetachSegments { .. } => detach_segments_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::DeleteDetachedSegments { .. } => delete_detached_segments_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::PurgeSegments { .. } => purge_segments_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::AttachSegments { .. } => attach_segments_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::MigrateSegments { .. } => migrate_segments_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::Replay { .. } => replay_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::BoundedReplay { .. } => bounded_replay_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::StopReplay { .. } => stop_replay_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::StopAllReplays { .. } => stop_all_replays_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::Replicate { .. } => replicate_request_2_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::TaggedReplicate { .. } => tagged_replicate_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::StopReplication { .. } => stop_replication_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::ListRecordings { .. } => list_recordings_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::ListRecording { .. } => list_recording_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::ListRecordingsForUri { .. } => list_recordings_for_uri_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::FindLastMatchingRecording { .. } => find_last_matching_recording_request_codec::SBE_TEMPLATE_ID,
+        ArchiveRequest::Other { template_id, .. } => *template_id,
+    }
+}
+
+fn decode_request(data: &[u8]) -> Option<ArchiveRequest> {
+    let header_decoder = MessageHeaderDecoder::default().wrap(ReadBuf::new(data), 0);
+    if header_decoder.schema_id() != control_response_codec::SBE_SCHEMA_ID {
+        return None;
+    }
+
+    let request = match header_decoder.template_id() {
+        auth_connect_request_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = AuthConnectRequestDecoder::default().header(header_decoder);
+            let correlation_id = decoder.correlation_id();
+            let response_stream_id = decoder.response_stream_id();
+            let coordinates = decoder.response_channel_decoder();
+            let response_channel = String::from_utf8_lossy(decoder.response_channel_slice(coordinates)).into_owned();
+            let coordinates = decoder.encoded_credentials_decoder();
+            let encoded_credentials = decoder.encoded_credentials_slice(coordinates).to_vec();
+
+            ArchiveRequest::Connect {
+                correlation_id,
+                response_stream_id,
+                response_channel,
+                encoded_credentials,
+            }
+        }
+        challenge_response_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = ChallengeResponseDecoder::default().header(header_decoder);
+            let control_session_id = decoder.control_session_id();
+            let correlation_id = decoder.correlation_id();
+            let coordinates = decoder.encoded_credentials_decoder();
+            let encoded_credentials = decoder.encoded_credentials_slice(coordinates).to_vec();
+
+            ArchiveRequest::ChallengeResponse {
+                control_session_id,
+                correlation_id,
+                encoded_credentials,
+            }
+        }
+        keep_alive_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = KeepAliveRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::KeepAlive {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+            }
+        }
+        close_session_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = CloseSessionRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::CloseSession {
+                control_session_id: decoder.control_session_id(),
+            }
+        }
+        start_recording_request_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = StartRecordingRequestDecoder::default().header(header_decoder);
+            let control_session_id = decoder.control_session_id();
+            let correlation_id = decoder.correlation_id();
+            let stream_id = decoder.stream_id();
+            let source_location = decoder.source_location();
+            let coordinates = decoder.channel_decoder();
+            let channel = String::from_utf8_lossy(decoder.channel_slice(coordinates)).into_owned();
+
+            ArchiveRequest::StartRecording {
+                control_session_id,
+                correlation_id,
+                stream_id,
+                source_location,
+                channel,
+            }
+        }
+        extend_recording_request_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = ExtendRecordingRequestDecoder::default().header(header_decoder);
+            let control_session_id = decoder.control_session_id();
+            let correlation_id = decoder.correlation_id();
+            let recording_id = decoder.recording_id();
+            let stream_id = decoder.stream_id();
+            let source_location = decoder.source_location();
+            let coordinates = decoder.channel_decoder();
+            let channel = String::from_utf8_lossy(decoder.channel_slice(coordinates)).into_owned();
+
+            ArchiveRequest::ExtendRecording {
+                control_session_id,
+                correlation_id,
+                recording_id,
+                stream_id,
+                source_location,
+                channel,
+            }
+        }
+        stop_recording_request_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = StopRecordingRequestDecoder::default().header(header_decoder);
+            let control_session_id = decoder.control_session_id();
+            let correlation_id = decoder.correlation_id();
+            let stream_id = decoder.stream_id();
+            let coordinates = decoder.channel_decoder();
+            let channel = String::from_utf8_lossy(decoder.channel_slice(coordinates)).into_owned();
+
+            ArchiveRequest::StopRecording {
+                control_session_id,
+                correlation_id,
+                stream_id,
+                channel,
+            }
+        }
+        stop_recording_subscription_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = StopRecordingSubscriptionRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::StopRecordingSubscription {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                subscription_id: decoder.subscription_id(),
+            }
+        }
+        stop_recording_by_identity_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = StopRecordingByIdentityRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::StopRecordingByIdentity {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                recording_id: decoder.recording_id(),
+            }
+        }
+        purge_recording_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = PurgeRecordingRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::PurgeRecording {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                recording_id: decoder.recording_id(),
+            }
+        }
+        detach_segments_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = DetachSegmentsRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::DetachSegments {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                recording_id: decoder.recording_id(),
+                new_start_position: decoder.new_start_position(),
+            }
+        }
+        delete_detached_segments_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = DeleteDetachedSegmentsRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::DeleteDetachedSegments {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                recording_id: decoder.recording_id(),
+            }
+        }
+        purge_segments_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = PurgeSegmentsRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::PurgeSegments {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                recording_id: decoder.recording_id(),
+                new_start_position: decoder.new_start_position(),
+            }
+        }
+        attach_segments_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = AttachSegmentsRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::AttachSegments {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                recording_id: decoder.recording_id(),
+            }
+        }
+        migrate_segments_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = MigrateSegmentsRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::MigrateSegments {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                src_recording_id: decoder.src_recording_id(),
+                dst_recording_id: decoder.dst_recording_id(),
+            }
+        }
+        replay_request_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = ReplayRequestDecoder::default().header(header_decoder);
+            let control_session_id = decoder.control_session_id();
+            let correlation_id = decoder.correlation_id();
+            let recording_id = decoder.recording_id();
+            let position = decoder.position();
+            let length = decoder.length();
+            let replay_stream_id = decoder.replay_stream_id();
+            let coordinates = decoder.replay_channel_decoder();
+            let replay_channel = String::from_utf8_lossy(decoder.replay_channel_slice(coordinates)).into_owned();
+
+            ArchiveRequest::Replay {
+                control_session_id,
+                correlation_id,
+                recording_id,
+                position,
+                length,
+                replay_stream_id,
+                replay_channel,
+            }
+        }
+        bounded_replay_request_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = BoundedReplayRequestDecoder::default().header(header_decoder);
+            let control_session_id = decoder.control_session_id();
+            let correlation_id = decoder.correlation_id();
+            let recording_id = decoder.recording_id();
+            let position = decoder.position();
+            let length = decoder.length();
+            let limit_counter_id = decoder.limit_counter_id();
+            let replay_stream_id = decoder.replay_stream_id();
+            let coordinates = decoder.replay_channel_decoder();
+            let replay_channel = String::from_utf8_lossy(decoder.replay_channel_slice(coordinates)).into_owned();
+
+            ArchiveRequest::BoundedReplay {
+                control_session_id,
+                correlation_id,
+                recording_id,
+                position,
+                length,
+                limit_counter_id,
+                replay_stream_id,
+                replay_channel,
+            }
+        }
+        stop_replay_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = StopReplayRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::StopReplay {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                replay_session_id: decoder.replay_session_id(),
+            }
+        }
+        stop_all_replays_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = StopAllReplaysRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::StopAllReplays {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                recording_id: decoder.recording_id(),
+            }
+        }
+        replicate_request_2_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = ReplicateRequest2Decoder::default().header(header_decoder);
+            let control_session_id = decoder.control_session_id();
+            let correlation_id = decoder.correlation_id();
+            let src_recording_id = decoder.src_recording_id();
+            let src_control_stream_id = decoder.src_control_stream_id();
+
+            let mut params = ReplicationParams::default();
+            params.set_dst_recording_id(decoder.dst_recording_id());
+            params.set_stop_position(decoder.stop_position());
+            params.set_channel_tag_id(decoder.channel_tag_id());
+            params.set_subscription_tag_id(decoder.subscription_tag_id());
+            params.set_file_io_max_length(decoder.file_io_max_length());
+
+            let coordinates = decoder.src_control_channel_decoder();
+            let src_control_channel = String::from_utf8_lossy(decoder.src_control_channel_slice(coordinates)).into_owned();
+            let coordinates = decoder.live_destination_decoder();
+            params.set_live_destination(String::from_utf8_lossy(decoder.live_destination_slice(coordinates)).into_owned());
+            let coordinates = decoder.replication_channel_decoder();
+            params.set_replication_channel(String::from_utf8_lossy(decoder.replication_channel_slice(coordinates)).into_owned());
+
+            ArchiveRequest::Replicate {
+                control_session_id,
+                correlation_id,
+                src_recording_id,
+                src_control_stream_id,
+                src_control_channel,
+                params,
+            }
+        }
+        tagged_replicate_request_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = TaggedReplicateRequestDecoder::default().header(header_decoder);
+            let control_session_id = decoder.control_session_id();
+            let correlation_id = decoder.correlation_id();
+            let src_recording_id = decoder.src_recording_id();
+            let dst_recording_id = decoder.dst_recording_id();
+            let channel_tag_id = decoder.channel_tag_id();
+            let subscription_tag_id = decoder.subscription_tag_id();
+            let src_control_stream_id = decoder.src_control_stream_id();
+            let coordinates = decoder.src_control_channel_decoder();
+            let src_control_channel = String::from_utf8_lossy(decoder.src_control_channel_slice(coordinates)).into_owned();
+            let coordinates = decoder.live_destination_decoder();
+            let live_destination = String::from_utf8_lossy(decoder.live_destination_slice(coordinates)).into_owned();
+
+            ArchiveRequest::TaggedReplicate {
+                control_session_id,
+                correlation_id,
+                src_recording_id,
+                dst_recording_id,
+                channel_tag_id,
+                subscription_tag_id,
+                src_control_stream_id,
+                src_control_channel,
+                live_destination,
+            }
+        }
+        stop_replication_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = StopReplicationRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::StopReplication {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                replication_id: decoder.replication_id(),
+            }
+        }
+        list_recordings_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = ListRecordingsRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::ListRecordings {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                from_recording_id: decoder.from_recording_id(),
+                record_count: decoder.record_count(),
+            }
+        }
+        list_recording_request_codec::SBE_TEMPLATE_ID => {
+            let decoder = ListRecordingRequestDecoder::default().header(header_decoder);
+            ArchiveRequest::ListRecording {
+                control_session_id: decoder.control_session_id(),
+                correlation_id: decoder.correlation_id(),
+                recording_id: decoder.recording_id(),
+            }
+        }
+        list_recordings_for_uri_request_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = ListRecordingsForUriRequestDecoder::default().header(header_decoder);
+            let control_session_id = decoder.control_session_id();
+            let correlation_id = decoder.correlation_id();
+            let from_recording_id = decoder.from_recording_id();
+            let record_count = decoder.record_count();
+            let stream_id = decoder.stream_id();
+            let coordinates = decoder.channel_decoder();
+            let channel = String::from_utf8_lossy(decoder.channel_slice(coordinates)).into_owned();
+
+            ArchiveRequest::ListRecordingsForUri {
+                control_session_id,
+                correlation_id,
+                from_recording_id,
+                record_count,
+                stream_id,
+                channel,
+            }
+        }
+        find_last_matching_recording_request_codec::SBE_TEMPLATE_ID => {
+            let mut decoder = FindLastMatchingRecordingRequestDecoder::default().header(header_decoder);
+            let control_session_id = decoder.control_session_id();
+            let correlation_id = decoder.correlation_id();
+            let min_recording_id = decoder.min_recording_id();
+            let session_id = decoder.session_id();
+            let stream_id = decoder.stream_id();
+            let coordinates = decoder.channel_decoder();
+            let channel = String::from_utf8_lossy(decoder.channel_slice(coordinates)).into_owned();
+
+            ArchiveRequest::FindLastMatchingRecording {
+                control_session_id,
+                correlation_id,
+                min_recording_id,
+                session_id,
+                stream_id,
+                channel,
+            }
+        }
+        template_id => {
+            let offset = message_header_codec::ENCODED_LENGTH;
+            let id_at = |index: usize| {
+                let start = offset + index * 8;
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(data.get(start..start + 8)?);
+                Some(i64::from_le_bytes(bytes))
+            };
+
+            ArchiveRequest::Other {
+                template_id,
+                control_session_id: id_at(0)?,
+                correlation_id: id_at(1)?,
+            }
+        }
+    };
+
+    Some(request)
+}
+
+fn encode_response(buffer: &mut [u8], control_session_id: i64, correlation_id: i64, response: &ScriptedResponse) -> usize {
+    let body_length = match response {
+        ScriptedResponse::Challenge { encoded_challenge } => {
+            let mut encoder = ChallengeEncoder::default().wrap(WriteBuf::new(buffer), message_header_codec::ENCODED_LENGTH);
+            encoder = encoder.header(0).parent().unwrap();
+            encoder.control_session_id(control_session_id);
+            encoder.correlation_id(correlation_id);
+            encoder.encoded_challenge(encoded_challenge);
+            encoder.encoded_length()
+        }
+        ScriptedResponse::ControlResponse {
+            code,
+            relevant_id,
+            error_message,
+        } => {
+            let mut encoder = ControlResponseEncoder::default().wrap(WriteBuf::new(buffer), message_header_codec::ENCODED_LENGTH);
+            encoder = encoder.header(0).parent().unwrap();
+            encoder.control_session_id(control_session_id);
+            encoder.correlation_id(correlation_id);
+            encoder.relevant_id(*relevant_id);
+            encoder.code(*code);
+            encoder.error_message(error_message.as_bytes());
+            encoder.encoded_length()
+        }
+        ScriptedResponse::Descriptor(descriptor) => {
+            let mut encoder =
+                RecordingDescriptorEncoder::default().wrap(WriteBuf::new(buffer), message_header_codec::ENCODED_LENGTH);
+            encoder = encoder.header(0).parent().unwrap();
+            encoder.control_session_id(control_session_id);
+            encoder.correlation_id(correlation_id);
+            encoder.recording_id(descriptor.recording_id);
+            encoder.start_timestamp(descriptor.start_timestamp);
+            encoder.stop_timestamp(descriptor.stop_timestamp);
+            encoder.start_position(descriptor.start_position);
+            encoder.stop_position(descriptor.stop_position);
+            encoder.initial_term_id(descriptor.initial_term_id);
+            encoder.segment_file_length(descriptor.segment_file_length);
+            encoder.term_buffer_length(descriptor.term_buffer_length);
+            encoder.mtu_length(descriptor.mtu_length);
+            encoder.session_id(descriptor.session_id);
+            encoder.stream_id(descriptor.stream_id);
+            encoder.stripped_channel(descriptor.stripped_channel.as_bytes());
+            encoder.original_channel(descriptor.original_channel.as_bytes());
+            encoder.source_identity(descriptor.source_identity.as_bytes());
+            encoder.encoded_length()
+        }
+        ScriptedResponse::Signal {
+            recording_id,
+            subscription_id,
+            position,
+            signal,
+        } => {
+            let mut encoder =
+                RecordingSignalEventEncoder::default().wrap(WriteBuf::new(buffer), message_header_codec::ENCODED_LENGTH);
+            encoder = encoder.header(0).parent().unwrap();
+            encoder.control_session_id(control_session_id);
+            encoder.correlation_id(correlation_id);
+            encoder.recording_id(*recording_id);
+            encoder.subscription_id(*subscription_id);
+            encoder.position(*position);
+            encoder.signal(*signal);
+            encoder.encoded_length()
+        }
+        ScriptedResponse::Pause(_) => unreachable!("pauses are not sent"),
+    };
+
+    message_header_codec::ENCODED_LENGTH + body_length
+}
@@ -0,0 +1,941 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+// Stand-in for a media driver which owns a CnC file in a temporary directory, records the commands clients send
+// and answers them with scripted responses, so client code can be tested end to end without aeronmd. The
+// response encoders of the command flyweights come with the embedded-driver feature, as do the tests using this.
+
+#![allow(dead_code)]
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use aeron_rs::{
+    cnc_file_descriptor,
+    command::{
+        client_timeout_flyweight::{ClientTimeoutFlyweight, CLIENT_TIMEOUT_LENGTH},
+        control_protocol_events::AeronCommand,
+        correlated_message_flyweight::CorrelatedMessageFlyweight,
+        counter_message_flyweight::CounterMessageFlyweight,
+        counter_update_flyweight::{CounterUpdateFlyweight, COUNTER_READY_LENGTH},
+        destination_message_flyweight::DestinationMessageFlyweight,
+        error_response_flyweight::ErrorResponseFlyweight,
+        image_buffers_ready_flyweight::ImageBuffersReadyFlyweight,
+        image_message_flyweight::ImageMessageFlyweight,
+        operation_succeeded_flyweight::{OperationSucceededFlyweight, OPERATION_SUCCEEDED_LENGTH},
+        publication_buffers_ready_flyweight::PublicationBuffersReadyFlyweight,
+        publication_message_flyweight::PublicationMessageFlyweight,
+        remove_message_flyweight::RemoveMessageFlyweight,
+        subscription_message_flyweight::SubscriptionMessageFlyweight,
+        subscription_ready_flyweight::{SubscriptionReadyFlyweight, SUBSCRIPTION_READY_LENGTH},
+    },
+    concurrent::{
+        atomic_buffer::AtomicBuffer,
+        broadcast::{broadcast_buffer_descriptor, broadcast_transmitter::BroadcastTransmitter},
+        counters::{self, CountersManager, CountersReader},
+        logbuffer::{
+            data_frame_header::{self, DataFrameHeaderDefn},
+            frame_descriptor,
+            log_buffer_descriptor::{self, LOG_META_DATA_LENGTH, PARTITION_COUNT},
+        },
+        ring_buffer::{self, ManyToOneRingBuffer},
+        status::status_indicator_reader::NO_ID_ALLOCATED,
+    },
+    context::Context,
+    driver::stream_counter,
+    utils::{bit_utils, memory_mapped_file::MemoryMappedFile, misc::unix_time_ms, types::Index},
+};
+use tempfile::TempDir;
+
+pub const TERM_LENGTH: Index = log_buffer_descriptor::TERM_MIN_LENGTH;
+pub const MTU_LENGTH: Index = 1408;
+pub const INITIAL_TERM_ID: i32 = 0;
+pub const SOURCE_IDENTITY: &str = "mock-driver";
+
+const TO_DRIVER_BUFFER_LENGTH: Index = 64 * 1024 + ring_buffer::TRAILER_LENGTH;
+const TO_CLIENTS_BUFFER_LENGTH: Index = 64 * 1024 + broadcast_buffer_descriptor::TRAILER_LENGTH;
+const COUNTER_VALUES_BUFFER_LENGTH: Index = 64 * 1024;
+const ERROR_BUFFER_LENGTH: Index = 64 * 1024;
+const CLIENT_LIVENESS_TIMEOUT_MS: u64 = 10_000;
+const PAGE_SIZE: Index = 4 * 1024;
+const COMMAND_LIMIT: i32 = 10;
+const AWAIT_TIMEOUT: Duration = Duration::from_secs(5);
+const RESPONSE_BUFFER_LENGTH: usize = 4 * 1024;
+
+/**
+ * Command received by the mock, decoded with the command flyweights.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum DriverCommand {
+    AddPublication {
+        client_id: i64,
+        correlation_id: i64,
+        stream_id: i32,
+        channel: String,
+        is_exclusive: bool,
+    },
+    RemovePublication {
+        client_id: i64,
+        correlation_id: i64,
+        registration_id: i64,
+    },
+    AddSubscription {
+        client_id: i64,
+        correlation_id: i64,
+        stream_id: i32,
+        channel: String,
+    },
+    RemoveSubscription {
+        client_id: i64,
+        correlation_id: i64,
+        registration_id: i64,
+    },
+    AddCounter {
+        client_id: i64,
+        correlation_id: i64,
+        type_id: i32,
+        key: Vec<u8>,
+        label: String,
+    },
+    RemoveCounter {
+        client_id: i64,
+        correlation_id: i64,
+        registration_id: i64,
+    },
+    AddRcvDestination {
+        client_id: i64,
+        correlation_id: i64,
+        registration_id: i64,
+        channel: String,
+    },
+    RemoveRcvDestination {
+        client_id: i64,
+        correlation_id: i64,
+        registration_id: i64,
+        channel: String,
+    },
+    ClientKeepAlive {
+        client_id: i64,
+    },
+    ClientClose {
+        client_id: i64,
+    },
+    // IC: every other command starts with the client id and the correlation id
+    Other {
+        command: AeronCommand,
+        client_id: i64,
+        correlation_id: i64,
+    },
+}
+
+impl DriverCommand {
+    pub fn client_id(&self) -> i64 {
+        match self {
+            DriverCommand::AddPublication { client_id, .. }
+            | DriverCommand::RemovePublication { client_id, .. }
+            | DriverCommand::AddSubscription { client_id, .. }
+            | DriverCommand::RemoveSubscription { client_id, .. }
+            | DriverCommand::AddCounter { client_id, .. }
+            | DriverCommand::RemoveCounter { client_id, .. }
+            | DriverCommand::AddRcvDestination { client_id, .. }
+            | DriverCommand::RemoveRcvDestination { client_id, .. }
+            | DriverCommand::ClientKeepAlive { client_id }
+            | DriverCommand::ClientClose { client_id }
+            | DriverCommand::Other { client_id, .. } => *client_id,
+        }
+    }
+
+    pub fn correlation_id(&self) -> Option<i64> {
+        match self {
+            DriverCommand::AddPublication { correlation_id, .. }
+            | DriverCommand::RemovePublication { correlation_id, .. }
+            | DriverCommand::AddSubscription { correlation_id, .. }
+            | DriverCommand::RemoveSubscription { correlation_id, .. }
+            | DriverCommand::AddCounter { correlation_id, .. }
+            | DriverCommand::RemoveCounter { correlation_id, .. }
+            | DriverCommand::AddRcvDestination { correlation_id, .. }
+            | DriverCommand::RemoveRcvDestination { correlation_id, .. }
+            | DriverCommand::Other { correlation_id, .. } => Some(*correlation_id),
+            DriverCommand::ClientKeepAlive { .. } | DriverCommand::ClientClose { .. } => None,
+        }
+    }
+}
+
+/**
+ * How the mock answers a command.
+ */
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptedResponse {
+    /// What a driver answers when the command succeeds, e.g. publication ready with a log buffer to map.
+    Ready,
+    /// Error response for the correlation id of the command.
+    Error { code: i32, message: String },
+    /// Leave the command unanswered so the client times out waiting.
+    NoResponse,
+}
+
+struct MockPublication {
+    registration_id: i64,
+    session_id: i32,
+    stream_id: i32,
+    channel: String,
+    publisher_limit_id: i32,
+    log_file_name: String,
+    meta_data: AtomicBuffer,
+    _log_file: MemoryMappedFile,
+}
+
+struct MockImage {
+    correlation_id: i64,
+    subscription_registration_id: i64,
+    publication_registration_id: i64,
+    subscriber_position_id: i32,
+}
+
+struct MockSubscription {
+    registration_id: i64,
+    stream_id: i32,
+    channel: String,
+}
+
+// IC: AlignedBuffer holds a raw pointer and cannot move to the driver thread, so responses are encoded here
+#[repr(C, align(16))]
+struct ResponseBuffer([u8; RESPONSE_BUFFER_LENGTH]);
+
+struct State {
+    aeron_dir: String,
+    to_driver: Arc<ManyToOneRingBuffer>,
+    transmitter: BroadcastTransmitter,
+    counters_manager: CountersManager,
+    counter_values: AtomicBuffer,
+    commands: Vec<DriverCommand>,
+    scripts: HashMap<i32, VecDeque<ScriptedResponse>>,
+    publications: Vec<MockPublication>,
+    subscriptions: Vec<MockSubscription>,
+    images: Vec<MockImage>,
+    counters: Vec<(i64, i32)>,
+    next_session_id: i32,
+    link_ipc: bool,
+    response_buffer: Box<ResponseBuffer>,
+    _cnc_file: MemoryMappedFile,
+}
+
+/**
+ * Media driver double which serves the CnC file of a temporary aeron directory on its own thread. Commands are
+ * recorded and answered with what a driver would answer unless a response has been scripted for them.
+ * Publications get a real log buffer which images can be linked to, so messages go from a Publication to a
+ * Subscription as they would over aeron:ipc. Logs are never cleaned, so a test should stay within the three
+ * terms of a log.
+ */
+pub struct MockDriver {
+    state: Arc<Mutex<State>>,
+    running: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+    _dir: TempDir,
+}
+
+impl MockDriver {
+    /**
+     * Create the CnC file in a new temporary directory and start serving it. Clients connect through
+     * client_context().
+     */
+    pub fn launch() -> Self {
+        let dir = tempfile::tempdir().expect("Error creating mock driver directory");
+        let aeron_dir = dir.path().to_str().unwrap().to_string();
+        std::fs::create_dir_all(format!("{}/publications", aeron_dir)).unwrap();
+
+        let counters_metadata_buffer_length =
+            COUNTER_VALUES_BUFFER_LENGTH * (counters::METADATA_LENGTH / counters::COUNTER_LENGTH);
+        let cnc_file = MemoryMappedFile::create_new(
+            format!("{}/{}", aeron_dir, cnc_file_descriptor::CNC_FILE),
+            0,
+            cnc_file_descriptor::compute_cnc_file_length(
+                TO_DRIVER_BUFFER_LENGTH
+                    + TO_CLIENTS_BUFFER_LENGTH
+                    + counters_metadata_buffer_length
+                    + COUNTER_VALUES_BUFFER_LENGTH
+                    + ERROR_BUFFER_LENGTH,
+            ),
+        )
+        .expect("Error creating mock CnC file");
+
+        let cnc_meta_data = cnc_file.atomic_buffer(0, *cnc_file_descriptor::META_DATA_LENGTH);
+        cnc_file_descriptor::fill_meta_data(
+            &cnc_meta_data,
+            TO_DRIVER_BUFFER_LENGTH,
+            TO_CLIENTS_BUFFER_LENGTH,
+            counters_metadata_buffer_length,
+            COUNTER_VALUES_BUFFER_LENGTH,
+            ERROR_BUFFER_LENGTH,
+            (CLIENT_LIVENESS_TIMEOUT_MS * 1_000_000) as i64,
+            unix_time_ms() as i64,
+            std::process::id() as i64,
+        );
+
+        let to_driver = Arc::new(ManyToOneRingBuffer::new(cnc_file_descriptor::create_to_driver_buffer(&cnc_file)).unwrap());
+        let transmitter = BroadcastTransmitter::new(cnc_file_descriptor::create_to_clients_buffer(&cnc_file)).unwrap();
+        let counter_values = cnc_file_descriptor::create_counter_values_buffer(&cnc_file);
+        let counters_manager =
+            CountersManager::new(cnc_file_descriptor::create_counter_metadata_buffer(&cnc_file), counter_values);
+
+        to_driver.set_consumer_heartbeat_time(unix_time_ms() as i64);
+        cnc_file_descriptor::signal_cnc_ready(&cnc_meta_data);
+
+        let state = Arc::new(Mutex::new(State {
+            aeron_dir,
+            to_driver: to_driver.clone(),
+            transmitter,
+            counters_manager,
+            counter_values,
+            commands: Vec::new(),
+            scripts: HashMap::new(),
+            publications: Vec::new(),
+            subscriptions: Vec::new(),
+            images: Vec::new(),
+            counters: Vec::new(),
+            next_session_id: 1,
+            link_ipc: false,
+            response_buffer: Box::new(ResponseBuffer([0; RESPONSE_BUFFER_LENGTH])),
+            _cnc_file: cnc_file,
+        }));
+        let running = Arc::new(AtomicBool::new(true));
+
+        let thread = {
+            let state = state.clone();
+            let running = running.clone();
+
+            thread::spawn(move || {
+                while running.load(Ordering::Acquire) {
+                    let commands_read = {
+                        let mut state = state.lock().unwrap();
+                        to_driver.set_consumer_heartbeat_time(unix_time_ms() as i64);
+                        let commands_read = to_driver.read(|command, buffer| state.on_command(command, buffer), COMMAND_LIMIT);
+                        state.link_ipc_images();
+                        state.update_publisher_limits();
+                        commands_read
+                    };
+
+                    if commands_read == 0 {
+                        thread::sleep(Duration::from_millis(1));
+                    }
+                }
+            })
+        };
+
+        Self {
+            state,
+            running,
+            thread: Some(thread),
+            _dir: dir,
+        }
+    }
+
+    pub fn aeron_dir(&self) -> String {
+        self.state.lock().unwrap().aeron_dir.clone()
+    }
+
+    /**
+     * Client context pointing at the aeron directory of the mock.
+     */
+    pub fn client_context(&self) -> Context {
+        let mut context = Context::new();
+        context.set_aeron_dir(self.aeron_dir());
+        context
+    }
+
+    /**
+     * Script the response to the next command of a type, e.g. AeronCommand::AddPublication. Calling this again
+     * queues the response for the following command of the same type.
+     */
+    pub fn script(&self, command: AeronCommand, response: ScriptedResponse) {
+        self.state
+            .lock()
+            .unwrap()
+            .scripts
+            .entry(command as i32)
+            .or_default()
+            .push_back(response);
+    }
+
+    /**
+     * Commands received so far in order of arrival.
+     */
+    pub fn commands(&self) -> Vec<DriverCommand> {
+        self.state.lock().unwrap().commands.clone()
+    }
+
+    /**
+     * Wait for a command matching the predicate to arrive.
+     */
+    pub fn await_command(&self, predicate: impl Fn(&DriverCommand) -> bool) -> DriverCommand {
+        let deadline = Instant::now() + AWAIT_TIMEOUT;
+
+        loop {
+            if let Some(command) = self.commands().into_iter().find(|command| predicate(command)) {
+                return command;
+            }
+            if Instant::now() > deadline {
+                panic!("Mock driver timed out waiting for a command");
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /**
+     * Make a publication available as an image to a subscription, joining at the current producer position, and
+     * mark the publication connected.
+     *
+     * @return the correlation id of the image.
+     */
+    pub fn add_image(&self, subscription_registration_id: i64, publication_registration_id: i64) -> i64 {
+        self.state
+            .lock()
+            .unwrap()
+            .add_image(subscription_registration_id, publication_registration_id)
+    }
+
+    /**
+     * Link publications to the subscriptions of the same stream on aeron:ipc as a driver would, so clients of the
+     * mock can talk to each other, e.g. an archive client and a mock archive, without the test adding images.
+     */
+    pub fn link_ipc(&self) {
+        self.state.lock().unwrap().link_ipc = true;
+    }
+
+    /**
+     * Set the number of transports the images of a publication are receiving from, as a driver does for the
+     * destinations of a multi-destination subscription.
+     */
+    pub fn set_active_transport_count(&self, publication_registration_id: i64, active_transport_count: i32) {
+        let state = self.state.lock().unwrap();
+        let publication = state
+            .publications
+            .iter()
+            .find(|publication| publication.registration_id == publication_registration_id)
+            .expect("Unknown publication");
+
+        log_buffer_descriptor::set_active_transport_count(&publication.meta_data, active_transport_count);
+    }
+
+    /**
+     * Tell the subscription of an image that it is no longer available.
+     */
+    pub fn remove_image(&self, image_correlation_id: i64) {
+        self.state.lock().unwrap().remove_image(image_correlation_id)
+    }
+
+    /**
+     * Tell clients that the driver has timed out a client, as when it stops sending keepalives.
+     */
+    pub fn client_timeout(&self, client_id: i64) {
+        self.state.lock().unwrap().transmit(|buffer| {
+            let mut client_timeout = ClientTimeoutFlyweight::new(buffer, 0);
+            client_timeout.set_client_id(client_id);
+
+            (AeronCommand::ResponseOnClientTimeout, CLIENT_TIMEOUT_LENGTH)
+        });
+    }
+
+    /**
+     * Stop serving the CnC file and wait for the thread to finish. Clients see the driver as gone once its
+     * heartbeat is older than their driver timeout.
+     */
+    pub fn close(&mut self) {
+        self.running.store(false, Ordering::Release);
+        if let Some(thread) = self.thread.take() {
+            thread.join().expect("Mock driver thread panicked");
+        }
+    }
+}
+
+impl Drop for MockDriver {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl State {
+    fn on_command(&mut self, command: AeronCommand, buffer: AtomicBuffer) {
+        let driver_command = decode_command(command, buffer);
+        self.commands.push(driver_command.clone());
+
+        let correlation_id = match driver_command.correlation_id() {
+            Some(correlation_id) => correlation_id,
+            None => return,
+        };
+
+        let response = self
+            .scripts
+            .get_mut(&(command as i32))
+            .and_then(|queue| queue.pop_front())
+            .unwrap_or(ScriptedResponse::Ready);
+
+        match response {
+            ScriptedResponse::Ready => self.on_ready(driver_command),
+            ScriptedResponse::Error { code, message } => self.send_error(correlation_id, code, &message),
+            ScriptedResponse::NoResponse => {}
+        }
+    }
+
+    fn on_ready(&mut self, command: DriverCommand) {
+        match command {
+            DriverCommand::AddPublication {
+                correlation_id,
+                stream_id,
+                channel,
+                is_exclusive,
+                ..
+            } => self.add_publication(correlation_id, stream_id, channel, is_exclusive),
+            DriverCommand::AddSubscription {
+                correlation_id,
+                stream_id,
+                channel,
+                ..
+            } => {
+                self.subscriptions.push(MockSubscription {
+                    registration_id: correlation_id,
+                    stream_id,
+                    channel,
+                });
+                self.transmit(|buffer| {
+                    let mut subscription_ready = SubscriptionReadyFlyweight::new(buffer, 0);
+                    subscription_ready.set_correlation_id(correlation_id);
+                    subscription_ready.set_channel_status_indicator_id(NO_ID_ALLOCATED);
+
+                    (AeronCommand::ResponseOnSubscriptionReady, SUBSCRIPTION_READY_LENGTH)
+                });
+            }
+            DriverCommand::AddCounter {
+                correlation_id,
+                type_id,
+                key,
+                label,
+                ..
+            } => {
+                let counter_id = self
+                    .counters_manager
+                    .allocate_opt(type_id, Some(&key), Option::<fn(&mut AtomicBuffer)>::None, &label)
+                    .expect("Mock driver out of counters");
+                self.counters.push((correlation_id, counter_id));
+                self.transmit(|buffer| {
+                    let mut counter_update = CounterUpdateFlyweight::new(buffer, 0);
+                    counter_update.set_correlation_id(correlation_id);
+                    counter_update.set_counter_id(counter_id);
+
+                    (AeronCommand::ResponseOnCounterReady, COUNTER_READY_LENGTH)
+                });
+            }
+            DriverCommand::RemoveSubscription {
+                correlation_id,
+                registration_id,
+                ..
+            } => {
+                self.subscriptions
+                    .retain(|subscription| subscription.registration_id != registration_id);
+                let (removed, images): (Vec<_>, Vec<_>) = self
+                    .images
+                    .drain(..)
+                    .partition(|image| image.subscription_registration_id == registration_id);
+                self.images = images;
+                for image in removed {
+                    self.counters_manager.free(image.subscriber_position_id);
+                }
+                self.send_operation_succeeded(correlation_id);
+            }
+            DriverCommand::RemoveCounter {
+                correlation_id,
+                registration_id,
+                ..
+            } => {
+                if let Some(index) = self.counters.iter().position(|(id, _)| *id == registration_id) {
+                    let (_, counter_id) = self.counters.remove(index);
+                    self.counters_manager.free(counter_id);
+                }
+                self.send_operation_succeeded(correlation_id);
+            }
+            // IC: publications are kept so images linked to them stay readable, like a lingering driver log
+            DriverCommand::RemovePublication { correlation_id, .. }
+            | DriverCommand::AddRcvDestination { correlation_id, .. }
+            | DriverCommand::RemoveRcvDestination { correlation_id, .. }
+            | DriverCommand::Other { correlation_id, .. } => self.send_operation_succeeded(correlation_id),
+            DriverCommand::ClientKeepAlive { .. } | DriverCommand::ClientClose { .. } => {}
+        }
+    }
+
+    fn add_publication(&mut self, registration_id: i64, stream_id: i32, channel: String, is_exclusive: bool) {
+        let session_id = self.next_session_id;
+        self.next_session_id += 1;
+
+        let log_file_name = format!("{}/publications/{}.logbuffer", self.aeron_dir, registration_id);
+        let (log_file, meta_data) = create_log(&log_file_name, session_id, stream_id);
+
+        let publisher_limit_id = stream_counter::allocate(
+            &mut self.counters_manager,
+            "pub-lmt",
+            stream_counter::PUBLISHER_LIMIT_TYPE_ID,
+            registration_id,
+            session_id,
+            stream_id,
+            &channel,
+        )
+        .expect("Mock driver out of counters");
+        self.counter_values
+            .put_ordered::<i64>(CountersReader::counter_offset(publisher_limit_id), 0);
+
+        self.transmit(|buffer| {
+            let mut publication_ready = PublicationBuffersReadyFlyweight::new(buffer, 0);
+            publication_ready.set_correlation_id(registration_id);
+            publication_ready.set_registration_id(registration_id);
+            publication_ready.set_stream_id(stream_id);
+            publication_ready.set_session_id(session_id);
+            publication_ready.set_position_limit_counter_id(publisher_limit_id);
+            publication_ready.set_channel_status_indicator_id(NO_ID_ALLOCATED);
+            publication_ready.set_log_file_name(log_file_name.as_bytes());
+
+            let command = if is_exclusive {
+                AeronCommand::ResponseOnExclusivePublicationReady
+            } else {
+                AeronCommand::ResponseOnPublicationReady
+            };
+            (command, publication_ready.length())
+        });
+
+        self.publications.push(MockPublication {
+            registration_id,
+            session_id,
+            stream_id,
+            channel,
+            publisher_limit_id,
+            log_file_name,
+            meta_data,
+            _log_file: log_file,
+        });
+    }
+
+    fn add_image(&mut self, subscription_registration_id: i64, publication_registration_id: i64) -> i64 {
+        let publication = self
+            .publications
+            .iter()
+            .find(|publication| publication.registration_id == publication_registration_id)
+            .expect("Unknown publication");
+        let (session_id, stream_id, meta_data, log_file_name, channel) = (
+            publication.session_id,
+            publication.stream_id,
+            publication.meta_data,
+            publication.log_file_name.clone(),
+            publication.channel.clone(),
+        );
+
+        let correlation_id = self.to_driver.next_correlation_id();
+        let subscriber_position_id = stream_counter::allocate(
+            &mut self.counters_manager,
+            "sub-pos",
+            stream_counter::SUBSCRIBER_POSITION_TYPE_ID,
+            subscription_registration_id,
+            session_id,
+            stream_id,
+            &channel,
+        )
+        .expect("Mock driver out of counters");
+        self.counter_values.put_ordered::<i64>(
+            CountersReader::counter_offset(subscriber_position_id),
+            producer_position(&meta_data),
+        );
+
+        self.transmit(|buffer| {
+            let mut image_ready = ImageBuffersReadyFlyweight::new(buffer, 0);
+            image_ready.set_correlation_id(correlation_id);
+            image_ready.set_stream_id(stream_id);
+            image_ready.set_session_id(session_id);
+            image_ready.set_subscription_registration_id(subscription_registration_id);
+            image_ready.set_subscriber_position_id(subscriber_position_id);
+            image_ready.set_log_file_name(log_file_name.as_bytes());
+            image_ready.set_source_identity(SOURCE_IDENTITY.as_bytes());
+
+            (AeronCommand::ResponseOnAvailableImage, image_ready.length())
+        });
+
+        self.images.push(MockImage {
+            correlation_id,
+            subscription_registration_id,
+            publication_registration_id,
+            subscriber_position_id,
+        });
+        log_buffer_descriptor::set_is_connected(&meta_data, true);
+
+        correlation_id
+    }
+
+    fn remove_image(&mut self, image_correlation_id: i64) {
+        let index = self
+            .images
+            .iter()
+            .position(|image| image.correlation_id == image_correlation_id)
+            .expect("Unknown image");
+        let image = self.images.remove(index);
+        let (stream_id, channel) = self
+            .subscriptions
+            .iter()
+            .find(|subscription| subscription.registration_id == image.subscription_registration_id)
+            .map(|subscription| (subscription.stream_id, subscription.channel.clone()))
+            .unwrap_or_default();
+
+        self.transmit(|buffer| {
+            let mut image_message = ImageMessageFlyweight::new(buffer, 0);
+            image_message.set_correlation_id(image.correlation_id);
+            image_message.set_subscription_registration_id(image.subscription_registration_id);
+            image_message.set_stream_id(stream_id);
+            image_message.set_channel(channel.as_bytes());
+
+            (AeronCommand::ResponseOnUnavailableImage, image_message.length())
+        });
+        self.counters_manager.free(image.subscriber_position_id);
+    }
+
+    fn link_ipc_images(&mut self) {
+        if !self.link_ipc {
+            return;
+        }
+
+        let mut links = Vec::new();
+        for publication in self.publications.iter().filter(|publication| is_ipc(&publication.channel)) {
+            for subscription in &self.subscriptions {
+                let is_linked = self.images.iter().any(|image| {
+                    image.subscription_registration_id == subscription.registration_id
+                        && image.publication_registration_id == publication.registration_id
+                });
+
+                if is_ipc(&subscription.channel) && subscription.stream_id == publication.stream_id && !is_linked {
+                    links.push((subscription.registration_id, publication.registration_id));
+                }
+            }
+        }
+
+        for (subscription_registration_id, publication_registration_id) in links {
+            self.add_image(subscription_registration_id, publication_registration_id);
+        }
+    }
+
+    /**
+     * Keep the limit of each publication with images half a term ahead of its slowest subscriber.
+     */
+    fn update_publisher_limits(&mut self) {
+        for publication in &self.publications {
+            let min_subscriber_position = self
+                .images
+                .iter()
+                .filter(|image| image.publication_registration_id == publication.registration_id)
+                .map(|image| {
+                    self.counter_values
+                        .get_volatile::<i64>(CountersReader::counter_offset(image.subscriber_position_id))
+                })
+                .min();
+
+            if let Some(position) = min_subscriber_position {
+                self.counter_values.put_ordered::<i64>(
+                    CountersReader::counter_offset(publication.publisher_limit_id),
+                    position + (TERM_LENGTH / 2) as i64,
+                );
+            }
+        }
+    }
+
+    fn send_operation_succeeded(&mut self, correlation_id: i64) {
+        self.transmit(|buffer| {
+            let mut operation_succeeded = OperationSucceededFlyweight::new(buffer, 0);
+            operation_succeeded.set_correlation_id(correlation_id);
+
+            (AeronCommand::ResponseOnOperationSuccess, OPERATION_SUCCEEDED_LENGTH)
+        });
+    }
+
+    fn send_error(&mut self, correlation_id: i64, code: i32, message: &str) {
+        self.transmit(|buffer| {
+            let mut error_response = ErrorResponseFlyweight::new(buffer, 0);
+            error_response.set_offending_command_correlation_id(correlation_id);
+            error_response.set_error_code(code);
+            error_response.set_error_message(message.as_bytes());
+
+            (AeronCommand::ResponseOnError, error_response.length())
+        });
+    }
+
+    fn transmit(&mut self, filler: impl FnOnce(AtomicBuffer) -> (AeronCommand, Index)) {
+        let buffer = AtomicBuffer::wrap_slice(&mut self.response_buffer.0);
+        let (command, length) = filler(buffer);
+
+        self.transmitter
+            .transmit(command as i32, &buffer, 0, length)
+            .expect("Mock driver failed to transmit response");
+    }
+}
+
+fn decode_command(command: AeronCommand, buffer: AtomicBuffer) -> DriverCommand {
+    match command {
+        AeronCommand::AddPublication | AeronCommand::AddExclusivePublication => {
+            let message = PublicationMessageFlyweight::new(buffer, 0);
+            DriverCommand::AddPublication {
+                client_id: message.client_id(),
+                correlation_id: message.correlation_id(),
+                stream_id: message.stream_id(),
+                channel: message.channel().to_string_lossy().into_owned(),
+                is_exclusive: command == AeronCommand::AddExclusivePublication,
+            }
+        }
+        AeronCommand::AddSubscription => {
+            let message = SubscriptionMessageFlyweight::new(buffer, 0);
+            DriverCommand::AddSubscription {
+                client_id: message.client_id(),
+                correlation_id: message.correlation_id(),
+                stream_id: message.stream_id(),
+                channel: message.channel().to_string_lossy().into_owned(),
+            }
+        }
+        AeronCommand::AddCounter => {
+            let message = CounterMessageFlyweight::new(buffer, 0);
+            DriverCommand::AddCounter {
+                client_id: message.client_id(),
+                correlation_id: message.correlation_id(),
+                type_id: message.type_id(),
+                key: message.key(),
+                label: message.label().to_string_lossy().into_owned(),
+            }
+        }
+        AeronCommand::RemovePublication | AeronCommand::RemoveSubscription | AeronCommand::RemoveCounter => {
+            let message = RemoveMessageFlyweight::new(buffer, 0);
+            let (client_id, correlation_id, registration_id) =
+                (message.client_id(), message.correlation_id(), message.registration_id());
+
+            match command {
+                AeronCommand::RemovePublication => DriverCommand::RemovePublication {
+                    client_id,
+                    correlation_id,
+                    registration_id,
+                },
+                AeronCommand::RemoveSubscription => DriverCommand::RemoveSubscription {
+                    client_id,
+                    correlation_id,
+                    registration_id,
+                },
+                _ => DriverCommand::RemoveCounter {
+                    client_id,
+                    correlation_id,
+                    registration_id,
+                },
+            }
+        }
+        AeronCommand::AddRcvDestination | AeronCommand::RemoveRcvDestination => {
+            let message = DestinationMessageFlyweight::new(buffer, 0);
+            let (client_id, correlation_id, registration_id, channel) = (
+                message.client_id(),
+                message.correlation_id(),
+                message.registration_id(),
+                message.channel().to_string_lossy().into_owned(),
+            );
+
+            if command == AeronCommand::AddRcvDestination {
+                DriverCommand::AddRcvDestination {
+                    client_id,
+                    correlation_id,
+                    registration_id,
+                    channel,
+                }
+            } else {
+                DriverCommand::RemoveRcvDestination {
+                    client_id,
+                    correlation_id,
+                    registration_id,
+                    channel,
+                }
+            }
+        }
+        AeronCommand::ClientKeepAlive => DriverCommand::ClientKeepAlive {
+            client_id: CorrelatedMessageFlyweight::new(buffer, 0).client_id(),
+        },
+        AeronCommand::ClientClose => DriverCommand::ClientClose {
+            client_id: CorrelatedMessageFlyweight::new(buffer, 0).client_id(),
+        },
+        command => {
+            let message = CorrelatedMessageFlyweight::new(buffer, 0);
+            DriverCommand::Other {
+                command,
+                client_id: message.client_id(),
+                correlation_id: message.correlation_id(),
+            }
+        }
+    }
+}
+
+fn is_ipc(channel: &str) -> bool {
+    channel.starts_with("aeron:ipc")
+}
+
+/**
+ * Create a log buffer file laid out as the driver lays them out, ready for clients to map.
+ */
+fn create_log(file_name: &str, session_id: i32, stream_id: i32) -> (MemoryMappedFile, AtomicBuffer) {
+    let log_file = MemoryMappedFile::create_new(file_name, 0, PARTITION_COUNT * TERM_LENGTH + LOG_META_DATA_LENGTH)
+        .expect("Error creating mock log buffer");
+    let meta_data = log_file.atomic_buffer(PARTITION_COUNT * TERM_LENGTH, LOG_META_DATA_LENGTH);
+
+    log_buffer_descriptor::initialize_tail_with_term_id(&meta_data, 0, INITIAL_TERM_ID);
+    for partition_index in 1..PARTITION_COUNT {
+        let expected_term_id = INITIAL_TERM_ID + partition_index - PARTITION_COUNT;
+        log_buffer_descriptor::initialize_tail_with_term_id(&meta_data, partition_index, expected_term_id);
+    }
+
+    meta_data.put::<i32>(*log_buffer_descriptor::LOG_INITIAL_TERM_ID_OFFSET, INITIAL_TERM_ID);
+    meta_data.put::<i32>(*log_buffer_descriptor::LOG_MTU_LENGTH_OFFSET, MTU_LENGTH);
+    meta_data.put::<i32>(*log_buffer_descriptor::LOG_TERM_LENGTH_OFFSET, TERM_LENGTH);
+    meta_data.put::<i32>(*log_buffer_descriptor::LOG_PAGE_SIZE_OFFSET, PAGE_SIZE);
+    meta_data.put::<i32>(
+        *log_buffer_descriptor::LOG_DEFAULT_FRAME_HEADER_LENGTH_OFFSET,
+        data_frame_header::LENGTH,
+    );
+    meta_data.put::<DataFrameHeaderDefn>(
+        log_buffer_descriptor::LOG_DEFAULT_FRAME_HEADER_OFFSET,
+        DataFrameHeaderDefn {
+            frame_length: 0,
+            version: data_frame_header::CURRENT_VERSION,
+            flags: frame_descriptor::UNFRAGMENTED,
+            frame_type: data_frame_header::HDR_TYPE_DATA,
+            term_offset: 0,
+            session_id,
+            stream_id,
+            term_id: 0,
+            reserved_value: 0,
+        },
+    );
+    log_buffer_descriptor::set_end_of_stream_position(&meta_data, i64::MAX);
+    log_buffer_descriptor::set_active_term_count_ordered(&meta_data, 0);
+
+    (log_file, meta_data)
+}
+
+fn producer_position(meta_data: &AtomicBuffer) -> i64 {
+    let raw_tail = log_buffer_descriptor::raw_tail_volatile(meta_data);
+    let term_offset = log_buffer_descriptor::term_offset(raw_tail, TERM_LENGTH as i64);
+
+    log_buffer_descriptor::compute_position(
+        log_buffer_descriptor::term_id(raw_tail),
+        term_offset,
+        bit_utils::number_of_trailing_zeroes(TERM_LENGTH),
+        INITIAL_TERM_ID,
+    )
+}
@@ -0,0 +1,332 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![cfg(feature = "embedded-driver")]
+
+extern crate aeron_rs;
+
+use std::ffi::CString;
+use std::ops::Range;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use aeron_rs::{
+    aeron::Aeron,
+    command::{control_protocol_events::AeronCommand, error_response_flyweight::ERROR_CODE_INVALID_CHANNEL},
+    concurrent::{
+        atomic_buffer::{AlignedBuffer, AtomicBuffer},
+        logbuffer::header::Header,
+    },
+    image::Image,
+    subscription::Subscription,
+    utils::{errors::AeronError, types::Index},
+};
+
+use crate::mock_driver::{DriverCommand, MockDriver, ScriptedResponse};
+
+// The mock serves its own temporary directory, so unlike the aeronmd tests these can run in parallel.
+mod mock_driver;
+
+const CHANNEL: &str = "aeron:ipc";
+const STREAM_ID: i32 = 1001;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn await_resource<T>(mut find: impl FnMut() -> Result<T, AeronError>) -> T {
+    let deadline = Instant::now() + TIMEOUT;
+
+    loop {
+        if let Ok(resource) = find() {
+            return resource;
+        }
+        assert!(Instant::now() < deadline, "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn await_condition(mut condition: impl FnMut() -> bool) {
+    let deadline = Instant::now() + TIMEOUT;
+
+    while !condition() {
+        assert!(Instant::now() < deadline, "timed out");
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn should_record_commands_from_client() {
+    let driver = MockDriver::launch();
+    let mut aeron = Aeron::new(driver.client_context()).expect("Error creating Aeron instance");
+
+    let publication_id = aeron.add_publication(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+    let subscription_id = aeron.add_subscription(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+    await_resource(|| aeron.find_publication(publication_id));
+    await_resource(|| aeron.find_subscription(subscription_id));
+
+    let client_id = aeron.client_id();
+    let commands = driver.commands();
+
+    assert!(commands.contains(&DriverCommand::AddPublication {
+        client_id,
+        correlation_id: publication_id,
+        stream_id: STREAM_ID,
+        channel: CHANNEL.to_string(),
+        is_exclusive: false,
+    }));
+    assert!(commands.contains(&DriverCommand::AddSubscription {
+        client_id,
+        correlation_id: subscription_id,
+        stream_id: STREAM_ID,
+        channel: CHANNEL.to_string(),
+    }));
+}
+
+#[test]
+fn should_exchange_messages_over_linked_image() {
+    let driver = MockDriver::launch();
+    let mut aeron = Aeron::new(driver.client_context()).expect("Error creating Aeron instance");
+
+    let publication_id = aeron.add_publication(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+    let subscription_id = aeron.add_subscription(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+    let publication = await_resource(|| aeron.find_publication(publication_id));
+    let subscription = await_resource(|| aeron.find_subscription(subscription_id));
+
+    assert!(!publication.is_connected());
+
+    driver.add_image(subscription_id, publication_id);
+    await_condition(|| subscription.lock().unwrap().is_connected());
+    await_condition(|| publication.is_connected());
+
+    let buffer = AlignedBuffer::with_capacity(64);
+    let src_buffer = AtomicBuffer::from_aligned(&buffer);
+    let message_count = 100;
+
+    let mut received = Vec::new();
+    let mut handler = |buffer: &AtomicBuffer, offset: Index, _length: Index, _header: &Header| {
+        received.push(buffer.get::<i64>(offset));
+    };
+
+    let mut fragments_read = 0;
+    for i in 0..message_count {
+        src_buffer.put::<i64>(0, i);
+        await_condition(|| {
+            if publication.offer_part(src_buffer, 0, 8).is_ok() {
+                return true;
+            }
+            fragments_read += subscription.lock().unwrap().poll(&mut handler, 10) as i64;
+            false
+        });
+    }
+
+    await_condition(|| {
+        fragments_read += subscription.lock().unwrap().poll(&mut handler, 10) as i64;
+        fragments_read == message_count
+    });
+
+    assert_eq!(received, (0..message_count).collect::<Vec<i64>>());
+}
+
+fn offer_messages(offer: &mut dyn FnMut(AtomicBuffer) -> Result<i64, AeronError>, values: Range<i64>) {
+    let buffer = AlignedBuffer::with_capacity(8);
+    let src_buffer = AtomicBuffer::from_aligned(&buffer);
+
+    for value in values {
+        src_buffer.put::<i64>(0, value);
+        await_condition(|| offer(src_buffer).is_ok());
+    }
+}
+
+fn poll_values(subscription: &Mutex<Subscription>, message_count: usize) -> Vec<i64> {
+    let mut received = Vec::new();
+    let mut handler = |buffer: &AtomicBuffer, offset: Index, _length: Index, _header: &Header| {
+        received.push(buffer.get::<i64>(offset));
+    };
+
+    let mut fragments_read = 0;
+    await_condition(|| {
+        fragments_read += subscription.lock().unwrap().poll(&mut handler, 10) as usize;
+        fragments_read == message_count
+    });
+
+    received
+}
+
+#[test]
+fn should_offer_from_threads_sharing_publication() {
+    let driver = MockDriver::launch();
+    let mut aeron = Aeron::new(driver.client_context()).expect("Error creating Aeron instance");
+
+    let publication_id = aeron.add_publication(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+    let subscription_id = aeron.add_subscription(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+    let publication = await_resource(|| aeron.find_publication(publication_id));
+    let subscription = await_resource(|| aeron.find_subscription(subscription_id));
+
+    driver.add_image(subscription_id, publication_id);
+    await_condition(|| publication.is_connected());
+
+    // IC: both threads offer through &self on the same publication, no lock around it
+    let publishers: Vec<_> = (0..2)
+        .map(|thread_index| {
+            let publication = publication.clone();
+            thread::spawn(move || {
+                let values = thread_index * 1000..thread_index * 1000 + 50;
+                offer_messages(&mut |buffer| publication.offer_part(buffer, 0, 8).map(|p| p as i64), values);
+            })
+        })
+        .collect();
+    for publisher in publishers {
+        publisher.join().unwrap();
+    }
+
+    let mut received = poll_values(&subscription, 100);
+    received.sort_unstable();
+    assert_eq!(received, (0..50).chain(1000..1050).collect::<Vec<i64>>());
+}
+
+#[test]
+fn should_offer_through_exclusive_publication_moved_to_another_thread() {
+    let driver = MockDriver::launch();
+    let mut aeron = Aeron::new(driver.client_context()).expect("Error creating Aeron instance");
+
+    let publication_id = aeron
+        .add_exclusive_publication(CString::new(CHANNEL).unwrap(), STREAM_ID)
+        .unwrap();
+    let subscription_id = aeron.add_subscription(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+    let mut publication = await_resource(|| aeron.find_exclusive_publication(publication_id));
+    let subscription = await_resource(|| aeron.find_subscription(subscription_id));
+
+    // IC: the only handle was handed out
+    assert!(aeron.find_exclusive_publication(publication_id).is_err());
+
+    driver.add_image(subscription_id, publication_id);
+    await_condition(|| publication.is_connected());
+
+    let publisher = thread::spawn(move || {
+        offer_messages(&mut |buffer| publication.offer_part(buffer, 0, 8), 0..50);
+        publication
+    });
+    let publication = publisher.join().unwrap();
+
+    assert_eq!(poll_values(&subscription, 50), (0..50).collect::<Vec<i64>>());
+
+    drop(publication);
+    driver.await_command(|command| {
+        matches!(command, DriverCommand::RemovePublication { registration_id, .. } if *registration_id == publication_id)
+    });
+}
+
+#[test]
+fn should_return_scripted_error() {
+    let driver = MockDriver::launch();
+    driver.script(
+        AeronCommand::AddPublication,
+        ScriptedResponse::Error {
+            code: ERROR_CODE_INVALID_CHANNEL,
+            message: "no such channel".to_string(),
+        },
+    );
+    let mut aeron = Aeron::new(driver.client_context()).expect("Error creating Aeron instance");
+
+    let publication_id = aeron.add_publication(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+
+    let mut result = aeron.find_publication(publication_id);
+    await_condition(|| {
+        result = aeron.find_publication(publication_id);
+        !matches!(result, Err(AeronError::PublicationNotReady(_)))
+    });
+
+    match result {
+        Err(AeronError::RegistrationException(code, message)) => {
+            assert_eq!(code, ERROR_CODE_INVALID_CHANNEL);
+            assert_eq!(message, "no such channel");
+        }
+        _ => panic!("expected the scripted error"),
+    }
+
+    // IC: the script is used up, the next publication is ready as usual
+    let publication_id = aeron.add_publication(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+    await_resource(|| aeron.find_publication(publication_id));
+}
+
+#[test]
+fn should_notify_unavailable_image() {
+    let driver = MockDriver::launch();
+    let image_gone = Arc::new(AtomicBool::new(false));
+
+    let mut context = driver.client_context();
+    {
+        let image_gone = image_gone.clone();
+        context.set_unavailable_image_handler(move |_image: &Image| image_gone.store(true, Ordering::Release));
+    }
+    let mut aeron = Aeron::new(context).expect("Error creating Aeron instance");
+
+    let publication_id = aeron.add_publication(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+    let subscription_id = aeron.add_subscription(CString::new(CHANNEL).unwrap(), STREAM_ID).unwrap();
+    await_resource(|| aeron.find_publication(publication_id));
+    let subscription = await_resource(|| aeron.find_subscription(subscription_id));
+
+    let image_id = driver.add_image(subscription_id, publication_id);
+    await_condition(|| subscription.lock().unwrap().image_count() == 1);
+
+    driver.remove_image(image_id);
+    await_condition(|| image_gone.load(Ordering::Acquire));
+    assert_eq!(subscription.lock().unwrap().image_count(), 0);
+}
+
+#[test]
+fn should_close_client_on_client_timeout() {
+    let driver = MockDriver::launch();
+    let timed_out = Arc::new(AtomicBool::new(false));
+
+    let mut context = driver.client_context();
+    {
+        let timed_out = timed_out.clone();
+        context.set_error_handler(move |error: AeronError| {
+            if let AeronError::ClientTimeoutException = error {
+                timed_out.store(true, Ordering::Release);
+            }
+        });
+    }
+    let aeron = Aeron::new(context).expect("Error creating Aeron instance");
+
+    driver.client_timeout(aeron.client_id());
+
+    await_condition(|| timed_out.load(Ordering::Acquire));
+    assert!(aeron.is_closed());
+}
+
+#[test]
+fn should_add_counter() {
+    let driver = MockDriver::launch();
+    let mut aeron = Aeron::new(driver.client_context()).expect("Error creating Aeron instance");
+
+    let counter_id = aeron.add_counter(1101, &[7u8; 8], "test counter").unwrap();
+    let counter = await_resource(|| aeron.find_counter(counter_id));
+
+    let command = driver.await_command(|command| matches!(command, DriverCommand::AddCounter { .. }));
+    assert_eq!(
+        command,
+        DriverCommand::AddCounter {
+            client_id: aeron.client_id(),
+            correlation_id: counter_id,
+            type_id: 1101,
+            key: vec![7u8; 8],
+            label: "test counter".to_string(),
+        }
+    );
+    assert_eq!(counter.label().unwrap().to_str().unwrap(), "test counter");
+}
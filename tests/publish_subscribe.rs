@@ -85,7 +85,7 @@ fn test_publication_create() {
     }
 
     // At this point publication must be created and be available for publishing
-    assert_eq!(publication.unwrap().lock().unwrap().channel_status(), CHANNEL_ENDPOINT_ACTIVE);
+    assert_eq!(publication.unwrap().channel_status(), CHANNEL_ENDPOINT_ACTIVE);
 
     common::stop_aeron_md(md);
 }
@@ -184,7 +184,7 @@ fn test_unfragmented_msg() {
 
     // At this point publication must be created and be available for publishing
     assert_eq!(subscription.lock().unwrap().channel_status(), CHANNEL_ENDPOINT_ACTIVE);
-    assert_eq!(publication.lock().unwrap().channel_status(), CHANNEL_ENDPOINT_ACTIVE);
+    assert_eq!(publication.channel_status(), CHANNEL_ENDPOINT_ACTIVE);
 
     let buffer = AlignedBuffer::with_capacity(256);
     let src_buffer = AtomicBuffer::from_aligned(&buffer);
@@ -194,7 +194,7 @@ fn test_unfragmented_msg() {
         src_buffer.put::<u8>(i, i as u8);
     }
 
-    let result = publication.lock().unwrap().offer(src_buffer);
+    let result = publication.offer(src_buffer);
 
     if let Ok(code) = result {
         println!("Sent with code {}!", code);
@@ -265,7 +265,7 @@ fn test_fragmented_msg() {
 
     // At this point publication must be created and be available for publishing
     assert_eq!(subscription.lock().unwrap().channel_status(), CHANNEL_ENDPOINT_ACTIVE);
-    assert_eq!(publication.lock().unwrap().channel_status(), CHANNEL_ENDPOINT_ACTIVE);
+    assert_eq!(publication.channel_status(), CHANNEL_ENDPOINT_ACTIVE);
 
     let buffer = AlignedBuffer::with_capacity(256);
     let src_buffer = AtomicBuffer::from_aligned(&buffer);
@@ -275,7 +275,7 @@ fn test_fragmented_msg() {
         src_buffer.put::<u8>(i, i as u8);
     }
 
-    let result = publication.lock().unwrap().offer(src_buffer);
+    let result = publication.offer(src_buffer);
 
     if let Ok(code) = result {
         println!("Sent with code {}!", code);
@@ -379,7 +379,7 @@ fn test_sequential_consistency() {
 
     // At this point publication must be created and be available for publishing
     assert_eq!(subscription.lock().unwrap().channel_status(), CHANNEL_ENDPOINT_ACTIVE);
-    assert_eq!(publication.lock().unwrap().channel_status(), CHANNEL_ENDPOINT_ACTIVE);
+    assert_eq!(publication.channel_status(), CHANNEL_ENDPOINT_ACTIVE);
 
     let subscriber_thread = thread::Builder::new()
         .name(String::from("Subscriber thread"))
@@ -399,7 +399,7 @@ fn test_sequential_consistency() {
     for seq_no in 0..messages_to_send {
         offer_idle_strategy.reset();
 
-        while publication.lock().unwrap().try_claim(I64_SIZE, &mut buffer_claim).is_err() {
+        while publication.try_claim(I64_SIZE, &mut buffer_claim).is_err() {
             offer_idle_strategy.idle();
         }
 
@@ -0,0 +1,411 @@
+/*
+ * Copyright 2020 UT OVERSEAS INC
+ *
+ * Licensed under the Apache License, Version 2.0 (the "License");
+ * you may not use this file except in compliance with the License.
+ * You may obtain a copy of the License at
+ *
+ * https://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing, software
+ * distributed under the License is distributed on an "AS IS" BASIS,
+ * WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+ * See the License for the specific language governing permissions and
+ * limitations under the License.
+ */
+
+#![cfg(feature = "embedded-driver")]
+
+extern crate aeron_rs;
+
+use std::ffi::CString;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use aeron_rs::{
+    aeron::Aeron,
+    archive::client::{
+        aeron_archive::AeronArchive,
+        context::Context as ArchiveContext,
+        replay_merge::{ReplayMerge, ReplayMergeState, MERGE_PROGRESS_TIMEOUT_DEFAULT_MS},
+    },
+    concurrent::{
+        atomic_buffer::{AlignedBuffer, AtomicBuffer},
+        logbuffer::{data_frame_header, header::Header},
+    },
+    exclusive_publication::ExclusivePublication,
+    subscription::Subscription,
+    utils::{
+        errors::{AeronError, ArchiveError, ArchiveErrorCode},
+        types::Index,
+    },
+};
+use io_aeron_archive_codecs::*;
+
+use crate::mock_archive::{ArchiveRequest, MockArchive, ScriptedResponse};
+use crate::mock_driver::{DriverCommand, MockDriver, TERM_LENGTH};
+
+// ReplayMerge needs a multi-destination subscription, which the embedded driver does not support, so the merge
+// runs against the mock driver with the mock archive talking to the client over its linked aeron:ipc streams.
+mod mock_archive;
+mod mock_driver;
+
+const CONTROL_CHANNEL: &str = "aeron:ipc";
+const CONTROL_STREAM_ID: i32 = 1010;
+const CONTROL_RESPONSE_STREAM_ID: i32 = 1020;
+const SUBSCRIPTION_CHANNEL: &str = "aeron:udp?control-mode=manual";
+const REPLAY_CHANNEL: &str = "aeron:udp";
+const REPLAY_DESTINATION: &str = "aeron:udp?endpoint=localhost:20121";
+const LIVE_DESTINATION: &str = "aeron:udp?endpoint=localhost:20122";
+const STREAM_ID: i32 = 1033;
+const RECORDING_ID: i64 = 7;
+const MESSAGE_LENGTH: Index = 1024 - data_frame_header::LENGTH;
+const FRAGMENT_LIMIT: i32 = 10;
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+// The replay is a publication to the replay destination linked to the subscription by the test, so the image
+// moves only as far as the test publishes. Fields drop in order: the mock archive closes before the driver.
+struct TestMerge {
+    mock: MockArchive,
+    archive: AeronArchive,
+    subscription: Arc<Mutex<Subscription>>,
+    replay_publication: ExclusivePublication,
+    _aeron: Arc<Mutex<Aeron>>,
+    driver: MockDriver,
+}
+
+fn await_resource<T>(mut find: impl FnMut() -> Result<T, AeronError>) -> T {
+    let deadline = Instant::now() + TIMEOUT;
+
+    loop {
+        if let Ok(resource) = find() {
+            return resource;
+        }
+        assert!(Instant::now() < deadline, "timed out");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn launch_merge() -> TestMerge {
+    let driver = MockDriver::launch();
+    driver.link_ipc();
+
+    let aeron = Arc::new(Mutex::new(
+        Aeron::new(driver.client_context()).expect("Error creating Aeron instance"),
+    ));
+    let mock = MockArchive::launch(&driver.aeron_dir(), CONTROL_CHANNEL, CONTROL_STREAM_ID);
+
+    let mut context = ArchiveContext::new();
+    context.set_aeron(aeron.clone());
+    context.set_control_request_channel(String::from(CONTROL_CHANNEL));
+    context.set_control_request_stream_id(CONTROL_STREAM_ID);
+    context.set_control_response_channel(String::from(CONTROL_CHANNEL));
+    context.set_control_response_stream_id(CONTROL_RESPONSE_STREAM_ID);
+    let archive = AeronArchive::connect(context).expect("Error connecting to archive");
+
+    let (subscription, replay_publication) = {
+        let mut aeron = aeron.lock().unwrap();
+        let subscription_id = aeron
+            .add_subscription(CString::new(SUBSCRIPTION_CHANNEL).unwrap(), STREAM_ID)
+            .unwrap();
+        let publication_id = aeron
+            .add_exclusive_publication(CString::new(REPLAY_DESTINATION).unwrap(), STREAM_ID)
+            .unwrap();
+
+        (
+            await_resource(|| aeron.find_subscription(subscription_id)),
+            await_resource(|| aeron.find_exclusive_publication(publication_id)),
+        )
+    };
+
+    driver.add_image(
+        subscription.lock().unwrap().registration_id(),
+        replay_publication.registration_id(),
+    );
+
+    TestMerge {
+        mock,
+        archive,
+        subscription,
+        replay_publication,
+        _aeron: aeron,
+        driver,
+    }
+}
+
+fn replay_merge<'a>(
+    archive: &'a mut AeronArchive,
+    subscription: &Arc<Mutex<Subscription>>,
+    merge_progress_timeout_ms: i64,
+) -> ReplayMerge<'a> {
+    ReplayMerge::new(
+        subscription.clone(),
+        archive,
+        REPLAY_CHANNEL,
+        REPLAY_DESTINATION,
+        LIVE_DESTINATION,
+        RECORDING_ID,
+        0,
+        merge_progress_timeout_ms,
+    )
+    .expect("Error creating ReplayMerge")
+}
+
+// Polls the merge like a duty cycle would, consuming what has been replayed, until the condition holds.
+fn poll_until(merge: &mut ReplayMerge, mut condition: impl FnMut(&ReplayMerge) -> bool) -> Result<(), AeronError> {
+    let deadline = Instant::now() + TIMEOUT;
+
+    while !condition(merge) {
+        assert!(Instant::now() < deadline, "timed out in state {:?}", merge.state());
+        merge.poll(
+            &mut |_buffer: &AtomicBuffer, _offset: Index, _length: Index, _header: &Header| {},
+            FRAGMENT_LIMIT,
+        )?;
+        thread::sleep(Duration::from_millis(1));
+    }
+
+    Ok(())
+}
+
+fn poll_until_failed(merge: &mut ReplayMerge) -> AeronError {
+    let deadline = Instant::now() + TIMEOUT;
+
+    loop {
+        assert!(Instant::now() < deadline, "timed out in state {:?}", merge.state());
+        if let Err(err) = merge.poll(
+            &mut |_buffer: &AtomicBuffer, _offset: Index, _length: Index, _header: &Header| {},
+            FRAGMENT_LIMIT,
+        ) {
+            return err;
+        }
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn publish(publication: &mut ExclusivePublication, message_count: i32) {
+    let buffer = AlignedBuffer::with_capacity(MESSAGE_LENGTH);
+    let src_buffer = AtomicBuffer::from_aligned(&buffer);
+
+    for _ in 0..message_count {
+        let deadline = Instant::now() + TIMEOUT;
+        while publication.offer_part(src_buffer, 0, MESSAGE_LENGTH).is_err() {
+            assert!(Instant::now() < deadline, "timed out offering");
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+}
+
+// Requests sent while polling the merge are only seen by the mock some time after they were sent.
+fn await_request(mock: &MockArchive, mut matcher: impl FnMut(&ArchiveRequest) -> bool) -> ArchiveRequest {
+    let deadline = Instant::now() + TIMEOUT;
+
+    loop {
+        if let Some(request) = mock.requests().into_iter().find(|request| matcher(request)) {
+            return request;
+        }
+        assert!(Instant::now() < deadline, "timed out waiting for request");
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+fn recording_position_requests(mock: &MockArchive) -> usize {
+    mock.requests()
+        .iter()
+        .filter(|request| {
+            matches!(request, ArchiveRequest::Other { template_id, .. }
+                if *template_id == recording_position_request_codec::SBE_TEMPLATE_ID)
+        })
+        .count()
+}
+
+fn destination_commands(driver: &MockDriver) -> (Vec<String>, Vec<String>) {
+    let mut added = Vec::new();
+    let mut removed = Vec::new();
+
+    for command in driver.commands() {
+        match command {
+            DriverCommand::AddRcvDestination { channel, .. } => added.push(channel),
+            DriverCommand::RemoveRcvDestination { channel, .. } => removed.push(channel),
+            _ => {}
+        }
+    }
+
+    (added, removed)
+}
+
+#[test]
+fn should_add_live_destination_only_within_catch_up_window() {
+    let mut test = launch_merge();
+    let mut archive = test.archive;
+    let mock = &test.mock;
+    let session_id = test.replay_publication.session_id();
+
+    // IC: the live destination is added once the replay is within a quarter of a term of the recording
+    let window = (TERM_LENGTH >> 2) as i64;
+    let outside_window_position = window + 1024;
+    test.mock.script(
+        recording_position_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(0)],
+    );
+    test.mock.script(
+        recording_position_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(outside_window_position)],
+    );
+    test.mock.script(
+        recording_position_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(outside_window_position + window)],
+    );
+    test.mock.script(
+        replay_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(session_id as i64)],
+    );
+
+    let mut merge = replay_merge(&mut archive, &test.subscription, MERGE_PROGRESS_TIMEOUT_DEFAULT_MS);
+    assert_eq!(merge.state(), ReplayMergeState::GetRecordingPosition);
+
+    poll_until(&mut merge, |merge| {
+        merge.state() == ReplayMergeState::Catchup && recording_position_requests(mock) == 2
+    })
+    .unwrap();
+    assert_eq!(merge.image_session_id(), Some(session_id));
+    assert!(!merge.is_live_added());
+    assert_eq!(destination_commands(&test.driver).0, vec![REPLAY_DESTINATION]);
+
+    let replay = test
+        .mock
+        .requests()
+        .into_iter()
+        .find(|request| matches!(request, ArchiveRequest::Replay { .. }))
+        .expect("No replay requested");
+    match replay {
+        ArchiveRequest::Replay {
+            recording_id,
+            position,
+            length,
+            replay_stream_id,
+            replay_channel,
+            ..
+        } => {
+            assert_eq!(recording_id, RECORDING_ID);
+            assert_eq!(position, 0);
+            assert_eq!(length, i64::MAX);
+            assert_eq!(replay_stream_id, STREAM_ID);
+            assert!(replay_channel.contains("endpoint=localhost:20121"));
+        }
+        request => panic!("Unexpected request {:?}", request),
+    }
+
+    // IC: catching up to the recording position makes the merge ask again, now answered within the window
+    publish(&mut test.replay_publication, (outside_window_position / 1024) as i32);
+    poll_until(&mut merge, |merge| merge.is_live_added()).unwrap();
+    assert_eq!(merge.state(), ReplayMergeState::Catchup);
+
+    let subscription_id = test.subscription.lock().unwrap().registration_id();
+    test.driver.await_command(|command| {
+        matches!(command, DriverCommand::AddRcvDestination { registration_id, channel, .. }
+            if *registration_id == subscription_id && channel == LIVE_DESTINATION)
+    });
+    assert_eq!(
+        destination_commands(&test.driver).0,
+        vec![REPLAY_DESTINATION, LIVE_DESTINATION]
+    );
+}
+
+#[test]
+fn should_remove_replay_destination_after_live_join() {
+    let test = launch_merge();
+    let mut archive = test.archive;
+    let mock = &test.mock;
+    let session_id = test.replay_publication.session_id();
+
+    // IC: unscripted recording position requests are answered with position 0, so the replay is always caught up
+    test.mock.script(
+        replay_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::ok(session_id as i64)],
+    );
+
+    let mut merge = replay_merge(&mut archive, &test.subscription, MERGE_PROGRESS_TIMEOUT_DEFAULT_MS);
+
+    poll_until(&mut merge, |merge| merge.is_live_added()).unwrap();
+    let requests_after_live_added = recording_position_requests(mock);
+
+    // IC: the replay stays until the image is receiving from both destinations
+    poll_until(&mut merge, |_| {
+        recording_position_requests(mock) > requests_after_live_added + 1
+    })
+    .unwrap();
+    assert!(!merge.is_merged());
+    assert!(destination_commands(&test.driver).1.is_empty());
+
+    test.driver
+        .set_active_transport_count(test.replay_publication.registration_id(), 2);
+    poll_until(&mut merge, |merge| merge.is_merged()).unwrap();
+
+    test.driver.await_command(
+        |command| matches!(command, DriverCommand::RemoveRcvDestination { channel, .. } if channel == REPLAY_DESTINATION),
+    );
+    await_request(
+        mock,
+        |request| matches!(request, ArchiveRequest::StopReplay { replay_session_id, .. } if *replay_session_id == session_id as i64),
+    );
+}
+
+#[test]
+fn should_fail_when_merge_makes_no_progress() {
+    let test = launch_merge();
+    let mut archive = test.archive;
+
+    // IC: the recording position request is never answered
+    test.mock
+        .script(recording_position_request_codec::SBE_TEMPLATE_ID, Vec::new());
+
+    let mut merge = replay_merge(&mut archive, &test.subscription, 100);
+
+    match poll_until_failed(&mut merge) {
+        AeronError::Archive(ArchiveError::MergeNoProgress(state)) => assert_eq!(state, "GET_RECORDING_POSITION"),
+        err => panic!("Unexpected error {:?}", err),
+    }
+    assert!(merge.has_failed());
+    assert_eq!(merge.do_work().unwrap(), 0);
+    assert!(merge.has_failed());
+}
+
+#[test]
+fn should_fail_on_archive_error_response() {
+    let test = launch_merge();
+    let mut archive = test.archive;
+
+    test.mock.script(
+        recording_position_request_codec::SBE_TEMPLATE_ID,
+        vec![ScriptedResponse::error(
+            ArchiveErrorCode::UnknownRecording.code(),
+            "unknown recording id: 7",
+        )],
+    );
+
+    let mut merge = replay_merge(&mut archive, &test.subscription, MERGE_PROGRESS_TIMEOUT_DEFAULT_MS);
+
+    match poll_until_failed(&mut merge) {
+        AeronError::Archive(ArchiveError::Response {
+            correlation_id,
+            error_code,
+            message,
+        }) => {
+            let request = test
+                .mock
+                .requests()
+                .into_iter()
+                .find(|request| {
+                    matches!(request, ArchiveRequest::Other { template_id, .. }
+                        if *template_id == recording_position_request_codec::SBE_TEMPLATE_ID)
+                })
+                .expect("No recording position requested");
+            assert_eq!(Some(correlation_id), request.correlation_id());
+            assert_eq!(error_code, ArchiveErrorCode::UnknownRecording);
+            assert_eq!(message, "unknown recording id: 7");
+        }
+        err => panic!("Unexpected error {:?}", err),
+    }
+    assert!(merge.has_failed());
+}